@@ -0,0 +1,242 @@
+//! The single-pass tests as they were before the bits were processed 64 at a time, i.e. reading
+//! the input one character or bit at a time. Logging, telemetry and the assembly of the test
+//! results are left out, the validation, the counting and the computation of the p-values are kept.
+
+use rust_nist_suite::bit_sequence::{BitInput, BitSequence};
+use rust_nist_suite::constants;
+use rust_nist_suite::customtypes::{self, NistError, Result};
+use statrs::distribution::ContinuousCDF;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// The validation and the block access of the inputs before they were read a word at a time
+pub trait Input: BitInput {
+    /// Scan the input once and collect its length, number of ones and the first invalid symbol.
+    fn validate_bitwise(&self) -> customtypes::ValidatedInput;
+
+    /// Get a range of the bits as ASCII '0' and '1' characters.
+    fn ascii_bitwise(&self, range: Range<usize>) -> Cow<'_, str>;
+}
+
+impl Input for str {
+    fn validate_bitwise(&self) -> customtypes::ValidatedInput {
+        let mut input = customtypes::ValidatedInput {
+            length: self.len(),
+            ..Default::default()
+        };
+
+        for (position, c) in self.char_indices() {
+            match c {
+                '1' => input.ones += 1,
+                '0' => (),
+                _ => {
+                    input.first_invalid = Some((position, c));
+                    break;
+                }
+            }
+        }
+
+        input
+    }
+
+    fn ascii_bitwise(&self, range: Range<usize>) -> Cow<'_, str> {
+        Cow::Borrowed(&self[range])
+    }
+}
+
+impl Input for BitSequence {
+    fn validate_bitwise(&self) -> customtypes::ValidatedInput {
+        customtypes::ValidatedInput {
+            length: self.len(),
+            ones: self.count_ones(),
+            ..Default::default()
+        }
+    }
+
+    fn ascii_bitwise(&self, range: Range<usize>) -> Cow<'_, str> {
+        Cow::Owned(
+            range
+                .map(|index| {
+                    if self.get(index).unwrap_or_default() {
+                        '1'
+                    } else {
+                        '0'
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Validate the input as evaluate_bit_string() did.
+fn evaluate(
+    test: customtypes::Test,
+    bit_string: &(impl Input + ?Sized),
+) -> Result<customtypes::ValidatedInput> {
+    if bit_string.bit_len() == 0 {
+        return Err(NistError::insufficient_length(test, 1, 0));
+    }
+
+    let input = bit_string.validate_bitwise();
+    match input.first_invalid {
+        Some((position, symbol)) => Err(NistError::InvalidBit { position, symbol }),
+        None => Ok(input),
+    }
+}
+
+/// Perform the Frequency Monobit Test and return the p-value.
+pub fn frequency_monobit(bit_string: &(impl Input + ?Sized)) -> Result<f64> {
+    let input = evaluate(customtypes::Test::FrequencyMonobit, bit_string)?;
+
+    let partial_sum = 2 * (input.ones as i128) - (input.length as i128);
+    let observed = (partial_sum.abs() as f64) / (input.length as f64).sqrt();
+
+    Ok(statrs::function::erf::erfc(
+        observed / std::f64::consts::SQRT_2,
+    ))
+}
+
+/// Perform the Runs Test and return the p-value.
+pub fn runs(bit_string: &(impl Input + ?Sized)) -> Result<f64> {
+    let input = evaluate(customtypes::Test::Runs, bit_string)?;
+    let length = input.length as f64;
+
+    let pre_test_proportion = input.ones as f64 / length;
+    let tau = 2.0 / length.sqrt();
+    let requirement = (pre_test_proportion - 0.5).abs();
+    if requirement >= tau {
+        return Err(NistError::NotApplicable {
+            test: customtypes::Test::Runs,
+            reason: format!("Tau ({}) < Requirement ({})", tau, requirement),
+        });
+    }
+
+    let mut v_n_observed: usize = 1;
+    let mut bits = bit_string.bits();
+    let mut previous = bits.next();
+
+    for bit in bits {
+        if previous != Some(bit) {
+            v_n_observed += 1;
+        }
+        previous = Some(bit);
+    }
+
+    let constant = pre_test_proportion * (1.0 - pre_test_proportion);
+    let numerator = ((v_n_observed as f64) - 2.0 * length * constant).abs();
+    let denominator = 2.0 * (2.0 * length).sqrt() * constant;
+
+    Ok(statrs::function::erf::erfc(numerator / denominator))
+}
+
+/// Perform the Cumulative Sums Test in forward mode and return the p-value.
+pub fn cumulative_sums(bit_string: &(impl Input + ?Sized)) -> Result<f64> {
+    let input = evaluate(customtypes::Test::CumulativeSums, bit_string)?;
+    let length = input.length;
+
+    let mut current_sum: i64 = 0;
+    let mut max_sum_z = 0;
+    let mut maximum = customtypes::WalkPoint { index: 0, sum: 0 };
+    let mut minimum = customtypes::WalkPoint { index: 0, sum: 0 };
+
+    for (index, bit) in bit_string.bits().enumerate() {
+        if bit {
+            current_sum += 1;
+        } else {
+            current_sum -= 1;
+        }
+
+        max_sum_z = max_sum_z.max(current_sum.abs());
+
+        let point = customtypes::WalkPoint {
+            index: index + 1,
+            sum: current_sum,
+        };
+        if point.sum > maximum.sum {
+            maximum = point;
+        }
+        if point.sum < minimum.sum {
+            minimum = point;
+        }
+    }
+
+    let upper_limit = (((length as f64) / (max_sum_z as f64) - 1.0) * 0.25) as i64;
+    let lower_limit_1 = ((-(length as f64) / (max_sum_z as f64) + 1.0) * 0.25) as i64;
+    let lower_limit_2 = ((-(length as f64) / (max_sum_z as f64) - 3.0) * 0.25) as i64;
+
+    let mut sum_1 = 0.0;
+    let mut sum_2 = 0.0;
+    let normal = statrs::distribution::Normal::new(0.0, 1.0).expect("valid normal distribution");
+    let denominator = (length as f64).sqrt();
+
+    for k in lower_limit_1..=upper_limit {
+        let numerator_1 = (4.0 * (k as f64) + 1.0) * (max_sum_z as f64);
+        let numerator_2 = (4.0 * (k as f64) - 1.0) * (max_sum_z as f64);
+        sum_1 += normal.cdf(numerator_1 / denominator) - normal.cdf(numerator_2 / denominator);
+    }
+
+    for k in lower_limit_2..=upper_limit {
+        let numerator_1 = (4.0 * (k as f64) + 3.0) * (max_sum_z as f64);
+        let numerator_2 = (4.0 * (k as f64) + 1.0) * (max_sum_z as f64);
+        sum_2 += normal.cdf(numerator_1 / denominator) - normal.cdf(numerator_2 / denominator);
+    }
+
+    Ok(1.0 - sum_1 + sum_2)
+}
+
+/// Perform the Longest Run of Ones in a Block Test and return the p-value. Only the configuration
+/// of inputs of at least constants::MAX_LENGTH bits is supported.
+pub fn longest_run(bit_string: &(impl Input + ?Sized)) -> Result<f64> {
+    let input = evaluate(customtypes::Test::LongestRun, bit_string)?;
+    if input.length < constants::MAX_LENGTH {
+        return Err(NistError::insufficient_length(
+            customtypes::Test::LongestRun,
+            constants::MAX_LENGTH,
+            input.length,
+        ));
+    }
+
+    let block_size = constants::MAX_SIZE_M;
+    let number_of_blocks = constants::MAX_SIZE_N;
+    let thresholds = constants::MAX_THRESHOLDS;
+    let pi_values = &constants::MAX_PI_VALUES;
+
+    let mut counts: BTreeMap<i32, i32> = BTreeMap::new();
+    for index in 0..number_of_blocks {
+        let start = index * block_size;
+        let block = bit_string.ascii_bitwise(start..start + block_size);
+
+        let mut max_count = 0;
+        let mut current_count = 0;
+        for bit in block.chars() {
+            if bit == '1' {
+                current_count += 1;
+                max_count = max_count.max(current_count);
+            } else {
+                current_count = 0
+            }
+        }
+        *counts.entry(max_count).or_insert(0) += 1;
+    }
+
+    let mut vi_counts: BTreeMap<i32, i32> = BTreeMap::new();
+    for (&key, &value) in &counts {
+        let key = key.clamp(thresholds.0, thresholds.1);
+        *vi_counts.entry(key).or_insert(0) += value;
+    }
+    for threshold in thresholds.0..=thresholds.1 {
+        vi_counts.entry(threshold).or_insert(0);
+    }
+
+    let mut chi_square = 0.0;
+    for ((_, vi_value), &pi_value) in vi_counts.iter().zip(pi_values.iter()) {
+        let constant = (number_of_blocks as f64) * pi_value;
+        chi_square += ((*vi_value as f64) - constant).powf(2.0) / constant;
+    }
+
+    Ok(statrs::function::gamma::gamma_ur(
+        ((pi_values.len() as f64) - 1.0) * 0.5,
+        chi_square * 0.5,
+    ))
+}
@@ -0,0 +1,113 @@
+//! Benchmarks of the single-pass tests on 10^6 bits. Each test is compared with the test as it was
+//! before processing 64 bits at a time, see the bitwise module. Run them by
+//! `cargo bench --bench single_pass`.
+
+mod bitwise;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use rust_nist_suite::bit_sequence::BitSequence;
+use rust_nist_suite::{cumulative_sums, customtypes, frequency_monobit, longest_run, runs};
+
+const LENGTH: usize = 1_000_000;
+const SEED: u64 = 42;
+
+/// Generate the bits to be tested as bit string and as packed sequence
+fn inputs() -> (String, BitSequence) {
+    let mut bytes = vec![0; LENGTH / 8];
+    StdRng::seed_from_u64(SEED).fill_bytes(&mut bytes);
+    let sequence = BitSequence::from_bytes(&bytes);
+
+    (sequence.to_bit_string(), sequence)
+}
+
+fn bench_frequency_monobit(c: &mut Criterion) {
+    let (bit_string, sequence) = inputs();
+    let mut group = c.benchmark_group("frequency_monobit");
+
+    group.bench_function(BenchmarkId::new("bitwise", "bit_string"), |b| {
+        b.iter(|| bitwise::frequency_monobit(black_box(bit_string.as_str())).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("bitwise", "bit_sequence"), |b| {
+        b.iter(|| bitwise::frequency_monobit(black_box(&sequence)).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_string"), |b| {
+        b.iter(|| frequency_monobit::run_test(black_box(bit_string.as_str())).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_sequence"), |b| {
+        b.iter(|| frequency_monobit::run_test(black_box(&sequence)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_runs(c: &mut Criterion) {
+    let (bit_string, sequence) = inputs();
+    let mut group = c.benchmark_group("runs");
+
+    group.bench_function(BenchmarkId::new("bitwise", "bit_string"), |b| {
+        b.iter(|| bitwise::runs(black_box(bit_string.as_str())).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("bitwise", "bit_sequence"), |b| {
+        b.iter(|| bitwise::runs(black_box(&sequence)).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_string"), |b| {
+        b.iter(|| runs::run_test(black_box(bit_string.as_str())).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_sequence"), |b| {
+        b.iter(|| runs::run_test(black_box(&sequence)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_cumulative_sums(c: &mut Criterion) {
+    let (bit_string, sequence) = inputs();
+    let mut group = c.benchmark_group("cumulative_sums");
+
+    group.bench_function(BenchmarkId::new("bitwise", "bit_string"), |b| {
+        b.iter(|| bitwise::cumulative_sums(black_box(bit_string.as_str())).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("bitwise", "bit_sequence"), |b| {
+        b.iter(|| bitwise::cumulative_sums(black_box(&sequence)).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_string"), |b| {
+        b.iter(|| {
+            cumulative_sums::run_test(black_box(bit_string.as_str()), customtypes::Mode::Forward)
+                .unwrap()
+        })
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_sequence"), |b| {
+        b.iter(|| {
+            cumulative_sums::run_test(black_box(&sequence), customtypes::Mode::Forward).unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn bench_longest_run(c: &mut Criterion) {
+    let (bit_string, sequence) = inputs();
+    let mut group = c.benchmark_group("longest_run");
+
+    group.bench_function(BenchmarkId::new("bitwise", "bit_string"), |b| {
+        b.iter(|| bitwise::longest_run(black_box(bit_string.as_str())).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("bitwise", "bit_sequence"), |b| {
+        b.iter(|| bitwise::longest_run(black_box(&sequence)).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_string"), |b| {
+        b.iter(|| longest_run::run_test(black_box(bit_string.as_str())).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("words", "bit_sequence"), |b| {
+        b.iter(|| longest_run::run_test(black_box(&sequence)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_frequency_monobit,
+    bench_runs,
+    bench_cumulative_sums,
+    bench_longest_run
+);
+criterion_main!(benches);
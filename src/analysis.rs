@@ -0,0 +1,418 @@
+//! This module contains the second-level analysis of NIST SP 800-22: instead of judging a single
+//! p-value, the p-values of many sequences are checked for the proportion of passing sequences
+//! and for uniformity.
+
+use crate::bit_sequence::{BitInput, BitSequence};
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::suite;
+
+/// Split one long bit string into subsequences and evaluate a test on them like evaluate().
+/// Trailing bits which do not fill a whole subsequence are discarded.
+///
+/// # Arguments
+///
+/// test - The test to be run on each subsequence
+/// bit_string - The bit string to be tested for randomness
+/// number_of_subsequences - The number of subsequences the bit string has to be divided into
+/// alpha - The significance level each subsequence is judged at
+///
+/// # Return
+///
+/// Ok(results) - The second-level result of each statistic of the test
+/// Err(err) - Some error occured, e.g. a subsequence is too short for the test
+pub fn two_level_test<T: BitInput + ?Sized>(
+    test: customtypes::Test,
+    bit_string: &T,
+    number_of_subsequences: usize,
+    alpha: f64,
+) -> Result<Vec<customtypes::SecondLevelResult>> {
+    log::trace!("analysis::two_level_test()");
+
+    let length = bit_string.bit_len();
+    if number_of_subsequences == 0 || number_of_subsequences > length {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "number_of_subsequences".to_owned(),
+            reason: format!(
+                "Number of subsequences ({}) must be between 1 and the bit string length ({})",
+                number_of_subsequences, length
+            ),
+        });
+    }
+    bit_string.ensure_ascii()?;
+    let subsequence_len = length / number_of_subsequences;
+    log::debug!(
+        "Two-level test: {} subsequences of {} bits",
+        number_of_subsequences,
+        subsequence_len
+    );
+
+    // pack the subsequences, so each of them is tested like a sequence of its own
+    let mut bits = bit_string.bits();
+    let subsequences: Vec<BitSequence> = (0..number_of_subsequences)
+        .map(|_| bits.by_ref().take(subsequence_len).collect())
+        .collect();
+    let subsequences: Vec<&BitSequence> = subsequences.iter().collect();
+
+    evaluate(test, &subsequences, alpha)
+}
+
+/// Run a test on each of many sequences with the parameters the suite chooses for it and check
+/// the proportion of passing sequences and the uniformity of the p-values of each statistic, e.g.
+/// of each state of the excursion tests. Sequences with too few cycles for the excursion tests are
+/// left out, as NIST does.
+///
+/// # Arguments
+///
+/// test - The test to be run on each sequence
+/// sequences - The sequences to be tested, e.g. the outputs of one generator
+/// alpha - The significance level each sequence is judged at
+///
+/// # Return
+///
+/// Ok(results) - The second-level result of each statistic of the test
+/// Err(err) - Some error occured, e.g. a sequence is too short for the test
+pub fn evaluate<T: BitInput + ?Sized>(
+    test: customtypes::Test,
+    sequences: &[&T],
+    alpha: f64,
+) -> Result<Vec<customtypes::SecondLevelResult>> {
+    log::trace!("analysis::evaluate()");
+
+    if !(alpha > 0.0 && alpha < 1.0) {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "alpha".to_owned(),
+            reason: format!("Significance level ({}) must be between 0 and 1", alpha),
+        });
+    }
+    if sequences.is_empty() {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: "No sequences passed".to_owned(),
+        });
+    }
+    let limits = customtypes::Limits::default();
+    let required_length = match suite::required_length(test, &limits) {
+        Some(required_length) => required_length,
+        None => {
+            return Err(customtypes::NistError::InvalidParameter {
+                test: Some(test),
+                name: "test".to_owned(),
+                reason: "Not part of the suite".to_owned(),
+            })
+        }
+    };
+
+    // collect the results of each statistic, which are in the same order for every sequence
+    let mut statistics = Vec::<Vec<customtypes::TestResult>>::new();
+    for (index, sequence) in sequences.iter().enumerate() {
+        let length = sequence.bit_len();
+        if length < required_length {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "{}: Sequence {} has {} bits, but at least {} bits are required",
+                    test, index, length, required_length
+                ),
+            });
+        }
+
+        let results = match suite::run_test(
+            test,
+            *sequence,
+            length,
+            &limits,
+            &customtypes::TestParams::default(),
+            &customtypes::ExecutionControl::default(),
+        ) {
+            Ok(results) => results,
+            Err(err) => match err.root() {
+                cycles @ customtypes::NistError::InsufficientCycles { .. } => {
+                    log::warn!("{}: Sequence {} left out, {}", test, index, cycles);
+                    continue;
+                }
+                _ => return Err(err).with_context(|| format!("Test failed on sequence {}", index)),
+            },
+        };
+
+        if statistics.is_empty() {
+            statistics.resize_with(results.len(), Vec::new);
+        }
+        if results.len() != statistics.len() {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "{}: Sequence {} yields {} statistics instead of {}",
+                    test,
+                    index,
+                    results.len(),
+                    statistics.len()
+                ),
+            });
+        }
+        for (statistic, result) in statistics.iter_mut().zip(results) {
+            statistic.push(result);
+        }
+    }
+    if statistics.is_empty() {
+        return Err(customtypes::NistError::NotApplicable {
+            test,
+            reason: "Not applicable to any of the passed sequences".to_owned(),
+        });
+    }
+
+    let mut evaluated = Vec::with_capacity(statistics.len());
+    for results in statistics {
+        let p_values: Vec<f64> = results.iter().map(|result| result.p_value).collect();
+        let passed_sequences = results
+            .iter()
+            .filter(|result| result.passed_at(alpha))
+            .count();
+        let result = customtypes::SecondLevelResult {
+            test,
+            parameters: results[0].parameters.clone(),
+            alpha,
+            number_of_sequences: results.len(),
+            passed_sequences,
+            confidence_interval: proportion_interval(results.len(), alpha),
+            uniformity: uniformity_with_bins(&p_values, constants::UNIFORMITY_BINS)?,
+        };
+        log::info!(
+            "{}: {}/{} sequences passed, confidence interval = {:?}, uniformity p-value = {}",
+            test,
+            result.passed_sequences,
+            result.number_of_sequences,
+            result.confidence_interval,
+            result.uniformity.p_value
+        );
+        evaluated.push(result);
+    }
+
+    Ok(evaluated)
+}
+
+/// Compute the confidence interval of the proportion of passing sequences, p +- 3 * sqrt(p * (1
+/// - p) / m) with p = 1 - alpha, bounded by [0, 1].
+///
+/// # Arguments
+///
+/// number_of_sequences - The number of tested sequences m
+/// alpha - The significance level each sequence is judged at
+///
+/// # Return
+///
+/// (lower, upper) - The bounds of the acceptable proportions
+pub fn proportion_interval(number_of_sequences: usize, alpha: f64) -> (f64, f64) {
+    log::trace!("analysis::proportion_interval()");
+
+    let proportion = 1.0 - alpha;
+    let deviation = constants::PROPORTION_INTERVAL_SIGMAS
+        * (proportion * alpha / (number_of_sequences as f64)).sqrt();
+
+    (
+        (proportion - deviation).max(0.0),
+        (proportion + deviation).min(1.0),
+    )
+}
+
+/// Compute the proportion of p-values passing the test, i.e. being greater than or equal to
+/// P_VALUE_THRESHOLD.
+///
+/// # Arguments
+///
+/// p_values - The p-values of the tested sequences
+///
+/// # Return
+///
+/// Ok(proportion) - The proportion of passing sequences
+/// Err(err) - Some error occured
+pub fn passing_proportion(p_values: &[f64]) -> Result<f64> {
+    log::trace!("analysis::passing_proportion()");
+
+    if p_values.is_empty() {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: "No p-values passed".to_owned(),
+        });
+    }
+
+    let passed = p_values
+        .iter()
+        .filter(|&&p_value| p_value >= constants::P_VALUE_THRESHOLD)
+        .count();
+
+    Ok((passed as f64) / (p_values.len() as f64))
+}
+
+/// Compute the p-value of the uniformity of given p-values (P-value_T). The interval [0, 1] is
+/// divided into ten bins and the bin counts are compared to the uniform distribution with a
+/// chi_square test.
+///
+/// # Arguments
+///
+/// p_values - The p-values of the tested sequences
+///
+/// # Return
+///
+/// Ok(p-value) - The uniformity p-value
+/// Err(err) - Some error occured
+pub fn uniformity_p_value(p_values: &[f64]) -> Result<f64> {
+    log::trace!("analysis::uniformity_p_value()");
+
+    let result = uniformity_with_bins(p_values, constants::UNIFORMITY_BINS)?;
+
+    Ok(result.p_value)
+}
+
+/// Check the uniformity of given p-values with a configurable number of bins. Fewer bins keep the
+/// expected count per bin adequate when only a few dozen sequences were tested.
+///
+/// # Arguments
+///
+/// p_values - The p-values of the tested sequences
+/// number_of_bins - The number of equally sized bins [0, 1] is divided into
+///
+/// # Return
+///
+/// Ok(result) - The bin counts, chi_square value and uniformity p-value
+/// Err(err) - Some error occured
+pub fn uniformity_with_bins(
+    p_values: &[f64],
+    number_of_bins: usize,
+) -> Result<customtypes::UniformityResult> {
+    log::trace!("analysis::uniformity_with_bins()");
+
+    if p_values.is_empty() {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: "No p-values passed".to_owned(),
+        });
+    }
+    if let Some(invalid) = p_values
+        .iter()
+        .find(|p_value| !(0.0..=1.0).contains(*p_value))
+    {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!("Invalid p-value {} passed", invalid),
+        });
+    }
+    if number_of_bins < 2 {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "number_of_bins".to_owned(),
+            reason: format!(
+                "Number of bins ({}) must be at least 2 to check uniformity",
+                number_of_bins
+            ),
+        });
+    }
+    if p_values.len() < constants::RECOMMENDED_NUMBER_OF_SEQUENCES {
+        log::warn!(
+            "Uniformity of p-values needs at least {} sequences to be meaningful, got {}",
+            constants::RECOMMENDED_NUMBER_OF_SEQUENCES,
+            p_values.len()
+        );
+    }
+
+    let expected = (p_values.len() as f64) / (number_of_bins as f64);
+    if expected < constants::MIN_EXPECTED_BIN_COUNT {
+        log::warn!(
+            "Expected count per bin ({}) is less than {}. Consider fewer bins",
+            expected,
+            constants::MIN_EXPECTED_BIN_COUNT
+        );
+    }
+
+    // a p-value of 1.0 belongs to the last bin
+    let mut bin_counts = vec![0_usize; number_of_bins];
+    for p_value in p_values {
+        let bin = ((p_value * (number_of_bins as f64)) as usize).min(number_of_bins - 1);
+        bin_counts[bin] += 1;
+    }
+    log::debug!("Bin counts of p-values: {:?}", bin_counts);
+
+    let chi_square = bin_counts
+        .iter()
+        .map(|&count| ((count as f64) - expected).powf(2.0) / expected)
+        .sum::<f64>();
+
+    let p_value = if chi_square == 0.0 {
+        1.0
+    } else {
+        statrs::function::gamma::gamma_ur(((number_of_bins - 1) as f64) * 0.5, chi_square * 0.5)
+    };
+    log::debug!(
+        "Uniformity: chi_square = {}, p-value = {}",
+        chi_square,
+        p_value
+    );
+
+    Ok(customtypes::UniformityResult {
+        bin_counts,
+        chi_square,
+        p_value,
+    })
+}
+
+/// Compute the q-value of each p-value to control the false discovery rate, i.e. the expected
+/// fraction of false alarms among all results flagged as non-random. A result is flagged at a
+/// false discovery rate of alpha if its q-value is less than alpha.
+///
+/// # Arguments
+///
+/// p_values - The p-values of all results, e.g. of all tests over all devices
+/// method - The procedure used to compute the q-values
+///
+/// # Return
+///
+/// Ok(q_values) - The q-values in the same order as the passed p-values
+/// Err(err) - Some error occured
+pub fn q_values(p_values: &[f64], method: customtypes::FdrMethod) -> Result<Vec<f64>> {
+    log::trace!("analysis::q_values()");
+
+    if p_values.is_empty() {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: "No p-values passed".to_owned(),
+        });
+    }
+    if let Some(invalid) = p_values
+        .iter()
+        .find(|p_value| !(0.0..=1.0).contains(*p_value))
+    {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!("Invalid p-value {} passed", invalid),
+        });
+    }
+
+    let number_of_p_values = p_values.len() as f64;
+
+    // estimate the proportion of true null hypotheses pi_0
+    let pi_0 = match method {
+        customtypes::FdrMethod::BenjaminiHochberg => 1.0,
+        customtypes::FdrMethod::Storey => {
+            let above_lambda = p_values
+                .iter()
+                .filter(|&&p_value| p_value > constants::STOREY_LAMBDA)
+                .count();
+            // without any p-value above lambda the estimate is 0, which would flag every result,
+            // so at least one p-value above lambda is counted
+            ((above_lambda.max(1) as f64) / (number_of_p_values * (1.0 - constants::STOREY_LAMBDA)))
+                .min(1.0)
+        }
+    };
+    log::debug!("FDR ({:?}): pi_0 = {}", method, pi_0);
+
+    // sort indices by ascending p-value
+    let mut order: Vec<usize> = (0..p_values.len()).collect();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+
+    // q_(i) = min_{j >= i} pi_0 * m * p_(j) / j, starting from the largest p-value
+    let mut q_values = vec![0.0; p_values.len()];
+    let mut running_min = 1.0_f64;
+    for (rank, &index) in order.iter().enumerate().rev() {
+        let q_value = pi_0 * number_of_p_values * p_values[index] / ((rank + 1) as f64);
+        running_min = running_min.min(q_value);
+        q_values[index] = running_min;
+    }
+    log::debug!("FDR ({:?}): q-values = {:?}", method, q_values);
+
+    Ok(q_values)
+}
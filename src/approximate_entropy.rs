@@ -0,0 +1,447 @@
+//! This module performs the Approximate Entropy Test.
+//!
+//! Description of test from NIST SP 800-22:
+//!
+//! "As with the Serial test of Section 2.11, the focus of this test is the frequency of all possible
+//! overlapping m-bit patterns across the entire sequence. The purpose of the test is to compare the
+//! frequency of overlapping blocks of two consecutive/adjacent lengths (m and m+1) against the
+//! expected result for a random sequence."
+//!
+//! The windowed variant keeps the counts of the m-bit and (m+1)-bit patterns of the last bits fed
+//! up to date as bits enter and leave the window, so the entropy of a monitored source can be
+//! tracked without recomputing the counts of each window from scratch.
+
+use crate::bit_sequence::BitInput;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
+use crate::utils;
+
+const TEST_NAME: customtypes::Test = customtypes::Test::ApproximateEntropy;
+
+/// Perform the Approximate Entropy Test by determining the p-value.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_length - The block length m
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value which indicates whether randomness is given or not
+/// Err(err) - Some error occured
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized), block_length: usize) -> Result<f64> {
+    log::trace!("approximate_entropy::perform_test()");
+
+    Ok(run_test(bit_string, block_length)?.p_value)
+}
+
+/// Perform the Approximate Entropy Test and return its result. The statistic is ApEn(m).
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_length - The block length m
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(
+    bit_string: &(impl BitInput + ?Sized),
+    block_length: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("approximate_entropy::run_test()");
+
+    run_test_cancellable(
+        bit_string,
+        block_length,
+        &customtypes::CancellationToken::new(),
+    )
+}
+
+/// Perform the Approximate Entropy Test like run_test() and stop once the passed token got
+/// cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_length - The block length m
+/// cancellation - The token to stop the test with
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    block_length: usize,
+    cancellation: &customtypes::CancellationToken,
+) -> Result<customtypes::TestResult> {
+    log::trace!("approximate_entropy::run_test_cancellable()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE)
+        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+    evaluate_block_length(length, block_length)?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // count the overlapping patterns of length m and m+1, wrapping around the end of the sequence
+    let phi_m = compute_phi(
+        &utils::count_overlapping_patterns(TEST_NAME, bit_string, block_length, cancellation)?,
+        length,
+    );
+    let phi_m_plus_one = compute_phi(
+        &utils::count_overlapping_patterns(TEST_NAME, bit_string, block_length + 1, cancellation)?,
+        length,
+    );
+    let ap_en = phi_m - phi_m_plus_one;
+    log::debug!(
+        "{}: phi(m) = {}, phi(m+1) = {}, ApEn = {}",
+        TEST_NAME,
+        phi_m,
+        phi_m_plus_one,
+        ap_en
+    );
+
+    let (chi_square, p_value) = compute_p_value(ap_en, length, block_length);
+    log::info!(
+        "{}: chi_square = {}, p-value = {}",
+        TEST_NAME,
+        chi_square,
+        p_value
+    );
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: ap_en,
+        parameters: utils::parameter_map(&[("n", length), ("m", block_length)]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    Ok(result)
+}
+
+/// Approximate Entropy over a sliding window of the last bits fed. The bits are kept in a ring
+/// buffer and the counts of the patterns are updated incrementally, so feeding a bit takes O(m)
+#[derive(Debug, Clone)]
+pub struct WindowedApproximateEntropy {
+    window_size: usize,
+    block_length: usize,
+    bits: Vec<bool>,
+    oldest: usize,
+    len: usize,
+    /// Counts of the m-bit patterns of the window, without wrapping around
+    counts_m: Vec<usize>,
+    /// Counts of the (m+1)-bit patterns of the window, without wrapping around
+    counts_m_plus_one: Vec<usize>,
+    /// The last m+1 bits fed, most recent bit last
+    recent: usize,
+}
+
+impl WindowedApproximateEntropy {
+    /// Create the window before the first bit.
+    ///
+    /// # Arguments
+    ///
+    /// window_size - The number of bits n the entropy is computed over
+    /// block_length - The block length m
+    ///
+    /// # Return
+    ///
+    /// Ok(window) - The empty window
+    /// Err(err) - Some error occured
+    pub fn new(window_size: usize, block_length: usize) -> Result<Self> {
+        log::trace!("WindowedApproximateEntropy::new()");
+
+        evaluate_block_length(window_size, block_length)?;
+
+        Ok(WindowedApproximateEntropy {
+            window_size,
+            block_length,
+            bits: vec![false; window_size],
+            oldest: 0,
+            len: 0,
+            counts_m: vec![0; 1 << block_length],
+            counts_m_plus_one: vec![0; 1 << (block_length + 1)],
+            recent: 0,
+        })
+    }
+
+    /// Feed the next bit. Once the window is full, the oldest bit leaves it.
+    ///
+    /// # Arguments
+    ///
+    /// bit - The next bit of the monitored source
+    pub fn feed(&mut self, bit: bool) {
+        let m = self.block_length;
+
+        if self.len == self.window_size {
+            // remove the patterns starting at the oldest bit before it leaves the window
+            let pattern = (0..=m).fold(0, |pattern, offset| {
+                (pattern << 1) | usize::from(self.bits[(self.oldest + offset) % self.window_size])
+            });
+            self.counts_m_plus_one[pattern] -= 1;
+            self.counts_m[pattern >> 1] -= 1;
+
+            self.oldest = (self.oldest + 1) % self.window_size;
+            self.len -= 1;
+        }
+
+        self.bits[(self.oldest + self.len) % self.window_size] = bit;
+        self.len += 1;
+        self.recent = ((self.recent << 1) | usize::from(bit)) & ((1 << (m + 1)) - 1);
+
+        // add the patterns ending at the new bit
+        if self.len >= m {
+            self.counts_m[self.recent & ((1 << m) - 1)] += 1;
+        }
+        if self.len > m {
+            self.counts_m_plus_one[self.recent] += 1;
+        }
+    }
+
+    /// Get the number of bits currently in the window.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether no bit has been fed so far.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check whether the window contains window_size bits.
+    pub fn is_full(&self) -> bool {
+        self.len == self.window_size
+    }
+
+    /// Compute ApEn(m) = phi(m) - phi(m+1) of the bits in the window. As for the whole sequence,
+    /// the window is wrapped around by the patterns crossing its end.
+    ///
+    /// # Return
+    ///
+    /// Some(ap_en) - The approximate entropy of the window
+    /// None - The window contains less than m+1 bits
+    pub fn approximate_entropy(&self) -> Option<f64> {
+        let m = self.block_length;
+        if self.len <= m {
+            return None;
+        }
+
+        let phi_m = self.phi(&self.counts_m, m);
+        let phi_m_plus_one = self.phi(&self.counts_m_plus_one, m + 1);
+        crate::logger::verbose_trace!(
+            "{}: phi(m) = {}, phi(m+1) = {}",
+            TEST_NAME,
+            phi_m,
+            phi_m_plus_one
+        );
+
+        Some(phi_m - phi_m_plus_one)
+    }
+
+    /// Compute the result of the Approximate Entropy Test on the bits in the window.
+    ///
+    /// # Return
+    ///
+    /// Ok(result) - The p-value, statistic ApEn(m), parameters and advisories of the test
+    /// Err(err) - The window contains less than m+1 bits
+    pub fn result(&self) -> Result<customtypes::TestResult> {
+        log::trace!("WindowedApproximateEntropy::result()");
+
+        // capture the current time before computing the result
+        let start_time = std::time::Instant::now();
+
+        let ap_en = match self.approximate_entropy() {
+            Some(ap_en) => ap_en,
+            None => {
+                return Err(customtypes::NistError::insufficient_length(
+                    TEST_NAME,
+                    self.block_length + 1,
+                    self.len,
+                ))
+            }
+        };
+
+        let (chi_square, p_value) = compute_p_value(ap_en, self.len, self.block_length);
+        log::debug!(
+            "{}: ApEn = {}, chi_square = {}, p-value = {} over {} bits",
+            TEST_NAME,
+            ap_en,
+            chi_square,
+            p_value,
+            self.len
+        );
+
+        let mut advisories = Vec::<customtypes::Advisory>::new();
+        if !self.is_full() {
+            advisories.push(customtypes::Advisory::weak(
+                "n",
+                format!("Window contains {} of {} bits", self.len, self.window_size),
+            ));
+        }
+
+        Ok(customtypes::TestResult {
+            test: TEST_NAME,
+            p_value,
+            advisories,
+            statistic: ap_en,
+            parameters: utils::parameter_map(&[("n", self.len), ("m", self.block_length)]),
+            elapsed_time: start_time.elapsed(),
+            alpha: constants::P_VALUE_THRESHOLD,
+            counts: Vec::new(),
+        })
+    }
+
+    /// Compute phi of a pattern length from the counts of the window and the patterns wrapping
+    /// around its end.
+    ///
+    /// # Arguments
+    ///
+    /// counts - The counts of the patterns of given length, without wrapping around
+    /// pattern_len - The length of the patterns
+    ///
+    /// # Return
+    ///
+    /// phi - The sum of pi_i * ln(pi_i) over all patterns
+    fn phi(&self, counts: &[usize], pattern_len: usize) -> f64 {
+        // the pattern_len - 1 patterns starting in the last bits continue at the first bits
+        let mut wrapped = Vec::<usize>::with_capacity(pattern_len);
+        for start in (self.len + 1 - pattern_len)..self.len {
+            let pattern = (0..pattern_len).fold(0, |pattern, offset| {
+                let index = (self.oldest + (start + offset) % self.len) % self.window_size;
+                (pattern << 1) | usize::from(self.bits[index])
+            });
+            wrapped.push(pattern);
+        }
+
+        let mut counts = counts.to_vec();
+        for pattern in wrapped {
+            counts[pattern] += 1;
+        }
+
+        compute_phi(&counts, self.len)
+    }
+}
+
+/// Check the block length m for validity.
+///
+/// # Arguments
+///
+/// length - The number of bits n the entropy is computed over
+/// block_length - The block length m
+///
+/// # Return
+///
+/// Ok() - The block length is valid
+/// Err(err) - Some error occured
+fn evaluate_block_length(length: usize, block_length: usize) -> Result<()> {
+    log::trace!("approximate_entropy::evaluate_block_length()");
+
+    if !(1..=constants::MAX_BLOCK_LENGTH_APPROXIMATE_ENTROPY).contains(&block_length) {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Block length m ({}) must be between 1 and {}",
+                block_length,
+                constants::MAX_BLOCK_LENGTH_APPROXIMATE_ENTROPY
+            ),
+        ));
+    }
+    if length <= block_length {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Length ({}) must exceed block length m ({})",
+                length, block_length
+            ),
+        ));
+    }
+
+    // NIST recommends m < floor(log2(n)) - 5, larger m lead to unreliable p-values
+    if block_length + 5 >= length.ilog2() as usize {
+        log::warn!(
+            "{}: Block length m ({}) should be less than log2(n) - 5 for length {}",
+            TEST_NAME,
+            block_length,
+            length
+        );
+    }
+
+    Ok(())
+}
+
+/// Compute phi, the sum of pi_i * ln(pi_i) over all patterns.
+///
+/// # Arguments
+///
+/// counts - The number of occurences of each pattern, including those wrapping around
+/// length - The number of bits n
+///
+/// # Return
+///
+/// phi - The value of phi
+fn compute_phi(counts: &[usize], length: usize) -> f64 {
+    let n = length as f64;
+
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let fraction = count as f64 / n;
+            fraction * fraction.ln()
+        })
+        .sum()
+}
+
+/// Compute chi_square = 2n(ln 2 - ApEn) and its p-value.
+///
+/// # Arguments
+///
+/// ap_en - The approximate entropy ApEn(m)
+/// length - The number of bits n
+/// block_length - The block length m
+///
+/// # Return
+///
+/// (chi_square, p_value) - The statistic and p-value
+fn compute_p_value(ap_en: f64, length: usize, block_length: usize) -> (f64, f64) {
+    let chi_square = 2.0 * (length as f64) * (std::f64::consts::LN_2 - ap_en);
+    let p_value = if chi_square <= 0.0 {
+        1.0
+    } else {
+        statrs::function::gamma::gamma_ur(2.0_f64.powi(block_length as i32 - 1), chi_square * 0.5)
+    };
+
+    (chi_square, p_value)
+}
@@ -0,0 +1,227 @@
+//! This module pins the numerical behavior of reports against baseline files, e.g. golden files
+//! of the crate's own tests or of downstream users, so refactorings do not change results
+//! unnoticed.
+//!
+//! A baseline file starts with the schema version of its format and contains one line per test
+//! with the name of the test, the p-value, the statistic and the parameters, separated by tabs.
+//! Other lines starting with '#' are comments. The results are written in the order of
+//! customtypes::Test and the parameters by name, so files of different runs can be diffed.
+
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use std::collections::BTreeMap;
+
+impl customtypes::SuiteReport {
+    /// Write the report to a baseline file, which later runs can be compared with.
+    ///
+    /// # Arguments
+    ///
+    /// file_path - The path of the baseline file to be written
+    ///
+    /// # Return
+    ///
+    /// Ok() - Successfully wrote the baseline file
+    /// Err(err) - Some error occured
+    pub fn write_baseline(&self, file_path: &str) -> Result<()> {
+        log::trace!("SuiteReport::write_baseline()");
+
+        let mut contents = format!(
+            "{}{}\n# test\tp-value\tstatistic\tparameters\n",
+            constants::SCHEMA_VERSION_PREFIX,
+            constants::REPORT_SCHEMA_VERSION
+        );
+        for result in self.sorted_results() {
+            let parameters = result
+                .parameters
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<String>>()
+                .join(",");
+            contents.push_str(&format!(
+                "{}{sep}{}{sep}{}{sep}{}\n",
+                result.test.name(),
+                result.p_value,
+                result.statistic,
+                parameters,
+                sep = constants::BASELINE_SEPARATOR
+            ));
+        }
+
+        std::fs::write(file_path, contents)
+            .with_context(|| format!("Failed to write baseline file '{}'", file_path))?;
+        log::info!(
+            "Wrote {} results to baseline file '{}'",
+            self.results.len(),
+            file_path
+        );
+
+        Ok(())
+    }
+
+    /// Compare the report with a baseline file. The tests and parameters have to be equal, while
+    /// p-values and statistics may differ by constants::BASELINE_TOLERANCE.
+    ///
+    /// # Arguments
+    ///
+    /// file_path - The path of the baseline file written by write_baseline()
+    ///
+    /// # Return
+    ///
+    /// Ok() - The report matches the baseline
+    /// Err(err) - The report deviates from the baseline or some error occured
+    pub fn assert_matches_baseline(&self, file_path: &str) -> Result<()> {
+        log::trace!("SuiteReport::assert_matches_baseline()");
+
+        let baseline = read_baseline(file_path)?;
+        if baseline.len() != self.results.len() {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "Baseline '{}' contains {} results, but the report contains {}",
+                    file_path,
+                    baseline.len(),
+                    self.results.len()
+                ),
+            });
+        }
+
+        let mut deviations = Vec::<String>::new();
+        for (result, (test, p_value, statistic, parameters)) in
+            self.sorted_results().into_iter().zip(&baseline)
+        {
+            if result.test != *test {
+                deviations.push(format!("Expected {}, got {}", test, result.test));
+            } else if (result.p_value - p_value).abs() > constants::BASELINE_TOLERANCE
+                || (result.statistic - statistic).abs() > constants::BASELINE_TOLERANCE
+                || result.parameters != *parameters
+            {
+                deviations.push(format!(
+                    "{}: Expected p-value {}, statistic {} and parameters {:?}, got {}, {} and {:?}",
+                    test,
+                    p_value,
+                    statistic,
+                    parameters,
+                    result.p_value,
+                    result.statistic,
+                    result.parameters
+                ));
+            }
+        }
+
+        if !deviations.is_empty() {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "Report deviates from baseline '{}':\n{}",
+                    file_path,
+                    deviations.join("\n")
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Get the results in the order of customtypes::Test. Results of the same test, e.g. of both
+    /// Cusum modes, keep the order they were run in.
+    ///
+    /// # Return
+    ///
+    /// results - The sorted results
+    pub fn sorted_results(&self) -> Vec<&customtypes::TestResult> {
+        let mut results: Vec<&customtypes::TestResult> = self.results.iter().collect();
+        results.sort_by_key(|result| result.test);
+
+        results
+    }
+}
+
+/// One line of a baseline file: test, p-value, statistic and parameters
+type BaselineEntry = (customtypes::Test, f64, f64, BTreeMap<String, usize>);
+
+/// Read and parse a baseline file.
+///
+/// # Arguments
+///
+/// file_path - The path of the baseline file
+///
+/// # Return
+///
+/// Ok(baseline) - The entries of the baseline file in their order
+/// Err(err) - Some error occured
+fn read_baseline(file_path: &str) -> Result<Vec<BaselineEntry>> {
+    log::trace!("baseline::read_baseline()");
+
+    let contents = std::fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read baseline file '{}'", file_path))?;
+
+    let mut baseline = Vec::<BaselineEntry>::new();
+    for (index, line) in contents.lines().enumerate() {
+        // files written before the schema version was introduced have the same format as version 1
+        if let Some(version) = line.strip_prefix(constants::SCHEMA_VERSION_PREFIX) {
+            let version = version
+                .trim()
+                .parse::<u32>()
+                .with_context(|| format!("Invalid schema version in baseline '{}'", file_path))?;
+            if version > constants::REPORT_SCHEMA_VERSION {
+                return Err(customtypes::NistError::InvalidInput { reason: format!("Baseline '{}' has schema version {}, but only versions up to {} are supported",
+                    file_path,
+                    version,
+                    constants::REPORT_SCHEMA_VERSION) });
+            }
+            continue;
+        }
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let entry = parse_line(line)
+            .with_context(|| format!("Invalid line {} in baseline '{}'", index + 1, file_path))?;
+        baseline.push(entry);
+    }
+
+    Ok(baseline)
+}
+
+/// Parse one line of a baseline file.
+///
+/// # Arguments
+///
+/// line - The line to be parsed
+///
+/// # Return
+///
+/// Ok(entry) - The test, p-value, statistic and parameters of the line
+/// Err(err) - Some error occured
+fn parse_line(line: &str) -> Result<BaselineEntry> {
+    let fields: Vec<&str> = line.split(constants::BASELINE_SEPARATOR).collect();
+    if fields.len() != 4 {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!("Expected 4 fields, got {}", fields.len()),
+        });
+    }
+
+    let test = customtypes::Test::from_name(fields[0])
+        .with_context(|| format!("Unknown test '{}'", fields[0]))?;
+    let p_value = fields[1]
+        .parse::<f64>()
+        .with_context(|| format!("Invalid p-value '{}'", fields[1]))?;
+    let statistic = fields[2]
+        .parse::<f64>()
+        .with_context(|| format!("Invalid statistic '{}'", fields[2]))?;
+
+    let mut parameters = BTreeMap::new();
+    for parameter in fields[3]
+        .split(',')
+        .filter(|parameter| !parameter.is_empty())
+    {
+        let (name, value) = parameter
+            .split_once('=')
+            .with_context(|| format!("Invalid parameter '{}'", parameter))?;
+        let value = value
+            .parse::<usize>()
+            .with_context(|| format!("Invalid value of parameter '{}'", name))?;
+        parameters.insert(name.to_owned(), value);
+    }
+
+    Ok((test, p_value, statistic, parameters))
+}
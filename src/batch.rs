@@ -0,0 +1,188 @@
+//! This module collects the reports of a batch of sequences and merges batches produced on
+//! different machines. The proportion of passing sequences and the uniformity of the p-values are
+//! recomputed over the union, as they cannot be combined from the analyses of the subsets.
+
+use crate::analysis;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use std::collections::BTreeMap;
+
+impl customtypes::SuiteReport {
+    /// Merge the report of other tests run on the same sequence, e.g. on another machine.
+    ///
+    /// # Arguments
+    ///
+    /// other - The report to be merged into this one
+    ///
+    /// # Return
+    ///
+    /// Ok() - The results and skipped tests of other were added
+    /// Err(err) - Both reports contain results of the same test, one of them skipped a test the
+    /// other one contains results of or they were preprocessed differently
+    pub fn merge(&mut self, other: customtypes::SuiteReport) -> Result<()> {
+        log::trace!("SuiteReport::merge()");
+
+        if self.pipeline != other.pipeline {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: "Reports of differently preprocessed sequences cannot be merged".to_owned(),
+            });
+        }
+
+        if let Some(result) = other
+            .results
+            .iter()
+            .find(|result| self.results.iter().any(|own| own.test == result.test))
+        {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!("{}: Both reports contain results of the test", result.test),
+            });
+        }
+
+        // a test cannot have been run and skipped on the same sequence
+        if let Some(skipped) = other
+            .skipped
+            .iter()
+            .find(|skipped| self.results.iter().any(|own| own.test == skipped.test))
+            .or_else(|| {
+                self.skipped.iter().find(|skipped| {
+                    other
+                        .results
+                        .iter()
+                        .any(|result| result.test == skipped.test)
+                })
+            })
+        {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "{}: One report contains results of the test, the other one skipped it",
+                    skipped.test
+                ),
+            });
+        }
+
+        self.results.extend(other.results);
+        self.skipped.extend(other.skipped);
+        if self.length_advice.is_empty() {
+            self.length_advice = other.length_advice;
+        }
+        self.cancelled |= other.cancelled;
+
+        Ok(())
+    }
+}
+
+impl customtypes::BatchReport {
+    /// Create a batch from the reports of its sequences and compute the analyses.
+    ///
+    /// # Arguments
+    ///
+    /// config - The configuration all sequences were tested with
+    /// reports - The report of each sequence by its index in the whole batch
+    ///
+    /// # Return
+    ///
+    /// Ok(batch) - The batch with the analyses over all sequences
+    /// Err(err) - Some error occured
+    pub fn new(
+        config: &customtypes::SuiteConfig,
+        reports: BTreeMap<usize, customtypes::SuiteReport>,
+    ) -> Result<Self> {
+        log::trace!("BatchReport::new()");
+
+        let mut batch = customtypes::BatchReport {
+            config: config.clone(),
+            reports,
+            analyses: Vec::new(),
+        };
+        batch.analyze()?;
+
+        Ok(batch)
+    }
+
+    /// Merge a batch of other sequences tested with the same configuration, e.g. on another
+    /// machine, and recompute the analyses over the union.
+    ///
+    /// # Arguments
+    ///
+    /// other - The batch to be merged into this one
+    ///
+    /// # Return
+    ///
+    /// Ok() - The reports of other were added
+    /// Err(err) - The configurations differ, the batches overlap or their union cannot be analyzed.
+    /// This batch is left unchanged then
+    pub fn merge(&mut self, other: customtypes::BatchReport) -> Result<()> {
+        log::trace!("BatchReport::merge()");
+
+        if self.config != other.config {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: "Batches were tested with different configurations".to_owned(),
+            });
+        }
+        if let Some(sequence) = other
+            .reports
+            .keys()
+            .find(|sequence| self.reports.contains_key(sequence))
+        {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!("Both batches contain a report of sequence {}", sequence),
+            });
+        }
+
+        // the analyses are only replaced on success, so a failing merge only has to take back the
+        // reports of other, e.g. if they contain p-values outside of [0, 1]
+        let merged_sequences: Vec<usize> = other.reports.keys().copied().collect();
+        self.reports.extend(other.reports);
+        if let Err(err) = self.analyze() {
+            self.reports
+                .retain(|sequence, _| merged_sequences.binary_search(sequence).is_err());
+            return Err(err).with_context(|| "Failed to analyze the merged batch");
+        }
+        log::info!(
+            "Merged {} reports into batch of {} reports",
+            merged_sequences.len(),
+            self.reports.len()
+        );
+
+        Ok(())
+    }
+
+    /// Recompute the proportion and uniformity of each p-value over all sequences. The p-values
+    /// of a test are matched by their order within each report, e.g. both modes of the Cusum test.
+    ///
+    /// # Return
+    ///
+    /// Ok() - The analyses were recomputed
+    /// Err(err) - Some error occured
+    fn analyze(&mut self) -> Result<()> {
+        log::trace!("BatchReport::analyze()");
+
+        let mut p_values = BTreeMap::<(customtypes::Test, usize), Vec<f64>>::new();
+        for report in self.reports.values() {
+            let mut counts = BTreeMap::<customtypes::Test, usize>::new();
+            for result in &report.results {
+                let index = counts.entry(result.test).or_insert(0);
+                p_values
+                    .entry((result.test, *index))
+                    .or_default()
+                    .push(result.p_value);
+                *index += 1;
+            }
+        }
+
+        let mut analyses = Vec::<customtypes::BatchAnalysis>::new();
+        for ((test, index), p_values) in p_values {
+            analyses.push(customtypes::BatchAnalysis {
+                test,
+                index,
+                number_of_sequences: p_values.len(),
+                proportion: analysis::passing_proportion(&p_values)?,
+                uniformity_p_value: analysis::uniformity_p_value(&p_values)
+                    .with_context(|| format!("{}: Failed to check uniformity", test))?,
+            });
+        }
+        self.analyses = analyses;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+//! Command line tool running the tests of NIST SP 800-22 on a file, similar to the "assess" tool
+//! of the NIST reference implementation. It prints one row per test with the p-value and the
+//! verdict, followed by the overall verdict, and exits with code 1 unless the overall verdict is
+//! PASS.
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use rust_nist_suite::bit_sequence::BitSequence;
+use rust_nist_suite::bit_source;
+use rust_nist_suite::constants;
+use rust_nist_suite::customtypes;
+use rust_nist_suite::logger;
+use rust_nist_suite::reader::ReaderSource;
+use rust_nist_suite::suite;
+
+/// Encoding of the input file
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// ASCII '0' and '1' characters, whitespace is skipped
+    Ascii,
+    /// Hexadecimal digits, whitespace is skipped
+    Hex,
+    /// Base64-encoded bytes, whitespace is skipped
+    Base64,
+    /// Raw bytes
+    Binary,
+}
+
+/// Order of the bits within the bytes of hex, base64 and binary files
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum BitOrder {
+    /// The most significant bit of each byte first
+    Msb,
+    /// The least significant bit of each byte first
+    Lsb,
+}
+
+/// Run the statistical tests of NIST SP 800-22 on a file
+#[derive(Debug, Parser)]
+#[command(name = "nist-suite", version)]
+struct Args {
+    /// The file containing the bits to be tested
+    file: String,
+
+    /// The encoding of the file
+    #[arg(short, long, value_enum, default_value_t = Format::Ascii)]
+    format: Format,
+
+    /// The order of the bits within each byte of hex, base64 and binary files
+    #[arg(long, value_enum, default_value_t = BitOrder::Msb)]
+    bit_order: BitOrder,
+
+    /// The tests to run by their names in the NIST reference implementation, e.g.
+    /// "Frequency,BlockFrequency,Runs". All tests of the suite are run by default
+    #[arg(short, long, value_delimiter = ',')]
+    tests: Vec<String>,
+
+    /// Block size M of the Frequency Test within a Block
+    #[arg(long)]
+    block_size: Option<usize>,
+
+    /// Template length m of the Non-overlapping Template Matching Test
+    #[arg(long)]
+    template_len: Option<usize>,
+
+    /// Number of blocks N of the Non-overlapping Template Matching Test
+    #[arg(long)]
+    template_blocks: Option<usize>,
+
+    /// Template length m of the Overlapping Template Matching Test
+    #[arg(long)]
+    overlapping_template_len: Option<usize>,
+
+    /// Block length m of the Serial Test
+    #[arg(long)]
+    serial_block_length: Option<usize>,
+
+    /// Block length m of the Approximate Entropy Test
+    #[arg(long)]
+    entropy_block_length: Option<usize>,
+
+    /// The significance level the p-values are judged at
+    #[arg(long, default_value_t = constants::P_VALUE_THRESHOLD)]
+    alpha: f64,
+
+    /// Refuse parameters and lengths outside the NIST recommendations
+    #[arg(long)]
+    strict: bool,
+
+    /// Run the other tests even if the Frequency Monobit Test fails
+    #[arg(long)]
+    keep_going: bool,
+
+    /// The log level, e.g. "warn", "info" or "debug"
+    #[arg(long, default_value = "warn")]
+    log_level: String,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    logger::init_logger(&args.log_level)?;
+    log::trace!("nist-suite::main()");
+
+    let bit_order = match args.bit_order {
+        BitOrder::Msb => customtypes::BitOrder::MsbFirst,
+        BitOrder::Lsb => customtypes::BitOrder::LsbFirst,
+    };
+    let bits = read_bits(&args.file, args.format, bit_order)?;
+    let tests = args
+        .tests
+        .iter()
+        .map(|name| parse_test(name))
+        .collect::<Result<Vec<_>>>()?;
+
+    let config = customtypes::SuiteConfig {
+        compliance_mode: if args.strict {
+            customtypes::ComplianceMode::Strict
+        } else {
+            customtypes::ComplianceMode::Permissive
+        },
+        stop_on_monobit_failure: !args.keep_going,
+        alpha: args.alpha,
+        params: customtypes::TestParams {
+            block_size: args.block_size,
+            template_len: args.template_len,
+            number_of_template_blocks: args.template_blocks,
+            overlapping_template_len: args.overlapping_template_len,
+            block_length_serial: args.serial_block_length,
+            block_length_approximate_entropy: args.entropy_block_length,
+        },
+        ..Default::default()
+    };
+
+    let report = if tests.is_empty() {
+        suite::run_all(&bits, &config)?
+    } else {
+        suite::run_selected(&bits, &tests, &config)?
+    };
+
+    println!("{}: {} bits", args.file, bits.len());
+    print!("{}", report);
+    println!(
+        "Verdict: {}",
+        report.verdict_with(config.verdict_policy.as_ref())
+    );
+
+    if config.verdict_policy.decide(&report.results) != customtypes::Verdict::Pass {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Read the bits of a file in the given encoding.
+///
+/// # Arguments
+///
+/// file_path - The path to the file
+/// format - The encoding of the file
+/// bit_order - The order of the bits within each byte, ignored for ASCII files
+///
+/// # Return
+///
+/// Ok(bits) - The bits of the file
+/// Err(err) - Some error occured
+fn read_bits(
+    file_path: &str,
+    format: Format,
+    bit_order: customtypes::BitOrder,
+) -> Result<BitSequence> {
+    log::trace!("nist-suite::read_bits()");
+
+    let bits = match format {
+        Format::Ascii => {
+            let mut source = ReaderSource::open(file_path, customtypes::InputFormat::Ascii)?;
+            let mut bits = Vec::<bool>::new();
+            bit_source::for_each_chunk(&mut source, |chunk| {
+                bits.extend_from_slice(chunk);
+                Ok(())
+            })
+            .with_context(|| format!("Failed to read bits from '{}'", file_path))?;
+            bits.into_iter().collect()
+        }
+        Format::Hex => {
+            let digits = std::fs::read_to_string(file_path)
+                .with_context(|| format!("Failed to read file '{}'", file_path))?;
+            BitSequence::from_hex(&digits, bit_order)
+                .with_context(|| format!("File '{}' contains invalid hex digits", file_path))?
+        }
+        Format::Base64 => {
+            let encoded = std::fs::read_to_string(file_path)
+                .with_context(|| format!("Failed to read file '{}'", file_path))?;
+            BitSequence::from_base64(&encoded, bit_order)
+                .with_context(|| format!("File '{}' contains invalid base64", file_path))?
+        }
+        Format::Binary => {
+            let bytes = std::fs::read(file_path)
+                .with_context(|| format!("Failed to read file '{}'", file_path))?;
+            BitSequence::from_bytes_with_order(&bytes, bit_order)
+        }
+    };
+
+    Ok(bits)
+}
+
+/// Find a test by its name in the NIST reference implementation, ignoring the case.
+///
+/// # Arguments
+///
+/// name - The name of the test, e.g. "BlockFrequency"
+///
+/// # Return
+///
+/// Ok(test) - The test with the name
+/// Err(err) - There is no test with the name
+fn parse_test(name: &str) -> Result<customtypes::Test> {
+    log::trace!("nist-suite::parse_test()");
+
+    customtypes::Test::ALL
+        .into_iter()
+        .find(|test| {
+            test.sts_name()
+                .is_some_and(|sts_name| sts_name.eq_ignore_ascii_case(name.trim()))
+        })
+        .with_context(|| format!("Unknown test '{}'", name))
+}
@@ -5,11 +5,15 @@
 //! "The focus of the test is the rank of disjoint sub-matrices of the entire sequence. The purpose of this test is
 //! to check for linear dependence among fixed length substrings of the original sequence."
 
+use crate::bit_sequence::BitInput;
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::Result;
+use crate::progress;
+use crate::tables;
+use crate::telemetry;
 use crate::utils;
-use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 const TEST_NAME: customtypes::Test = customtypes::Test::BinaryMatrixRank;
 
@@ -17,7 +21,7 @@ const TEST_NAME: customtypes::Test = customtypes::Test::BinaryMatrixRank;
 ///
 /// # Arguments
 ///
-/// bit_string - The bit string to be tested for randomnesshas been running for over 60 seconds
+/// bit_string - The bit string to be tested for randomness
 /// matrix_rows_m - The numbers of rows all matrices need to have
 /// matrix_columns_q - The number of columns all matrices need to have
 ///
@@ -26,24 +30,178 @@ const TEST_NAME: customtypes::Test = customtypes::Test::BinaryMatrixRank;
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
 pub fn perform_test(
-    bit_string: &str,
+    bit_string: &(impl BitInput + ?Sized),
     matrix_rows_m: usize,
     matrix_columns_q: usize,
 ) -> Result<f64> {
     log::trace!("binary_matrix_rank::perform_test()");
 
+    Ok(run_test(bit_string, matrix_rows_m, matrix_columns_q)?.p_value)
+}
+
+/// Perform the Binary Matrix Rank Test and return its result.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// matrix_rows_m - The numbers of rows all matrices need to have
+/// matrix_columns_q - The number of columns all matrices need to have
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(
+    bit_string: &(impl BitInput + ?Sized),
+    matrix_rows_m: usize,
+    matrix_columns_q: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("binary_matrix_rank::run_test()");
+
+    run_test_with_progress(
+        bit_string,
+        matrix_rows_m,
+        matrix_columns_q,
+        &progress::ignore,
+    )
+}
+
+/// Perform the Binary Matrix Rank Test and report the fraction of matrices done to a callback,
+/// e.g. to display the progress of a long-running test.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// matrix_rows_m - The numbers of rows all matrices need to have
+/// matrix_columns_q - The number of columns all matrices need to have
+/// progress - The callback receiving the progress of the test
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_with_progress(
+    bit_string: &(impl BitInput + ?Sized),
+    matrix_rows_m: usize,
+    matrix_columns_q: usize,
+    progress: &dyn Fn(customtypes::Progress),
+) -> Result<customtypes::TestResult> {
+    log::trace!("binary_matrix_rank::run_test_with_progress()");
+
+    run_test_cancellable(
+        bit_string,
+        matrix_rows_m,
+        matrix_columns_q,
+        progress,
+        &customtypes::CancellationToken::new(),
+    )
+}
+
+/// Perform the Binary Matrix Rank Test like run_test_with_progress() and stop at the next block
+/// boundary once the passed token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// matrix_rows_m - The numbers of rows all matrices need to have
+/// matrix_columns_q - The number of columns all matrices need to have
+/// progress - The callback receiving the progress of the test
+/// cancellation - The token to stop the test with
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    matrix_rows_m: usize,
+    matrix_columns_q: usize,
+    progress: &dyn Fn(customtypes::Progress),
+    cancellation: &customtypes::CancellationToken,
+) -> Result<customtypes::TestResult> {
+    log::trace!("binary_matrix_rank::run_test_cancellable()");
+
+    Ok(run_test_internal(
+        bit_string,
+        matrix_rows_m,
+        matrix_columns_q,
+        progress,
+        cancellation,
+    )?
+    .result)
+}
+
+/// Perform the Binary Matrix Rank Test and return the observed rank distribution alongside the
+/// expected probabilities, showing how far the distribution deviates instead of just the p-value.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// matrix_rows_m - The numbers of rows all matrices need to have
+/// matrix_columns_q - The number of columns all matrices need to have
+///
+/// # Return
+///
+/// Ok(distribution) - The result of the test, the count of each rank and the expected
+/// probabilities of the rank classes
+/// Err(err) - Some error occured
+pub fn run_test_with_distribution(
+    bit_string: &(impl BitInput + ?Sized),
+    matrix_rows_m: usize,
+    matrix_columns_q: usize,
+) -> Result<customtypes::RankDistribution> {
+    log::trace!("binary_matrix_rank::run_test_with_distribution()");
+
+    run_test_internal(
+        bit_string,
+        matrix_rows_m,
+        matrix_columns_q,
+        &progress::ignore,
+        &customtypes::CancellationToken::new(),
+    )
+}
+
+/// Perform the Binary Matrix Rank Test and keep the rank distribution.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// matrix_rows_m - The numbers of rows all matrices need to have
+/// matrix_columns_q - The number of columns all matrices need to have
+/// progress - The callback receiving the progress of the test
+/// cancellation - The token to stop the test with
+///
+/// # Return
+///
+/// Ok(distribution) - The result of the test and the rank distribution
+/// Err(err) - Some error occured or the test got cancelled
+fn run_test_internal(
+    bit_string: &(impl BitInput + ?Sized),
+    matrix_rows_m: usize,
+    matrix_columns_q: usize,
+    progress: &dyn Fn(customtypes::Progress),
+    cancellation: &customtypes::CancellationToken,
+) -> Result<customtypes::RankDistribution> {
+    log::trace!("binary_matrix_rank::run_test_internal()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(
+    let input = utils::evaluate_bit_string(
         TEST_NAME,
         bit_string,
         constants::RECOMMENDED_SIZE_MATRIX_TEST,
     )?;
+    let length = input.length;
+    let mut advisories = input.advisories;
 
     // the test is optimized for M = Q = 32 and a bit size of n = 32 * 32 * 38. If the values are
-    // not matching, log a warning because approximations may not fit anymore
+    // not matching, log a warning because the rank probabilities may not fit anymore
     if matrix_rows_m != constants::MATRIX_ROWS_M {
         log::warn!(
             "{}: Recommended size for rows: {}, passed rows: {}",
@@ -51,6 +209,14 @@ pub fn perform_test(
             constants::MATRIX_ROWS_M,
             matrix_rows_m
         );
+        advisories.push(customtypes::Advisory::invalid(
+            "M",
+            format!(
+                "The rank probabilities are computed for {} rows, passed: {}",
+                constants::MATRIX_ROWS_M,
+                matrix_rows_m
+            ),
+        ));
     }
     if matrix_columns_q != constants::MATRIX_COLUMNS_Q {
         log::warn!(
@@ -59,17 +225,38 @@ pub fn perform_test(
             constants::MATRIX_COLUMNS_Q,
             matrix_columns_q
         );
+        advisories.push(customtypes::Advisory::invalid(
+            "Q",
+            format!(
+                "The rank probabilities are computed for {} columns, passed: {}",
+                constants::MATRIX_COLUMNS_Q,
+                matrix_columns_q
+            ),
+        ));
     }
 
+    // the bit string has to contain at least one full matrix of size M * Q
+    let matrix_size = evaluate_matrix_size(length, matrix_rows_m, matrix_columns_q)?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
     // create matrices from the given bit string by iterating over chunks of size M * Q
+    let n_matrices = length / matrix_size;
     let matrices = construct_matrices(bit_string, matrix_rows_m, matrix_columns_q);
+    log::debug!("{}: Number of matrices: {}", TEST_NAME, n_matrices);
 
     // determine the rank of each matrix and count their occurences
-    let n_matrices = length / (matrix_rows_m * matrix_columns_q);
-    let mut rank_counts: HashMap<usize, usize> = HashMap::new();
+    let mut rank_counts: BTreeMap<usize, usize> = BTreeMap::new();
+
+    let reporter = progress::ProgressReporter::new(TEST_NAME, n_matrices, progress);
 
-    for mut matrix in matrices.into_iter() {
-        *rank_counts.entry(compute_rank(&mut matrix)).or_insert(0) += 1;
+    for (done, mut matrix) in matrices.enumerate() {
+        cancellation.check(TEST_NAME)?;
+        *rank_counts
+            .entry(compute_rank(&mut matrix, matrix_columns_q))
+            .or_insert(0) += 1;
+        reporter.report(done + 1);
     }
 
     log::debug!("{}: Counts of ranks: {:?}", TEST_NAME, rank_counts);
@@ -100,14 +287,10 @@ pub fn perform_test(
     );
 
     // Compute chi_square statistics by calculating the three fractions (one fraction per rank)
-    let first_fraction = compute_fraction(full_rank_m, n_matrices, constants::APPROXIMATIONS[0]);
-    let second_fraction = compute_fraction(
-        full_rank_m_minus_one,
-        n_matrices,
-        constants::APPROXIMATIONS[1],
-    );
-    let third_fraction =
-        compute_fraction(remaining_ranks, n_matrices, constants::APPROXIMATIONS[2]);
+    let probabilities = tables::rank_probabilities();
+    let first_fraction = compute_fraction(full_rank_m, n_matrices, probabilities[0]);
+    let second_fraction = compute_fraction(full_rank_m_minus_one, n_matrices, probabilities[1]);
+    let third_fraction = compute_fraction(remaining_ranks, n_matrices, probabilities[2]);
 
     let chi_square = first_fraction + second_fraction + third_fraction;
     log::debug!("{}: Chi_square value: {}", TEST_NAME, chi_square);
@@ -116,15 +299,85 @@ pub fn perform_test(
     let p_value = (-chi_square * 0.5).exp();
     log::info!("{}: p-value = {}", TEST_NAME, p_value);
 
+    phase.exit();
+
     // capture the current time after the test got executed and calculate elapsed time
     let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
 
-    Ok(p_value)
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories,
+        statistic: chi_square,
+        parameters: utils::parameter_map(&[
+            ("n", length),
+            ("M", matrix_rows_m),
+            ("Q", matrix_columns_q),
+            ("N", n_matrices),
+        ]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: vec![full_rank_m, full_rank_m_minus_one, remaining_ranks],
+    };
+    span.record_result(&result);
+
+    Ok(customtypes::RankDistribution {
+        result,
+        rank_counts,
+        observed: [full_rank_m, full_rank_m_minus_one, remaining_ranks],
+        probabilities,
+    })
 }
 
-/// Construct matrices from the given bit string.
+/// Evaluate the passed matrix dimensions and return the number of bits per matrix.
+///
+/// # Arguments
+///
+/// length - Bit string length
+/// rows - The number of rows the matrices will have
+/// columns - The number of columns the matrices will have
+///
+/// # Return
+///
+/// Ok(matrix_size) - The number of bits per matrix M * Q
+/// Err(err) - Some error occured
+fn evaluate_matrix_size(length: usize, rows: usize, columns: usize) -> Result<usize> {
+    log::trace!("binary_matrix_rank::evaluate_matrix_size()");
+
+    if rows < 2 || columns < 2 {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "M",
+            format!(
+                "Matrices need at least 2 rows and 2 columns, passed {} x {}",
+                rows, columns
+            ),
+        ));
+    }
+
+    let matrix_size = match rows.checked_mul(columns) {
+        Some(matrix_size) if matrix_size <= length => matrix_size,
+        _ => {
+            return Err(customtypes::NistError::insufficient_length(
+                TEST_NAME,
+                rows.saturating_mul(columns),
+                length,
+            ))
+        }
+    };
+
+    Ok(matrix_size)
+}
+
+/// Construct matrices from the given bit string one at a time, so only the matrix whose rank is
+/// computed is held in memory. Each row is packed into 64-bit words, the first column being the
+/// most significant bit of the first word.
 ///
 /// # Arguments
 ///
@@ -134,97 +387,144 @@ pub fn perform_test(
 ///
 /// # Return
 ///
-/// matrices - All of the constructed matrices
-fn construct_matrices(
-    bit_string: &str,
+/// matrices - An iterator over the constructed matrices, each one as its rows of words
+fn construct_matrices<'a>(
+    bit_string: &'a (impl BitInput + ?Sized),
     rows: usize,
     columns: usize,
-) -> Vec<nalgebra::DMatrix<rug::Integer>> {
+) -> impl Iterator<Item = Vec<Vec<u64>>> + 'a {
     log::trace!("binary_matrix_rank::construct_matrices()");
 
-    let total_elements = rows * columns;
-    let mut matrices = Vec::new();
-
-    // Divide the bitstring into substrings of length rows * columns
-    let binding = bit_string.chars().collect::<Vec<_>>();
-    let substrings = binding.chunks(total_elements);
-    log::debug!(
-        "{}: Discarded {} bits from input",
-        TEST_NAME,
-        bit_string.len() % total_elements
-    );
-
-    // Iterate over the substrings to construct matrices
-    for chunk in substrings {
-        if chunk.len() == total_elements {
-            let mut matrix = nalgebra::DMatrix::from_element(rows, columns, rug::Integer::new());
-            for (index, &bit) in chunk.iter().enumerate() {
-                let row = index / columns;
-                let col = index % columns;
-                matrix[(row, col)] = rug::Integer::from(bit.to_digit(2).unwrap());
+    let words_per_row = columns.div_ceil(constants::MATRIX_WORD_BITS);
+    let number_of_matrices = bit_string.bit_len() / (rows * columns);
+    let mut bits = bit_string.bits();
+
+    // Divide the bitstring into substrings of length rows * columns and fill the rows of a matrix
+    // with each of them. Remaining bits are discarded
+    (0..number_of_matrices).map(move |_| {
+        let mut matrix = vec![vec![0_u64; words_per_row]; rows];
+        for row in matrix.iter_mut() {
+            for (col, bit) in bits.by_ref().take(columns).enumerate() {
+                if bit {
+                    row[col / constants::MATRIX_WORD_BITS] |= column_mask(col);
+                }
             }
-            log::trace!("{}: Constructed matrix: {}", TEST_NAME, &matrix);
-            matrices.push(matrix);
         }
-    }
-
-    log::debug!(
-        "{}: Number of constructed matrices: {}",
-        TEST_NAME,
-        &matrices.len()
-    );
-    matrices
+        crate::logger::verbose_trace!(
+            "{}: Constructed matrix: {:?}",
+            TEST_NAME,
+            matrix
+                .iter()
+                .map(|row| format_row(row, columns))
+                .collect::<Vec<String>>()
+        );
+        matrix
+    })
 }
 
-/// Compute the rank of the given matrix.
+/// Compute the rank of the given matrix over GF(2). The forward elimination brings the matrix
+/// into row echelon form, the backward elimination clears the columns above each pivot. Adding a
+/// row over GF(2) is the XOR of its words.
 ///
 /// # Arguments
 ///
-/// matrix - The matrix the rank has to be determined from
+/// matrix - The rows of the matrix the rank has to be determined from
+/// columns - The number of columns of the matrix
 ///
 /// # Return
 ///
 /// rank - The rank of the given matrix
-fn compute_rank(matrix: &mut nalgebra::DMatrix<rug::Integer>) -> usize {
-    log::trace!("binary_matrix_rank::compute_rank()");
-
-    let (mut row, mut col) = (0, 0);
-    let mut rank = 0;
-
-    while row < matrix.nrows() && col < matrix.ncols() {
-        // Find the pivot for this column
-        let mut max_row = row;
-        for i in row + 1..matrix.nrows() {
-            let abs_value_i = matrix[(i, col)].clone().abs();
-            let abs_value_max_row = matrix[(max_row, col)].clone().abs();
-            if abs_value_i > abs_value_max_row {
-                max_row = i;
-            }
+fn compute_rank(matrix: &mut [Vec<u64>], columns: usize) -> usize {
+    crate::logger::verbose_trace!("binary_matrix_rank::compute_rank()");
+
+    let mut pivots = Vec::<(usize, usize)>::with_capacity(matrix.len());
+
+    // forward elimination: eliminate the ones below the pivot of each column
+    let mut row = 0;
+    for col in 0..columns {
+        if row == matrix.len() {
+            break;
         }
 
-        if matrix[(max_row, col)].is_zero() {
-            // All elements in this column are zero
-            col += 1;
-        } else {
-            // Swap the rows to move the pivot to the current row
-            matrix.swap_rows(row, max_row);
-
-            // Perform row operations to eliminate elements below the pivot
-            for i in row + 1..matrix.nrows() {
-                let factor = matrix[(i, col)].clone() / matrix[(row, col)].clone();
-                for j in col..matrix.ncols() {
-                    let temp = factor.clone() * matrix[(row, j)].clone();
-                    matrix[(i, j)] -= temp;
-                }
-            }
+        let word = col / constants::MATRIX_WORD_BITS;
+        let mask = column_mask(col);
+        let pivot = match (row..matrix.len()).find(|&i| matrix[i][word] & mask != 0) {
+            Some(pivot) => pivot,
+            // all elements in this column are zero
+            None => continue,
+        };
+        matrix.swap(row, pivot);
 
-            row += 1;
-            col += 1;
-            rank += 1;
+        let (upper, lower) = matrix.split_at_mut(row + 1);
+        for other in lower.iter_mut().filter(|other| other[word] & mask != 0) {
+            xor_row(other, &upper[row], word);
         }
+        pivots.push((row, col));
+        row += 1;
+    }
+
+    // backward elimination: eliminate the ones above the pivots, starting with the last one
+    for &(row, col) in pivots.iter().rev() {
+        let word = col / constants::MATRIX_WORD_BITS;
+        let mask = column_mask(col);
+
+        let (upper, lower) = matrix.split_at_mut(row);
+        for other in upper.iter_mut().filter(|other| other[word] & mask != 0) {
+            xor_row(other, &lower[0], word);
+        }
+    }
+
+    // each non-zero row of the reduced matrix holds one pivot
+    pivots.len()
+}
+
+/// Add a row to another one over GF(2), starting at the word of the pivot as all previous words of
+/// the row are zero.
+///
+/// # Arguments
+///
+/// target - The row the other row is added to
+/// source - The row to be added
+/// first_word - The first word which may be non-zero
+fn xor_row(target: &mut [u64], source: &[u64], first_word: usize) {
+    for (target_word, source_word) in target[first_word..].iter_mut().zip(&source[first_word..]) {
+        *target_word ^= source_word;
     }
+}
+
+/// Get the mask of a column within its word, the first column being the most significant bit.
+///
+/// # Arguments
+///
+/// col - The column
+///
+/// # Return
+///
+/// mask - The mask selecting the column in the word col / 64
+fn column_mask(col: usize) -> u64 {
+    1 << (constants::MATRIX_WORD_BITS - 1 - col % constants::MATRIX_WORD_BITS)
+}
 
-    rank
+/// Render a row of a matrix as its bits for tracing.
+///
+/// # Arguments
+///
+/// row - The words of the row
+/// columns - The number of columns of the matrix
+///
+/// # Return
+///
+/// bits - The row as ASCII '0' and '1' characters
+fn format_row(row: &[u64], columns: usize) -> String {
+    (0..columns)
+        .map(|col| {
+            if row[col / constants::MATRIX_WORD_BITS] & column_mask(col) != 0 {
+                '1'
+            } else {
+                '0'
+            }
+        })
+        .collect()
 }
 
 /// Compute the fractions needed to determine the chi_square value.
@@ -233,15 +533,15 @@ fn compute_rank(matrix: &mut nalgebra::DMatrix<rug::Integer>) -> usize {
 ///
 /// rank - The rank of a matrix
 /// n_matrices - The overall number of matrices
-/// approximation - The pre-calculated approximation for the fraction
+/// probability - The probability of the rank class
 ///
 /// # Return
 ///
 /// fraction - The calculated fraction
-fn compute_fraction(rank: usize, n_matrices: usize, approximation: f64) -> f64 {
-    log::trace!("binary_matrix::rank::compute_fraction()");
+fn compute_fraction(rank: usize, n_matrices: usize, probability: f64) -> f64 {
+    crate::logger::verbose_trace!("binary_matrix_rank::compute_fraction()");
 
-    let constant = approximation * (n_matrices as f64);
+    let constant = probability * (n_matrices as f64);
     let fraction = ((rank as f64) - constant).powf(2.0) / constant;
 
     log::debug!("{}: Computed fraction: {}", TEST_NAME, fraction);
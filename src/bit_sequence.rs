@@ -0,0 +1,724 @@
+//! This module contains a bit-packed representation of the sequence to be tested. The bits are
+//! stored in 64-bit words (most significant bit first) behind a shared pointer, so transformed
+//! views like the reversed or complemented sequence can be created without copying the data.
+//!
+//! The tests take their input through the BitInput trait, which is implemented for the packed
+//! sequence and for strings of ASCII '0' and '1' characters. A packed sequence needs one eighth
+//! of the memory of a bit string, which matters for sequences of gigabits.
+
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::utils;
+use base64::Engine;
+use rand::RngCore;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
+
+pub(crate) const WORD_BITS: usize = u64::BITS as usize;
+
+/// Bit-packed sequence of bits. Cloning a sequence or creating a view of it is cheap because
+/// the underlying words are shared.
+#[derive(Debug, Clone)]
+pub struct BitSequence {
+    /// The packed bits, most significant bit of each word first
+    words: Arc<[u64]>,
+    /// Number of valid bits in words
+    len: usize,
+    /// Whether the bits are read from the end to the start
+    reversed: bool,
+    /// Whether each bit is inverted when read
+    complemented: bool,
+}
+
+impl BitSequence {
+    /// Create a bit sequence from a string of ASCII '0' and '1' characters.
+    ///
+    /// # Arguments
+    ///
+    /// bit_string - The bit string to be converted
+    ///
+    /// # Return
+    ///
+    /// Ok(sequence) - The packed bit sequence
+    /// Err(err) - Some error occured
+    pub fn from_bit_string(bit_string: &str) -> Result<Self> {
+        log::trace!("BitSequence::from_bit_string()");
+
+        if let Some((position, symbol)) = bit_string
+            .char_indices()
+            .find(|&(_, c)| c != '0' && c != '1')
+        {
+            return Err(customtypes::NistError::InvalidBit { position, symbol });
+        }
+
+        Ok(bit_string.chars().map(|c| c == '1').collect())
+    }
+
+    /// Create a bit sequence from bytes, e.g. the raw output of a random number generator. The
+    /// most significant bit of each byte comes first.
+    ///
+    /// # Arguments
+    ///
+    /// bytes - The bytes to be converted
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        log::trace!("BitSequence::from_bytes()");
+
+        let words: Vec<u64> = bytes
+            .chunks(WORD_BITS / 8)
+            .map(|chunk| {
+                let mut word = [0; WORD_BITS / 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_be_bytes(word)
+            })
+            .collect();
+
+        BitSequence {
+            words: words.into(),
+            len: bytes.len() * 8,
+            reversed: false,
+            complemented: false,
+        }
+    }
+
+    /// Create a bit sequence from bytes, the most significant bit of each byte first. This is the
+    /// same as from_bytes().
+    ///
+    /// # Arguments
+    ///
+    /// bytes - The bytes to be converted
+    pub fn from_bytes_msb(bytes: &[u8]) -> Self {
+        log::trace!("BitSequence::from_bytes_msb()");
+
+        BitSequence::from_bytes(bytes)
+    }
+
+    /// Create a bit sequence from bytes, the least significant bit of each byte first.
+    ///
+    /// # Arguments
+    ///
+    /// bytes - The bytes to be converted
+    pub fn from_bytes_lsb(bytes: &[u8]) -> Self {
+        log::trace!("BitSequence::from_bytes_lsb()");
+
+        let reversed: Vec<u8> = bytes.iter().map(|byte| byte.reverse_bits()).collect();
+        BitSequence::from_bytes(&reversed)
+    }
+
+    /// Create a bit sequence from bytes in passed bit order.
+    ///
+    /// # Arguments
+    ///
+    /// bytes - The bytes to be converted
+    /// bit_order - The order in which the bits of each byte enter the sequence
+    pub fn from_bytes_with_order(bytes: &[u8], bit_order: customtypes::BitOrder) -> Self {
+        log::trace!("BitSequence::from_bytes_with_order()");
+
+        match bit_order {
+            customtypes::BitOrder::MsbFirst => BitSequence::from_bytes_msb(bytes),
+            customtypes::BitOrder::LsbFirst => BitSequence::from_bytes_lsb(bytes),
+        }
+    }
+
+    /// Create a bit sequence from hexadecimal digits, e.g. the dump of a hardware random number
+    /// generator. Whitespace, e.g. line breaks, is skipped and both cases are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// hex_string - The hexadecimal digits, two per byte
+    /// bit_order - The order in which the bits of each byte enter the sequence
+    ///
+    /// # Return
+    ///
+    /// Ok(sequence) - The packed bit sequence
+    /// Err(err) - The digits are invalid or of odd number
+    pub fn from_hex(hex_string: &str, bit_order: customtypes::BitOrder) -> Result<Self> {
+        log::trace!("BitSequence::from_hex()");
+
+        let digits: String = hex_string.split_whitespace().collect();
+        let bytes = hex::decode(digits).map_err(|e| customtypes::NistError::InvalidInput {
+            reason: format!("Failed to parse hexadecimal string: {}", e),
+        })?;
+
+        Ok(BitSequence::from_bytes_with_order(&bytes, bit_order))
+    }
+
+    /// Create a bit sequence from base64-encoded bytes with the standard alphabet and padding,
+    /// e.g. the export of a capture tool. Whitespace, e.g. line breaks, is skipped.
+    ///
+    /// # Arguments
+    ///
+    /// base64_string - The base64-encoded bytes
+    /// bit_order - The order in which the bits of each byte enter the sequence
+    ///
+    /// # Return
+    ///
+    /// Ok(sequence) - The packed bit sequence
+    /// Err(err) - The string is no valid base64
+    pub fn from_base64(base64_string: &str, bit_order: customtypes::BitOrder) -> Result<Self> {
+        log::trace!("BitSequence::from_base64()");
+
+        let encoded: String = base64_string.split_whitespace().collect();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| customtypes::NistError::InvalidInput {
+                reason: format!("Failed to parse base64 string: {}", e),
+            })?;
+
+        Ok(BitSequence::from_bytes_with_order(&bytes, bit_order))
+    }
+
+    /// Create a bit sequence from the output of a random number generator. The bytes are pulled
+    /// by fill_bytes(), so the sequence equals the one read from a file the generator's output was
+    /// written to. Surplus bits of the last byte are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// rng - The random number generator to pull the bits from
+    /// number_of_bits - The number of bits to pull
+    ///
+    /// # Return
+    ///
+    /// Ok(sequence) - The packed bit sequence
+    /// Err(err) - Some error occured
+    pub fn from_rng<R: RngCore + ?Sized>(rng: &mut R, number_of_bits: usize) -> Result<Self> {
+        log::trace!("BitSequence::from_rng()");
+
+        let mut bytes = vec![0; number_of_bits.div_ceil(8)];
+        rng.try_fill_bytes(&mut bytes)
+            .context("Failed to pull bytes from the random number generator")?;
+
+        let mut words = BitSequence::from_bytes(&bytes).words.to_vec();
+        // the padding bits of the last word have to be zero
+        let padding = words.len() * WORD_BITS - number_of_bits;
+        if let Some(word) = words.last_mut().filter(|_| padding > 0) {
+            *word &= u64::MAX << padding;
+        }
+
+        Ok(BitSequence {
+            words: words.into(),
+            len: number_of_bits,
+            reversed: false,
+            complemented: false,
+        })
+    }
+
+    /// Get the number of bits of the sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the sequence does not contain any bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the bit at given index of the sequence as seen through this view.
+    ///
+    /// # Arguments
+    ///
+    /// index - The index of the bit
+    ///
+    /// # Return
+    ///
+    /// Some(bit) - The bit at given index
+    /// None - Index is out of range
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.len {
+            return None;
+        }
+
+        let position = if self.reversed {
+            self.len - 1 - index
+        } else {
+            index
+        };
+        let word = self.words[position / WORD_BITS];
+        let bit = (word >> (WORD_BITS - 1 - position % WORD_BITS)) & 1 == 1;
+
+        Some(bit ^ self.complemented)
+    }
+
+    /// Iterate over all bits of the sequence as seen through this view.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).filter_map(move |index| self.get(index))
+    }
+
+    /// Count the ones of the sequence.
+    pub fn count_ones(&self) -> usize {
+        let ones = self
+            .words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum::<usize>();
+
+        // the padding bits of the last word are always zero, so they do not need to be masked
+        if self.complemented {
+            self.len - ones
+        } else {
+            ones
+        }
+    }
+
+    /// Read bits of the sequence as seen through this view.
+    ///
+    /// # Arguments
+    ///
+    /// start - The index of the first bit
+    /// count - The number of bits to read, at most one word and within the sequence
+    ///
+    /// # Return
+    ///
+    /// word - The bits in the most significant positions, followed by zeros
+    fn view_word(&self, start: usize, count: usize) -> u64 {
+        let word = if self.reversed {
+            // the bits of the view are the ones in front of position len - start in reverse order
+            self.read_word(self.len - start - count, count)
+                .reverse_bits()
+                << (WORD_BITS - count)
+        } else {
+            self.read_word(start, count)
+        };
+
+        if self.complemented {
+            word ^ leading_mask(count)
+        } else {
+            word
+        }
+    }
+
+    /// Read bits of the underlying words regardless of the view.
+    ///
+    /// # Arguments
+    ///
+    /// start - The position of the first bit
+    /// count - The number of bits to read, at most one word
+    ///
+    /// # Return
+    ///
+    /// word - The bits in the most significant positions, followed by zeros
+    fn read_word(&self, start: usize, count: usize) -> u64 {
+        let index = start / WORD_BITS;
+        let offset = start % WORD_BITS;
+
+        let mut word = self.words[index] << offset;
+        if offset > 0 && index + 1 < self.words.len() {
+            word |= self.words[index + 1] >> (WORD_BITS - offset);
+        }
+
+        word & leading_mask(count)
+    }
+
+    /// Get a view of the sequence with the bits in reverse order. The data is not copied.
+    pub fn reversed(&self) -> Self {
+        log::trace!("BitSequence::reversed()");
+
+        BitSequence {
+            reversed: !self.reversed,
+            ..self.clone()
+        }
+    }
+
+    /// Get a view of the sequence with each bit inverted. The data is not copied.
+    pub fn complemented(&self) -> Self {
+        log::trace!("BitSequence::complemented()");
+
+        BitSequence {
+            complemented: !self.complemented,
+            ..self.clone()
+        }
+    }
+
+    /// Convert the sequence into a string of ASCII '0' and '1' characters, e.g. to pass it to
+    /// the tests taking a bit string.
+    pub fn to_bit_string(&self) -> String {
+        log::trace!("BitSequence::to_bit_string()");
+
+        self.iter().map(|bit| if bit { '1' } else { '0' }).collect()
+    }
+}
+
+impl From<&[u8]> for BitSequence {
+    fn from(bytes: &[u8]) -> Self {
+        BitSequence::from_bytes(bytes)
+    }
+}
+
+impl From<Vec<u8>> for BitSequence {
+    fn from(bytes: Vec<u8>) -> Self {
+        BitSequence::from_bytes(&bytes)
+    }
+}
+
+impl FromIterator<bool> for BitSequence {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut words = Vec::<u64>::new();
+        let mut len = 0;
+
+        for bit in iter {
+            if len % WORD_BITS == 0 {
+                words.push(0);
+            }
+            if bit {
+                if let Some(word) = words.last_mut() {
+                    *word |= 1 << (WORD_BITS - 1 - len % WORD_BITS);
+                }
+            }
+            len += 1;
+        }
+
+        BitSequence {
+            words: words.into(),
+            len,
+            reversed: false,
+            complemented: false,
+        }
+    }
+}
+
+impl PartialEq for BitSequence {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for BitSequence {}
+
+/// Input of the tests: a string of ASCII '0' and '1' characters or a bit-packed sequence. Tests
+/// validate the input once and then read its bits, either all at once or block by block. Inputs are
+/// shared between threads by the "parallel" feature, so they have to be Sync.
+pub trait BitInput: Sync {
+    /// Get the number of bits, including invalid symbols of a bit string.
+    fn bit_len(&self) -> usize;
+
+    /// Scan the input once and collect its length, number of ones and the first invalid symbol.
+    fn validate(&self) -> customtypes::ValidatedInput;
+
+    /// Make sure the input can be split at any bit position, i.e. a bit string only consists of
+    /// ASCII characters.
+    fn ensure_ascii(&self) -> Result<(), customtypes::NistError>;
+
+    /// Iterate over the bits. Invalid symbols of a bit string are read as zeros, so the input has
+    /// to be validated first.
+    fn bits(&self) -> impl DoubleEndedIterator<Item = bool> + '_;
+
+    /// Iterate over the bits packed into 64-bit words, the first bit in the most significant
+    /// position, e.g. to count ones or runs a word at a time. The padding bits of the last word
+    /// are zero. Invalid symbols of a bit string are read as either bit, so the input has to be
+    /// validated first.
+    fn words(&self) -> impl Iterator<Item = u64> + '_;
+
+    /// Read up to one word of bits starting at a position, e.g. to read a block a word at a time
+    /// without copying it. Invalid symbols of a bit string are read as either bit, so the input
+    /// has to be validated first.
+    ///
+    /// # Arguments
+    ///
+    /// start - The position of the first bit
+    /// count - The number of bits to read, at most one word and within the input
+    ///
+    /// # Return
+    ///
+    /// word - The bits in the most significant positions, followed by zeros
+    fn word(&self, start: usize, count: usize) -> u64;
+
+    /// Get a range of the bits as ASCII '0' and '1' characters. A bit string is borrowed, while
+    /// only the range of a packed sequence is expanded.
+    ///
+    /// # Arguments
+    ///
+    /// range - The positions of the bits, which have to be within the input
+    fn ascii(&self, range: Range<usize>) -> Cow<'_, str>;
+}
+
+impl BitInput for str {
+    fn bit_len(&self) -> usize {
+        self.len()
+    }
+
+    fn validate(&self) -> customtypes::ValidatedInput {
+        utils::validate_bit_string(self)
+    }
+
+    fn ensure_ascii(&self) -> Result<(), customtypes::NistError> {
+        utils::ensure_ascii(self)
+    }
+
+    fn bits(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        self.bytes().map(|bit| bit == b'1')
+    }
+
+    fn words(&self) -> impl Iterator<Item = u64> + '_ {
+        self.as_bytes().chunks(WORD_BITS).map(pack_ascii)
+    }
+
+    fn word(&self, start: usize, count: usize) -> u64 {
+        pack_ascii(&self.as_bytes()[start..start + count])
+    }
+
+    fn ascii(&self, range: Range<usize>) -> Cow<'_, str> {
+        Cow::Borrowed(&self[range])
+    }
+}
+
+impl BitInput for String {
+    fn bit_len(&self) -> usize {
+        self.as_str().bit_len()
+    }
+
+    fn validate(&self) -> customtypes::ValidatedInput {
+        self.as_str().validate()
+    }
+
+    fn ensure_ascii(&self) -> Result<(), customtypes::NistError> {
+        self.as_str().ensure_ascii()
+    }
+
+    fn bits(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        self.as_str().bits()
+    }
+
+    fn words(&self) -> impl Iterator<Item = u64> + '_ {
+        self.as_str().words()
+    }
+
+    fn word(&self, start: usize, count: usize) -> u64 {
+        self.as_str().word(start, count)
+    }
+
+    fn ascii(&self, range: Range<usize>) -> Cow<'_, str> {
+        self.as_str().ascii(range)
+    }
+}
+
+impl BitInput for BitSequence {
+    fn bit_len(&self) -> usize {
+        self.len
+    }
+
+    fn validate(&self) -> customtypes::ValidatedInput {
+        customtypes::ValidatedInput {
+            length: self.len,
+            ones: self.count_ones(),
+            ..Default::default()
+        }
+    }
+
+    fn ensure_ascii(&self) -> Result<(), customtypes::NistError> {
+        Ok(())
+    }
+
+    fn bits(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        (0..self.len).map(move |index| self.get(index).unwrap_or_default())
+    }
+
+    fn words(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len)
+            .step_by(WORD_BITS)
+            .map(move |start| self.view_word(start, (self.len - start).min(WORD_BITS)))
+    }
+
+    fn word(&self, start: usize, count: usize) -> u64 {
+        self.view_word(start, count)
+    }
+
+    fn ascii(&self, range: Range<usize>) -> Cow<'_, str> {
+        let mut ascii = String::with_capacity(range.len());
+
+        // expand a word at a time instead of looking up each bit
+        for start in range.clone().step_by(WORD_BITS) {
+            let count = (range.end - start).min(WORD_BITS);
+            let word = self.view_word(start, count);
+            ascii.extend((0..count).map(|shift| {
+                if (word << shift) >> (WORD_BITS - 1) == 1 {
+                    '1'
+                } else {
+                    '0'
+                }
+            }));
+        }
+
+        Cow::Owned(ascii)
+    }
+}
+
+/// Block of consecutive bits of an input, e.g. one of the blocks of a block-based test. The block
+/// borrows the input and reads it a word at a time, so neither a bit string nor a packed sequence
+/// is copied.
+pub struct Block<'a, T: BitInput + ?Sized> {
+    /// The input the block belongs to
+    input: &'a T,
+    /// The position of the first bit within the input
+    start: usize,
+    /// Number of bits of the block
+    len: usize,
+}
+
+impl<T: BitInput + ?Sized> Clone for Block<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: BitInput + ?Sized> Copy for Block<'_, T> {}
+
+impl<'a, T: BitInput + ?Sized> Block<'a, T> {
+    /// Create a block of an input.
+    ///
+    /// # Arguments
+    ///
+    /// input - The input the block belongs to
+    /// range - The positions of the bits, which have to be within the input
+    pub(crate) fn new(input: &'a T, range: Range<usize>) -> Self {
+        Block {
+            input,
+            start: range.start,
+            len: range.len(),
+        }
+    }
+
+    /// Get the number of bits of the block.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the block has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the bits of the block packed into 64-bit words like BitInput::words(). The
+    /// padding bits of the last word are zero.
+    pub fn words(&self) -> impl Iterator<Item = u64> + 'a {
+        let Block { input, start, len } = *self;
+        (0..len)
+            .step_by(WORD_BITS)
+            .map(move |offset| input.word(start + offset, (len - offset).min(WORD_BITS)))
+    }
+
+    /// Count the ones of the block a word at a time.
+    pub fn count_ones(&self) -> usize {
+        self.words().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Count the occurences of a pattern in the block by sliding a window over its words.
+    ///
+    /// # Arguments
+    ///
+    /// pattern - The bits of the pattern in the least significant positions
+    /// pattern_len - The length of the pattern, between 1 and 64
+    /// overlapping - Whether the window slides one bit after a match instead of past the match
+    ///
+    /// # Return
+    ///
+    /// occurences - The number of positions the pattern ends at
+    pub fn count_pattern(&self, pattern: u64, pattern_len: usize, overlapping: bool) -> usize {
+        let mask = u64::MAX >> (WORD_BITS - pattern_len);
+        let mut window = 0;
+        let mut filled = 0;
+        let mut occurences = 0;
+        let mut remaining = self.len;
+
+        for word in self.words() {
+            let valid = remaining.min(WORD_BITS);
+            remaining -= valid;
+
+            for shift in 0..valid {
+                window = ((window << 1) | ((word >> (WORD_BITS - 1 - shift)) & 1)) & mask;
+                filled += 1;
+                if filled >= pattern_len && window == pattern {
+                    occurences += 1;
+
+                    // a non-overlapping search starts the next window after the match
+                    if !overlapping {
+                        filled = 0;
+                    }
+                }
+            }
+        }
+
+        occurences
+    }
+
+    /// Get the bits of the block as ASCII '0' and '1' characters like BitInput::ascii(). A block of
+    /// a bit string is borrowed, while a block of a packed sequence is expanded.
+    pub fn ascii(&self) -> Cow<'a, str> {
+        self.input.ascii(self.start..self.start + self.len)
+    }
+}
+
+/// Render the block as ASCII '0' and '1' characters, e.g. for the trace log
+impl<T: BitInput + ?Sized> std::fmt::Display for Block<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ascii())
+    }
+}
+
+/// Get a word with the given number of leading bits set, e.g. to mask the valid bits of the last
+/// word of a sequence.
+///
+/// # Arguments
+///
+/// bits - The number of leading bits to set, at most one word
+pub(crate) fn leading_mask(bits: usize) -> u64 {
+    if bits == 0 {
+        0
+    } else {
+        u64::MAX << (WORD_BITS - bits)
+    }
+}
+
+/// Pack up to 64 ASCII '0' and '1' characters into a word, eight characters at a time. Only the
+/// lowest bit of each character is read, so other symbols are read as either bit.
+///
+/// # Arguments
+///
+/// chunk - The characters to be packed
+///
+/// # Return
+///
+/// word - The bits in the most significant positions, followed by zeros
+fn pack_ascii(chunk: &[u8]) -> u64 {
+    let mut word = 0;
+
+    // a complete word of characters is packed by an unrolled loop
+    if let Ok(characters) = <&[u8; WORD_BITS]>::try_from(chunk) {
+        for index in 0..8 {
+            let mut lane = [0; 8];
+            lane.copy_from_slice(&characters[8 * index..8 * index + 8]);
+            word |= pack_lane(lane) << (WORD_BITS - 8 - 8 * index);
+        }
+        return word;
+    }
+
+    let mut lanes = chunk.chunks_exact(8);
+    for (index, characters) in lanes.by_ref().enumerate() {
+        let mut lane = [0; 8];
+        lane.copy_from_slice(characters);
+        word |= pack_lane(lane) << (WORD_BITS - 8 - 8 * index);
+    }
+
+    // the characters of an incomplete lane are followed by '0' characters
+    let remainder = lanes.remainder();
+    if !remainder.is_empty() {
+        let mut lane = [b'0'; 8];
+        lane[..remainder.len()].copy_from_slice(remainder);
+        word |= pack_lane(lane) << (WORD_BITS - 8 - (chunk.len() - remainder.len()));
+    }
+
+    word
+}
+
+/// Pack eight ASCII '0' and '1' characters into a byte.
+///
+/// # Arguments
+///
+/// lane - The characters to be packed
+///
+/// # Return
+///
+/// bits - The bits in the lowest byte, the first character in its most significant bit
+fn pack_lane(lane: [u8; 8]) -> u64 {
+    // the multiplication gathers the lowest bit of each byte into the top byte
+    ((u64::from_be_bytes(lane) & 0x0101_0101_0101_0101).wrapping_mul(0x0102_0408_1020_4080)) >> 56
+}
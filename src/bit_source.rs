@@ -0,0 +1,139 @@
+//! This module contains the sources the streaming variants of the tests read their bits from in
+//! chunks. Unlike a bit string, a source does not have to hold the whole sequence in memory, so
+//! tests can run memory-bounded or follow the output of a generator while it is monitored.
+
+use crate::bit_sequence::BitSequence;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::Result;
+
+/// Source of bits read in chunks of arbitrary size
+pub trait BitSource {
+    /// Read the next bits into a buffer.
+    ///
+    /// # Arguments
+    ///
+    /// buffer - The buffer receiving one bit per element
+    ///
+    /// # Return
+    ///
+    /// Ok(count) - The number of bits read, 0 once the source is exhausted
+    /// Err(err) - Some error occured
+    fn read_bits(&mut self, buffer: &mut [bool]) -> Result<usize>;
+}
+
+/// Source reading the bits of a string of ASCII '0' and '1' characters
+#[derive(Debug, Clone)]
+pub struct BitStringSource<'a> {
+    bit_string: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitStringSource<'a> {
+    /// Create a source starting at the first bit of a bit string. Invalid characters are reported
+    /// once they are read.
+    ///
+    /// # Arguments
+    ///
+    /// bit_string - The bit string to be read
+    pub fn new(bit_string: &'a str) -> Self {
+        BitStringSource {
+            bit_string: bit_string.as_bytes(),
+            position: 0,
+        }
+    }
+}
+
+impl BitSource for BitStringSource<'_> {
+    fn read_bits(&mut self, buffer: &mut [bool]) -> Result<usize> {
+        let count = buffer.len().min(self.bit_string.len() - self.position);
+
+        for (offset, bit) in buffer[..count].iter_mut().enumerate() {
+            let position = self.position + offset;
+            *bit = match self.bit_string[position] {
+                b'0' => false,
+                b'1' => true,
+                symbol => {
+                    return Err(customtypes::NistError::InvalidBit {
+                        position,
+                        symbol: symbol as char,
+                    })
+                }
+            };
+        }
+        self.position += count;
+
+        Ok(count)
+    }
+}
+
+/// Source reading the bits of a bit sequence as seen through its view
+#[derive(Debug, Clone)]
+pub struct BitSequenceSource {
+    sequence: BitSequence,
+    position: usize,
+}
+
+impl BitSequenceSource {
+    /// Create a source starting at the first bit of a sequence. The data is not copied.
+    ///
+    /// # Arguments
+    ///
+    /// sequence - The sequence to be read
+    pub fn new(sequence: &BitSequence) -> Self {
+        BitSequenceSource {
+            sequence: sequence.clone(),
+            position: 0,
+        }
+    }
+}
+
+impl BitSource for BitSequenceSource {
+    fn read_bits(&mut self, buffer: &mut [bool]) -> Result<usize> {
+        let count = buffer.len().min(self.sequence.len() - self.position);
+
+        for (offset, bit) in buffer[..count].iter_mut().enumerate() {
+            *bit = self
+                .sequence
+                .get(self.position + offset)
+                .unwrap_or_default();
+        }
+        self.position += count;
+
+        Ok(count)
+    }
+}
+
+/// Read a source until it is exhausted and pass the bits to a callback in chunks of
+/// constants::STREAM_CHUNK_SIZE bits, so single-pass tests can accumulate their counts
+/// incrementally.
+///
+/// # Arguments
+///
+/// source - The source of the bits
+/// f - The callback receiving each chunk of bits in order
+///
+/// # Return
+///
+/// Ok(length) - The number of bits read from the source
+/// Err(err) - Some error occured while reading or in the callback
+pub fn for_each_chunk(
+    source: &mut dyn BitSource,
+    mut f: impl FnMut(&[bool]) -> Result<()>,
+) -> Result<usize> {
+    log::trace!("bit_source::for_each_chunk()");
+
+    let mut buffer = vec![false; constants::STREAM_CHUNK_SIZE];
+    let mut length = 0;
+    loop {
+        let read = source.read_bits(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        f(&buffer[..read])?;
+        length += read;
+    }
+    log::debug!("Read {} bits from source", length);
+
+    Ok(length)
+}
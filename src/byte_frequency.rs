@@ -0,0 +1,165 @@
+//! This module performs the Byte Frequency Test. It is not part of NIST SP 800-22, but a fast
+//! first-look diagnostic for byte-oriented generators.
+//!
+//! The bit string is divided into non-overlapping 8-bit symbols (most significant bit first) and
+//! the observed frequency of each of the 256 possible byte values is compared to the uniform
+//! distribution with a chi_square goodness-of-fit test. Trailing bits which do not fill a whole
+//! byte are discarded.
+
+use crate::bit_sequence::BitInput;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::utils;
+
+const TEST_NAME: customtypes::Test = customtypes::Test::ByteFrequency;
+
+/// Perform the Byte Frequency Test by determining the p-value.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value which indicates whether randomness is given or not
+/// Err(err) - Some error occured
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized)) -> Result<f64> {
+    log::trace!("byte_frequency::perform_test()");
+
+    Ok(run_test(bit_string)?.p_value)
+}
+
+/// Perform the Byte Frequency Test and return its result.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(bit_string: &(impl BitInput + ?Sized)) -> Result<customtypes::TestResult> {
+    log::trace!("byte_frequency::run_test()");
+
+    let start_time = std::time::Instant::now();
+    let result = perform_test_detailed(bit_string)?;
+    let elapsed_time = std::time::Instant::now().duration_since(start_time);
+
+    Ok(customtypes::TestResult {
+        test: TEST_NAME,
+        p_value: result.p_value,
+        advisories: result.advisories,
+        statistic: result.chi_square,
+        parameters: utils::parameter_map(&[("n", bit_string.bit_len())]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: result.counts,
+    })
+}
+
+/// Perform the Byte Frequency Test and return the p-value, the counts of each byte value and the
+/// most over- and under-represented byte values.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The p-value, chi_square value and byte statistics
+/// Err(err) - Some error occured
+pub fn perform_test_detailed(
+    bit_string: &(impl BitInput + ?Sized),
+) -> Result<customtypes::ByteFrequencyResult> {
+    log::trace!("byte_frequency::perform_test_detailed()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(
+        TEST_NAME,
+        bit_string,
+        constants::RECOMMENDED_SIZE_BYTE_FREQUENCY,
+    )
+    .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+
+    let number_of_bytes = length / constants::BYTE_BITS;
+    if number_of_bytes == 0 {
+        return Err(customtypes::NistError::insufficient_length(
+            TEST_NAME,
+            constants::BYTE_BITS,
+            length,
+        ));
+    }
+
+    // count the occurences of each byte value
+    let mut counts = vec![0_usize; constants::NUMBER_OF_BYTE_VALUES];
+    for byte in utils::blocks(bit_string, constants::BYTE_BITS)? {
+        let value =
+            byte.words().next().unwrap_or_default() >> (u64::BITS as usize - constants::BYTE_BITS);
+        counts[value as usize] += 1;
+    }
+    log::debug!("{}: Byte counts: {:?}", TEST_NAME, counts);
+
+    // compute chi_square statistics against the uniform distribution
+    let expected = (number_of_bytes as f64) / (constants::NUMBER_OF_BYTE_VALUES as f64);
+    let chi_square = counts
+        .iter()
+        .map(|&count| ((count as f64) - expected).powf(2.0) / expected)
+        .sum::<f64>();
+    log::debug!(
+        "{}: Expected count = {}, chi_square = {}",
+        TEST_NAME,
+        expected,
+        chi_square
+    );
+
+    // determine the byte values deviating most from the expectation. The counts are compared
+    // directly because all byte values share the same expectation
+    let mut most_over_represented = 0_u8;
+    let mut most_under_represented = 0_u8;
+    for (value, byte) in (0..=u8::MAX).enumerate() {
+        if counts[value] > counts[usize::from(most_over_represented)] {
+            most_over_represented = byte;
+        }
+        if counts[value] < counts[usize::from(most_under_represented)] {
+            most_under_represented = byte;
+        }
+    }
+    log::debug!(
+        "{}: Most over-represented byte: {:#04x}, most under-represented byte: {:#04x}",
+        TEST_NAME,
+        most_over_represented,
+        most_under_represented
+    );
+
+    // finally compute p-value with the incomplete gamma function: igamc(255/2, chi_square/2)
+    let p_value = if chi_square == 0.0 {
+        1.0
+    } else {
+        statrs::function::gamma::gamma_ur(
+            ((constants::NUMBER_OF_BYTE_VALUES - 1) as f64) * 0.5,
+            chi_square * 0.5,
+        )
+    };
+    log::info!("{}: p-value = {}", TEST_NAME, p_value);
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
+    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+
+    Ok(customtypes::ByteFrequencyResult {
+        p_value,
+        chi_square,
+        counts,
+        expected,
+        most_over_represented,
+        most_under_represented,
+        advisories: input.advisories,
+    })
+}
@@ -0,0 +1,104 @@
+//! This module caches the reports of suite runs by a content hash of the input and the
+//! configuration. Running the suite again on identical input, e.g. on the same fixture in CI,
+//! returns the previously computed report instead of re-running all tests.
+
+use crate::customtypes;
+use crate::customtypes::Result;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Handle of a cache for suite reports. Clones share the same cached reports, so one cache can
+/// be passed to several runs, e.g. on different threads
+#[derive(Debug, Default, Clone)]
+pub struct ReportCache {
+    reports: Arc<Mutex<HashMap<u64, customtypes::SuiteReport>>>,
+}
+
+impl ReportCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of cached reports.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check whether the cache does not contain any report.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all cached reports.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Return the cached report of identical input and configuration or run the suite and cache
+    /// its report. Failed runs are not cached.
+    ///
+    /// # Arguments
+    ///
+    /// bit_string - The bit string the suite is run on
+    /// config - The configuration of the suite run
+    /// run - The suite run computing the report on a cache miss
+    ///
+    /// # Return
+    ///
+    /// Ok(report) - The cached or freshly computed report
+    /// Err(err) - Some error occured while running the suite
+    pub fn get_or_run<F>(
+        &self,
+        bit_string: &str,
+        config: &customtypes::SuiteConfig,
+        run: F,
+    ) -> Result<customtypes::SuiteReport>
+    where
+        F: FnOnce() -> Result<customtypes::SuiteReport>,
+    {
+        log::trace!("ReportCache::get_or_run()");
+
+        let hash = input_hash(bit_string, config);
+        if let Some(report) = self.lock().get(&hash) {
+            log::info!("Returning cached report for input hash {:016x}", hash);
+            return Ok(report.clone());
+        }
+
+        // do not hold the lock while running the suite, as this may take hours
+        let report = run()?;
+        self.lock().insert(hash, report.clone());
+        log::debug!("Cached report for input hash {:016x}", hash);
+
+        Ok(report)
+    }
+
+    /// Lock the cached reports. A panic while holding the lock cannot leave the map in an
+    /// inconsistent state, so a poisoned lock is recovered.
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, customtypes::SuiteReport>> {
+        self.reports
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Compute the content hash of a bit string together with the configuration it is tested with.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be hashed
+/// config - The configuration of the suite run
+///
+/// # Return
+///
+/// hash - The content hash
+pub fn input_hash(bit_string: &str, config: &customtypes::SuiteConfig) -> u64 {
+    log::trace!("cache::input_hash()");
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bit_string.hash(&mut hasher);
+    config.hash(&mut hasher);
+
+    hasher.finish()
+}
@@ -0,0 +1,404 @@
+//! This module loads the exports of logic analyzers, e.g. the capture of the output pin of a
+//! hardware random number generator. Captures list the transitions of each channel with their
+//! timestamps, either as CSV (a time column followed by one column per channel) or as a Value
+//! Change Dump (VCD). The selected channel is sampled at a fixed rate into a bit sequence.
+
+use crate::bit_sequence::BitSequence;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+
+/// Read a capture file and sample a channel of it. Files with the extension ".vcd" are read as
+/// VCD, all others as CSV.
+///
+/// # Arguments
+///
+/// file_path - The path to the capture file
+/// channel - The name of the channel, i.e. its CSV column header or its VCD reference
+/// sample_rate - The number of samples per second
+///
+/// # Return
+///
+/// Ok(sequence) - The sampled bits of the channel
+/// Err(err) - Some error occured
+pub fn read_capture(file_path: &str, channel: &str, sample_rate: f64) -> Result<BitSequence> {
+    log::trace!("capture::read_capture()");
+
+    let content = std::fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read capture file '{}'", file_path))?;
+
+    let is_vcd = std::path::Path::new(file_path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(constants::CAPTURE_VCD_EXTENSION));
+    let sequence = if is_vcd {
+        parse_vcd(&content, channel, sample_rate)
+    } else {
+        parse_csv(&content, channel, sample_rate)
+    }
+    .with_context(|| format!("Failed to load capture file '{}'", file_path))?;
+
+    log::info!(
+        "Successfully sampled {} bits of channel '{}' from '{}'",
+        sequence.len(),
+        channel,
+        file_path
+    );
+
+    Ok(sequence)
+}
+
+/// Sample a channel of a CSV capture. The first line is the header, the first column holds the
+/// timestamps in seconds and each further column the value (0 or 1) of a channel from this
+/// timestamp on, as exported by Saleae Logic.
+///
+/// # Arguments
+///
+/// content - The content of the capture
+/// channel - The column header of the channel, e.g. "Channel 0"
+/// sample_rate - The number of samples per second
+///
+/// # Return
+///
+/// Ok(sequence) - The sampled bits of the channel
+/// Err(err) - Some error occured
+pub fn parse_csv(content: &str, channel: &str, sample_rate: f64) -> Result<BitSequence> {
+    log::trace!("capture::parse_csv()");
+
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let header: Vec<&str> = match lines.next() {
+        Some((_, header)) => header.split(',').map(str::trim).collect(),
+        None => {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: "Capture is empty".to_owned(),
+            })
+        }
+    };
+    let column = match header.iter().skip(1).position(|&name| name == channel) {
+        Some(position) => position + 1,
+        None => {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "Channel '{}' not found, available channels: {:?}",
+                    channel,
+                    &header[1..]
+                ),
+            })
+        }
+    };
+
+    let mut transitions = Vec::<(f64, bool)>::new();
+    for (index, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() != header.len() {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "Line {}: Expected {} fields, found {}",
+                    index + 1,
+                    header.len(),
+                    fields.len()
+                ),
+            });
+        }
+
+        let time = fields[0]
+            .parse::<f64>()
+            .with_context(|| format!("Line {}: Invalid timestamp '{}'", index + 1, fields[0]))?;
+        let value = match fields[column] {
+            "0" => false,
+            "1" => true,
+            value => {
+                return Err(customtypes::NistError::InvalidInput {
+                    reason: format!("Line {}: Invalid value '{}'", index + 1, value),
+                })
+            }
+        };
+        add_transition(&mut transitions, time, value)
+            .with_context(|| format!("Line {}: Invalid transition", index + 1))?;
+    }
+
+    // the capture ends with its last row
+    let end_time = match transitions.last() {
+        Some(&(time, _)) => time,
+        None => {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: "Capture does not contain any rows".to_owned(),
+            })
+        }
+    };
+
+    sample_transitions(&transitions, end_time, sample_rate)
+}
+
+/// Sample a channel of a Value Change Dump (VCD, IEEE 1364). The channel has to be a 1-bit
+/// variable and is selected by its reference name or by its identifier code.
+///
+/// # Arguments
+///
+/// content - The content of the capture
+/// channel - The reference or identifier code of the channel, e.g. "D0"
+/// sample_rate - The number of samples per second
+///
+/// # Return
+///
+/// Ok(sequence) - The sampled bits of the channel
+/// Err(err) - Some error occured
+pub fn parse_vcd(content: &str, channel: &str, sample_rate: f64) -> Result<BitSequence> {
+    log::trace!("capture::parse_vcd()");
+
+    let mut tokens = content.split_whitespace();
+    let mut timescale = 1.0;
+    let mut identifier: Option<String> = None;
+    let mut references = Vec::<String>::new();
+
+    // header: the timescale and the variables up to $enddefinitions
+    while let Some(token) = tokens.next() {
+        match token {
+            "$timescale" => {
+                let value: String = tokens.by_ref().take_while(|&t| t != "$end").collect();
+                timescale = parse_timescale(&value)?;
+            }
+            "$var" => {
+                let fields: Vec<&str> = tokens.by_ref().take_while(|&t| t != "$end").collect();
+                if fields.len() < 4 {
+                    return Err(customtypes::NistError::InvalidInput {
+                        reason: format!("Invalid variable definition {:?}", fields),
+                    });
+                }
+                let (size, code, reference) = (fields[1], fields[2], fields[3]);
+                if (reference == channel || code == channel) && identifier.is_none() {
+                    if size != "1" {
+                        return Err(customtypes::NistError::InvalidInput {
+                            reason: format!("Channel '{}' has {} bits instead of 1", channel, size),
+                        });
+                    }
+                    identifier = Some(code.to_owned());
+                }
+                references.push(reference.to_owned());
+            }
+            "$enddefinitions" => {
+                tokens.by_ref().take_while(|&t| t != "$end").for_each(drop);
+                break;
+            }
+            _ if token.starts_with('$') => {
+                tokens.by_ref().take_while(|&t| t != "$end").for_each(drop);
+            }
+            _ => {
+                return Err(customtypes::NistError::InvalidInput {
+                    reason: format!("Unexpected token '{}' in header", token),
+                })
+            }
+        }
+    }
+    let identifier = match identifier {
+        Some(identifier) => identifier,
+        None => {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "Channel '{}' not found, available channels: {:?}",
+                    channel, references
+                ),
+            })
+        }
+    };
+
+    // value changes: timestamps, scalar changes like "1!" and vector changes like "b1 !"
+    let mut transitions = Vec::<(f64, bool)>::new();
+    let mut time: Option<f64> = None;
+    while let Some(token) = tokens.next() {
+        let (value, code) = if let Some(ticks) = token.strip_prefix('#') {
+            let ticks = ticks
+                .parse::<u64>()
+                .with_context(|| format!("Invalid timestamp '{}'", token))?;
+            let seconds = (ticks as f64) * timescale;
+            if time.is_some_and(|time| seconds < time) {
+                return Err(customtypes::NistError::InvalidInput {
+                    reason: format!("Timestamp '{}' is not in ascending order", token),
+                });
+            }
+            time = Some(seconds);
+            continue;
+        } else if token == "$comment" {
+            tokens.by_ref().take_while(|&t| t != "$end").for_each(drop);
+            continue;
+        } else if token.starts_with('$') {
+            // the keywords around the value changes, e.g. $dumpvars ... $end
+            continue;
+        } else if let Some(value) = token.strip_prefix('b').or_else(|| token.strip_prefix('B')) {
+            let code = tokens
+                .next()
+                .with_context(|| format!("Missing identifier of vector value '{}'", token))?;
+            (value, code)
+        } else if token.starts_with(['r', 'R']) {
+            // real values cannot belong to a 1-bit channel, so only skip their identifier
+            tokens.next();
+            continue;
+        } else {
+            token.split_at(1)
+        };
+
+        if code != identifier {
+            continue;
+        }
+        let value = match value {
+            "0" => false,
+            "1" => true,
+            value => {
+                return Err(customtypes::NistError::InvalidInput {
+                    reason: format!("Channel '{}' has undefined value '{}'", channel, value),
+                })
+            }
+        };
+        let time = time.with_context(|| "Value change before the first timestamp")?;
+        add_transition(&mut transitions, time, value)?;
+    }
+
+    // the capture ends with its last timestamp, which may not change any value
+    let end_time = match time {
+        Some(time) => time,
+        None => {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: "Capture does not contain any timestamps".to_owned(),
+            })
+        }
+    };
+
+    sample_transitions(&transitions, end_time, sample_rate)
+}
+
+/// Parse the timescale of a VCD, e.g. "1ns" or "10 us".
+///
+/// # Arguments
+///
+/// timescale - The content of the $timescale section
+///
+/// # Return
+///
+/// Ok(seconds) - The duration of one tick in seconds
+/// Err(err) - Some error occured
+fn parse_timescale(timescale: &str) -> Result<f64> {
+    log::trace!("capture::parse_timescale()");
+
+    let split = timescale
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(timescale.len());
+    let (number, unit) = timescale.split_at(split);
+
+    let number = number
+        .parse::<u32>()
+        .with_context(|| format!("Invalid timescale '{}'", timescale))?;
+    match constants::CAPTURE_VCD_TIME_UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+    {
+        Some((_, seconds)) => Ok(f64::from(number) * seconds),
+        None => Err(customtypes::NistError::InvalidInput {
+            reason: format!("Invalid unit of timescale '{}'", timescale),
+        }),
+    }
+}
+
+/// Add a transition of the channel, checking that the timestamps are in ascending order.
+///
+/// # Arguments
+///
+/// transitions - The transitions read so far
+/// time - The timestamp of the transition in seconds
+/// value - The value of the channel from the timestamp on
+///
+/// # Return
+///
+/// Ok() - The transition was added
+/// Err(err) - The timestamp is invalid or precedes the last transition
+fn add_transition(transitions: &mut Vec<(f64, bool)>, time: f64, value: bool) -> Result<()> {
+    log::trace!("capture::add_transition()");
+
+    if !time.is_finite() {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!("Timestamp {} is not finite", time),
+        });
+    }
+    if let Some(&(last, _)) = transitions.last() {
+        if time < last {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!(
+                    "Timestamp {} precedes the previous timestamp {}",
+                    time, last
+                ),
+            });
+        }
+    }
+    transitions.push((time, value));
+
+    Ok(())
+}
+
+/// Sample the value of a channel in the middle of each sample period, starting at its first
+/// transition and ending at the end of the capture. Sampling in the middle keeps samples away
+/// from the edges if the sample rate matches the bit rate of the channel.
+///
+/// # Arguments
+///
+/// transitions - The transitions of the channel in ascending order
+/// end_time - The end of the capture in seconds
+/// sample_rate - The number of samples per second
+///
+/// # Return
+///
+/// Ok(sequence) - The sampled bits
+/// Err(err) - Some error occured
+fn sample_transitions(
+    transitions: &[(f64, bool)],
+    end_time: f64,
+    sample_rate: f64,
+) -> Result<BitSequence> {
+    log::trace!("capture::sample_transitions()");
+
+    if !sample_rate.is_finite() || sample_rate <= 0.0 {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!("Sample rate ({}) must be positive", sample_rate),
+        });
+    }
+    let start_time = match transitions.first() {
+        Some(&(time, _)) => time,
+        None => {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: "Channel does not have any values".to_owned(),
+            })
+        }
+    };
+
+    let mut current = 0;
+    let mut bits = Vec::<bool>::new();
+    loop {
+        let time = start_time + ((bits.len() as f64) + 0.5) / sample_rate;
+        if time >= end_time {
+            break;
+        }
+        while current + 1 < transitions.len() && transitions[current + 1].0 <= time {
+            current += 1;
+        }
+        bits.push(transitions[current].1);
+    }
+
+    if bits.is_empty() {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!(
+                "Capture of {} seconds is too short for a sample at {} samples per second",
+                end_time - start_time,
+                sample_rate
+            ),
+        });
+    }
+    log::debug!(
+        "Sampled {} bits over {} seconds at {} samples per second",
+        bits.len(),
+        end_time - start_time,
+        sample_rate
+    );
+
+    Ok(bits.into_iter().collect())
+}
@@ -3,9 +3,20 @@
 /// Treshold for p-value to check if bit string is random or not
 pub const P_VALUE_THRESHOLD: f64 = 0.01;
 
+/// Smallest significance level NIST SP 800-22 considers typical, i.e. alpha in [0.001, 0.01]
+pub const MIN_P_VALUE_THRESHOLD: f64 = 0.001;
+
 /// Usual recommended size for several tests
 pub const RECOMMENDED_SIZE: usize = 100;
 
+/// Number of bits the streaming variants of the tests read from a source at once
+pub const STREAM_CHUNK_SIZE: usize = 65536;
+/// Number of bytes the reader module reads from a byte stream at once
+pub const READ_BUFFER_SIZE: usize = 65536;
+
+/// Recommended minimum block size M for "Frequency Test within a Block"
+pub const RECOMMENDED_BLOCK_SIZE: usize = 20;
+
 /// Recommended size for "Discrete Fourier Transform (Spectral) Test
 pub const RECOMMENDED_SIZE_DFT: usize = 1000;
 
@@ -18,6 +29,8 @@ pub const MIN_SIZE_M: usize = 8;
 pub const MID_SIZE_M: usize = 128;
 pub const MAX_SIZE_M: usize = 10000;
 
+/// Number of blocks N of the minimum length of each configuration. Longer bit strings use all
+/// N = n / M blocks
 pub const MIN_SIZE_N: usize = 16;
 pub const MID_SIZE_N: usize = 49;
 pub const MAX_SIZE_N: usize = 75;
@@ -42,8 +55,39 @@ pub const RECOMMENDED_SIZE_MATRIX_TEST: usize = 38912;
 
 pub const MATRIX_ROWS_M: usize = 32;
 pub const MATRIX_COLUMNS_Q: usize = 32;
+/// The rows of the matrices are packed into words of this number of bits
+pub const MATRIX_WORD_BITS: usize = 64;
+
+/// Constants for the "Maurer's Universal Statistical" Test. The block length L is chosen by the
+/// minimum bit string length of each row, the number of initialization blocks Q is 10 * 2^L
+pub static UNIVERSAL_MIN_LENGTHS: [(usize, usize); 11] = [
+    (RECOMMENDED_SIZE_UNIVERSAL, 6),
+    (904960, 7),
+    (2068480, 8),
+    (4654080, 9),
+    (10342400, 10),
+    (22753280, 11),
+    (49643520, 12),
+    (107560960, 13),
+    (231669760, 14),
+    (496435200, 15),
+    (1059061760, 16),
+];
+pub const UNIVERSAL_INIT_BLOCKS_FACTOR: usize = 10;
+pub const RECOMMENDED_SIZE_UNIVERSAL: usize = 387840;
+/// Expected value and variance of the test statistic for L = 6, ..., 16
+pub static UNIVERSAL_EXPECTED_VALUES: [f64; 11] = [
+    5.2177052, 6.1962507, 7.1836656, 8.1764248, 9.1723243, 10.170032, 11.168765, 12.168070,
+    13.167693, 14.167488, 15.167379,
+];
+pub static UNIVERSAL_VARIANCES: [f64; 11] = [
+    2.954, 3.125, 3.238, 3.311, 3.356, 3.384, 3.401, 3.410, 3.416, 3.419, 3.421,
+];
 
-pub static APPROXIMATIONS: [f64; 3] = [0.2888, 0.5776, 0.1336];
+/// Constants for the "Linear Complexity" Test
+pub const RECOMMENDED_SIZE_LINEAR_COMPLEXITY: usize = 1000000;
+/// Default block size M, NIST recommends 500 <= M <= 5000
+pub const LINEAR_COMPLEXITY_BLOCK_SIZE: usize = 500;
 
 /// Constants for the "Discrete Fourier Transform (Spectral)" Test
 pub const LOG_ARG: f64 = 1.0 / 0.05;
@@ -53,11 +97,161 @@ pub const NORMALIZED_DIFF_CONSTANT: f64 = 0.95 * 0.05 * 0.25;
 /// Constants for the "Non-overlapping Template Matching" Test
 pub const TEMPLATE_LEN: (usize, usize) = (2, 21);
 pub const RECOMMENDED_TEMPLATE_LEN: (usize, usize) = (9, 10);
-pub const TEMPLATE_SUB_PATH: &str = "/templates/template";
-pub const TMP_DIR: &str = "/tmp";
+/// Longest template length whose aperiodic templates are generated at runtime. Each template is
+/// matched against the whole bit string, and there are about 2^m / 4 aperiodic templates, so longer
+/// templates do not finish in practice
+pub const MAX_GENERATED_TEMPLATE_LEN: usize = 24;
+/// Lengths of the aperiodic template tables embedded with the "template-tables" feature
+pub const TEMPLATE_TABLE_LEN: (usize, usize) = (2, 16);
+/// First template of the template set the reference implementation uses for m = 9
+pub const REFERENCE_TEMPLATE: &str = "000000001";
 
 /// Constants for the "Overlapping Template Matching" Test
 pub const RECOMMENDED_SIZE_OVERLAPPING_TEMPLATE: usize = 1000000;
 pub const MAX_N_OVERLAPPING_TEMPLATE: f64 = 5.0;
+/// Blocks are classified by 0, ..., 4 and at least 5 occurences of the all-ones template
+pub const CLASSES_OVERLAPPING_TEMPLATE: usize = 6;
+/// Block size M of NIST SP 800-22, giving N = 968 blocks of a sequence of 10^6 bits
+pub const BLOCK_SIZE_OVERLAPPING_TEMPLATE: usize = 1032;
+/// The chi_square approximation needs at least five expected blocks per class
+pub const MIN_EXPECTED_COUNT_OVERLAPPING_TEMPLATE: f64 = 5.0;
+/// More exact probabilities pi_0, ..., pi_5 for m = 9 and M = 1032 of section 3.8 of NIST
+/// SP 800-22, which the reference implementation does not use
 pub static PI_VALUES_OVERLAPPING_TEMPLATE: [f64; 6] =
     [0.364091, 0.185659, 0.139381, 0.100571, 0.0704323, 0.139865];
+
+/// Constants for the "Serial" Test. The counts of all 2^m patterns are kept, so the block length
+/// m is bounded, and the second p-value needs m >= 2
+pub const MIN_BLOCK_LENGTH_SERIAL: usize = 2;
+pub const MAX_BLOCK_LENGTH_SERIAL: usize = 20;
+/// Shortest length for which a block length m >= 2 satisfies m < floor(log2(n)) - 2
+pub const MIN_LENGTH_SERIAL: usize = 32;
+
+/// Constants for the "Approximate Entropy" Test. The counts of all 2^(m+1) patterns are kept, so
+/// the block length m is bounded
+pub const MAX_BLOCK_LENGTH_APPROXIMATE_ENTROPY: usize = 20;
+/// Longest pattern length whose overlapping occurences are counted, the patterns of the
+/// "Approximate Entropy" Test being one bit longer than its blocks
+pub const MAX_PATTERN_LEN: usize = MAX_BLOCK_LENGTH_APPROXIMATE_ENTROPY + 1;
+/// Shortest length for which a block length m >= 1 satisfies m < floor(log2(n)) - 5
+pub const MIN_LENGTH_APPROXIMATE_ENTROPY: usize = 128;
+
+/// Constants for the "Random Excursions" and "Random Excursions Variant" Tests. The tests are not
+/// applicable if the random walk has less than max(0.005 * sqrt(n), 500) cycles J
+pub const RECOMMENDED_SIZE_EXCURSIONS: usize = 1000000;
+pub const MIN_CYCLES_EXCURSIONS: usize = 500;
+pub const MIN_CYCLES_FACTOR_EXCURSIONS: f64 = 0.005;
+/// The states -4, ..., -1, 1, ..., 4 of the "Random Excursions" Test
+pub const STATES_EXCURSIONS: i64 = 4;
+/// The visits of a state within a cycle are classified as 0, ..., 4 and at least 5
+pub const VISIT_CLASSES_EXCURSIONS: usize = 6;
+/// The states -9, ..., -1, 1, ..., 9 of NIST SP 800-22
+pub const STATES_EXCURSIONS_VARIANT: i64 = 9;
+/// Upper bound of the largest state of the "Random Excursions Variant" Test. A counter is kept for
+/// each of the 2 * x_max + 1 states, so the bound keeps the caller from allocating counters in the
+/// order of the bit string length
+pub const MAX_STATES_EXCURSIONS_VARIANT: i64 = 100;
+
+/// Constants for the "Byte Frequency" Test
+pub const BYTE_BITS: usize = 8;
+pub const NUMBER_OF_BYTE_VALUES: usize = 256;
+/// At least five expected occurences of each byte value: 256 * 5 * 8 bits
+pub const RECOMMENDED_SIZE_BYTE_FREQUENCY: usize = 10240;
+
+/// Constants for the "Cross-Correlation" Test
+pub const RECOMMENDED_SIZE_CROSS_CORRELATION: usize = 10000;
+
+/// Constants for the continuous health tests of NIST SP 800-90B for binary samples with full
+/// entropy (H = 1) and a false positive probability of 2^-20
+pub const HEALTH_REPETITION_CUTOFF_BINARY: usize = 21;
+pub const HEALTH_PROPORTION_WINDOW_BINARY: usize = 1024;
+pub const HEALTH_PROPORTION_CUTOFF_BINARY: usize = 589;
+/// Number of alarms kept by a health monitor
+pub const HEALTH_ALARM_LOG_SIZE: usize = 16;
+
+/// Constants for the second-level analysis of multiple p-values
+pub const UNIFORMITY_P_VALUE_THRESHOLD: f64 = 0.0001;
+pub const UNIFORMITY_BINS: usize = 10;
+pub const RECOMMENDED_NUMBER_OF_SEQUENCES: usize = 55;
+pub const MIN_EXPECTED_BIN_COUNT: f64 = 5.0;
+pub const PROPORTION_INTERVAL_SIGMAS: f64 = 3.0;
+/// Tuning parameter lambda of Storey's estimate of the proportion of true null hypotheses
+pub const STOREY_LAMBDA: f64 = 0.5;
+
+/// Constants for the suite run of all tests. The template tests, the "Serial" Test and the
+/// "Approximate Entropy" Test use the defaults of the reference implementation
+pub const SUITE_TEMPLATE_LEN: usize = 9;
+pub const SUITE_NUMBER_OF_TEMPLATE_BLOCKS: usize = 8;
+pub const SUITE_BLOCK_LENGTH_SERIAL: usize = 16;
+pub const SUITE_BLOCK_LENGTH_APPROXIMATE_ENTROPY: usize = 10;
+
+/// Number of bits the single-pass tests process between two checks of the cancellation token. A
+/// multiple of the 64-bit words they count on
+pub const CANCELLATION_INTERVAL: usize = 1 << 16;
+
+/// Constants for the sweep of the parameter-sensitive tests over several scales. Scales invalid
+/// for a bit string length are skipped
+pub const SWEEP_BLOCK_SIZES: [usize; 5] = [128, 1024, 8192, 65536, 524288];
+pub const SWEEP_TEMPLATE_LENS: [usize; 4] = [3, 5, 7, 9];
+pub const SWEEP_BLOCK_LENGTHS: [usize; 5] = [2, 4, 6, 8, 10];
+
+/// Constants for the regression testing against baseline files
+pub const BASELINE_TOLERANCE: f64 = 1e-9;
+pub const BASELINE_SEPARATOR: char = '\t';
+/// Version of the format of serialized reports. Increase it whenever the format changes and keep
+/// parsing older versions
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+pub const SCHEMA_VERSION_PREFIX: &str = "# schema-version ";
+
+/// Constants for the validation against the reference sequences. NIST SP 800-22, appendix B,
+/// publishes the p-values of the first 1,000,000 bits of the binary expansions of e, pi, sqrt(2)
+/// and sqrt(3), listed in this order. The p-values are rounded to six decimal places
+pub const REFERENCE_LENGTH: usize = 1000000;
+pub const REFERENCE_TOLERANCE: f64 = 1e-6;
+pub const REFERENCE_BLOCK_SIZE: usize = 128;
+pub const REFERENCE_P_VALUES_MONOBIT: [f64; 4] = [0.953749, 0.578211, 0.811881, 0.610051];
+pub const REFERENCE_P_VALUES_BLOCK: [f64; 4] = [0.211072, 0.380615, 0.833222, 0.473961];
+pub const REFERENCE_P_VALUES_RUNS: [f64; 4] = [0.561917, 0.419268, 0.313427, 0.261123];
+pub const REFERENCE_P_VALUES_LONGEST_RUN: [f64; 4] = [0.718945, 0.024390, 0.012117, 0.446726];
+pub const REFERENCE_P_VALUES_MATRIX_RANK: [f64; 4] = [0.306156, 0.083553, 0.823810, 0.314498];
+pub const REFERENCE_P_VALUES_DFT: [f64; 4] = [0.847187, 0.010186, 0.581909, 0.776046];
+pub const REFERENCE_P_VALUES_CUSUM_FORWARD: [f64; 4] = [0.669887, 0.628308, 0.879009, 0.917121];
+pub const REFERENCE_P_VALUES_CUSUM_BACKWARD: [f64; 4] = [0.724266, 0.663369, 0.957206, 0.689519];
+pub const REFERENCE_P_VALUES_OVERLAPPING: [f64; 4] = [0.110434, 0.296897, 0.791982, 0.082716];
+pub const REFERENCE_P_VALUES_APPROXIMATE_ENTROPY: [f64; 4] =
+    [0.700073, 0.361595, 0.884740, 0.180481];
+pub const REFERENCE_P_VALUES_SERIAL_1: [f64; 4] = [0.766182, 0.143005, 0.861925, 0.157500];
+pub const REFERENCE_P_VALUES_SERIAL_2: [f64; 4] = [0.462921, 0.034354, 0.629225, 0.171100];
+/// The excursion tests are published for the states x = +1 and x = -1 only
+pub const REFERENCE_STATE_EXCURSIONS: i64 = 1;
+pub const REFERENCE_STATE_EXCURSIONS_VARIANT: i64 = -1;
+pub const REFERENCE_P_VALUES_EXCURSIONS: [f64; 4] = [0.786868, 0.844143, 0.216235, 0.783283];
+pub const REFERENCE_P_VALUES_EXCURSIONS_VARIANT: [f64; 4] =
+    [0.826009, 0.760966, 0.566118, 0.155066];
+
+/// Constants for the cross-validation against the output of the NIST reference implementation.
+/// It writes p-values with six decimal places
+pub const STS_FINAL_ANALYSIS_REPORT: &str = "finalAnalysisReport.txt";
+pub const STS_RESULTS_FILE: &str = "results.txt";
+pub const STS_NUMBER_OF_BINS: usize = 10;
+pub const STS_TOLERANCE: f64 = 1e-6;
+
+/// Maximum number of operations a verdict script may run before it is aborted
+pub const SCRIPT_MAX_OPERATIONS: u64 = 1000000;
+
+/// Constants for loading tests from plugins. Increase the ABI version whenever the C ABI of the
+/// registration changes
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+pub const PLUGIN_REGISTRATION_SYMBOL: &[u8] = b"nist_suite_register_tests\0";
+
+/// Constants for loading logic-analyzer captures. A capture is read as VCD if its file name has
+/// the VCD extension, and as CSV otherwise. The units of the VCD timescale are given in seconds
+pub const CAPTURE_VCD_EXTENSION: &str = "vcd";
+pub static CAPTURE_VCD_TIME_UNITS: [(&str, f64); 6] = [
+    ("s", 1.0),
+    ("ms", 1e-3),
+    ("us", 1e-6),
+    ("ns", 1e-9),
+    ("ps", 1e-12),
+    ("fs", 1e-15),
+];
@@ -0,0 +1,164 @@
+//! This module performs the Cross-Correlation Test. It is not part of NIST SP 800-22, which only
+//! looks at single sequences, but detects coupling between two sequences, e.g. two channels of
+//! the same TRNG or the output of a generator and a recorded input.
+//!
+//! Both sequences are mapped to -1/+1 and for each offset d of a sweep, the mean product of the
+//! overlapping bits is computed. For independent random sequences, this correlation is
+//! approximately normally distributed with mean 0 and variance 1/L, where L is the number of
+//! overlapping bits.
+
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::utils;
+
+const TEST_NAME: customtypes::Test = customtypes::Test::CrossCorrelation;
+
+/// Perform the Cross-Correlation Test for all offsets between -max_offset and max_offset.
+///
+/// # Arguments
+///
+/// bit_string_a - The first bit string
+/// bit_string_b - The second bit string. Only the first min(len_a, len_b) bits of both are used
+/// max_offset - The largest offset (in both directions) to be tested
+///
+/// # Return
+///
+/// Ok(result) - The correlations, significance bounds and p-values of each offset
+/// Err(err) - Some error occured
+pub fn perform_test(
+    bit_string_a: &str,
+    bit_string_b: &str,
+    max_offset: usize,
+) -> Result<customtypes::CrossCorrelationResult> {
+    log::trace!("cross_correlation::perform_test()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // check if bit strings contain invalid characters
+    let length_a = utils::evaluate_bit_string(
+        TEST_NAME,
+        bit_string_a,
+        constants::RECOMMENDED_SIZE_CROSS_CORRELATION,
+    )
+    .with_context(|| "Invalid character(s) in first bit string detected")?
+    .length;
+    let length_b = utils::evaluate_bit_string(
+        TEST_NAME,
+        bit_string_b,
+        constants::RECOMMENDED_SIZE_CROSS_CORRELATION,
+    )
+    .with_context(|| "Invalid character(s) in second bit string detected")?
+    .length;
+
+    if length_a != length_b {
+        log::warn!(
+            "{}: Bit strings differ in length ({} vs. {}). Using the first {} bits of both",
+            TEST_NAME,
+            length_a,
+            length_b,
+            length_a.min(length_b)
+        );
+    }
+    let length = length_a.min(length_b);
+
+    // the sums of products are bounded by the length, so they cannot overflow once it fits
+    utils::checked_i64(TEST_NAME, length, "correlation sums")?;
+
+    // at least half of the bits have to overlap at each offset to get meaningful results
+    if max_offset > length / 2 {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "max_offset",
+            format!(
+                "Maximum offset ({}) must not exceed half of the bit string length ({})",
+                max_offset,
+                length / 2
+            ),
+        ));
+    }
+
+    // map bits to -1/+1
+    let bits_a: Vec<i64> = bit_string_a.bytes().take(length).map(to_sign).collect();
+    let bits_b: Vec<i64> = bit_string_b.bytes().take(length).map(to_sign).collect();
+
+    // the bound of |correlation| * sqrt(L) at the significance level
+    let critical_value =
+        std::f64::consts::SQRT_2 * statrs::function::erf::erfc_inv(constants::P_VALUE_THRESHOLD);
+
+    let mut result = customtypes::CrossCorrelationResult {
+        p_value: 1.0,
+        ..Default::default()
+    };
+    let mut min_p_value = f64::INFINITY;
+
+    for offset in -(max_offset as isize)..=(max_offset as isize) {
+        let shift = offset.unsigned_abs();
+        let overlap = length - shift;
+
+        // pair bit i of the first sequence with bit i + offset of the second one
+        let (slice_a, slice_b) = if offset >= 0 {
+            (&bits_a[..overlap], &bits_b[shift..])
+        } else {
+            (&bits_a[shift..], &bits_b[..overlap])
+        };
+        let sum: i64 = slice_a.iter().zip(slice_b).map(|(a, b)| a * b).sum();
+
+        let correlation = (sum as f64) / (overlap as f64);
+        let bound = critical_value / (overlap as f64).sqrt();
+        let p_value = statrs::function::erf::erfc(
+            correlation.abs() * (overlap as f64).sqrt() / std::f64::consts::SQRT_2,
+        );
+        crate::logger::verbose_trace!(
+            "{}: Offset {}: correlation = {}, bound = {}, p-value = {}",
+            TEST_NAME,
+            offset,
+            correlation,
+            bound,
+            p_value
+        );
+
+        if p_value < min_p_value {
+            min_p_value = p_value;
+            result.worst_offset = offset;
+        }
+
+        result.correlations.insert(offset, correlation);
+        result.bounds.insert(offset, bound);
+        result.p_values.insert(offset, p_value);
+    }
+
+    // correct the smallest p-value for the number of tested offsets (Bonferroni)
+    result.p_value = (min_p_value * (result.p_values.len() as f64)).min(1.0);
+    log::info!(
+        "{}: p-value = {} (worst offset: {})",
+        TEST_NAME,
+        result.p_value,
+        result.worst_offset
+    );
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
+    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+
+    Ok(result)
+}
+
+/// Map an ASCII bit to -1 ('0') or +1 ('1').
+///
+/// # Arguments
+///
+/// bit - The ASCII bit to be mapped
+///
+/// # Return
+///
+/// sign - The mapped value
+fn to_sign(bit: u8) -> i64 {
+    if bit == b'1' {
+        1
+    } else {
+        -1
+    }
+}
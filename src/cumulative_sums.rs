@@ -10,14 +10,72 @@
 //! zero. For certain types of non-random sequences, the excursions of this random walk from zero will be
 //! large."
 
+use crate::bit_sequence::{BitInput, WORD_BITS};
+use crate::bit_source::{self, BitSource};
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 use statrs::distribution::ContinuousCDF;
 
 const TEST_NAME: customtypes::Test = customtypes::Test::CumulativeSums;
 
+/// The random walk of each byte value, most significant bit first
+const BYTE_WALKS: [ByteWalk; 256] = byte_walks();
+
+/// Struct for the random walk of the eight bits of a byte
+#[derive(Clone, Copy)]
+struct ByteWalk {
+    /// The sum of the steps
+    sum: i64,
+    /// The highest of the eight partial sums
+    highest: i64,
+    /// The lowest of the eight partial sums
+    lowest: i64,
+}
+
+/// Compute the random walk of each byte value at compile time.
+///
+/// # Return
+///
+/// walks - The random walks indexed by the byte value
+const fn byte_walks() -> [ByteWalk; 256] {
+    let mut walks = [ByteWalk {
+        sum: 0,
+        highest: 0,
+        lowest: 0,
+    }; 256];
+
+    let mut byte = 0;
+    while byte < 256 {
+        let mut walk = ByteWalk {
+            sum: 0,
+            highest: i64::MIN,
+            lowest: i64::MAX,
+        };
+        let mut shift = 0;
+        while shift < 8 {
+            if (byte >> (7 - shift)) & 1 == 1 {
+                walk.sum += 1;
+            } else {
+                walk.sum -= 1;
+            }
+            if walk.sum > walk.highest {
+                walk.highest = walk.sum;
+            }
+            if walk.sum < walk.lowest {
+                walk.lowest = walk.sum;
+            }
+            shift += 1;
+        }
+        walks[byte] = walk;
+        byte += 1;
+    }
+
+    walks
+}
+
 /// Perform the Cumulative Sums Test.
 ///
 /// # Arguments
@@ -29,49 +87,460 @@ const TEST_NAME: customtypes::Test = customtypes::Test::CumulativeSums;
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str, mode: customtypes::Mode) -> Result<f64> {
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized), mode: customtypes::Mode) -> Result<f64> {
     log::trace!("cumulative_sums::perform_test()");
 
+    Ok(run_test(bit_string, mode)?.p_value)
+}
+
+/// Perform the Cumulative Sums Test and return its result.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// mode - A switch to process forward (mode = 0) or backward (mode = 1) through sequence
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(
+    bit_string: &(impl BitInput + ?Sized),
+    mode: customtypes::Mode,
+) -> Result<customtypes::TestResult> {
+    log::trace!("cumulative_sums::run_test()");
+
+    run_test_with_limits(bit_string, mode, &customtypes::Limits::default())
+}
+
+/// Perform the Cumulative Sums Test like run_test() with thresholds overriding the NIST recommendations.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// mode - A switch to process forward (mode = 0) or backward (mode = 1) through sequence
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_with_limits(
+    bit_string: &(impl BitInput + ?Sized),
+    mode: customtypes::Mode,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("cumulative_sums::run_test_with_limits()");
+
+    run_test_cancellable(
+        bit_string,
+        mode,
+        &customtypes::CancellationToken::new(),
+        limits,
+    )
+}
+
+/// Perform the Cumulative Sums Test like run_test_with_limits() and stop once the passed token got
+/// cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// mode - A switch to process forward (mode = 0) or backward (mode = 1) through sequence
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    mode: customtypes::Mode,
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("cumulative_sums::run_test_cancellable()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, limits.recommended_size)
+        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+
+    // each partial sum is bounded by the length, so they cannot overflow once the length fits
+    utils::checked_i64(TEST_NAME, length, "partial sums")?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // walk forward through the words a byte at a time and keep the extremes of S_0, ..., S_n. The
+    // partial sums of the reversed sequence are S_n - S_j for j = n - 1, ..., 0, so the extremes
+    // determine the maximum z of both modes
+    let mut current_sum: i64 = 0;
+    let mut highest: i64 = 0;
+    let mut lowest: i64 = 0;
+    let mut remaining = length;
+    for word in bit_string.words() {
+        cancellation.check_at(TEST_NAME, length - remaining)?;
+        let valid = remaining.min(WORD_BITS);
+        remaining -= valid;
+
+        for (index, byte) in word.to_be_bytes().into_iter().enumerate() {
+            let bits = valid.saturating_sub(8 * index).min(8);
+            if bits == 8 {
+                let walk = &BYTE_WALKS[byte as usize];
+                highest = highest.max(current_sum + walk.highest);
+                lowest = lowest.min(current_sum + walk.lowest);
+                current_sum += walk.sum;
+                continue;
+            }
+
+            // the bits of the last byte of the sequence are walked one at a time
+            for shift in 0..bits {
+                if (byte >> (7 - shift)) & 1 == 1 {
+                    current_sum += 1;
+                } else {
+                    current_sum -= 1;
+                }
+                highest = highest.max(current_sum);
+                lowest = lowest.min(current_sum);
+            }
+        }
+    }
+
+    let max_sum_z = if mode == customtypes::Mode::Forward {
+        highest.max(-lowest)
+    } else {
+        (current_sum - lowest).max(highest - current_sum)
+    };
+    log::debug!(
+        "{}: Determined maximum value z of cumulative sums: {}",
+        TEST_NAME,
+        max_sum_z
+    );
+
+    let p_value = compute_p_value(length, max_sum_z, mode)?;
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: max_sum_z as f64,
+        parameters: utils::parameter_map(&[("n", length)]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    Ok(result)
+}
+
+/// Perform the Cumulative Sums Test and return the partial-sum path S_k of the random walk along
+/// with its extreme excursions, e.g. to visualize drift in an entropy source.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// mode - A switch to process forward (mode = 0) or backward (mode = 1) through sequence
+///
+/// # Return
+///
+/// Ok(path) - The result of the test, the partial sums S_1 ... S_n in the order processed and
+/// the extreme excursions
+/// Err(err) - Some error occured
+pub fn run_test_with_path(
+    bit_string: &(impl BitInput + ?Sized),
+    mode: customtypes::Mode,
+) -> Result<customtypes::CusumPath> {
+    log::trace!("cumulative_sums::run_test_with_path()");
+
+    let mut partial_sums = Vec::<i64>::with_capacity(bit_string.bit_len());
+    let excursions =
+        run_test_internal(bit_string, mode, &customtypes::Limits::default(), |point| {
+            partial_sums.push(point.sum)
+        })?;
+
+    Ok(customtypes::CusumPath {
+        excursions,
+        partial_sums,
+    })
+}
+
+/// Perform the Cumulative Sums Test and stream each point of the random walk to a sink instead of
+/// keeping the whole path in memory.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// mode - A switch to process forward (mode = 0) or backward (mode = 1) through sequence
+/// sink - The callback receiving the points (k, S_k) in the order processed
+///
+/// # Return
+///
+/// Ok(excursions) - The result of the test and the extreme excursions
+/// Err(err) - Some error occured
+pub fn run_test_with_sink(
+    bit_string: &(impl BitInput + ?Sized),
+    mode: customtypes::Mode,
+    sink: &mut dyn FnMut(customtypes::WalkPoint),
+) -> Result<customtypes::CusumExcursions> {
+    log::trace!("cumulative_sums::run_test_with_sink()");
+
+    run_test_internal(bit_string, mode, &customtypes::Limits::default(), sink)
+}
+
+/// Perform the Cumulative Sums Test on the bits of a source, which are read in chunks instead of
+/// being held in memory. The source is read once in both modes: the partial sums of the reversed
+/// sequence are S_n - S_j for j = n - 1, ..., 0, so the extremes of the forward partial sums
+/// determine the maximum z of the backward ones.
+///
+/// # Arguments
+///
+/// source - The source of the bits to be tested for randomness
+/// mode - A switch to process forward (mode = 0) or backward (mode = 1) through sequence
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_streaming(
+    source: &mut dyn BitSource,
+    mode: customtypes::Mode,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("cumulative_sums::run_test_streaming()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("reading");
+
+    // compute the partial sums chunk by chunk. Besides the maximum of |S_k|, keep the extremes of
+    // S_0, ..., S_(n-1) for the backward mode
+    let mut current_sum: i64 = 0;
+    let mut forward_z = 0;
+    let mut highest = 0;
+    let mut lowest = 0;
+    let length = bit_source::for_each_chunk(source, |bits| {
+        for &bit in bits {
+            highest = highest.max(current_sum);
+            lowest = lowest.min(current_sum);
+            if bit {
+                current_sum += 1;
+            } else {
+                current_sum -= 1;
+            }
+            forward_z = forward_z.max(current_sum.abs());
+        }
+        Ok(())
+    })?;
+    if length == 0 {
+        return Err(customtypes::NistError::insufficient_length(TEST_NAME, 1, 0));
+    }
+    let mut advisories = Vec::<customtypes::Advisory>::new();
+    utils::check_recommended_size(TEST_NAME, length, limits.recommended_size, &mut advisories);
+
+    let max_sum_z = if mode == customtypes::Mode::Forward {
+        forward_z
+    } else {
+        (current_sum - lowest).max(highest - current_sum)
+    };
+    log::debug!(
+        "{}: Determined maximum value z of cumulative sums: {}",
+        TEST_NAME,
+        max_sum_z
+    );
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    let p_value = compute_p_value(length, max_sum_z, mode)?;
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories,
+        statistic: max_sum_z as f64,
+        parameters: utils::parameter_map(&[("n", length)]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    Ok(result)
+}
+
+/// Perform the Cumulative Sums Test and pass each point of the random walk to a sink. The sink is
+/// generic, so run_test_with_path() does not pay for a dynamic call per bit.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// mode - A switch to process forward (mode = 0) or backward (mode = 1) through sequence
+/// limits - The thresholds applied to the input
+/// sink - The callback receiving the points (k, S_k) in the order processed
+///
+/// # Return
+///
+/// Ok(excursions) - The result of the test and the extreme excursions
+/// Err(err) - Some error occured
+fn run_test_internal(
+    bit_string: &(impl BitInput + ?Sized),
+    mode: customtypes::Mode,
+    limits: &customtypes::Limits,
+    mut sink: impl FnMut(customtypes::WalkPoint),
+) -> Result<customtypes::CusumExcursions> {
+    log::trace!("cumulative_sums::run_test_internal()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE)
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, limits.recommended_size)
         .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+
+    // each partial sum is bounded by the length, so they cannot overflow once the length fits
+    utils::checked_i64(TEST_NAME, length, "partial sums")?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
 
     // Create cumulative sums depending on chosen mode
     // In "Forward" mode, the bit string remains unchanged.
     // In "Backward" mode, just revert the bit string
-    let new_bit_string = if mode == customtypes::Mode::Forward {
-        bit_string.to_string()
+    let bits: Box<dyn Iterator<Item = bool> + '_> = if mode == customtypes::Mode::Forward {
+        Box::new(bit_string.bits())
     } else {
-        bit_string.chars().rev().collect::<String>()
+        Box::new(bit_string.bits().rev())
     };
 
     // now compute the particular sums and determine the maximum sum. '1' is a +1 whereas '0' is a
     // -1
     let mut current_sum: i64 = 0;
     let mut max_sum_z = 0;
+    let mut maximum = customtypes::WalkPoint { index: 0, sum: 0 };
+    let mut minimum = customtypes::WalkPoint { index: 0, sum: 0 };
 
-    for bit in new_bit_string.chars() {
-        if bit == '1' {
+    for (index, bit) in bits.enumerate() {
+        if bit {
             current_sum += 1;
         } else {
             current_sum -= 1;
         }
 
         max_sum_z = max_sum_z.max(current_sum.abs());
+
+        // keep the first point reaching each extreme
+        let point = customtypes::WalkPoint {
+            index: index + 1,
+            sum: current_sum,
+        };
+        if point.sum > maximum.sum {
+            maximum = point;
+        }
+        if point.sum < minimum.sum {
+            minimum = point;
+        }
+        sink(point);
     }
     log::debug!(
-        "{}: Determined maximum value z of cumulative sums: {}",
+        "{}: Determined maximum value z of cumulative sums: {} (maximum {:?}, minimum {:?})",
         TEST_NAME,
-        max_sum_z
+        max_sum_z,
+        maximum,
+        minimum
     );
 
+    let p_value = compute_p_value(length, max_sum_z, mode)?;
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: max_sum_z as f64,
+        parameters: utils::parameter_map(&[("n", length)]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    Ok(customtypes::CusumExcursions {
+        result,
+        maximum,
+        minimum,
+    })
+}
+
+/// Compute the p-value from the maximum z of the absolute partial sums.
+///
+/// # Arguments
+///
+/// length - The length n of the bit string
+/// max_sum_z - The maximum z of the absolute partial sums
+/// mode - The mode the partial sums were computed in
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value which indicates whether randomness is given or not
+/// Err(err) - Some error occured
+fn compute_p_value(length: usize, max_sum_z: i64, mode: customtypes::Mode) -> Result<f64> {
+    log::trace!("cumulative_sums::compute_p_value()");
+
     // compute lower and upper limits for the sums before generating p-value
     let upper_limit = (((length as f64) / (max_sum_z as f64) - 1.0) * 0.25) as i64;
-    let lower_limit_1 = ((-1.0 * (length as f64) / (max_sum_z as f64) + 1.0) * 0.25) as i64;
-    let lower_limit_2 = ((-1.0 * (length as f64) / (max_sum_z as f64) - 3.0) * 0.25) as i64;
+    let lower_limit_1 = ((-(length as f64) / (max_sum_z as f64) + 1.0) * 0.25) as i64;
+    let lower_limit_2 = ((-(length as f64) / (max_sum_z as f64) - 3.0) * 0.25) as i64;
     log::debug!(
         "{}: Upper limit: {}, Lower Limit 1: {}, Lower Limit 2: {}",
         TEST_NAME,
@@ -84,7 +553,8 @@ pub fn perform_test(bit_string: &str, mode: customtypes::Mode) -> Result<f64> {
     // function
     let mut sum_1 = 0.0;
     let mut sum_2 = 0.0;
-    let normal = statrs::distribution::Normal::new(0.0, 1.0).unwrap();
+    let normal = statrs::distribution::Normal::new(0.0, 1.0)
+        .with_context(|| format!("{}: Failed to create normal distribution", TEST_NAME))?;
     let denominator = (length as f64).sqrt();
 
     // we do have two sums to generate to get the p-value in the end
@@ -93,7 +563,7 @@ pub fn perform_test(bit_string: &str, mode: customtypes::Mode) -> Result<f64> {
         let numerator_2 = (4.0 * (k as f64) - 1.0) * (max_sum_z as f64);
 
         sum_1 += normal.cdf(numerator_1 / denominator) - normal.cdf(numerator_2 / denominator);
-        log::trace!(
+        crate::logger::verbose_trace!(
             "{}: Value of sum in first loop for k = {}: {}",
             TEST_NAME,
             k,
@@ -106,7 +576,7 @@ pub fn perform_test(bit_string: &str, mode: customtypes::Mode) -> Result<f64> {
         let numerator_2 = (4.0 * (k as f64) + 1.0) * (max_sum_z as f64);
 
         sum_2 += normal.cdf(numerator_1 / denominator) - normal.cdf(numerator_2 / denominator);
-        log::trace!(
+        crate::logger::verbose_trace!(
             "{}: Value of sum in second loop for k = {}: {}",
             TEST_NAME,
             k,
@@ -117,10 +587,5 @@ pub fn perform_test(bit_string: &str, mode: customtypes::Mode) -> Result<f64> {
     let p_value = 1.0 - sum_1 + sum_2;
     log::info!("{}: p-value = {} ('{:?}' Mode)", TEST_NAME, p_value, mode);
 
-    // capture the current time after the test got executed and calculate elapsed time
-    let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
-
     Ok(p_value)
 }
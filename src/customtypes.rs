@@ -1,6 +1,12 @@
 //! This module contains custom types like enums and structs and their respective implementations.
 
+use crate::constants;
+use crate::policy;
+use std::collections::BTreeMap;
+
 /// The names of the particular tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Test {
     FrequencyMonobit,
     FrequencyBlock,
@@ -17,32 +23,1209 @@ pub enum Test {
     CumulativeSums,
     RandomExcursions,
     RandomExcursionsVariant,
+    ByteFrequency,
+    CrossCorrelation,
+    /// A test loaded from a plugin, identified by the name the plugin registered it with
+    Plugin(&'static str),
+}
+
+impl Test {
+    /// All built-in tests in the order of NIST SP 800-22, followed by the additional diagnostics
+    pub const ALL: [Test; 17] = [
+        Test::FrequencyMonobit,
+        Test::FrequencyBlock,
+        Test::Runs,
+        Test::LongestRun,
+        Test::BinaryMatrixRank,
+        Test::DFTSpectral,
+        Test::NonOverlappingTemplate,
+        Test::OverlappingTemplate,
+        Test::MaurersUniversalStatistical,
+        Test::LinearComplexity,
+        Test::Serial,
+        Test::ApproximateEntropy,
+        Test::CumulativeSums,
+        Test::RandomExcursions,
+        Test::RandomExcursionsVariant,
+        Test::ByteFrequency,
+        Test::CrossCorrelation,
+    ];
+
+    /// Find the test with given name, as returned by name().
+    ///
+    /// # Arguments
+    ///
+    /// name - The name of the test
+    pub fn from_name(name: &str) -> Option<Test> {
+        Test::ALL.into_iter().find(|test| test.name() == name)
+    }
+
+    /// Get the name of the test as written in NIST SP 800-22.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Test::FrequencyMonobit => "Frequency Monobit Test",
+            Test::FrequencyBlock => "Frequency Test within a Block",
+            Test::Runs => "Runs Test",
+            Test::LongestRun => "Longest Run of Ones in a Block Test",
+            Test::BinaryMatrixRank => "Binary Matrix Rank Test",
+            Test::DFTSpectral => "Discrete Fourier Transform (Spectral) Test",
+            Test::NonOverlappingTemplate => "Non-overlapping Template Matching Test",
+            Test::OverlappingTemplate => "Overlapping Template Matching Test",
+            Test::MaurersUniversalStatistical => "Maurer's Universal Statistical Test",
+            Test::LinearComplexity => "Linear Complexity Test",
+            Test::Serial => "Serial Test",
+            Test::ApproximateEntropy => "Approximate Entropy Test",
+            Test::CumulativeSums => "Cumulative Sums (Cusums) Test",
+            Test::RandomExcursions => "Random Excursions Test",
+            Test::RandomExcursionsVariant => "Random Excursions Variant Test",
+            Test::ByteFrequency => "Byte Frequency Test",
+            Test::CrossCorrelation => "Cross-Correlation Test",
+            Test::Plugin(name) => name,
+        }
+    }
+}
+
+impl Test {
+    /// Check whether the test belongs to NIST SP 800-22 and not to the additional diagnostics.
+    pub fn is_nist(&self) -> bool {
+        !matches!(
+            self,
+            Test::ByteFrequency | Test::CrossCorrelation | Test::Plugin(_)
+        )
+    }
+
+    /// Get the name of the test in the output of the NIST reference implementation, which is
+    /// used in finalAnalysisReport.txt and as directory of its result files.
+    pub fn sts_name(&self) -> Option<&'static str> {
+        match self {
+            Test::FrequencyMonobit => Some("Frequency"),
+            Test::FrequencyBlock => Some("BlockFrequency"),
+            Test::Runs => Some("Runs"),
+            Test::LongestRun => Some("LongestRun"),
+            Test::BinaryMatrixRank => Some("Rank"),
+            Test::DFTSpectral => Some("FFT"),
+            Test::NonOverlappingTemplate => Some("NonOverlappingTemplate"),
+            Test::OverlappingTemplate => Some("OverlappingTemplate"),
+            Test::MaurersUniversalStatistical => Some("Universal"),
+            Test::LinearComplexity => Some("LinearComplexity"),
+            Test::Serial => Some("Serial"),
+            Test::ApproximateEntropy => Some("ApproximateEntropy"),
+            Test::CumulativeSums => Some("CumulativeSums"),
+            Test::RandomExcursions => Some("RandomExcursions"),
+            Test::RandomExcursionsVariant => Some("RandomExcursionsVariant"),
+            Test::ByteFrequency | Test::CrossCorrelation | Test::Plugin(_) => None,
+        }
+    }
+
+    /// Find the test with given name in the output of the NIST reference implementation, as
+    /// returned by sts_name().
+    ///
+    /// # Arguments
+    ///
+    /// name - The name of the test
+    pub fn from_sts_name(name: &str) -> Option<Test> {
+        Test::ALL
+            .into_iter()
+            .find(|test| test.sts_name() == Some(name))
+    }
 }
 
 impl std::fmt::Display for Test {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}'", self.name())
+    }
+}
+
+/// Severity of an advisory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Severity {
+    /// The parameters violate the assumptions of the test. The p-value is not meaningful
+    Invalid,
+    /// The parameters are valid, but outside the NIST recommendations. The p-value is
+    /// statistically weak
+    Weak,
+}
+
+/// Machine-readable record of a parameter or input violating the NIST SP 800-22 recommendations
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Advisory {
+    /// Whether the result is invalid or only statistically weak
+    pub severity: Severity,
+    /// The violating parameter, e.g. "n" for the bit string length or "M" for the block size
+    pub parameter: String,
+    /// Human-readable description of the violation
+    pub message: String,
+}
+
+impl Advisory {
+    /// Create an advisory for a parameter which makes the result invalid.
+    pub fn invalid(parameter: &str, message: String) -> Self {
+        Advisory {
+            severity: Severity::Invalid,
+            parameter: parameter.to_owned(),
+            message,
+        }
+    }
+
+    /// Create an advisory for a parameter which makes the result statistically weak.
+    pub fn weak(parameter: &str, message: String) -> Self {
+        Advisory {
+            severity: Severity::Weak,
+            parameter: parameter.to_owned(),
+            message,
+        }
+    }
+}
+
+/// Struct for the result of a single test
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    /// The test the result belongs to
+    pub test: Test,
+    /// The p-value which indicates whether randomness is given or not
+    pub p_value: f64,
+    /// Violations of the NIST recommendations detected while running the test
+    pub advisories: Vec<Advisory>,
+    /// The raw test statistic the p-value is computed from, e.g. S_obs or chi_square
+    pub statistic: f64,
+    /// The configuration chosen for the test, e.g. "n" for the bit string length or "M" for the
+    /// block size
+    pub parameters: BTreeMap<String, usize>,
+    /// Time it took to run the test
+    pub elapsed_time: std::time::Duration,
+    /// The significance level the p-value is judged at, P_VALUE_THRESHOLD unless configured
+    pub alpha: f64,
+    /// The observed frequencies the statistic is computed from, e.g. the number of blocks per
+    /// class, empty if the test does not count any
+    pub counts: Vec<usize>,
+}
+
+impl TestResult {
+    /// Check whether any advisory marks the result as invalid.
+    pub fn is_invalid(&self) -> bool {
+        self.advisories
+            .iter()
+            .any(|advisory| advisory.severity == Severity::Invalid)
+    }
+
+    /// Check whether the p-value is greater than or equal to the significance level alpha.
+    pub fn passed(&self) -> bool {
+        self.passed_at(self.alpha)
+    }
+
+    /// Check whether the p-value is greater than or equal to passed significance level.
+    ///
+    /// # Arguments
+    ///
+    /// alpha - The significance level, e.g. 0.001 for a stricter verdict than the default
+    pub fn passed_at(&self, alpha: f64) -> bool {
+        self.p_value >= alpha
+    }
+
+    /// Judge the result at passed significance level instead of the current one.
+    ///
+    /// # Arguments
+    ///
+    /// alpha - The significance level the p-value is judged at
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Compare two results for regression testing. The test, parameters and advisories have to
+    /// be equal, while the p-value and statistic may differ by the passed tolerance, e.g. because
+    /// of different rounding after a refactoring. The elapsed time is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// other - The result to compare with
+    /// tolerance - The maximum absolute difference of p-value and statistic
+    pub fn approx_eq(&self, other: &TestResult, tolerance: f64) -> bool {
+        self.test == other.test
+            && (self.p_value - other.p_value).abs() <= tolerance
+            && (self.statistic - other.statistic).abs() <= tolerance
+            && self.parameters == other.parameters
+            && self.advisories == other.advisories
+    }
+
+    /// Get the verdict of the result as shown in reports.
+    pub fn verdict(&self) -> &'static str {
+        if self.is_invalid() {
+            "INVALID"
+        } else if self.passed() {
+            "PASS"
+        } else {
+            "FAIL"
+        }
+    }
+}
+
+/// Render the result as one row of the report table
+impl std::fmt::Display for TestResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<44} {:>10.6} {:>12.6} {:<8} {:>10.6}s",
+            self.test.name(),
+            self.p_value,
+            self.statistic,
+            self.verdict(),
+            self.elapsed_time.as_secs_f64()
+        )
+    }
+}
+
+/// Struct for the scales a parameter sweep runs the parameter-sensitive tests at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepConfig {
+    /// Block sizes M of the "Frequency Test within a Block"
+    pub block_sizes: Vec<usize>,
+    /// Template lengths m of the "Non-overlapping Template Matching" test
+    pub template_lens: Vec<usize>,
+    /// Block lengths m of the "Approximate Entropy" test
+    pub block_lengths: Vec<usize>,
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        SweepConfig {
+            block_sizes: constants::SWEEP_BLOCK_SIZES.to_vec(),
+            template_lens: constants::SWEEP_TEMPLATE_LENS.to_vec(),
+            block_lengths: constants::SWEEP_BLOCK_LENGTHS.to_vec(),
+        }
+    }
+}
+
+/// Struct for the result of a test at one scale of a parameter sweep
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepResult {
+    /// The name of the swept parameter, e.g. "M"
+    pub parameter: &'static str,
+    /// The value of the swept parameter
+    pub value: usize,
+    /// The result of the test at this scale
+    pub result: TestResult,
+}
+
+/// Struct for a scale of a parameter sweep the test could not be run at
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepSkip {
+    /// The test not run
+    pub test: Test,
+    /// The name of the swept parameter, e.g. "M"
+    pub parameter: &'static str,
+    /// The value of the swept parameter
+    pub value: usize,
+    /// Why the test could not be run at this scale
+    pub reason: String,
+}
+
+/// Struct for the results of a parameter sweep on one bit string
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SweepReport {
+    /// The result of each test at each scale in the order run
+    pub results: Vec<SweepResult>,
+    /// The scales the tests could not be run at
+    pub skipped: Vec<SweepSkip>,
+}
+
+impl SweepReport {
+    /// Get the p-values of a test by the value of its swept parameter.
+    ///
+    /// # Arguments
+    ///
+    /// test - The test to get the p-values of
+    pub fn p_values(&self, test: Test) -> BTreeMap<usize, f64> {
+        self.results
+            .iter()
+            .filter(|sweep| sweep.result.test == test)
+            .map(|sweep| (sweep.value, sweep.result.p_value))
+            .collect()
+    }
+}
+
+/// Enum for the reason the suite did not run a test or has no result of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SkipReason {
+    /// The bit string is shorter than the length the test requires
+    TooShort { required_length: usize },
+    /// The "Frequency Monobit Test" failed, which all other tests depend on
+    MonobitFailed,
+    /// The random walk has too few cycles J for the excursion tests to be applicable
+    TooFewCycles {
+        number_of_cycles: usize,
+        required_cycles: usize,
+    },
+    /// The run got cancelled before the test completed
+    Cancelled,
+    /// The test failed with an error, which does not abort the remaining tests
+    Error { error: NistError },
+}
+
+/// Struct for a test the suite did not run
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SkippedTest {
+    /// The skipped test
+    pub test: Test,
+    /// Why the test was skipped
+    pub reason: SkipReason,
+}
+
+/// Render the skipped test as one row of the report table
+impl std::fmt::Display for SkippedTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match &self.reason {
+            SkipReason::TooShort { required_length } => format!("n < {}", required_length),
+            SkipReason::MonobitFailed => "monobit failed".to_owned(),
+            SkipReason::TooFewCycles {
+                required_cycles, ..
+            } => format!("J < {}", required_cycles),
+            SkipReason::Cancelled => "cancelled".to_owned(),
+            SkipReason::Error { .. } => "error".to_owned(),
+        };
+        write!(
+            f,
+            "{:<44} {:>10} {:>12} {:<8} {:>11}",
+            self.test.name(),
+            "-",
+            "-",
+            "SKIPPED",
+            reason
+        )
+    }
+}
+
+/// The overall verdict of a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Invalid,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail => write!(f, "FAIL"),
+            Verdict::Invalid => write!(f, "INVALID"),
+        }
+    }
+}
+
+/// Struct for the results of several tests run on the same bit string
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SuiteReport {
+    /// The result of each test in the order the tests were run
+    pub results: Vec<TestResult>,
+    /// The tests not run, e.g. because the bit string is too short for them
+    pub skipped: Vec<SkippedTest>,
+    /// The transforms applied to the input before testing, empty if it was tested as passed
+    pub pipeline: Vec<Transform>,
+    /// The length advice of the tests not implemented yet, which do not count as skipped
+    pub length_advice: Vec<LengthAdvice>,
+    /// Whether the run got cancelled, e.g. by the token of its ExecutionControl. The tests not
+    /// completed are listed as skipped
+    pub cancelled: bool,
+}
+
+impl SuiteReport {
+    /// Get the overall verdict of the report. Any failed test fails the report and any invalid
+    /// result makes it invalid. If tests got skipped, the verdict is qualified by their number,
+    /// and marked as cancelled if the run did not complete.
+    pub fn verdict(&self) -> String {
+        self.verdict_with(&policy::AnyFail)
+    }
+
+    /// Get the overall verdict of the report decided by a policy. If tests got skipped, the
+    /// verdict is qualified by their number, and marked as cancelled if the run did not complete.
+    ///
+    /// # Arguments
+    ///
+    /// policy - The policy deciding the verdict from the results
+    pub fn verdict_with(&self, policy: &dyn policy::VerdictPolicy) -> String {
+        let verdict = policy.decide(&self.results);
+
+        if self.cancelled {
+            format!("{} (cancelled, {} skipped)", verdict, self.skipped.len())
+        } else if self.skipped.is_empty() {
+            verdict.to_string()
+        } else {
+            format!("{} (qualified, {} skipped)", verdict, self.skipped.len())
+        }
+    }
+}
+
+/// Enum for the transforms a pipeline applies to the input before testing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Transform {
+    /// Keep the bits of one channel of an input interleaving several channels bit by bit
+    Deinterleave { channels: usize, channel: usize },
+    /// Keep every factor-th bit, starting with the first one
+    Decimate { factor: usize },
+    /// Von Neumann debiasing: map the pairs 01 to 0 and 10 to 1 and discard the pairs 00 and 11
+    VonNeumann,
+    /// Invert each bit
+    Invert,
+}
+
+impl std::fmt::Display for Transform {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Test::FrequencyMonobit => write!(f, "'Frequency Monobit Test'"),
-            Test::FrequencyBlock => write!(f, "'Frequency Test within a Block'"),
-            Test::Runs => write!(f, "'Runs Test'"),
-            Test::LongestRun => write!(f, "'Longest Run of Ones in a Block Test'"),
-            Test::BinaryMatrixRank => write!(f, "'Binary Matrix Rank Test'"),
-            Test::DFTSpectral => write!(f, "'Discrete Fourier Transform (Spectral) Test'"),
-            Test::NonOverlappingTemplate => write!(f, "'Non-overlapping Template Matching Test'"),
-            Test::OverlappingTemplate => write!(f, "'Overlapping Template Matching Test'"),
-            Test::MaurersUniversalStatistical => {
-                write!(f, "'Maurer's Universal Statistical Test'")
+            Transform::Deinterleave { channels, channel } => {
+                write!(f, "deinterleave({}/{})", channel, channels)
             }
-            Test::LinearComplexity => write!(f, "'Linear Complexity Test'"),
-            Test::Serial => write!(f, "'Serial Test'"),
-            Test::ApproximateEntropy => write!(f, "'Approximate Entropy Test'"),
-            Test::CumulativeSums => write!(f, "'Cumulative Sums (Cusums) Test'"),
-            Test::RandomExcursions => write!(f, "'Random Excursions Test'"),
-            Test::RandomExcursionsVariant => write!(f, "'Random Excursions Variant Test'"),
+            Transform::Decimate { factor } => write!(f, "decimate({})", factor),
+            Transform::VonNeumann => write!(f, "von-neumann"),
+            Transform::Invert => write!(f, "invert"),
         }
     }
 }
 
+/// Struct for the reports of a batch of sequences tested with the same configuration, e.g. on
+/// several machines each testing a subset of the sequences
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BatchReport {
+    /// The configuration all sequences were tested with
+    pub config: SuiteConfig,
+    /// The report of each sequence by its index in the whole batch
+    pub reports: BTreeMap<usize, SuiteReport>,
+    /// The second-level analysis of each p-value over all sequences
+    pub analyses: Vec<BatchAnalysis>,
+}
+
+/// Struct for the proportion of passing sequences and the uniformity of the p-values of one test
+/// over a batch of sequences
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchAnalysis {
+    /// The test the p-values belong to
+    pub test: Test,
+    /// Index of the p-value among those of the test, e.g. 1 for the backward mode of the Cusum
+    /// test
+    pub index: usize,
+    /// Number of sequences the test computed the p-value for
+    pub number_of_sequences: usize,
+    /// Proportion of sequences passing the test
+    pub proportion: f64,
+    /// p-value of the uniformity of the p-values (P-value_T)
+    pub uniformity_p_value: f64,
+}
+
+/// Render the report as a table with one row per test
+impl std::fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.pipeline.is_empty() {
+            let pipeline: Vec<String> = self.pipeline.iter().map(|t| t.to_string()).collect();
+            writeln!(f, "Pipeline: {}", pipeline.join(" -> "))?;
+        }
+        writeln!(
+            f,
+            "{:<44} {:>10} {:>12} {:<8} {:>11}",
+            "Test", "p-value", "statistic", "verdict", "duration"
+        )?;
+        for result in &self.results {
+            writeln!(f, "{}", result)?;
+        }
+        for skipped in &self.skipped {
+            writeln!(f, "{}", skipped)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Enum for how violations of the NIST SP 800-22 recommendations are handled
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ComplianceMode {
+    /// Refuse any parameter or length outside the recommendations, e.g. for accredited labs
+    Strict,
+    /// Run anyway and attach advisories to the result, e.g. for experiments
+    #[default]
+    Permissive,
+}
+
+/// Struct for the configuration shared by all tests of a suite run
+#[derive(Debug, Clone)]
+pub struct SuiteConfig {
+    /// How violations of the NIST recommendations are handled
+    pub compliance_mode: ComplianceMode,
+    /// Thresholds of the tests, which default to the NIST recommendations
+    pub limits: Limits,
+    /// The policy deciding the overall verdict, policy::AnyFail by default
+    pub verdict_policy: std::sync::Arc<dyn policy::VerdictPolicy>,
+    /// Whether the suite skips all other tests if the "Frequency Monobit Test" fails, true by
+    /// default. Disable it to get the p-values of all tests, e.g. for a batch analysis
+    pub stop_on_monobit_failure: bool,
+    /// The significance level all results are judged at, P_VALUE_THRESHOLD by default. Strict
+    /// mode only accepts levels between MIN_P_VALUE_THRESHOLD and P_VALUE_THRESHOLD
+    pub alpha: f64,
+    /// Parameters of the tests overriding the ones the suite derives from the bit string length
+    pub params: TestParams,
+}
+
+impl Default for SuiteConfig {
+    fn default() -> Self {
+        SuiteConfig {
+            compliance_mode: ComplianceMode::default(),
+            limits: Limits::default(),
+            verdict_policy: std::sync::Arc::new(policy::AnyFail),
+            stop_on_monobit_failure: true,
+            alpha: constants::P_VALUE_THRESHOLD,
+            params: TestParams::default(),
+        }
+    }
+}
+
+/// Policies are compared by their Debug representation, which contains all their parameters,
+/// and alpha by its bits
+impl PartialEq for SuiteConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.compliance_mode == other.compliance_mode
+            && self.limits == other.limits
+            && format!("{:?}", self.verdict_policy) == format!("{:?}", other.verdict_policy)
+            && self.stop_on_monobit_failure == other.stop_on_monobit_failure
+            && self.alpha.to_bits() == other.alpha.to_bits()
+            && self.params == other.params
+    }
+}
+
+impl Eq for SuiteConfig {}
+
+impl std::hash::Hash for SuiteConfig {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.compliance_mode.hash(state);
+        self.limits.hash(state);
+        format!("{:?}", self.verdict_policy).hash(state);
+        self.stop_on_monobit_failure.hash(state);
+        self.alpha.to_bits().hash(state);
+        self.params.hash(state);
+    }
+}
+
+/// Struct for the parameters of the tests a suite run uses instead of deriving them from the bit
+/// string length, like the parameter adjustments of the NIST reference implementation. Unset
+/// parameters keep the derived ones
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct TestParams {
+    /// Block size M of the "Frequency Test within a Block"
+    pub block_size: Option<usize>,
+    /// Template length m of the "Non-overlapping Template Matching" test
+    pub template_len: Option<usize>,
+    /// Number of blocks N of the "Non-overlapping Template Matching" test
+    pub number_of_template_blocks: Option<usize>,
+    /// Template length m of the "Overlapping Template Matching" test
+    pub overlapping_template_len: Option<usize>,
+    /// Block length m of the "Serial" test
+    pub block_length_serial: Option<usize>,
+    /// Block length m of the "Approximate Entropy" test
+    pub block_length_approximate_entropy: Option<usize>,
+}
+
+/// Struct for the thresholds the tests apply to their input and parameters. The defaults are the
+/// values of NIST SP 800-22 in constants. Overriding them is meant for research, e.g. to run tests
+/// on shorter sequences, and is refused in strict mode
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Limits {
+    /// Minimum recommended bit string length of several tests
+    pub recommended_size: usize,
+    /// Upper bound of the number of blocks N of the block-based tests
+    pub max_number_of_blocks: usize,
+    /// Minimum recommended block size M of the "Frequency Test within a Block"
+    pub recommended_block_size: usize,
+    /// Lower bound and upper bound of pre-computed template lengths of the template tests
+    pub template_len: (usize, usize),
+    /// Recommended range of template lengths of the template tests
+    pub recommended_template_len: (usize, usize),
+    /// Minimum bit string length of the "Longest Run of Ones in a Block" test
+    pub min_length_longest_run: usize,
+    /// Upper bound of the largest state x_max of the "Random Excursions Variant" test
+    pub max_state_excursions_variant: i64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            recommended_size: constants::RECOMMENDED_SIZE,
+            max_number_of_blocks: constants::RECOMMENDED_SIZE,
+            recommended_block_size: constants::RECOMMENDED_BLOCK_SIZE,
+            template_len: constants::TEMPLATE_LEN,
+            recommended_template_len: constants::RECOMMENDED_TEMPLATE_LEN,
+            min_length_longest_run: constants::MIN_LENGTH,
+            max_state_excursions_variant: constants::MAX_STATES_EXCURSIONS_VARIANT,
+        }
+    }
+}
+
+impl Limits {
+    /// Check whether any threshold differs from the NIST recommendations.
+    pub fn is_overridden(&self) -> bool {
+        *self != Limits::default()
+    }
+}
+
+/// Struct for the progress of a long-running test passed to the callback of the caller
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// The test the progress belongs to
+    pub test: Test,
+    /// Fraction of the work done so far, between 0.0 and 1.0
+    pub fraction: f64,
+    /// Estimated remaining time, extrapolated from the time taken so far
+    pub eta: Option<std::time::Duration>,
+}
+
+/// Token to cooperatively cancel running tests, e.g. from another thread. Clones share the same
+/// state, so cancelling one of them cancels all of them. A token with a deadline cancels itself
+/// once the deadline has passed
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken {
+    cancelled: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    deadline: Option<std::time::Instant>,
+}
+
+impl CancellationToken {
+    /// Create a token which is not cancelled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a token which is cancelled once the deadline has passed, e.g. to bound the time a
+    /// service spends on one request.
+    ///
+    /// # Arguments
+    ///
+    /// deadline - The instant the token is cancelled at
+    pub fn with_deadline(deadline: std::time::Instant) -> Self {
+        CancellationToken {
+            deadline: Some(deadline),
+            ..Default::default()
+        }
+    }
+
+    /// Request all tests checking this token to stop at the next block boundary.
+    pub fn cancel(&self) {
+        self.cancelled
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Check whether cancellation was requested or the deadline has passed.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(std::sync::atomic::Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| std::time::Instant::now() >= deadline)
+    }
+
+    /// Return an error if cancellation was requested.
+    ///
+    /// # Arguments
+    ///
+    /// test - The test checking the token
+    pub fn check(&self, test: Test) -> Result<(), NistError> {
+        if self.is_cancelled() {
+            return Err(NistError::Cancelled { test });
+        }
+
+        Ok(())
+    }
+
+    /// Return an error if cancellation was requested, checking the token only every
+    /// CANCELLATION_INTERVAL bits of a loop over the whole input.
+    ///
+    /// # Arguments
+    ///
+    /// test - The test checking the token
+    /// position - The number of bits processed so far
+    pub fn check_at(&self, test: Test, position: usize) -> Result<(), NistError> {
+        if position.is_multiple_of(constants::CANCELLATION_INTERVAL) {
+            return self.check(test);
+        }
+
+        Ok(())
+    }
+}
+
+/// Control of a suite run by the caller, e.g. a GUI or a service which has to stay responsive
+/// while 10^8 bits get tested. The callback receives the progress of each test, and cancelling
+/// the token stops the run, which returns the results completed so far
+#[derive(Clone)]
+pub struct ExecutionControl<'a> {
+    /// The callback receiving the progress of each test
+    pub progress: &'a dyn Fn(Progress),
+    /// The token to abort the run with
+    pub cancellation: CancellationToken,
+}
+
+impl Default for ExecutionControl<'_> {
+    fn default() -> Self {
+        ExecutionControl {
+            progress: &crate::progress::ignore,
+            cancellation: CancellationToken::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ExecutionControl<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionControl")
+            .field("cancellation", &self.cancellation)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The reference sequences NIST SP 800-22, appendix B, publishes p-values for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReferenceSequence {
+    E,
+    Pi,
+    Sqrt2,
+    Sqrt3,
+}
+
+impl ReferenceSequence {
+    /// All reference sequences in the order of the published tables
+    pub const ALL: [ReferenceSequence; 4] = [
+        ReferenceSequence::E,
+        ReferenceSequence::Pi,
+        ReferenceSequence::Sqrt2,
+        ReferenceSequence::Sqrt3,
+    ];
+
+    /// Get the name of the sequence as used in the published tables.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReferenceSequence::E => "e",
+            ReferenceSequence::Pi => "pi",
+            ReferenceSequence::Sqrt2 => "sqrt(2)",
+            ReferenceSequence::Sqrt3 => "sqrt(3)",
+        }
+    }
+}
+
+impl std::fmt::Display for ReferenceSequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Struct for the comparison of a test result on a reference sequence with the published p-value
+#[derive(Debug, Clone)]
+pub struct ReferenceCheck {
+    /// The reference sequence the test was run on
+    pub sequence: ReferenceSequence,
+    /// The p-value published by NIST
+    pub published_p_value: f64,
+    /// The result of the test
+    pub result: TestResult,
+}
+
+impl ReferenceCheck {
+    /// Check whether the p-value matches the published one within the rounding of the tables.
+    pub fn matches(&self) -> bool {
+        (self.result.p_value - self.published_p_value).abs() <= constants::REFERENCE_TOLERANCE
+    }
+}
+
+impl std::fmt::Display for ReferenceCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} published p-value {:.6}, got {:.6}",
+            self.result.test, self.sequence, self.published_p_value, self.result.p_value
+        )
+    }
+}
+
+/// Struct for one row of the finalAnalysisReport.txt written by the NIST reference
+/// implementation. Tests computing several p-values per sequence have one row per p-value
+#[derive(Debug, Clone, PartialEq)]
+pub struct StsRow {
+    /// The test the row belongs to
+    pub test: Test,
+    /// Number of p-values in each of the bins C1 = [0, 0.1) to C10 = [0.9, 1]
+    pub histogram: [usize; constants::STS_NUMBER_OF_BINS],
+    /// P-value of the uniformity of the p-values. None if too few sequences were tested
+    pub uniformity_p_value: Option<f64>,
+    /// Number of sequences passing the test
+    pub passed: usize,
+    /// Number of sequences tested
+    pub sequences: usize,
+}
+
+/// Struct for the output of the NIST reference implementation on a single sequence
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StsOutput {
+    /// The rows of finalAnalysisReport.txt in their order
+    pub rows: Vec<StsRow>,
+    /// The p-values of each test read from its results.txt in their order
+    pub p_values: BTreeMap<Test, Vec<f64>>,
+}
+
+/// The ways a result of the reference implementation and of a report can differ
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiscrepancyKind {
+    /// The reference implementation computed the p-value, the report does not contain it
+    MissingInReport,
+    /// The report contains the p-value, the reference implementation did not compute it
+    MissingInReference,
+    /// The p-values differ by more than the rounding of the reference implementation
+    PValue { reference: f64, report: f64 },
+    /// The p-values fall into different bins of the histogram
+    Bin { reference: usize, report: usize },
+    /// Only one of both passes the test
+    Passed { reference: bool, report: bool },
+}
+
+/// Struct for a difference between the output of the NIST reference implementation and a report
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    /// The test the p-value belongs to
+    pub test: Test,
+    /// Index of the p-value among those of the test, e.g. 1 for the backward mode of the Cusum
+    /// test
+    pub index: usize,
+    /// How the results differ
+    pub kind: DiscrepancyKind,
+}
+
+impl std::fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} #{}: ", self.test, self.index)?;
+        match &self.kind {
+            DiscrepancyKind::MissingInReport => write!(f, "Missing in report"),
+            DiscrepancyKind::MissingInReference => write!(f, "Missing in reference output"),
+            DiscrepancyKind::PValue { reference, report } => write!(
+                f,
+                "Reference p-value {:.6}, report p-value {:.6}",
+                reference, report
+            ),
+            DiscrepancyKind::Bin { reference, report } => write!(
+                f,
+                "Reference bin C{}, report bin C{}",
+                reference + 1,
+                report + 1
+            ),
+            DiscrepancyKind::Passed { reference, report } => write!(
+                f,
+                "Reference {}, report {}",
+                if *reference { "passed" } else { "failed" },
+                if *report { "passed" } else { "failed" }
+            ),
+        }
+    }
+}
+
+/// Result of all fallible functions of the crate
+pub type Result<T, E = NistError> = std::result::Result<T, E>;
+
+/// Errors of the tests and their helpers, e.g. to tell a too short bit string from a bad
+/// parameter choice. Errors with added context are wrapped into NistError::Context, so match on
+/// root() to handle the original error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NistError {
+    /// The bit string contains a symbol other than '0' and '1'. Multibyte UTF-8 characters are
+    /// rejected before any test slices the bit string by byte index
+    InvalidBit {
+        /// Byte offset of the first invalid symbol
+        position: usize,
+        /// The first invalid symbol
+        symbol: char,
+    },
+    /// The bit string is shorter than the test requires, e.g. empty
+    InsufficientLength {
+        test: Test,
+        /// The minimum length of the test
+        required: usize,
+        /// The length n of the bit string
+        actual: usize,
+    },
+    /// A parameter is outside of the range the test supports or refused by the compliance mode.
+    /// Parameters of the configuration, e.g. alpha, do not belong to a test
+    InvalidParameter {
+        test: Option<Test>,
+        /// The parameter, e.g. "M" for the block size
+        name: String,
+        /// Human-readable description of the violation
+        reason: String,
+    },
+    /// The bit string does not meet a prerequisite of the test, e.g. the proportion of ones of
+    /// the "Runs Test"
+    NotApplicable { test: Test, reason: String },
+    /// The random walk has less cycles J than required by the excursion tests. NIST considers
+    /// these tests not applicable to such a bit string instead of failed
+    InsufficientCycles {
+        test: Test,
+        /// The number of cycles J of the random walk
+        number_of_cycles: usize,
+        /// The minimum number of cycles max(0.005 * sqrt(n), 500)
+        required_cycles: usize,
+    },
+    /// An integer computation of a test exceeds the range of its type, e.g. the partial sums of
+    /// a bit string longer than i64::MAX bits
+    Overflow {
+        test: Test,
+        /// Description of the overflowing computation
+        operation: String,
+    },
+    /// The test got stopped by a CancellationToken
+    Cancelled { test: Test },
+    /// The input cannot be decoded into bits, e.g. a file containing neither hex bytes nor a bit
+    /// string
+    InvalidInput { reason: String },
+    /// A test could not compute its result, e.g. a plugin returning an error code
+    TestFailed { test: Test, reason: String },
+    /// Reading or writing a file or another byte stream failed
+    Io { source: ErrorCause },
+    /// An error of a dependency, e.g. a number failing to parse
+    Other { source: ErrorCause },
+    /// Another error with a description of the failed operation
+    Context {
+        /// Description of the failed operation
+        context: String,
+        /// The error the operation failed with
+        source: Box<NistError>,
+    },
+}
+
+impl NistError {
+    /// Create an error for a parameter of a test.
+    pub fn invalid_parameter(test: Test, name: &str, reason: String) -> Self {
+        NistError::InvalidParameter {
+            test: Some(test),
+            name: name.to_owned(),
+            reason,
+        }
+    }
+
+    /// Create an error for a bit string which is too short for a test.
+    pub fn insufficient_length(test: Test, required: usize, actual: usize) -> Self {
+        NistError::InsufficientLength {
+            test,
+            required,
+            actual,
+        }
+    }
+
+    /// Get the original error below all added context.
+    pub fn root(&self) -> &NistError {
+        match self {
+            NistError::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// Wrap an error of a dependency, keeping a NistError as it is.
+    ///
+    /// # Arguments
+    ///
+    /// error - The error to be wrapped
+    pub fn wrap(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        let error: Box<dyn std::error::Error + Send + Sync> = Box::new(error);
+        match error.downcast::<NistError>() {
+            Ok(error) => *error,
+            Err(error) if error.is::<std::io::Error>() => NistError::Io {
+                source: ErrorCause(error.into()),
+            },
+            Err(error) => NistError::Other {
+                source: ErrorCause(error.into()),
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for NistError {
+    fn from(error: std::io::Error) -> Self {
+        NistError::wrap(error)
+    }
+}
+
+impl From<crate::health::HealthError> for NistError {
+    fn from(error: crate::health::HealthError) -> Self {
+        match error {
+            crate::health::HealthError::Empty => NistError::InvalidInput {
+                reason: error.to_string(),
+            },
+            crate::health::HealthError::InvalidBit { position, symbol } => {
+                NistError::InvalidBit { position, symbol }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for NistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NistError::InvalidBit { position, symbol } => write!(
+                f,
+                "Bit string contains invalid character {:?} at byte position {}",
+                symbol, position
+            ),
+            NistError::InsufficientLength {
+                test, actual: 0, ..
+            } => {
+                write!(f, "{}: Bit string is empty", test)
+            }
+            NistError::InsufficientLength {
+                test,
+                required,
+                actual,
+            } => write!(
+                f,
+                "{}: Bit string needs at least {} bits! Actual length: {}",
+                test, required, actual
+            ),
+            NistError::InvalidParameter {
+                test: Some(test),
+                reason,
+                ..
+            } => write!(f, "{}: {}", test, reason),
+            NistError::InvalidParameter { reason, .. } => write!(f, "{}", reason),
+            NistError::NotApplicable { test, reason } => {
+                write!(f, "{} is not applicable! {}", test, reason)
+            }
+            NistError::InsufficientCycles {
+                test,
+                number_of_cycles,
+                required_cycles,
+            } => write!(
+                f,
+                "{}: Number of cycles J ({}) is less than the required {}",
+                test, number_of_cycles, required_cycles
+            ),
+            NistError::Overflow { test, operation } => {
+                write!(f, "{}: Arithmetic overflow in {}", test, operation)
+            }
+            NistError::Cancelled { test } => write!(f, "{}: Cancelled", test),
+            NistError::InvalidInput { reason } => write!(f, "{}", reason),
+            NistError::TestFailed { test, reason } => write!(f, "{}: {}", test, reason),
+            NistError::Io { source } | NistError::Other { source } => write!(f, "{}", source),
+            // the alternate form appends the errors below the context, like "{:#}" of anyhow
+            NistError::Context { context, source } if f.alternate() => {
+                write!(f, "{}: {:#}", context, source)
+            }
+            NistError::Context { context, .. } => write!(f, "{}", context),
+        }
+    }
+}
+
+impl std::error::Error for NistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NistError::Io { source } | NistError::Other { source } => Some(source.get()),
+            NistError::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Error of a dependency wrapped into a NistError. Causes are compared by their message
+#[derive(Debug, Clone)]
+pub struct ErrorCause(std::sync::Arc<dyn std::error::Error + Send + Sync>);
+
+impl ErrorCause {
+    /// Get the wrapped error, e.g. to downcast it to a std::io::Error.
+    pub fn get(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self.0.as_ref()
+    }
+}
+
+impl std::fmt::Display for ErrorCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl PartialEq for ErrorCause {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl Eq for ErrorCause {}
+
+/// Add a description of the failed operation to an error, like the Context trait of anyhow
+pub trait Context<T> {
+    /// Wrap the error with a description of the failed operation.
+    ///
+    /// # Arguments
+    ///
+    /// context - The description of the failed operation
+    fn context(self, context: impl std::fmt::Display) -> Result<T>;
+
+    /// Wrap the error with a description which is only created if an error occured.
+    ///
+    /// # Arguments
+    ///
+    /// f - The function creating the description of the failed operation
+    fn with_context<C: std::fmt::Display>(self, f: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: std::error::Error + Send + Sync + 'static> Context<T> for Result<T, E> {
+    fn context(self, context: impl std::fmt::Display) -> Result<T> {
+        self.with_context(|| context)
+    }
+
+    fn with_context<C: std::fmt::Display>(self, f: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|error| NistError::Context {
+            context: f().to_string(),
+            source: Box::new(NistError::wrap(error)),
+        })
+    }
+}
+
+/// A missing value is reported as invalid input described by the context
+impl<T> Context<T> for Option<T> {
+    fn context(self, context: impl std::fmt::Display) -> Result<T> {
+        self.with_context(|| context)
+    }
+
+    fn with_context<C: std::fmt::Display>(self, f: impl FnOnce() -> C) -> Result<T> {
+        self.ok_or_else(|| NistError::InvalidInput {
+            reason: f().to_string(),
+        })
+    }
+}
+
+/// Encoding of the bits of a file or another byte stream read by the reader module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Raw bytes, the most significant bit of each byte first
+    Binary,
+    /// ASCII '0' and '1' characters. Whitespace, e.g. line breaks, is skipped
+    Ascii,
+}
+
+/// Order in which the bits of a byte enter a bit sequence
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The most significant bit first, e.g. the byte 0x01 becomes "00000001"
+    #[default]
+    MsbFirst,
+    /// The least significant bit first, e.g. the byte 0x01 becomes "10000000", as shifted out by
+    /// many serial interfaces
+    LsbFirst,
+}
+
+/// Struct for the result of the bit string validation shared by all tests
+#[derive(Debug, Default, PartialEq)]
+pub struct ValidatedInput {
+    /// Length of the bit string
+    pub length: usize,
+    /// Number of ones in the bit string
+    pub ones: usize,
+    /// Byte position and character of the first symbol which is neither '0' nor '1'
+    pub first_invalid: Option<(usize, char)>,
+    /// Advisories about the bit string, e.g. if it is shorter than recommended
+    pub advisories: Vec<Advisory>,
+}
+
 /// Struct for "Longest Run of Ones in a Block" test
 #[derive(Debug)]
 pub struct LongestRunConfig<'a> {
@@ -68,6 +1251,530 @@ impl<'a> LongestRunConfig<'a> {
     }
 }
 
+/// Struct for the parameters of the "Maurer's Universal Statistical" test chosen for a bit string
+/// length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UniversalConfig {
+    /// Length L of each block
+    pub block_length: usize,
+    /// Number Q of initialization blocks
+    pub init_blocks: usize,
+    /// Expected value of the test statistic
+    pub expected_value: f64,
+    /// Variance of the test statistic
+    pub variance: f64,
+}
+
+/// Struct for whether a bit string length meets the recommended sample size of a test and which
+/// parameters the test chooses for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LengthAdvice {
+    /// The test the advice is given for
+    pub test: Test,
+    /// The length n of the bit string
+    pub length: usize,
+    /// The recommended minimum length of the test
+    pub recommended_length: usize,
+    /// The parameters chosen for the length, e.g. "L" and "Q". Empty if the test cannot run
+    pub parameters: BTreeMap<String, usize>,
+}
+
+impl LengthAdvice {
+    /// Check whether the length meets the recommended sample size.
+    pub fn is_adequate(&self) -> bool {
+        self.length >= self.recommended_length
+    }
+
+    /// Get the advisory to be attached when the test is run or skipped. Lengths below the
+    /// recommendation are weak if the test can still choose its parameters, otherwise invalid.
+    pub fn advisory(&self) -> Option<Advisory> {
+        if self.is_adequate() {
+            return None;
+        }
+
+        let mut message = format!(
+            "Bit string length {} is less than the recommended {} bits",
+            self.length, self.recommended_length
+        );
+        if self.parameters.is_empty() {
+            message.push_str(", no parameters are defined for this length");
+            return Some(Advisory::invalid("n", message));
+        }
+
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect::<Vec<String>>()
+            .join(", ");
+        message.push_str(&format!(", running with {}", parameters));
+        Some(Advisory::weak("n", message))
+    }
+}
+
+/// Struct for "Non-overlapping Template Matching" and "Overlapping Template Matching" tests
+#[derive(Debug, Default)]
+pub struct TemplateMatchingResult {
+    /// chi_square value of each template
+    pub chi_squares: BTreeMap<String, f64>,
+    /// p-value of each template
+    pub p_values: BTreeMap<String, f64>,
+    /// Number of occurences of each template per block
+    pub counts: BTreeMap<String, Vec<usize>>,
+    /// Violations of the NIST recommendations detected while running the test
+    pub advisories: Vec<Advisory>,
+}
+
+impl TemplateMatchingResult {
+    /// Compute the mean of all template p-values.
+    pub fn p_value_mean(&self) -> f64 {
+        self.p_values.values().sum::<f64>() / (self.p_values.len() as f64)
+    }
+
+    /// Compute the mean of all template chi_square values.
+    pub fn chi_square_mean(&self) -> f64 {
+        self.chi_squares.values().sum::<f64>() / (self.chi_squares.len() as f64)
+    }
+}
+
+/// Render the result like the per-template report of the NIST reference implementation: one row
+/// per template with its counts per block, chi_square value, p-value and verdict
+impl std::fmt::Display for TemplateMatchingResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (template, p_value) in &self.p_values {
+            write!(f, "{} ", template)?;
+            for count in self.counts.get(template).into_iter().flatten() {
+                write!(f, "{:>4} ", count)?;
+            }
+            writeln!(
+                f,
+                "{:>10.6} {:>10.6} {}",
+                self.chi_squares.get(template).copied().unwrap_or(f64::NAN),
+                p_value,
+                if *p_value < constants::P_VALUE_THRESHOLD {
+                    "FAIL"
+                } else {
+                    "PASS"
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Enum for "Non-overlapping Template Matching" test. Selects the templates to search for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSet {
+    /// All aperiodic templates of given length, in the order of the NIST template tables
+    Aperiodic(usize),
+    /// Templates chosen by the caller. They may differ in length
+    Custom(Vec<String>),
+}
+
+/// Enum for "Longest Run of Ones in a Block" test. Selects whether runs of ones or runs of zeros
+/// (i.e. runs of ones of the complemented sequence) are evaluated
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RunBit {
+    Ones,
+    Zeros,
+}
+
+/// Point of the random walk of the "Cumulative Sums" test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalkPoint {
+    /// The index k of the partial sum, starting at 1, in the order the bits are processed. Index 0
+    /// is the origin of the walk
+    pub index: usize,
+    /// The partial sum S_k
+    pub sum: i64,
+}
+
+/// Struct for the "Cumulative Sums" test to report the extreme excursions of the random walk
+#[derive(Debug, Clone, PartialEq)]
+pub struct CusumExcursions {
+    /// The result of the test
+    pub result: TestResult,
+    /// The first point reaching the maximum partial sum, the origin if no sum is positive
+    pub maximum: WalkPoint,
+    /// The first point reaching the minimum partial sum, the origin if no sum is negative
+    pub minimum: WalkPoint,
+}
+
+impl CusumExcursions {
+    /// Get the extreme excursion determining the statistic z, preferring the maximum on a tie.
+    pub fn maximal_excursion(&self) -> WalkPoint {
+        if self.maximum.sum >= -self.minimum.sum {
+            self.maximum
+        } else {
+            self.minimum
+        }
+    }
+}
+
+/// Struct for the "Cumulative Sums" test to report the whole random walk
+#[derive(Debug, Clone, PartialEq)]
+pub struct CusumPath {
+    /// The result of the test and the extreme excursions
+    pub excursions: CusumExcursions,
+    /// The partial sums S_1 ... S_n in the order the bits are processed
+    pub partial_sums: Vec<i64>,
+}
+
+/// Struct for the "Binary Matrix Rank" test to report the observed rank distribution
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankDistribution {
+    /// The result of the test
+    pub result: TestResult,
+    /// Number of matrices of each rank
+    pub rank_counts: BTreeMap<usize, usize>,
+    /// Number of matrices of full rank M, rank M - 1 and any lower rank
+    pub observed: [usize; 3],
+    /// Probabilities of the three rank classes the test applies
+    pub probabilities: [f64; 3],
+}
+
+impl RankDistribution {
+    /// Get the expected number of matrices of full rank M, rank M - 1 and any lower rank.
+    pub fn expected(&self) -> [f64; 3] {
+        let number_of_matrices = self.observed.iter().sum::<usize>() as f64;
+
+        self.probabilities
+            .map(|probability| probability * number_of_matrices)
+    }
+}
+
+/// Struct for "Longest Run of Ones in a Block" test to report both run types at once
+#[derive(Debug, Default)]
+pub struct LongestRunResult {
+    /// p-value of the longest runs of ones
+    pub p_value_ones: f64,
+    /// p-value of the longest runs of zeros
+    pub p_value_zeros: f64,
+}
+
+/// Struct for "Serial" test to report both p-values at once
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SerialResult {
+    /// p-value of the first difference del psi^2_m
+    pub p_value_1: f64,
+    /// p-value of the second difference del^2 psi^2_m
+    pub p_value_2: f64,
+}
+
+/// Struct for "Byte Frequency" test
+#[derive(Debug, Default)]
+pub struct ByteFrequencyResult {
+    /// p-value of the chi_square statistics over all 256 byte values
+    pub p_value: f64,
+    /// chi_square value over all 256 byte values
+    pub chi_square: f64,
+    /// Number of occurences of each byte value, indexed by the byte value
+    pub counts: Vec<usize>,
+    /// Expected number of occurences of each byte value
+    pub expected: f64,
+    /// The byte value occuring most often compared to the expectation
+    pub most_over_represented: u8,
+    /// The byte value occuring least often compared to the expectation
+    pub most_under_represented: u8,
+    /// Violations of the recommendations detected while running the test
+    pub advisories: Vec<Advisory>,
+}
+
+/// Struct for "Cross-Correlation" test. All maps are indexed by the offset d, which pairs bit i of
+/// the first sequence with bit i + d of the second one
+#[derive(Debug, Default)]
+pub struct CrossCorrelationResult {
+    /// Correlation coefficient of the overlapping bits at each offset
+    pub correlations: BTreeMap<isize, f64>,
+    /// Bound the absolute correlation must not exceed at significance level P_VALUE_THRESHOLD
+    pub bounds: BTreeMap<isize, f64>,
+    /// p-value of the correlation at each offset
+    pub p_values: BTreeMap<isize, f64>,
+    /// Offset with the smallest p-value
+    pub worst_offset: isize,
+    /// Smallest p-value, Bonferroni-corrected for the number of tested offsets
+    pub p_value: f64,
+}
+
+/// Struct for the uniformity check of p-values of the analysis module
+#[derive(Debug, Default)]
+pub struct UniformityResult {
+    /// Number of p-values in each bin, starting with the bin containing 0.0
+    pub bin_counts: Vec<usize>,
+    /// chi_square value of the bin counts
+    pub chi_square: f64,
+    /// p-value of the uniformity (P-value_T)
+    pub p_value: f64,
+}
+
+/// Struct for the second-level evaluation of one statistic of a test over many sequences, as in
+/// section 4.2 of NIST SP 800-22
+#[derive(Debug)]
+pub struct SecondLevelResult {
+    /// The test the statistic belongs to
+    pub test: Test,
+    /// The parameters of the statistic on the first sequence, e.g. "x" for the state of the
+    /// excursion tests
+    pub parameters: BTreeMap<String, usize>,
+    /// The significance level each sequence is judged at
+    pub alpha: f64,
+    /// The number of sequences the statistic was computed on
+    pub number_of_sequences: usize,
+    /// The number of sequences passing at the significance level
+    pub passed_sequences: usize,
+    /// The range of proportions of passing sequences acceptable for a random generator
+    pub confidence_interval: (f64, f64),
+    /// The uniformity of the p-values of all sequences
+    pub uniformity: UniformityResult,
+}
+
+impl SecondLevelResult {
+    /// Get the proportion of passing sequences.
+    pub fn proportion(&self) -> f64 {
+        (self.passed_sequences as f64) / (self.number_of_sequences as f64)
+    }
+
+    /// Check whether the proportion of passing sequences lies within the confidence interval.
+    pub fn proportion_passed(&self) -> bool {
+        let proportion = self.proportion();
+        proportion >= self.confidence_interval.0 && proportion <= self.confidence_interval.1
+    }
+
+    /// Check whether the p-values are uniformly distributed, i.e. whether P-value_T is greater
+    /// than or equal to UNIFORMITY_P_VALUE_THRESHOLD.
+    pub fn uniformity_passed(&self) -> bool {
+        self.uniformity.p_value >= constants::UNIFORMITY_P_VALUE_THRESHOLD
+    }
+
+    /// Check whether both second-level checks passed.
+    pub fn passed(&self) -> bool {
+        self.proportion_passed() && self.uniformity_passed()
+    }
+}
+
+/// Render the result as one row of the final analysis report of NIST: the bin counts, P-value_T,
+/// the proportion of passing sequences and the test
+impl std::fmt::Display for SecondLevelResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for count in &self.uniformity.bin_counts {
+            write!(f, "{:>4} ", count)?;
+        }
+        write!(
+            f,
+            "{:>10.6}{} {:>6}/{:<6}{} {}",
+            self.uniformity.p_value,
+            if self.uniformity_passed() { ' ' } else { '*' },
+            self.passed_sequences,
+            self.number_of_sequences,
+            if self.proportion_passed() { ' ' } else { '*' },
+            self.test.name()
+        )
+    }
+}
+
+/// Struct for the block shuffle control experiment of the diagnostics module
+#[derive(Debug, Default)]
+pub struct ShuffleControlResult {
+    /// p-value of the original bit string
+    pub original: f64,
+    /// p-value of the block-shuffled bit string
+    pub shuffled: f64,
+}
+
+/// Struct for the result of a state of the random walk of the excursion tests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateResult {
+    /// The total number of visits of the state over all cycles
+    pub visits: usize,
+    /// The statistic of the state
+    pub statistic: f64,
+    /// p-value of the state
+    pub p_value: f64,
+}
+
+/// Struct for the "Random Excursions" and "Random Excursions Variant" tests, reporting one
+/// p-value per state
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExcursionsResult {
+    /// The test the result belongs to
+    pub test: Test,
+    /// The number of cycles J of the random walk
+    pub number_of_cycles: usize,
+    /// The result of each state x
+    pub states: BTreeMap<i64, StateResult>,
+    /// Violations of the NIST recommendations detected while running the test
+    pub advisories: Vec<Advisory>,
+    /// Time it took to run the test
+    pub elapsed_time: std::time::Duration,
+}
+
+impl ExcursionsResult {
+    /// Get the p-value of each state.
+    pub fn p_values(&self) -> BTreeMap<i64, f64> {
+        self.states
+            .iter()
+            .map(|(&state, result)| (state, result.p_value))
+            .collect()
+    }
+
+    /// Split the result into one test result per state, as listed by the NIST reference
+    /// implementation. The parameters hold the bit string length "n", the number of cycles "J"
+    /// and the state as "x" for positive and "-x" for negative states.
+    ///
+    /// # Arguments
+    ///
+    /// length - The length n of the tested bit string
+    pub fn to_test_results(&self, length: usize) -> Vec<TestResult> {
+        self.states
+            .iter()
+            .map(|(&state, result)| {
+                let key = if state < 0 { "-x" } else { "x" };
+                TestResult {
+                    test: self.test,
+                    p_value: result.p_value,
+                    advisories: self.advisories.clone(),
+                    statistic: result.statistic,
+                    parameters: [
+                        ("n".to_owned(), length),
+                        ("J".to_owned(), self.number_of_cycles),
+                        (key.to_owned(), state.unsigned_abs() as usize),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    elapsed_time: self.elapsed_time,
+                    alpha: constants::P_VALUE_THRESHOLD,
+                    counts: Vec::new(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Struct for the frequency of ones at one bit position of the bytes of a sequence, computed by
+/// the diagnostics module
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitPositionBias {
+    /// The bit number within the byte, 7 being the most significant bit, i.e. the first bit of
+    /// each byte in the bit string
+    pub bit: usize,
+    /// The number of ones at this position
+    pub ones: usize,
+    /// The frequency of ones at this position
+    pub frequency: f64,
+    /// Wilson score interval of the frequency at the confidence level 1 - alpha
+    pub confidence_interval: (f64, f64),
+    /// p-value of the frequency test on the bits at this position
+    pub p_value: f64,
+}
+
+impl BitPositionBias {
+    /// Check whether the confidence interval excludes a frequency of 0.5.
+    pub fn is_biased(&self) -> bool {
+        !(self.confidence_interval.0..=self.confidence_interval.1).contains(&0.5)
+    }
+}
+
+/// Struct for the results of one phase sub-stream of the phase analysis of the diagnostics module
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseResult {
+    /// The offset j of the phase, i.e. the sub-stream consists of the bits j, j + k, j + 2k, ...
+    pub phase: usize,
+    /// The number of bits of the sub-stream
+    pub length: usize,
+    /// The number of ones of the sub-stream
+    pub ones: usize,
+    /// The results of the fast tests on the sub-stream, up to the first diverging one
+    pub results: Vec<TestResult>,
+    /// Whether any result of the phase fails at the significance level corrected for the number
+    /// of phases
+    pub diverges: bool,
+}
+
+/// Struct for the phase analysis of the diagnostics module
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseAnalysis {
+    /// The results of each phase, ordered by phase
+    pub phases: Vec<PhaseResult>,
+    /// The p-value of the chi_square test whether all phases share the same proportion of ones
+    pub homogeneity_p_value: f64,
+}
+
+impl PhaseAnalysis {
+    /// Get the offsets of the phases whose results diverge.
+    pub fn diverging_phases(&self) -> Vec<usize> {
+        self.phases
+            .iter()
+            .filter(|phase| phase.diverges)
+            .map(|phase| phase.phase)
+            .collect()
+    }
+}
+
+/// Struct for the result of a test with one block of the sequence left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockInfluence {
+    /// Index of the block left out
+    pub block: usize,
+    /// The bits left out, as a range of positions in the sequence
+    pub range: std::ops::Range<usize>,
+    /// The statistic of the test without the block
+    pub statistic: f64,
+    /// The p-value of the test without the block
+    pub p_value: f64,
+    /// How much leaving out the block raises the p-value. Positive values mark blocks pushing the
+    /// p-value down
+    pub influence: f64,
+}
+
+/// Struct for the jackknife analysis of the diagnostics module
+#[derive(Debug, Clone, PartialEq)]
+pub struct JackknifeAnalysis {
+    /// The result of the test on the whole sequence
+    pub result: TestResult,
+    /// The result with each block left out, ordered by block
+    pub blocks: Vec<BlockInfluence>,
+    /// The jackknife estimate of the standard error of the statistic
+    pub statistic_std_error: f64,
+}
+
+impl JackknifeAnalysis {
+    /// Get the blocks ordered by descending influence on the p-value.
+    ///
+    /// # Arguments
+    ///
+    /// count - The maximum number of blocks returned
+    pub fn most_influential(&self, count: usize) -> Vec<&BlockInfluence> {
+        let mut blocks: Vec<&BlockInfluence> = self.blocks.iter().collect();
+        blocks.sort_by(|a, b| b.influence.total_cmp(&a.influence));
+        blocks.truncate(count);
+        blocks
+    }
+
+    /// Get the blocks without which a failed test passes. If there are any, the failure is driven
+    /// by a single anomalous region rather than by a weak bias spread over the whole sequence.
+    pub fn decisive_blocks(&self) -> Vec<usize> {
+        if self.result.passed() {
+            return Vec::new();
+        }
+
+        self.blocks
+            .iter()
+            .filter(|block| block.p_value >= constants::P_VALUE_THRESHOLD)
+            .map(|block| block.block)
+            .collect()
+    }
+}
+
+/// Enum for the false discovery rate procedures of the analysis module
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FdrMethod {
+    /// Benjamini-Hochberg, assuming all null hypotheses are true
+    BenjaminiHochberg,
+    /// Storey, estimating the proportion of true null hypotheses from the p-values
+    Storey,
+}
+
 /// Enum for "Cumulative Sums (Cusum)" test
 #[derive(Debug, PartialEq)]
 pub enum Mode {
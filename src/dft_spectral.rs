@@ -7,10 +7,13 @@
 //! sequence that would indicate a deviation from the assumption of randomness. The intention is to detect
 //! whether the number of peaks exceeding the 95 % threshold is significantly different than 5 %."
 
+use crate::bit_sequence::BitInput;
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::progress;
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 use rustfft::{num_complex::Complex, FftPlanner};
 
 const TEST_NAME: customtypes::Test = customtypes::Test::DFTSpectral;
@@ -25,21 +28,80 @@ const TEST_NAME: customtypes::Test = customtypes::Test::DFTSpectral;
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str) -> Result<f64> {
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized)) -> Result<f64> {
     log::trace!("dft_spectral::perform_test()");
 
+    Ok(run_test(bit_string)?.p_value)
+}
+
+/// Perform the Discrete Fourier Transform (Spectral) Test and return its result.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(bit_string: &(impl BitInput + ?Sized)) -> Result<customtypes::TestResult> {
+    log::trace!("dft_spectral::run_test()");
+
+    run_test_cancellable(
+        bit_string,
+        &progress::ignore,
+        &customtypes::CancellationToken::new(),
+    )
+}
+
+/// Perform the Discrete Fourier Transform (Spectral) Test like run_test(), reporting its progress
+/// to the passed callback and stopping once the passed token got cancelled. The transform itself
+/// cannot be interrupted, so the token is checked before and after it.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// progress - The callback receiving the progress of the test
+/// cancellation - The token to stop the test with
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    progress: &dyn Fn(customtypes::Progress),
+    cancellation: &customtypes::CancellationToken,
+) -> Result<customtypes::TestResult> {
+    log::trace!("dft_spectral::run_test_cancellable()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE_DFT)
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE_DFT)
         .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // the work consists of the transform and counting the peaks of the half spectrum
+    let reporter = progress::ProgressReporter::new(TEST_NAME, length + length / 2, progress);
+    cancellation.check(TEST_NAME)?;
+    reporter.report(0);
 
     // perform discrete fourier transform on given bit string to retrieve the results
     let spectrum = apply_dft(bit_string, length);
+    cancellation.check(TEST_NAME)?;
+    reporter.report(length);
 
-    // calculate height threshold T = sqrt(log(1/0.05) * length)
-    let height_threshold = (constants::LOG_ARG.log10() * (length as f64)).sqrt();
+    // calculate height threshold T = sqrt(ln(1/0.05) * length), the 95 % peak height
+    let height_threshold = (constants::LOG_ARG.ln() * (length as f64)).sqrt();
     log::debug!("{}: Height Threshold T = {}", TEST_NAME, height_threshold);
 
     // calculate expected theoretical (95%) number of peaks N_0 = (0.95 * length) / 2.0
@@ -54,6 +116,7 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
             n_1 += 1.0;
         }
     }
+    reporter.report(length + length / 2);
     log::debug!("{}: N_0 = {}, N_1 = {}", TEST_NAME, n_0, n_1);
 
     // compute normalized difference d = (N_1 - N_0) / (sqrt((length * 0.95 * 0.05) / 4.0))
@@ -70,12 +133,30 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
     let p_value = statrs::function::erf::erfc(normalized_diff.abs() / std::f64::consts::SQRT_2);
     log::info!("{}: p-value = {}", TEST_NAME, p_value);
 
+    phase.exit();
+
     // capture the current time after the test got executed and calculate elapsed time
     let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
 
-    Ok(p_value)
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: normalized_diff,
+        parameters: utils::parameter_map(&[("n", length)]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    Ok(result)
 }
 
 /// Perform the discrete fourier transform on given bit string.
@@ -88,15 +169,15 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
 /// # Return
 ///
 /// abs_real_part - The performed DFT
-fn apply_dft(bit_string: &str, signal_len: usize) -> Vec<Complex<f64>> {
+fn apply_dft(bit_string: &(impl BitInput + ?Sized), signal_len: usize) -> Vec<Complex<f64>> {
     log::trace!("dft_spectral::apply_dft()");
 
     // convert the bit string into a sequence of real numbers
     let signal: Vec<f64> = bit_string
-        .chars()
-        .map(|c| if c == '1' { 1.0 } else { -1.0 })
+        .bits()
+        .map(|bit| if bit { 1.0 } else { -1.0 })
         .collect();
-    log::trace!("{}: Signal: {:?}", TEST_NAME, signal);
+    crate::logger::verbose_trace!("{}: Signal: {:?}", TEST_NAME, signal);
 
     // create a planner for FFT with the given signal length
     let mut planner = FftPlanner::<f64>::new();
@@ -109,7 +190,7 @@ fn apply_dft(bit_string: &str, signal_len: usize) -> Vec<Complex<f64>> {
     fft.process(&mut spectrum);
 
     for (i, value) in spectrum.iter().enumerate() {
-        log::trace!("{}: Frequency Bin {}: {:?}", TEST_NAME, i, value);
+        crate::logger::verbose_trace!("{}: Frequency Bin {}: {:?}", TEST_NAME, i, value);
     }
 
     spectrum
@@ -0,0 +1,463 @@
+//! This module contains diagnostic helpers to narrow down why a sequence fails a test, e.g. by
+//! re-running the test on a transformed copy of the sequence.
+
+use crate::constants;
+use crate::cumulative_sums;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::frequency_monobit;
+use crate::runs;
+use crate::utils;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Function running one of the fast tests of the phase analysis
+type PhaseTestFn = fn(&str) -> Result<customtypes::TestResult>;
+
+/// Randomly permute fixed-size blocks of the bit string. The bits within each block keep their
+/// order and trailing bits which do not fill a whole block stay at the end.
+///
+/// # Arguments
+///
+/// bit_string - The bit string whose blocks shall be shuffled
+/// block_size - The size of the blocks to be permuted
+/// seed - The seed of the random number generator to make the permutation reproducible
+///
+/// # Return
+///
+/// Ok(shuffled) - The bit string with permuted blocks
+/// Err(err) - Some error occured
+pub fn shuffle_blocks(bit_string: &str, block_size: usize, seed: u64) -> Result<String> {
+    log::trace!("diagnostics::shuffle_blocks()");
+
+    if block_size == 0 || block_size > bit_string.len() {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "block_size".to_owned(),
+            reason: format!(
+                "Block size ({}) must be between 1 and the bit string length ({})",
+                block_size,
+                bit_string.len()
+            ),
+        });
+    }
+    utils::ensure_ascii(bit_string)?;
+
+    let mut blocks: Vec<&[u8]> = bit_string.as_bytes().chunks_exact(block_size).collect();
+    let remainder = bit_string.as_bytes().chunks_exact(block_size).remainder();
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    blocks.shuffle(&mut rng);
+
+    let mut shuffled = Vec::<u8>::with_capacity(bit_string.len());
+    for block in blocks {
+        shuffled.extend_from_slice(block);
+    }
+    shuffled.extend_from_slice(remainder);
+
+    log::debug!(
+        "Shuffled {} blocks of size {} with seed {}",
+        bit_string.len() / block_size,
+        block_size,
+        seed
+    );
+
+    // only ASCII bytes were moved, so the result is valid UTF-8
+    String::from_utf8(shuffled).with_context(|| "Shuffled bit string is not valid UTF-8")
+}
+
+/// Run a test on the bit string and on a block-shuffled copy of it. Shuffling destroys any
+/// structure spanning more than one block but keeps the bias within the blocks. If a failure
+/// disappears after shuffling, it is caused by long-range structure. If it persists, it is caused
+/// by the marginal bias of the bits.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_size - The size of the blocks to be permuted
+/// seed - The seed of the random number generator to make the permutation reproducible
+/// test - The test to be run, e.g. |bits| runs::perform_test(bits)
+///
+/// # Return
+///
+/// Ok(result) - The p-values of the original and of the shuffled bit string
+/// Err(err) - Some error occured
+pub fn block_shuffle_control<F>(
+    bit_string: &str,
+    block_size: usize,
+    seed: u64,
+    test: F,
+) -> Result<customtypes::ShuffleControlResult>
+where
+    F: Fn(&str) -> Result<f64>,
+{
+    log::trace!("diagnostics::block_shuffle_control()");
+
+    let original = test(bit_string).with_context(|| "Test failed on original bit string")?;
+
+    let shuffled_bit_string = shuffle_blocks(bit_string, block_size, seed)?;
+    let shuffled =
+        test(&shuffled_bit_string).with_context(|| "Test failed on shuffled bit string")?;
+
+    log::info!(
+        "Block shuffle control (block size {}, seed {}): original p-value = {}, shuffled p-value = {}",
+        block_size,
+        seed,
+        original,
+        shuffled
+    );
+
+    Ok(customtypes::ShuffleControlResult { original, shuffled })
+}
+
+/// Run a test on the bit string and again with each of its blocks left out (jackknife). The blocks
+/// whose removal raises the p-value most are the regions driving a failure. If leaving out a
+/// single block lets a failed test pass, the failure is local, whereas a weak bias spread over the
+/// whole sequence persists without any of the blocks. Trailing bits which do not fill a whole
+/// block are never left out.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// number_of_blocks - The number of blocks the bit string is divided into
+/// test - The test to be run, e.g. |bits| runs::run_test(bits)
+///
+/// # Return
+///
+/// Ok(analysis) - The result on the whole bit string and without each block
+/// Err(err) - Some error occured
+pub fn jackknife<F>(
+    bit_string: &str,
+    number_of_blocks: usize,
+    test: F,
+) -> Result<customtypes::JackknifeAnalysis>
+where
+    F: Fn(&str) -> Result<customtypes::TestResult>,
+{
+    log::trace!("diagnostics::jackknife()");
+
+    let input = utils::validate_bit_string(bit_string);
+    if let Some((position, symbol)) = input.first_invalid {
+        return Err(customtypes::NistError::InvalidBit { position, symbol });
+    }
+    if number_of_blocks < 2 || number_of_blocks > bit_string.len() {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "number_of_blocks".to_owned(),
+            reason: format!(
+                "Number of blocks ({}) must be between 2 and the bit string length ({})",
+                number_of_blocks,
+                bit_string.len()
+            ),
+        });
+    }
+
+    let result = test(bit_string).with_context(|| "Test failed on whole bit string")?;
+    let block_size = bit_string.len() / number_of_blocks;
+
+    let mut blocks = Vec::<customtypes::BlockInfluence>::with_capacity(number_of_blocks);
+    for block in 0..number_of_blocks {
+        let range = block * block_size..(block + 1) * block_size;
+        let remaining = [&bit_string[..range.start], &bit_string[range.end..]].concat();
+        let block_result = test(&remaining)
+            .with_context(|| format!("Test failed with block {} left out", block))?;
+        crate::logger::verbose_trace!(
+            "Jackknife: p-value = {} without block {}",
+            block_result.p_value,
+            block
+        );
+
+        blocks.push(customtypes::BlockInfluence {
+            block,
+            range,
+            statistic: block_result.statistic,
+            p_value: block_result.p_value,
+            influence: block_result.p_value - result.p_value,
+        });
+    }
+
+    // jackknife standard error: sqrt((k - 1) / k * sum((statistic_i - mean)^2))
+    let k = number_of_blocks as f64;
+    let mean = blocks.iter().map(|block| block.statistic).sum::<f64>() / k;
+    let statistic_std_error = ((k - 1.0) / k
+        * blocks
+            .iter()
+            .map(|block| (block.statistic - mean).powi(2))
+            .sum::<f64>())
+    .sqrt();
+
+    let analysis = customtypes::JackknifeAnalysis {
+        result,
+        blocks,
+        statistic_std_error,
+    };
+    log::info!(
+        "Jackknife with {} blocks of {}: p-value = {}, statistic standard error = {}, decisive blocks: {:?}",
+        number_of_blocks,
+        analysis.result.test,
+        analysis.result.p_value,
+        statistic_std_error,
+        analysis.decisive_blocks()
+    );
+
+    Ok(analysis)
+}
+
+/// Split the bit string into k phase sub-streams, where sub-stream j consists of every k-th bit
+/// starting at offset j.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be split
+/// phases - The number of phases k
+///
+/// # Return
+///
+/// Ok(sub_streams) - The sub-streams ordered by phase
+/// Err(err) - Some error occured
+pub fn split_phases(bit_string: &str, phases: usize) -> Result<Vec<String>> {
+    log::trace!("diagnostics::split_phases()");
+
+    if phases == 0 || phases > bit_string.len() {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "phases".to_owned(),
+            reason: format!(
+                "Number of phases ({}) must be between 1 and the bit string length ({})",
+                phases,
+                bit_string.len()
+            ),
+        });
+    }
+    utils::ensure_ascii(bit_string)?;
+
+    let mut sub_streams = vec![String::with_capacity(bit_string.len() / phases + 1); phases];
+    for (index, bit) in bit_string.chars().enumerate() {
+        sub_streams[index % phases].push(bit);
+    }
+
+    Ok(sub_streams)
+}
+
+/// Split the bit string into k phase sub-streams and run the fast tests Frequency (Monobit), Runs
+/// and forward Cumulative Sums on each of them. Artifacts correlated with the clock of a hardware
+/// source, e.g. a stuck bit in every 8th position, only show in some phases. A phase diverges if
+/// any of its p-values is less than alpha / k, in which case the remaining tests are skipped for
+/// it. The proportions of ones of all phases are compared by a chi_square test of homogeneity.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// phases - The number of phases k, each sub-stream needs at least constants::RECOMMENDED_SIZE bits
+///
+/// # Return
+///
+/// Ok(analysis) - The results of each phase and the p-value of the homogeneity of the phases
+/// Err(err) - Some error occured
+pub fn phase_analysis(bit_string: &str, phases: usize) -> Result<customtypes::PhaseAnalysis> {
+    log::trace!("diagnostics::phase_analysis()");
+
+    let input = utils::validate_bit_string(bit_string);
+    if let Some((position, symbol)) = input.first_invalid {
+        return Err(customtypes::NistError::InvalidBit { position, symbol });
+    }
+    if phases < 2 {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "phases".to_owned(),
+            reason: format!("Number of phases ({}) must be at least 2", phases),
+        });
+    }
+    if bit_string.len() / phases < constants::RECOMMENDED_SIZE {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "phases".to_owned(),
+            reason: format!(
+                "Each of the {} phases needs at least {} bits, bit string length: {}",
+                phases,
+                constants::RECOMMENDED_SIZE,
+                bit_string.len()
+            ),
+        });
+    }
+
+    // correct the significance level for testing each phase
+    let alpha = constants::P_VALUE_THRESHOLD / (phases as f64);
+
+    let tests: [(customtypes::Test, PhaseTestFn); 3] = [
+        (
+            customtypes::Test::FrequencyMonobit,
+            frequency_monobit::run_test,
+        ),
+        (customtypes::Test::Runs, runs::run_test),
+        (customtypes::Test::CumulativeSums, |bits| {
+            cumulative_sums::run_test(bits, customtypes::Mode::Forward)
+        }),
+    ];
+
+    let mut results = Vec::<customtypes::PhaseResult>::with_capacity(phases);
+    for (phase, sub_stream) in split_phases(bit_string, phases)?.iter().enumerate() {
+        let mut phase_results = Vec::<customtypes::TestResult>::new();
+        let mut diverges = false;
+        for (test, run_test) in tests {
+            // a phase failing the prerequisite of the Runs test diverges as well, so the
+            // remaining tests are not run on it
+            match run_test(sub_stream) {
+                Ok(result) => {
+                    diverges = result.p_value < alpha;
+                    phase_results.push(result);
+                }
+                Err(err) => {
+                    log::debug!("{}: Not applicable to phase {}: {:#}", test, phase, err);
+                    diverges = true;
+                }
+            }
+            if diverges {
+                break;
+            }
+        }
+        if diverges {
+            log::warn!(
+                "Phase {} of {} diverges: {:?}",
+                phase,
+                phases,
+                phase_results
+                    .iter()
+                    .map(|result| result.p_value)
+                    .collect::<Vec<f64>>()
+            );
+        }
+
+        results.push(customtypes::PhaseResult {
+            phase,
+            length: sub_stream.len(),
+            ones: sub_stream.bytes().filter(|&bit| bit == b'1').count(),
+            results: phase_results,
+            diverges,
+        });
+    }
+
+    // chi_square test of homogeneity of the proportions of ones, with k - 1 degrees of freedom
+    let length = results.iter().map(|phase| phase.length).sum::<usize>() as f64;
+    let ones = results.iter().map(|phase| phase.ones).sum::<usize>() as f64;
+    let proportion = ones / length;
+    let chi_square = if proportion == 0.0 || proportion == 1.0 {
+        0.0
+    } else {
+        results
+            .iter()
+            .map(|phase| {
+                let expected = (phase.length as f64) * proportion;
+                ((phase.ones as f64) - expected).powi(2) / (expected * (1.0 - proportion))
+            })
+            .sum::<f64>()
+    };
+    let homogeneity_p_value = if chi_square == 0.0 {
+        1.0
+    } else {
+        statrs::function::gamma::gamma_ur(((phases - 1) as f64) * 0.5, chi_square * 0.5)
+    };
+
+    let analysis = customtypes::PhaseAnalysis {
+        phases: results,
+        homogeneity_p_value,
+    };
+    log::info!(
+        "Phase analysis with {} phases: homogeneity p-value = {}, diverging phases: {:?}",
+        phases,
+        homogeneity_p_value,
+        analysis.diverging_phases()
+    );
+
+    Ok(analysis)
+}
+
+/// Compute the frequency of ones at each of the 8 bit positions of the bytes of a sequence. A
+/// position stuck at one value, e.g. "bit 7 is always 0" of a capture treating the bytes as 7-bit
+/// characters, shows immediately and is worth checking before running the full battery. Trailing
+/// bits not filling a whole byte are ignored.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be checked, each 8 bits forming a byte with the most
+/// significant bit first
+///
+/// # Return
+///
+/// Ok(positions) - The frequency of ones at each bit position, ordered from bit 0 to bit 7
+/// Err(err) - Some error occured
+pub fn bit_position_bias(bit_string: &str) -> Result<Vec<customtypes::BitPositionBias>> {
+    log::trace!("diagnostics::bit_position_bias()");
+
+    let input = utils::validate_bit_string(bit_string);
+    if let Some((position, symbol)) = input.first_invalid {
+        return Err(customtypes::NistError::InvalidBit { position, symbol });
+    }
+    let number_of_bytes = input.length / constants::BYTE_BITS;
+    if number_of_bytes == 0 {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!(
+                "Bit string needs at least {} bits! Actual length: {}",
+                constants::BYTE_BITS,
+                input.length
+            ),
+        });
+    }
+
+    // the first bit of each byte is bit 7
+    let mut ones = [0_usize; constants::BYTE_BITS];
+    for byte in utils::blocks(bit_string, constants::BYTE_BITS)? {
+        let value =
+            byte.words().next().unwrap_or_default() >> (u64::BITS as usize - constants::BYTE_BITS);
+        for (bit, ones) in ones.iter_mut().enumerate() {
+            *ones += ((value >> bit) & 1) as usize;
+        }
+    }
+
+    // critical value z of the two-sided confidence level 1 - alpha
+    let z =
+        std::f64::consts::SQRT_2 * statrs::function::erf::erfc_inv(constants::P_VALUE_THRESHOLD);
+    let n = number_of_bytes as f64;
+
+    let positions: Vec<customtypes::BitPositionBias> = ones
+        .iter()
+        .enumerate()
+        .map(|(bit, &ones)| {
+            let frequency = (ones as f64) / n;
+
+            // the Wilson score interval stays meaningful for frequencies of 0 and 1
+            let center = (frequency + z * z / (2.0 * n)) / (1.0 + z * z / n);
+            let half_width = z / (1.0 + z * z / n)
+                * (frequency * (1.0 - frequency) / n + z * z / (4.0 * n * n)).sqrt();
+            let p_value = statrs::function::erf::erfc(
+                ((2 * ones) as f64 - n).abs() / n.sqrt() / std::f64::consts::SQRT_2,
+            );
+
+            customtypes::BitPositionBias {
+                bit,
+                ones,
+                frequency,
+                confidence_interval: (center - half_width, center + half_width),
+                p_value,
+            }
+        })
+        .collect();
+
+    for position in positions.iter().filter(|position| position.is_biased()) {
+        log::warn!(
+            "Bit {} of {} bytes is biased: frequency of ones = {}, p-value = {}",
+            position.bit,
+            number_of_bytes,
+            position.frequency,
+            position.p_value
+        );
+    }
+    log::debug!(
+        "Bit position bias of {} bytes: {:?}",
+        number_of_bytes,
+        positions
+    );
+
+    Ok(positions)
+}
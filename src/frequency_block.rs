@@ -7,10 +7,13 @@
 //! assumption of randomness. For block size M=1, this test degenerates to test 1, the Frequency (Monobit)
 //! test."
 
+use crate::bit_sequence::BitInput;
+use crate::bit_source::{self, BitSource};
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 
 const TEST_NAME: customtypes::Test = customtypes::Test::FrequencyBlock;
 
@@ -25,53 +28,234 @@ const TEST_NAME: customtypes::Test = customtypes::Test::FrequencyBlock;
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str, block_size: usize) -> Result<f64> {
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized), block_size: usize) -> Result<f64> {
     log::trace!("frequency_block::perform_test()");
 
+    Ok(run_test(bit_string, block_size)?.p_value)
+}
+
+/// Perform the Frequency within a Block test and return its result.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_size - Divide the bit string into equal blocks of size M
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(
+    bit_string: &(impl BitInput + ?Sized),
+    block_size: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("frequency_block::run_test()");
+
+    run_test_cancellable(
+        bit_string,
+        block_size,
+        &customtypes::CancellationToken::new(),
+        &customtypes::Limits::default(),
+    )
+}
+
+/// Perform the Frequency within a Block test and stop at the next block boundary once the passed
+/// token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_size - Divide the bit string into equal blocks of size M
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input and the block size
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    block_size: usize,
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("frequency_block::run_test_cancellable()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE)
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, limits.recommended_size)
         .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+    let mut advisories = input.advisories;
 
     // check block size M for validity and get number of blocks N
-    let number_of_blocks = evaluate_block_size(length, block_size).with_context(|| {
-        format!(
-            "{}: Either block size M or number of blocks N does not fit to defined requirements",
-            TEST_NAME
-        )
-    })?;
+    let number_of_blocks = evaluate_test_params(length, block_size, limits, &mut advisories)?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
 
     // determine the number of ones in each block. Then calculate pi_i = #ones_per_block/block_size
-    let mut pi_i = Vec::<f64>::new();
-    pi_i.reserve_exact(number_of_blocks);
-    let mut index = 0;
-
-    for current_block in 0..number_of_blocks {
-        let block = &bit_string[index..(index + block_size)];
-        let count_ones = block.chars().filter(|&c| c == '1').count() as f64;
-        log::trace!(
-            "{}: Block {}/{}: '{}' consists of {} ones",
-            TEST_NAME,
-            current_block + 1,
-            number_of_blocks,
-            block,
-            count_ones
-        );
+    let pi_i = utils::try_map_blocks(
+        bit_string,
+        block_size,
+        number_of_blocks,
+        |current_block, block| {
+            cancellation.check(TEST_NAME)?;
 
-        pi_i.push(count_ones / (block_size as f64));
+            let count_ones = block.count_ones() as f64;
+            crate::logger::verbose_trace!(
+                "{}: Block {}/{}: '{}' consists of {} ones",
+                TEST_NAME,
+                current_block + 1,
+                number_of_blocks,
+                block,
+                count_ones
+            );
 
-        index += block_size;
-    }
+            Ok(count_ones / (block_size as f64))
+        },
+    )?;
 
     // now compute the chi_square statistics: chi_square = 4 * M * sum(p_i - 0.5)^2
     let mut observed = 0.0;
 
     for (index, pi) in pi_i.iter().enumerate() {
-        log::trace!("pi_{}: {}", index + 1, pi);
+        crate::logger::verbose_trace!("pi_{}: {}", index + 1, pi);
         observed += (pi - 0.5).powf(2.0);
     }
+
+    Ok(compute_result(
+        customtypes::ValidatedInput {
+            length,
+            advisories,
+            ..Default::default()
+        },
+        block_size,
+        number_of_blocks,
+        observed,
+        start_time,
+        &span,
+        phase,
+    ))
+}
+
+/// Perform the Frequency within a Block test on the bits of a source, which are read in chunks
+/// instead of being held in memory. The proportion of ones of each block is added to the
+/// statistic once the block is complete, so the source may be of any length. The bits after the
+/// last complete block are discarded.
+///
+/// # Arguments
+///
+/// source - The source of the bits to be tested for randomness
+/// block_size - Divide the bits into equal blocks of size M
+/// limits - The thresholds applied to the input and the block size
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_streaming(
+    source: &mut dyn BitSource,
+    block_size: usize,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("frequency_block::run_test_streaming()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("reading");
+
+    if block_size == 0 {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "M",
+            "Block size M must be at least 1".to_owned(),
+        ));
+    }
+
+    // sum up (pi_i - 0.5)^2 block by block. A block may span several chunks
+    let mut observed = 0.0;
+    let mut count_ones = 0;
+    let mut position_in_block = 0;
+    let length = bit_source::for_each_chunk(source, |bits| {
+        for &bit in bits {
+            if bit {
+                count_ones += 1;
+            }
+            position_in_block += 1;
+
+            if position_in_block == block_size {
+                let pi = (count_ones as f64) / (block_size as f64);
+                observed += (pi - 0.5).powf(2.0);
+                count_ones = 0;
+                position_in_block = 0;
+            }
+        }
+        Ok(())
+    })?;
+    if length == 0 {
+        return Err(customtypes::NistError::insufficient_length(TEST_NAME, 1, 0));
+    }
+    let mut advisories = Vec::<customtypes::Advisory>::new();
+    utils::check_recommended_size(TEST_NAME, length, limits.recommended_size, &mut advisories);
+
+    // check block size M for validity and get number of blocks N
+    let number_of_blocks = evaluate_test_params(length, block_size, limits, &mut advisories)?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    Ok(compute_result(
+        customtypes::ValidatedInput {
+            length,
+            advisories,
+            ..Default::default()
+        },
+        block_size,
+        number_of_blocks,
+        observed,
+        start_time,
+        &span,
+        phase,
+    ))
+}
+
+/// Compute the chi_square statistics and the p-value from the sum of (pi_i - 0.5)^2 over all
+/// blocks.
+///
+/// # Arguments
+///
+/// input - The length and advisories of the bit string
+/// block_size - The block size M
+/// number_of_blocks - The number of blocks N
+/// observed - The sum of (pi_i - 0.5)^2 over all blocks
+/// start_time - The time the test started at
+/// span - The span of the test
+/// phase - The span of the computation phase, exited once the p-value is known
+///
+/// # Return
+///
+/// result - The p-value, statistic, parameters, elapsed time and advisories of the test
+fn compute_result(
+    input: customtypes::ValidatedInput,
+    block_size: usize,
+    number_of_blocks: usize,
+    observed: f64,
+    start_time: std::time::Instant,
+    span: &telemetry::Span,
+    phase: telemetry::Span,
+) -> customtypes::TestResult {
+    log::trace!("frequency_block::compute_result()");
+
     log::debug!("{}: Calculated observed value {}", TEST_NAME, observed);
 
     let chi_square = 4.0 * (block_size as f64) * observed;
@@ -91,12 +275,82 @@ pub fn perform_test(bit_string: &str, block_size: usize) -> Result<f64> {
     };
     log::info!("{}: p-value = {}", TEST_NAME, p_value);
 
+    phase.exit();
+
     // capture the current time after the test got executed and calculate elapsed time
     let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: chi_square,
+        parameters: utils::parameter_map(&[
+            ("n", input.length),
+            ("M", block_size),
+            ("N", number_of_blocks),
+        ]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    result
+}
+
+/// Evaluate the block size M, attach an advisory if it is shorter than recommended and return the
+/// number of blocks N.
+///
+/// # Arguments
+///
+/// length - Bit string length
+/// block_size - The block size M to be evaluated
+/// limits - The thresholds applied to the block size
+/// advisories - Collects the advisory about the block size
+///
+/// # Return
+///
+/// Ok(number_of_blocks) - Number of blocks to be processed based on block size M
+/// Err(err) - Some error occured
+fn evaluate_test_params(
+    length: usize,
+    block_size: usize,
+    limits: &customtypes::Limits,
+    advisories: &mut Vec<customtypes::Advisory>,
+) -> Result<usize> {
+    log::trace!("frequency_block::evaluate_test_params()");
+
+    // NIST recommends a block size M of at least 20 bits
+    if block_size < limits.recommended_block_size {
+        log::warn!(
+            "{}: Recommended block size M is at least {}, passed: {}",
+            TEST_NAME,
+            limits.recommended_block_size,
+            block_size
+        );
+        advisories.push(customtypes::Advisory::weak(
+            "M",
+            format!(
+                "Block size {} is less than the recommended {} bits",
+                block_size, limits.recommended_block_size
+            ),
+        ));
+    }
 
-    Ok(p_value)
+    // check block size M for validity and get number of blocks N
+    evaluate_block_size(length, block_size, limits.max_number_of_blocks).with_context(|| {
+        format!(
+            "{}: Either block size M or number of blocks N does not fit to defined requirements",
+            TEST_NAME
+        )
+    })
 }
 
 /// Evaluate passed block size and return number of blocks.
@@ -105,33 +359,52 @@ pub fn perform_test(bit_string: &str, block_size: usize) -> Result<f64> {
 ///
 /// length - Bit string length
 /// block_size - The block size M to be evaluated
+/// max_number_of_blocks - The upper bound of the number of blocks N
 ///
 /// # Return
 ///
 /// Ok(number_of_blocks) - Number of blocks to be processed based on block size M
 /// Err(err) - Some error occured
-fn evaluate_block_size(length: usize, block_size: usize) -> Result<usize> {
+fn evaluate_block_size(
+    length: usize,
+    block_size: usize,
+    max_number_of_blocks: usize,
+) -> Result<usize> {
     log::trace!("frequency_block::evaluate_block_size()");
 
+    // the lower bound of M is derived from the maximum number of blocks
+    if max_number_of_blocks == 0 {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "N",
+            "Maximum number of blocks must be at least 1".to_owned(),
+        ));
+    }
+
     // M should be less than bit string length but greater than (length / 100)
-    if block_size >= length || block_size <= (length / constants::RECOMMENDED_SIZE) {
-        anyhow::bail!(
-            "{}: Choose block size as of {} < M < {}",
+    if block_size >= length || block_size <= (length / max_number_of_blocks) {
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            length / constants::RECOMMENDED_SIZE,
-            length
-        );
+            "M",
+            format!(
+                "Choose block size as of {} < M < {}",
+                length / max_number_of_blocks,
+                length
+            ),
+        ));
     }
 
     // calculate number of blocks N by floor(length/block_size). N should be < 100
     let number_of_blocks = length / block_size;
-    if number_of_blocks >= constants::RECOMMENDED_SIZE {
-        anyhow::bail!(
-            "{}: Number of blocks exceed {}: {}. Please choose a larger M",
+    if number_of_blocks >= max_number_of_blocks {
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            constants::RECOMMENDED_SIZE,
-            number_of_blocks
-        );
+            "N",
+            format!(
+                "Number of blocks exceed {}: {}. Please choose a larger M",
+                max_number_of_blocks, number_of_blocks
+            ),
+        ));
     }
 
     log::info!(
@@ -9,10 +9,13 @@
 //! is, the number of ones and zeroes in a sequence should be about the same. All subsequent tests depend on
 //! the passing of this test."
 
+use crate::bit_sequence::{BitInput, WORD_BITS};
+use crate::bit_source::{self, BitSource};
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 
 const TEST_NAME: customtypes::Test = customtypes::Test::FrequencyMonobit;
 
@@ -26,27 +29,183 @@ const TEST_NAME: customtypes::Test = customtypes::Test::FrequencyMonobit;
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str) -> Result<f64> {
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized)) -> Result<f64> {
     log::trace!("frequency_monobit::perform_test()");
 
+    Ok(run_test(bit_string)?.p_value)
+}
+
+/// Perform the Frequency Monobit Test and return its result.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(bit_string: &(impl BitInput + ?Sized)) -> Result<customtypes::TestResult> {
+    log::trace!("frequency_monobit::run_test()");
+
+    run_test_with_limits(bit_string, &customtypes::Limits::default())
+}
+
+/// Perform the Frequency Monobit Test like run_test() with thresholds overriding the NIST recommendations.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_with_limits(
+    bit_string: &(impl BitInput + ?Sized),
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("frequency_monobit::run_test_with_limits()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, limits.recommended_size)
+        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    Ok(compute_result(input, start_time, &span, phase))
+}
+
+/// Perform the Frequency Monobit Test like run_test_with_limits() and stop once the passed token
+/// got cancelled. The ones are counted a word at a time, checking the token in between.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("frequency_monobit::run_test_cancellable()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE)
-        .with_context(|| "Invalid character(s) in passed bit string detected")?
-        as f64;
-
-    // first of all, we need to compute the partial sum S_n. '1' is a +1 and '0' is a -1.
-    let mut partial_sum: i32 = 0;
-
-    for bit in bit_string.chars() {
-        if bit == '1' {
-            partial_sum += 1;
-        } else {
-            partial_sum -= 1;
-        }
+    let mut input = utils::evaluate_bit_string(TEST_NAME, bit_string, limits.recommended_size)
+        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // count the ones a word at a time. The padding bits of the last word are zero
+    input.ones = 0;
+    for (index, word) in bit_string.words().enumerate() {
+        cancellation.check_at(TEST_NAME, index * WORD_BITS)?;
+        input.ones += word.count_ones() as usize;
+    }
+
+    Ok(compute_result(input, start_time, &span, phase))
+}
+
+/// Perform the Frequency Monobit Test on the bits of a source, which are read in chunks instead of
+/// being held in memory. Only the number of ones is kept, so the source may be of any length.
+///
+/// # Arguments
+///
+/// source - The source of the bits to be tested for randomness
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_streaming(
+    source: &mut dyn BitSource,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("frequency_monobit::run_test_streaming()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("reading");
+
+    // count the ones chunk by chunk
+    let mut ones = 0;
+    let length = bit_source::for_each_chunk(source, |bits| {
+        ones += bits.iter().filter(|&&bit| bit).count();
+        Ok(())
+    })?;
+    let mut input = customtypes::ValidatedInput {
+        length,
+        ones,
+        ..Default::default()
+    };
+    if input.length == 0 {
+        return Err(customtypes::NistError::insufficient_length(TEST_NAME, 1, 0));
     }
+    utils::check_recommended_size(
+        TEST_NAME,
+        input.length,
+        limits.recommended_size,
+        &mut input.advisories,
+    );
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    Ok(compute_result(input, start_time, &span, phase))
+}
+
+/// Compute the statistic and the p-value from the number of ones.
+///
+/// # Arguments
+///
+/// input - The length, number of ones and advisories of the bit string
+/// start_time - The time the test started at
+/// span - The span of the test
+/// phase - The span of the computation phase, exited once the p-value is known
+///
+/// # Return
+///
+/// result - The p-value, statistic, parameters, elapsed time and advisories of the test
+fn compute_result(
+    input: customtypes::ValidatedInput,
+    start_time: std::time::Instant,
+    span: &telemetry::Span,
+    phase: telemetry::Span,
+) -> customtypes::TestResult {
+    log::trace!("frequency_monobit::compute_result()");
+
+    let length = input.length as f64;
+
+    // first of all, we need to compute the partial sum S_n. '1' is a +1 and '0' is a -1, so the
+    // sum is the number of ones minus the number of zeros. Computed in i128, it cannot overflow
+    let partial_sum = 2 * (input.ones as i128) - (input.length as i128);
     log::debug!("{}: Partial Sum S_n: {}", TEST_NAME, partial_sum);
 
     // now calculate observed value S_obs = |S_n| / sqrt(length)
@@ -58,10 +217,28 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
     let p_value = statrs::function::erf::erfc(observed / std::f64::consts::SQRT_2);
     log::info!("{}: p-value = {}", TEST_NAME, p_value);
 
+    phase.exit();
+
     // capture the current time after the test got executed and calculate elapsed time
     let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: observed,
+        parameters: utils::parameter_map(&[("n", input.length)]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
 
-    Ok(p_value)
+    result
 }
@@ -0,0 +1,315 @@
+//! This module performs the continuous health tests of NIST SP 800-90B, section 4.4, on the raw
+//! samples of a noise source: the Repetition Count Test and the Adaptive Proportion Test.
+//!
+//! The tests are fed one sample at a time. Their cutoffs and window sizes are const generics, the
+//! alarm log is a fixed-size buffer and their errors hold no heap data. The module is the only one
+//! built without the "std" feature, so the same code checks a noise source on-line inside firmware
+//! and recorded samples on the host.
+
+use crate::constants;
+
+/// The continuous health tests of NIST SP 800-90B
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthTest {
+    RepetitionCount,
+    AdaptiveProportion,
+}
+
+/// Struct for a health test detecting a failure of the noise source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthAlarm {
+    /// The health test raising the alarm
+    pub test: HealthTest,
+    /// The sample repeated too often
+    pub sample: u8,
+    /// Index of the sample raising the alarm, counted from the first sample fed
+    pub position: u64,
+}
+
+/// Error of the health tests. Unlike customtypes::NistError it does not allocate, so it can be
+/// returned inside firmware. With the "std" feature it converts into a NistError
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthError {
+    /// No samples were passed
+    Empty,
+    /// The recorded bit string contains a symbol other than '0' and '1'
+    InvalidBit {
+        /// Byte offset of the first invalid symbol
+        position: usize,
+        /// The first invalid symbol
+        symbol: char,
+    },
+}
+
+impl core::fmt::Display for HealthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HealthError::Empty => write!(f, "Bit string is empty"),
+            HealthError::InvalidBit { position, symbol } => write!(
+                f,
+                "Bit string contains invalid character {:?} at byte position {}",
+                symbol, position
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HealthError {}
+
+/// Repetition Count Test detecting a noise source stuck at one value. An alarm is raised once a
+/// sample is repeated CUTOFF times in a row
+#[derive(Debug, Clone)]
+pub struct RepetitionCountTest<const CUTOFF: usize> {
+    last_sample: Option<u8>,
+    count: usize,
+}
+
+impl<const CUTOFF: usize> RepetitionCountTest<CUTOFF> {
+    /// Create the test before the first sample.
+    pub const fn new() -> Self {
+        RepetitionCountTest {
+            last_sample: None,
+            count: 0,
+        }
+    }
+
+    /// Feed the next sample to the test.
+    ///
+    /// # Arguments
+    ///
+    /// sample - The next sample of the noise source
+    ///
+    /// # Return
+    ///
+    /// alarm - Whether the sample completes a run of CUTOFF identical samples
+    pub fn feed(&mut self, sample: u8) -> bool {
+        if self.last_sample == Some(sample) {
+            self.count += 1;
+        } else {
+            self.last_sample = Some(sample);
+            self.count = 1;
+        }
+
+        // raise the alarm once per run instead of for each further repetition
+        self.count == CUTOFF
+    }
+}
+
+impl<const CUTOFF: usize> Default for RepetitionCountTest<CUTOFF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adaptive Proportion Test detecting a noise source losing entropy. The samples are divided into
+/// windows of WINDOW samples and an alarm is raised once the first sample of a window occurs
+/// CUTOFF times within it
+#[derive(Debug, Clone)]
+pub struct AdaptiveProportionTest<const WINDOW: usize, const CUTOFF: usize> {
+    reference: u8,
+    matches: usize,
+    position: usize,
+}
+
+impl<const WINDOW: usize, const CUTOFF: usize> AdaptiveProportionTest<WINDOW, CUTOFF> {
+    /// Create the test before the first window.
+    pub const fn new() -> Self {
+        AdaptiveProportionTest {
+            reference: 0,
+            matches: 0,
+            position: 0,
+        }
+    }
+
+    /// Feed the next sample to the test.
+    ///
+    /// # Arguments
+    ///
+    /// sample - The next sample of the noise source
+    ///
+    /// # Return
+    ///
+    /// alarm - Whether the sample is the CUTOFF-th occurence of the reference in its window
+    pub fn feed(&mut self, sample: u8) -> bool {
+        let mut alarm = false;
+        if self.position == 0 {
+            self.reference = sample;
+            self.matches = 1;
+        } else if sample == self.reference {
+            self.matches += 1;
+            alarm = self.matches == CUTOFF;
+        }
+
+        self.position += 1;
+        if self.position == WINDOW {
+            self.position = 0;
+        }
+
+        alarm
+    }
+}
+
+impl<const WINDOW: usize, const CUTOFF: usize> Default for AdaptiveProportionTest<WINDOW, CUTOFF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Monitor running both health tests on each sample and keeping the last LOG_SIZE alarms
+#[derive(Debug, Clone)]
+pub struct HealthMonitor<
+    const REPETITION_CUTOFF: usize,
+    const PROPORTION_WINDOW: usize,
+    const PROPORTION_CUTOFF: usize,
+    const LOG_SIZE: usize,
+> {
+    repetition_count: RepetitionCountTest<REPETITION_CUTOFF>,
+    adaptive_proportion: AdaptiveProportionTest<PROPORTION_WINDOW, PROPORTION_CUTOFF>,
+    samples: u64,
+    alarm_count: u64,
+    alarms: heapless::HistoryBuffer<HealthAlarm, LOG_SIZE>,
+}
+
+/// Health monitor for binary samples with full entropy, as produced by most bit generators
+pub type BinaryHealthMonitor = HealthMonitor<
+    { constants::HEALTH_REPETITION_CUTOFF_BINARY },
+    { constants::HEALTH_PROPORTION_WINDOW_BINARY },
+    { constants::HEALTH_PROPORTION_CUTOFF_BINARY },
+    { constants::HEALTH_ALARM_LOG_SIZE },
+>;
+
+impl<
+        const REPETITION_CUTOFF: usize,
+        const PROPORTION_WINDOW: usize,
+        const PROPORTION_CUTOFF: usize,
+        const LOG_SIZE: usize,
+    > HealthMonitor<REPETITION_CUTOFF, PROPORTION_WINDOW, PROPORTION_CUTOFF, LOG_SIZE>
+{
+    /// Create the monitor before the first sample.
+    pub const fn new() -> Self {
+        HealthMonitor {
+            repetition_count: RepetitionCountTest::new(),
+            adaptive_proportion: AdaptiveProportionTest::new(),
+            samples: 0,
+            alarm_count: 0,
+            alarms: heapless::HistoryBuffer::new(),
+        }
+    }
+
+    /// Feed the next sample to both health tests.
+    ///
+    /// # Arguments
+    ///
+    /// sample - The next sample of the noise source
+    ///
+    /// # Return
+    ///
+    /// Ok() - The sample did not raise an alarm
+    /// Err(alarm) - The alarm raised by the sample. If both tests raise one, the alarm of the
+    /// Adaptive Proportion Test is returned and both are logged
+    pub fn feed(&mut self, sample: u8) -> Result<(), HealthAlarm> {
+        let position = self.samples;
+        self.samples += 1;
+
+        let mut result = Ok(());
+        if self.repetition_count.feed(sample) {
+            result = Err(self.raise(HealthTest::RepetitionCount, sample, position));
+        }
+        if self.adaptive_proportion.feed(sample) {
+            result = Err(self.raise(HealthTest::AdaptiveProportion, sample, position));
+        }
+
+        result
+    }
+
+    /// Get the number of samples fed so far.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Get the number of alarms raised so far, including those no longer kept in the log.
+    pub fn alarm_count(&self) -> u64 {
+        self.alarm_count
+    }
+
+    /// Check whether no alarm has been raised so far.
+    pub fn is_healthy(&self) -> bool {
+        self.alarm_count == 0
+    }
+
+    /// Get the last LOG_SIZE alarms, oldest first.
+    pub fn alarms(&self) -> impl Iterator<Item = &HealthAlarm> {
+        self.alarms.oldest_ordered()
+    }
+
+    /// Log an alarm.
+    fn raise(&mut self, test: HealthTest, sample: u8, position: u64) -> HealthAlarm {
+        let alarm = HealthAlarm {
+            test,
+            sample,
+            position,
+        };
+        crate::logger::verbose_trace!("Health alarm: {:?}", alarm);
+
+        self.alarm_count += 1;
+        self.alarms.write(alarm);
+
+        alarm
+    }
+}
+
+impl<
+        const REPETITION_CUTOFF: usize,
+        const PROPORTION_WINDOW: usize,
+        const PROPORTION_CUTOFF: usize,
+        const LOG_SIZE: usize,
+    > Default for HealthMonitor<REPETITION_CUTOFF, PROPORTION_WINDOW, PROPORTION_CUTOFF, LOG_SIZE>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run the health tests for binary samples on a recorded bit string, e.g. on the host after
+/// collecting the raw output of a noise source.
+///
+/// # Arguments
+///
+/// bit_string - The recorded samples, one bit per sample
+///
+/// # Return
+///
+/// Ok(monitor) - The monitor after feeding all samples, containing the raised alarms
+/// Err(err) - The bit string is empty or contains an invalid symbol
+pub fn check_bit_string(bit_string: &str) -> Result<BinaryHealthMonitor, HealthError> {
+    log::trace!("health::check_bit_string()");
+
+    if bit_string.is_empty() {
+        return Err(HealthError::Empty);
+    }
+    if let Some((position, symbol)) = bit_string
+        .char_indices()
+        .find(|&(_, symbol)| symbol != '0' && symbol != '1')
+    {
+        return Err(HealthError::InvalidBit { position, symbol });
+    }
+
+    let mut monitor = BinaryHealthMonitor::new();
+    for bit in bit_string.bytes() {
+        // alarms are collected in the monitor, so the remaining samples are checked as well
+        let _ = monitor.feed(u8::from(bit == b'1'));
+    }
+
+    if monitor.is_healthy() {
+        log::info!("Health tests passed on {} samples", monitor.samples());
+    } else {
+        log::warn!(
+            "Health tests raised {} alarms on {} samples",
+            monitor.alarm_count(),
+            monitor.samples()
+        );
+    }
+
+    Ok(monitor)
+}
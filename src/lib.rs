@@ -0,0 +1,100 @@
+//! Statistical tests of the NIST SP 800-22 suite to verify the randomness of given numbers.
+//!
+//! # Errors
+//!
+//! All fallible functions return customtypes::Result with a customtypes::NistError, e.g. for a
+//! too short bit string, an invalid symbol, a parameter outside of its range or a failed read.
+//! Errors may be wrapped into NistError::Context on their way up, so match on err.root() to
+//! handle the original error. Errors of dependencies are kept as NistError::Io or
+//! NistError::Other and returned by std::error::Error::source().
+//!
+//! # no_std
+//!
+//! Without the default "std" feature, the crate is built as no_std and only contains the health
+//! module and the constants and logging it relies on, e.g. for firmware. All other modules and the
+//! features pulling them in require "std".
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod approximate_entropy;
+#[cfg(feature = "std")]
+pub mod baseline;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "matrix-rank")]
+pub mod binary_matrix_rank;
+#[cfg(feature = "std")]
+pub mod bit_sequence;
+#[cfg(feature = "std")]
+pub mod bit_source;
+#[cfg(feature = "std")]
+pub mod byte_frequency;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod capture;
+pub mod constants;
+#[cfg(feature = "std")]
+pub mod cross_correlation;
+#[cfg(feature = "std")]
+pub mod cumulative_sums;
+#[cfg(feature = "std")]
+pub mod customtypes;
+#[cfg(feature = "spectral")]
+pub mod dft_spectral;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "std")]
+pub mod frequency_block;
+#[cfg(feature = "std")]
+pub mod frequency_monobit;
+pub mod health;
+pub mod logger;
+#[cfg(feature = "std")]
+pub mod longest_run;
+#[cfg(feature = "std")]
+pub mod non_overlapping_template;
+#[cfg(feature = "std")]
+pub mod overlapping_template;
+#[cfg(feature = "std")]
+pub mod pipeline;
+#[cfg(feature = "plugins")]
+pub mod plugins;
+#[cfg(feature = "std")]
+pub mod policy;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "std")]
+pub mod random_excursions;
+#[cfg(feature = "std")]
+pub mod random_excursions_variant;
+#[cfg(feature = "std")]
+pub mod reader;
+#[cfg(feature = "reference-data")]
+pub mod reference;
+#[cfg(feature = "serde")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod runs;
+#[cfg(feature = "scripting")]
+pub mod script;
+#[cfg(feature = "std")]
+pub mod serial;
+#[cfg(feature = "std")]
+pub mod sts;
+#[cfg(feature = "std")]
+pub mod suite;
+#[cfg(feature = "std")]
+pub mod sweep;
+#[cfg(feature = "std")]
+pub mod tables;
+#[cfg(feature = "std")]
+pub mod telemetry;
+#[cfg(feature = "std")]
+pub mod utils;
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
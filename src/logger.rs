@@ -1,8 +1,12 @@
 /// Set up the logger. It can be only initialized once.
-use anyhow::Result;
+#[cfg(feature = "std")]
+use crate::customtypes::Result;
+#[cfg(feature = "std")]
 use env_logger::Env;
 
+#[cfg(feature = "std")]
 const CRATE_NAME: &str = "rust_nist_suite";
+#[cfg(feature = "std")]
 static START: std::sync::Once = std::sync::Once::new();
 
 /// Initialize the logger.
@@ -15,6 +19,7 @@ static START: std::sync::Once = std::sync::Once::new();
 ///
 /// Ok() - Successfully initialized logger
 /// Err(err) - Some error occured
+#[cfg(feature = "std")]
 pub fn init_logger(loglevel: &str) -> Result<()> {
     let loglevel = match loglevel.to_lowercase().as_str() {
         "off" => log::LevelFilter::Off,
@@ -35,3 +40,16 @@ pub fn init_logger(loglevel: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Log a trace message from a hot loop, e.g. once per block or per template. The message is only
+/// formatted if the crate is built with the "verbose-trace" feature, so release builds do not pay
+/// for these diagnostics even if trace logging is enabled at runtime. The macro is not exported,
+/// since cfg! would check the features of the calling crate instead.
+macro_rules! verbose_trace {
+    ($($arg:tt)+) => {
+        if cfg!(feature = "verbose-trace") {
+            log::trace!($($arg)+);
+        }
+    };
+}
+pub(crate) use verbose_trace;
@@ -9,10 +9,12 @@
 //! the expected length of the longest run of ones implies that there is also an irregularity in the expected
 //! length of the longest run of zeroes. Therefore, only a test for ones is necessary."
 
+use crate::bit_sequence::{self, BitInput, WORD_BITS};
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 use std::collections::BTreeMap;
 
 const TEST_NAME: customtypes::Test = customtypes::Test::LongestRun;
@@ -27,35 +29,162 @@ const TEST_NAME: customtypes::Test = customtypes::Test::LongestRun;
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str) -> Result<f64> {
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized)) -> Result<f64> {
     log::trace!("longest_run::perform_test()");
 
+    perform_test_with_run_bit(bit_string, customtypes::RunBit::Ones)
+}
+
+/// Perform the Longest Run of Ones in a Block test and return its result.
+///
+/// # Arguments
+///
+/// bit_string -  The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(bit_string: &(impl BitInput + ?Sized)) -> Result<customtypes::TestResult> {
+    log::trace!("longest_run::run_test()");
+
+    run_test_with_run_bit(bit_string, customtypes::RunBit::Ones)
+}
+
+/// Perform the Longest Run test for both runs of ones and runs of zeros. For random data, both
+/// p-values behave the same, but asymmetric faults like stuck-at-0 bursts only show up in one
+/// of them.
+///
+/// # Arguments
+///
+/// bit_string -  The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The p-values of the longest runs of ones and zeros
+/// Err(err) - Some error occured
+pub fn perform_test_both(
+    bit_string: &(impl BitInput + ?Sized),
+) -> Result<customtypes::LongestRunResult> {
+    log::trace!("longest_run::perform_test_both()");
+
+    let p_value_ones = perform_test_with_run_bit(bit_string, customtypes::RunBit::Ones)?;
+    let p_value_zeros = perform_test_with_run_bit(bit_string, customtypes::RunBit::Zeros)?;
+
+    Ok(customtypes::LongestRunResult {
+        p_value_ones,
+        p_value_zeros,
+    })
+}
+
+/// Perform the Longest Run test for either runs of ones or runs of zeros. Evaluating runs of
+/// zeros is the same as evaluating runs of ones of the complemented sequence.
+///
+/// # Arguments
+///
+/// bit_string -  The bit string to be tested for randomness
+/// run_bit - Whether the longest runs of ones or zeros shall be evaluated
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value which indicates whether randomness is given or not
+/// Err(err) - Some error occured
+pub fn perform_test_with_run_bit(
+    bit_string: &(impl BitInput + ?Sized),
+    run_bit: customtypes::RunBit,
+) -> Result<f64> {
+    log::trace!("longest_run::perform_test_with_run_bit()");
+
+    Ok(run_test_with_run_bit(bit_string, run_bit)?.p_value)
+}
+
+/// Perform the Longest Run test for either runs of ones or runs of zeros and return its result.
+///
+/// # Arguments
+///
+/// bit_string -  The bit string to be tested for randomness
+/// run_bit - Whether the longest runs of ones or zeros shall be evaluated
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_with_run_bit(
+    bit_string: &(impl BitInput + ?Sized),
+    run_bit: customtypes::RunBit,
+) -> Result<customtypes::TestResult> {
+    log::trace!("longest_run::run_test_with_run_bit()");
+
+    run_test_cancellable(
+        bit_string,
+        run_bit,
+        &customtypes::CancellationToken::new(),
+        &customtypes::Limits::default(),
+    )
+}
+
+/// Perform the Longest Run test for either runs of ones or runs of zeros and stop at the next
+/// block boundary once the passed token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string -  The bit string to be tested for randomness
+/// run_bit - Whether the longest runs of ones or zeros shall be evaluated
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input, e.g. the minimum length
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    run_bit: customtypes::RunBit,
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("longest_run::run_test_cancellable()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::MIN_LENGTH)
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, limits.min_length_longest_run)
         .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
 
     // evaluate bit string length and determine longest run configuration
-    let config = get_longest_run_config(length).with_context(|| {
-        format!(
-            "{}: Failed to retrieve longest run configuration",
-            TEST_NAME
-        )
-    })?;
+    let config =
+        get_longest_run_config(length, limits.min_length_longest_run).with_context(|| {
+            format!(
+                "{}: Failed to retrieve longest run configuration",
+                TEST_NAME
+            )
+        })?;
 
-    // determine the number of runs per block and calculate v_i. A "longest" run is defined as the
-    // maximum number of consecutive ones in a block, e.g., "110010111" has the longest run as of 3
-    let mut counts: BTreeMap<i32, i32> = BTreeMap::new();
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
 
-    for block_num in 0..config.number_of_blocks {
-        let start_index = block_num * config.block_size;
-        let end_index = (block_num + 1) * config.block_size;
-        let block = &bit_string[start_index..end_index];
-        let max_consecutive_ones = count_max_consecutive_ones(block);
+    // determine the number of runs per block and calculate v_i. A "longest" run is defined as the
+    // maximum number of consecutive ones (or zeros) in a block, e.g., "110010111" has the longest
+    // run of ones as of 3
+    let longest_runs = utils::try_map_blocks(
+        bit_string,
+        config.block_size,
+        config.number_of_blocks,
+        |_, block| {
+            cancellation.check(TEST_NAME)?;
+            Ok(count_max_consecutive(block, run_bit))
+        },
+    )?;
 
-        *counts.entry(max_consecutive_ones).or_insert(0) += 1;
+    let mut counts: BTreeMap<i32, i32> = BTreeMap::new();
+    for max_consecutive in longest_runs {
+        *counts.entry(max_consecutive).or_insert(0) += 1;
     }
 
     log::debug!("{}: Number of runs before merge: {:?}", TEST_NAME, counts);
@@ -67,7 +196,7 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
 
     // iterate over vi_values and pi_values at the same time because both have same size
     for ((_, vi_value), &pi_value) in vi_counts.iter().zip(config.pi_values.iter()) {
-        log::trace!(
+        crate::logger::verbose_trace!(
             "{}: Current vi_value: {}, current pi_value: {}",
             TEST_NAME,
             *vi_value,
@@ -84,14 +213,41 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
         ((config.pi_values.len() as f64) - 1.0) * 0.5,
         chi_square * 0.5,
     );
-    log::info!("{}: p-value = {}", TEST_NAME, p_value);
+    log::info!(
+        "{}: p-value = {} ('{:?}' runs)",
+        TEST_NAME,
+        p_value,
+        run_bit
+    );
+
+    phase.exit();
 
     // capture the current time after the test got executed and calculate elapsed time
     let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
 
-    Ok(p_value)
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: chi_square,
+        parameters: utils::parameter_map(&[
+            ("n", length),
+            ("M", config.block_size),
+            ("N", config.number_of_blocks),
+        ]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: vi_counts.values().map(|&count| count as usize).collect(),
+    };
+    span.record_result(&result);
+
+    Ok(result)
 }
 
 /// Evaluate bit string length and select configuration parameters based on it.
@@ -99,46 +255,68 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
 /// # Arguments
 ///
 /// length - Bit string length
+/// min_length - The minimum bit string length, constants::MIN_LENGTH unless overridden
 ///
 /// # Return
 ///
 /// Ok(config) - Config parameters based on bit string size
 /// Err(err) - Some error occured
-fn get_longest_run_config(length: usize) -> Result<customtypes::LongestRunConfig<'static>> {
+pub(crate) fn get_longest_run_config(
+    length: usize,
+    min_length: usize,
+) -> Result<customtypes::LongestRunConfig<'static>> {
     log::trace!("longest_run::get_longest_run_config()");
 
     // it is crucial to have at least 128 bit passed for the test
+    if length < min_length {
+        return Err(customtypes::NistError::insufficient_length(
+            TEST_NAME, min_length, length,
+        ));
+    }
+
+    // with an overridden minimum length, shorter bit strings use the smallest configuration with
+    // as many blocks as fit into the bit string
     if length < constants::MIN_LENGTH {
-        anyhow::bail!(
-            "{}: Bit string needs at least {} bits! Actual length: {}",
-            TEST_NAME,
-            constants::MIN_LENGTH,
-            length
-        );
+        let number_of_blocks = length / constants::MIN_SIZE_M;
+        if number_of_blocks == 0 {
+            return Err(customtypes::NistError::insufficient_length(
+                TEST_NAME,
+                constants::MIN_SIZE_M,
+                length,
+            ));
+        }
+
+        return Ok(customtypes::LongestRunConfig::create(
+            constants::MIN_SIZE_M,
+            number_of_blocks,
+            constants::MIN_THRESHOLDS,
+            &constants::MIN_PI_VALUES,
+        ));
     }
 
     // depending on length of bit string, choose the correct value for M (number of bits per
-    // block), N (number of blocks), thresholds (min, max) and the pre-computed pi_values
+    // block), thresholds (min, max) and the pre-computed pi_values. As in the reference
+    // implementation, all N = n / M blocks are used and trailing bits are discarded
     let config: customtypes::LongestRunConfig;
 
     if (constants::MIN_LENGTH..constants::MID_LENGTH).contains(&length) {
         config = customtypes::LongestRunConfig::create(
             constants::MIN_SIZE_M,
-            constants::MIN_SIZE_N,
+            length / constants::MIN_SIZE_M,
             constants::MIN_THRESHOLDS,
             &constants::MIN_PI_VALUES,
         );
     } else if (constants::MID_LENGTH..constants::MAX_LENGTH).contains(&length) {
         config = customtypes::LongestRunConfig::create(
             constants::MID_SIZE_M,
-            constants::MID_SIZE_N,
+            length / constants::MID_SIZE_M,
             constants::MID_THRESHOLDS,
             &constants::MID_PI_VALUES,
         );
     } else {
         config = customtypes::LongestRunConfig::create(
             constants::MAX_SIZE_M,
-            constants::MAX_SIZE_N,
+            length / constants::MAX_SIZE_M,
             constants::MAX_THRESHOLDS,
             &constants::MAX_PI_VALUES,
         );
@@ -148,34 +326,66 @@ fn get_longest_run_config(length: usize) -> Result<customtypes::LongestRunConfig
     Ok(config)
 }
 
-/// Get the longest run of ones in a given block.
+/// Get the longest run of a given bit in a given block a word at a time. Within a word, the
+/// longest run of ones is the number of times the word can be ANDed with itself shifted by one bit
+/// until it is zero. Runs spanning words are joined by the trailing and leading ones of the words.
 ///
 /// # Arguments
 ///
 /// block - The block the longest run has to be computed from
+/// run_bit - The bit the runs consist of
 ///
 /// # Return
 ///
 /// max_count - Longest run number
-fn count_max_consecutive_ones(block: &str) -> i32 {
-    log::trace!("longest_run::count_max_consecutive_ones()");
+fn count_max_consecutive(
+    block: bit_sequence::Block<'_, impl BitInput + ?Sized>,
+    run_bit: customtypes::RunBit,
+) -> i32 {
+    crate::logger::verbose_trace!("longest_run::count_max_consecutive()");
 
     let mut max_count = 0;
     let mut current_count = 0;
+    let mut remaining = block.len();
+
+    for word in block.words() {
+        let valid = remaining.min(WORD_BITS);
+        remaining -= valid;
+
+        // the bits of the runs are ones, the padding bits are zeros
+        let mask = bit_sequence::leading_mask(valid);
+        let word = match run_bit {
+            customtypes::RunBit::Ones => word,
+            customtypes::RunBit::Zeros => !word & mask,
+        };
 
-    for bit in block.chars() {
-        if bit == '1' {
-            current_count += 1;
+        // a word of ones continues the run of the previous words
+        if word == mask {
+            current_count += valid as i32;
             max_count = max_count.max(current_count);
-        } else {
-            current_count = 0
+            continue;
         }
+
+        // the run of the previous words ends with the leading ones of this word
+        max_count = max_count.max(current_count + word.leading_ones() as i32);
+
+        let mut shifted = word;
+        let mut run_length = 0;
+        while shifted != 0 {
+            shifted &= shifted << 1;
+            run_length += 1;
+        }
+        max_count = max_count.max(run_length);
+
+        // the trailing ones of the valid bits start the run of the next words
+        current_count = (word >> (WORD_BITS - valid)).trailing_ones() as i32;
     }
 
-    log::trace!(
-        "{}: Block '{}', longest run of ones: {}",
+    crate::logger::verbose_trace!(
+        "{}: Block '{}', longest run of '{:?}': {}",
         TEST_NAME,
         block,
+        run_bit,
         max_count
     );
     max_count
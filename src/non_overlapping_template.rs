@@ -8,14 +8,59 @@
 //! search for a specific m-bit pattern. If the pattern is not found, the window slides one bit position. If the
 //! pattern is found, the window is reset to the bit after the found pattern, and the search resumes."
 
+use crate::bit_sequence::BitInput;
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::progress;
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 use std::io::{BufRead, BufReader};
 
 const TEST_NAME: customtypes::Test = customtypes::Test::NonOverlappingTemplate;
 
+/// The aperiodic template tables of the NIST reference implementation, indexed by the template
+/// length minus constants::TEMPLATE_TABLE_LEN.0
+#[cfg(feature = "template-tables")]
+static TEMPLATE_TABLES: [&[u8]; 15] = [
+    include_bytes!("../templates/template2.tar.gz"),
+    include_bytes!("../templates/template3.tar.gz"),
+    include_bytes!("../templates/template4.tar.gz"),
+    include_bytes!("../templates/template5.tar.gz"),
+    include_bytes!("../templates/template6.tar.gz"),
+    include_bytes!("../templates/template7.tar.gz"),
+    include_bytes!("../templates/template8.tar.gz"),
+    include_bytes!("../templates/template9.tar.gz"),
+    include_bytes!("../templates/template10.tar.gz"),
+    include_bytes!("../templates/template11.tar.gz"),
+    include_bytes!("../templates/template12.tar.gz"),
+    include_bytes!("../templates/template13.tar.gz"),
+    include_bytes!("../templates/template14.tar.gz"),
+    include_bytes!("../templates/template15.tar.gz"),
+    include_bytes!("../templates/template16.tar.gz"),
+];
+
+impl customtypes::TemplateSet {
+    /// Get the templates of the set. Aperiodic templates are taken from the NIST template tables
+    /// if available and generated at runtime otherwise.
+    ///
+    /// # Return
+    ///
+    /// Ok(templates) - The templates to search for
+    /// Err(err) - Some error occured
+    pub fn templates(&self) -> Result<Vec<String>> {
+        log::trace!("TemplateSet::templates()");
+
+        match self {
+            customtypes::TemplateSet::Aperiodic(template_len) => get_templates(*template_len),
+            customtypes::TemplateSet::Custom(templates) => {
+                evaluate_templates(templates, &mut Vec::new())?;
+                Ok(templates.clone())
+            }
+        }
+    }
+}
+
 /// Perform the Non-overlapping Template Matching Test by determining the p-value.
 ///
 /// # Arguments
@@ -28,122 +73,650 @@ const TEST_NAME: customtypes::Test = customtypes::Test::NonOverlappingTemplate;
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str, template_len: usize, number_of_blocks: usize) -> Result<f64> {
+pub fn perform_test(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+) -> Result<f64> {
     log::trace!("non_overlapping_template::perform_test()");
 
+    Ok(run_test(bit_string, template_len, number_of_blocks)?.p_value)
+}
+
+/// Perform the Non-overlapping Template Matching Test and return its result. The p-value is the
+/// mean of the p-values of all aperiodic templates of given length. Templates longer than the
+/// embedded tables are generated at runtime up to constants::MAX_GENERATED_TEMPLATE_LEN bits,
+/// longer templates are refused.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length of templates to be used for test
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("non_overlapping_template::run_test()");
+
+    run_test_with_progress(
+        bit_string,
+        template_len,
+        number_of_blocks,
+        &progress::ignore,
+    )
+}
+
+/// Perform the Non-overlapping Template Matching Test and report the fraction of templates done to
+/// a callback, e.g. to display the progress of a long-running test.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length of templates to be used for test
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+/// progress - The callback receiving the progress of the test
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_with_progress(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+    progress: &dyn Fn(customtypes::Progress),
+) -> Result<customtypes::TestResult> {
+    log::trace!("non_overlapping_template::run_test_with_progress()");
+
+    run_test_cancellable(
+        bit_string,
+        template_len,
+        number_of_blocks,
+        progress,
+        &customtypes::CancellationToken::new(),
+        &customtypes::Limits::default(),
+    )
+}
+
+/// Perform the Non-overlapping Template Matching Test like run_test_with_progress() and stop at
+/// the next block boundary once the passed token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length of templates to be used for test
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+/// progress - The callback receiving the progress of the test
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input and the template length
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+    progress: &dyn Fn(customtypes::Progress),
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("non_overlapping_template::run_test_cancellable()");
+
+    run_test_with_template_set(
+        bit_string,
+        &customtypes::TemplateSet::Aperiodic(template_len),
+        number_of_blocks,
+        progress,
+        cancellation,
+        limits,
+    )
+}
+
+/// Perform the Non-overlapping Template Matching Test like run_test_cancellable() with the
+/// templates of the passed set, e.g. the NIST template table of a length or a handful of
+/// templates of interest. The p-value is the mean of the p-values of all templates and the
+/// parameter "m" is the length of the longest template.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_set - The templates to be searched for
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+/// progress - The callback receiving the progress of the test
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input and the template length
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_with_template_set(
+    bit_string: &(impl BitInput + ?Sized),
+    template_set: &customtypes::TemplateSet,
+    number_of_blocks: usize,
+    progress: &dyn Fn(customtypes::Progress),
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("non_overlapping_template::run_test_with_template_set()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
-    // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE)
-        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
 
-    // check if we got bit string only containing zeros or ones
-    if bit_string.chars().all(|c| c == '0') || bit_string.chars().all(|c| c == '1') {
-        anyhow::bail!("Given bit string either contains only zeros or only ones");
-    }
+    // check if bit string contains invalid characters or consists of only one kind of bit
+    let input = evaluate_bit_string(bit_string, limits.recommended_size)?;
+    let mut advisories = input.advisories;
+
+    // the longest of the passed templates determines whether the parameters are valid
+    let template_len = match template_set {
+        customtypes::TemplateSet::Aperiodic(template_len) => *template_len,
+        customtypes::TemplateSet::Custom(templates) => {
+            evaluate_templates(templates, &mut advisories)?
+        }
+    };
 
     // evaluate the other input and get the block size m
-    let block_size = evaluate_test_params(length, template_len, number_of_blocks)
-        .with_context(|| "Template length does not match defined requirements")?;
+    let block_size = evaluate_test_params(
+        input.length,
+        template_len,
+        number_of_blocks,
+        limits,
+        &mut advisories,
+    )
+    .with_context(|| "Template length does not match defined requirements")?;
 
-    // calculate number of templates
-    let number_of_templates = 2_usize.pow(template_len.try_into().unwrap()) as f64;
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
 
-    // calculate theoretical mean and variance
-    let first_fraction = 1.0 / number_of_templates;
-    let second_fraction =
-        (2.0 * (template_len as f64) - 1.0) / 2.0_f64.powf(2.0 * (template_len as f64));
+    // now iterate over each template and search for it in each substring
+    let templates = template_set
+        .templates()
+        .with_context(|| "Failed to get templates")?;
+    let reporter = progress::ProgressReporter::new(TEST_NAME, templates.len(), progress);
+    let result = compute_results(
+        bit_string,
+        &templates,
+        block_size,
+        number_of_blocks,
+        &reporter,
+        cancellation,
+    )?;
 
-    let mean = ((block_size.wrapping_sub(template_len) + 1) as f64) / number_of_templates;
-    let variance = (block_size as f64) * (first_fraction - second_fraction);
-    log::debug!(
-        "{}: Theoretical mean = {}, Variance = {}",
+    let p_values_mean = result.p_value_mean();
+    log::info!("{}: Mean of p-values = {}", TEST_NAME, p_values_mean);
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
         TEST_NAME,
-        mean,
-        variance
+        elapsed_time.as_secs_f64()
     );
 
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value: p_values_mean,
+        advisories,
+        statistic: result.chi_square_mean(),
+        parameters: utils::parameter_map(&[
+            ("n", input.length),
+            ("m", template_len),
+            ("M", block_size),
+            ("N", number_of_blocks),
+        ]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    Ok(result)
+}
+
+/// Perform the Non-overlapping Template Matching Test with templates chosen by the caller instead
+/// of all aperiodic templates of a given length.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// templates - The templates to be searched for. They may differ in length
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(p-value) - The mean of the p-values of all passed templates
+/// Err(err) - Some error occured
+pub fn perform_test_with_templates(
+    bit_string: &(impl BitInput + ?Sized),
+    templates: &[String],
+    number_of_blocks: usize,
+) -> Result<f64> {
+    log::trace!("non_overlapping_template::perform_test_with_templates()");
+
+    let result = perform_test_detailed(bit_string, templates, number_of_blocks)?;
+
+    Ok(result.p_value_mean())
+}
+
+/// Perform the Non-overlapping Template Matching Test and return the chi_square value, the p-value
+/// and the number of occurences per block of each template. Use get_templates() to test with the
+/// same templates perform_test() uses. Displaying the result renders the per-template report of
+/// the NIST reference implementation.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// templates - The templates to be searched for. They may differ in length
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(result) - The results of all passed templates
+/// Err(err) - Some error occured
+pub fn perform_test_detailed(
+    bit_string: &(impl BitInput + ?Sized),
+    templates: &[String],
+    number_of_blocks: usize,
+) -> Result<customtypes::TemplateMatchingResult> {
+    log::trace!("non_overlapping_template::perform_test_detailed()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // check if bit string contains invalid characters or consists of only one kind of bit
+    let input = evaluate_bit_string(bit_string, constants::RECOMMENDED_SIZE)?;
+    let mut advisories = input.advisories;
+
+    // check the passed templates. The longest one determines whether the parameters are valid
+    let max_template_len = evaluate_templates(templates, &mut advisories)?;
+
+    // evaluate the other input and get the block size m
+    let block_size = evaluate_test_params(
+        input.length,
+        max_template_len,
+        number_of_blocks,
+        &customtypes::Limits::default(),
+        &mut advisories,
+    )
+    .with_context(|| "Template length does not match defined requirements")?;
+
     // now iterate over each template and search for it in each substring
-    let mut p_values = Vec::<f64>::new();
-    let templates = get_templates(template_len).with_context(|| "Failed to get templates")?;
-    p_values.reserve_exact(templates.len());
+    let reporter = progress::ProgressReporter::new(TEST_NAME, templates.len(), &progress::ignore);
+    let mut result = compute_results(
+        bit_string,
+        templates,
+        block_size,
+        number_of_blocks,
+        &reporter,
+        &customtypes::CancellationToken::new(),
+    )?;
+    result.advisories = advisories;
+    log::info!(
+        "{}: Mean of p-values = {}",
+        TEST_NAME,
+        result.p_value_mean()
+    );
 
-    for template in templates {
-        let mut template_counters = Vec::<usize>::new();
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
+    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
 
-        // now iterate over blocks 1...N and count occurences of respective aperiodic template in substring
-        for block in 0..number_of_blocks {
-            let start_index = block * block_size;
-            let end_index = (block + 1) * block_size;
-            let substring = &bit_string[start_index..end_index];
+    Ok(result)
+}
 
-            let mut counter = 0;
-            let mut index = 0;
+/// Perform the Non-overlapping Template Matching Test for exactly one template instead of all
+/// aperiodic templates of a given length.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template - The template to be searched for, e.g. constants::REFERENCE_TEMPLATE
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value of the passed template
+/// Err(err) - Some error occured
+pub fn perform_test_single_template(
+    bit_string: &(impl BitInput + ?Sized),
+    template: &str,
+    number_of_blocks: usize,
+) -> Result<f64> {
+    log::trace!("non_overlapping_template::perform_test_single_template()");
 
-            while let Some(start) = substring[index..].find(&template) {
-                counter += 1;
+    perform_test_with_templates(bit_string, &[template.to_owned()], number_of_blocks)
+}
 
-                // move the index to the next possible occurence
-                index += start + template_len;
-            }
+/// Read templates from a file containing one template per line. Empty lines are skipped.
+///
+/// # Arguments
+///
+/// file_path - The path to the file containing the templates
+///
+/// # Return
+///
+/// Ok(templates) - The read templates
+/// Err(err) - Some error occured
+pub fn read_templates(file_path: &str) -> Result<Vec<String>> {
+    log::trace!("non_overlapping_template::read_templates()");
 
-            log::trace!(
-                "{}: Template '{}' in substring '{}' found {} times",
-                TEST_NAME,
-                template,
-                substring,
-                counter
-            );
-            template_counters.push(counter);
-        }
-        // compute chi_square statistics
-        let mut chi_square = 0.0;
-        for counter in &template_counters {
-            chi_square += ((*counter as f64) - mean).powf(2.0) / variance;
+    let template_file = std::fs::File::open(file_path)
+        .with_context(|| format!("Failed to open template file '{}'", file_path))?;
+
+    let templates = parse_templates(BufReader::new(template_file))
+        .with_context(|| format!("Template file '{}' is invalid", file_path))?;
+
+    log::info!(
+        "{}: Read {} templates from '{}'",
+        TEST_NAME,
+        templates.len(),
+        file_path
+    );
+
+    Ok(templates)
+}
+
+/// Parse templates given one per line. Empty lines are skipped.
+///
+/// # Arguments
+///
+/// reader - The reader providing the lines
+///
+/// # Return
+///
+/// Ok(templates) - The parsed templates
+/// Err(err) - Some error occured
+fn parse_templates(reader: impl BufRead) -> Result<Vec<String>> {
+    log::trace!("non_overlapping_template::parse_templates()");
+
+    // read the contents line by line. A line failing to read fails the whole file instead of
+    // shortening the templates
+    let mut templates = Vec::<String>::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.with_context(|| format!("Failed to read line {}", index + 1))?;
+        let template = line.trim();
+        if !template.is_empty() {
+            templates.push(template.to_owned());
         }
-        log::trace!(
-            "{}: Chi_square = {} for template '{}'",
+    }
+    evaluate_templates(&templates, &mut Vec::new())?;
+
+    Ok(templates)
+}
+
+/// Check the bit string for validity. Besides invalid characters, bit strings only consisting of
+/// zeros or ones are rejected.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be evaluated
+/// recommended_size - Attach an advisory if passed bit string has not recommended size
+///
+/// # Return
+///
+/// Ok(input) - Return length, number of ones and advisories of bit string if everything is okay
+/// Err(err) - Some error occured
+fn evaluate_bit_string(
+    bit_string: &(impl BitInput + ?Sized),
+    recommended_size: usize,
+) -> Result<customtypes::ValidatedInput> {
+    log::trace!("non_overlapping_template::evaluate_bit_string()");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, recommended_size)
+        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+
+    // check if we got bit string only containing zeros or ones
+    if input.ones == 0 || input.ones == input.length {
+        return Err(customtypes::NistError::NotApplicable {
+            test: TEST_NAME,
+            reason: "Given bit string either contains only zeros or only ones".to_owned(),
+        });
+    }
+
+    Ok(input)
+}
+
+/// Check passed templates for validity and return the length of the longest one.
+///
+/// # Arguments
+///
+/// templates - The templates to be evaluated
+/// advisories - Collects advisories about templates violating the test assumptions
+///
+/// # Return
+///
+/// Ok(max_template_len) - The length of the longest template
+/// Err(err) - Some error occured
+fn evaluate_templates(
+    templates: &[String],
+    advisories: &mut Vec<customtypes::Advisory>,
+) -> Result<usize> {
+    log::trace!("non_overlapping_template::evaluate_templates()");
+
+    if templates.is_empty() {
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            chi_square,
-            template
-        );
+            "templates",
+            "No templates passed".to_owned(),
+        ));
+    }
 
-        // now compute p-value for current template with incomplete gamma function
-        let p_value = if chi_square == 0.0 {
-            1.0
-        } else {
-            statrs::function::gamma::gamma_ur((number_of_blocks as f64) * 0.5, chi_square * 0.5)
-        };
+    for template in templates {
+        if template.len() < constants::TEMPLATE_LEN.0
+            || template.chars().any(|c| c != '0' && c != '1')
+        {
+            return Err(customtypes::NistError::invalid_parameter(
+                TEST_NAME,
+                "templates",
+                format!(
+                    "Template '{}' is either shorter than {} or contains invalid character(s)",
+                    template,
+                    constants::TEMPLATE_LEN.0
+                ),
+            ));
+        }
 
-        if p_value < constants::P_VALUE_THRESHOLD {
+        // mean and variance of the test assume aperiodic templates
+        let template_len = template.len();
+        if (1..template_len).any(|shift| template[shift..] == template[..template_len - shift]) {
             log::warn!(
-                "{}: p-value ({}) for template '{}' is below threshold",
+                "{}: Template '{}' is periodic. Results may not be statistically valid",
                 TEST_NAME,
-                p_value,
-                &template
+                template
             );
+            advisories.push(customtypes::Advisory::weak(
+                "B",
+                format!("Template '{}' is periodic", template),
+            ));
         }
+    }
 
-        log::trace!(
-            "{}: p-value = {} for template '{}'",
-            TEST_NAME,
-            p_value,
-            template
-        );
+    let max_template_len = templates
+        .iter()
+        .map(|template| template.len())
+        .max()
+        .unwrap_or(0);
+
+    Ok(max_template_len)
+}
+
+/// Search each template in each block and compute its p-value.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// templates - The templates to be searched for
+/// block_size - The block size M
+/// number_of_blocks - The number of blocks N
+/// progress - Reporter receiving the number of templates done
+/// cancellation - The token to stop the computation with
+///
+/// # Return
+///
+/// Ok(result) - The chi_square value, p-value and counts per block of each template
+/// Err(err) - Some error occured
+fn compute_results(
+    bit_string: &(impl BitInput + ?Sized),
+    templates: &[String],
+    block_size: usize,
+    number_of_blocks: usize,
+    progress: &progress::ProgressReporter,
+    cancellation: &customtypes::CancellationToken,
+) -> Result<customtypes::TemplateMatchingResult> {
+    log::trace!("non_overlapping_template::compute_results()");
+
+    let mut result = customtypes::TemplateMatchingResult::default();
+
+    // check the bit string once. The blocks are read a word at a time for each template instead of
+    // being copied
+    let blocks = utils::blocks(bit_string, block_size)?.take(number_of_blocks);
+
+    // the templates of a batch are processed in parallel with the "parallel" feature
+    let mut done = 0;
+    for batch in templates.chunks(utils::parallel_batch_size()) {
+        let batch_results = utils::try_map(batch, |template| {
+            let template_len = template.len();
+
+            // calculate number of possible templates 2^m. Since the template length is not bounded
+            // by the pre-computed templates anymore, use floating point arithmetic to avoid
+            // overflows
+            let number_of_templates = 2.0_f64.powf(template_len as f64);
+
+            // calculate theoretical mean and variance
+            let first_fraction = 1.0 / number_of_templates;
+            let second_fraction =
+                (2.0 * (template_len as f64) - 1.0) / 2.0_f64.powf(2.0 * (template_len as f64));
+
+            let mean = ((block_size - template_len + 1) as f64) / number_of_templates;
+            let variance = (block_size as f64) * (first_fraction - second_fraction);
+            crate::logger::verbose_trace!(
+                "{}: Theoretical mean = {}, Variance = {} for template '{}'",
+                TEST_NAME,
+                mean,
+                variance,
+                template
+            );
 
-        p_values.push(p_value);
+            let pattern = utils::pack_template(TEST_NAME, template)?;
+            let mut template_counters = Vec::<usize>::new();
+
+            // now iterate over blocks 1...N and count occurences of respective aperiodic template in
+            // block. The window moves past each match to the next possible occurence
+            for block in blocks.clone() {
+                cancellation.check(TEST_NAME)?;
+
+                let counter = block.count_pattern(pattern, template_len, false);
+                crate::logger::verbose_trace!(
+                    "{}: Template '{}' in block '{}' found {} times",
+                    TEST_NAME,
+                    template,
+                    block,
+                    counter
+                );
+                template_counters.push(counter);
+            }
+            // compute chi_square statistics
+            let mut chi_square = 0.0;
+            for counter in &template_counters {
+                chi_square += ((*counter as f64) - mean).powf(2.0) / variance;
+            }
+            crate::logger::verbose_trace!(
+                "{}: Chi_square = {} for template '{}'",
+                TEST_NAME,
+                chi_square,
+                template
+            );
+
+            // now compute p-value for current template with incomplete gamma function
+            let p_value = if chi_square == 0.0 {
+                1.0
+            } else {
+                statrs::function::gamma::gamma_ur((number_of_blocks as f64) * 0.5, chi_square * 0.5)
+            };
+
+            if p_value < constants::P_VALUE_THRESHOLD {
+                log::warn!(
+                    "{}: p-value ({}) for template '{}' is below threshold",
+                    TEST_NAME,
+                    p_value,
+                    template
+                );
+            }
+
+            crate::logger::verbose_trace!(
+                "{}: p-value = {} for template '{}'",
+                TEST_NAME,
+                p_value,
+                template
+            );
+
+            Ok((chi_square, p_value, template_counters))
+        })?;
+
+        for (template, (chi_square, p_value, template_counters)) in batch.iter().zip(batch_results)
+        {
+            result.chi_squares.insert(template.clone(), chi_square);
+            result.p_values.insert(template.clone(), p_value);
+            result.counts.insert(template.clone(), template_counters);
+        }
+        done += batch.len();
+        progress.report(done);
     }
 
-    let p_values_mean = p_values.iter().sum::<f64>() / (p_values.len() as f64);
-    log::info!("{}: Mean of p-values = {}", TEST_NAME, p_values_mean);
+    Ok(result)
+}
 
-    // capture the current time after the test got executed and calculate elapsed time
-    let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+/// Derive a template length and number of blocks satisfying all requirements of the test for a
+/// bit string of given length. The parameters of the suite (m = 9, N = 8) are preferred. Inputs
+/// too short for them get fewer blocks first and shorter templates then.
+///
+/// # Arguments
+///
+/// bit_string_length - Length of bit string
+/// limits - The bounds of the template length and the number of blocks
+///
+/// # Return
+///
+/// Ok((template_len, number_of_blocks)) - The first valid pair in order of preference
+/// Err(err) - No template length and number of blocks fit the bit string length
+pub fn tune_params(
+    bit_string_length: usize,
+    limits: &customtypes::Limits,
+) -> Result<(usize, usize)> {
+    log::trace!("non_overlapping_template::tune_params()");
 
-    Ok(p_values_mean)
+    utils::tune_template_params(
+        TEST_NAME,
+        bit_string_length,
+        limits,
+        |template_len, number_of_blocks| {
+            evaluate_test_params(
+                bit_string_length,
+                template_len,
+                number_of_blocks,
+                limits,
+                &mut Vec::new(),
+            )
+        },
+    )
 }
 
 /// Evaluate passed test parameters and return the resulting block size M.
@@ -153,6 +726,8 @@ pub fn perform_test(bit_string: &str, template_len: usize, number_of_blocks: usi
 /// bit_string_length - Length of bit string
 /// template_len - Length of template to be searched later in substrings
 /// number_of_blocks - The number of blocks the bitstring has to be divided into
+/// limits - The bounds of the template length and the number of blocks
+/// advisories - Collects advisories about parameters outside the NIST recommendations
 ///
 /// # Return
 ///
@@ -162,40 +737,79 @@ fn evaluate_test_params(
     bit_string_length: usize,
     template_len: usize,
     number_of_blocks: usize,
+    limits: &customtypes::Limits,
+    advisories: &mut Vec<customtypes::Advisory>,
 ) -> Result<usize> {
     log::trace!("non_overlapping_template::evaluate_test_params()");
 
-    // check whether template length is between thresholds for meaningful results
-    if !(constants::TEMPLATE_LEN.0..=constants::TEMPLATE_LEN.1).contains(&template_len) {
-        anyhow::bail!(
-            "{}: Passed template length '{}' must be between {} and {}",
+    // check whether template length exceeds lower threshold for meaningful results
+    if template_len < limits.template_len.0 {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Passed template length '{}' must be at least {}",
+                template_len, limits.template_len.0
+            ),
+        ));
+    }
+
+    // the template tables end at constants::TEMPLATE_TABLE_LEN.1. Longer templates are generated at
+    // runtime, but their expected number of matches gets very small which weakens the approximation
+    if template_len > constants::TEMPLATE_TABLE_LEN.1 {
+        log::warn!(
+            "{}: Template length {} exceeds the template tables up to {}. Templates are generated at runtime up to a length of {} and the results may not be statistically valid",
             TEST_NAME,
             template_len,
-            constants::TEMPLATE_LEN.0,
-            constants::TEMPLATE_LEN.1
+            constants::TEMPLATE_TABLE_LEN.1,
+            constants::MAX_GENERATED_TEMPLATE_LEN
         );
+        advisories.push(customtypes::Advisory::weak(
+            "m",
+            format!(
+                "Template length {} exceeds the template tables up to {}, the expected number of matches is very small",
+                template_len,
+                constants::TEMPLATE_TABLE_LEN.1
+            ),
+        ));
     }
 
     // recommended sizes for template lengths: 9, 10. Log a warning if they do not match
-    if !(constants::RECOMMENDED_TEMPLATE_LEN.0..=constants::RECOMMENDED_TEMPLATE_LEN.1)
+    if !(limits.recommended_template_len.0..=limits.recommended_template_len.1)
         .contains(&template_len)
     {
         log::warn!(
             "{}: Recommended size for template length: {}, {}",
             TEST_NAME,
-            constants::RECOMMENDED_TEMPLATE_LEN.0,
-            constants::RECOMMENDED_TEMPLATE_LEN.1
+            limits.recommended_template_len.0,
+            limits.recommended_template_len.1
         );
+        advisories.push(customtypes::Advisory::weak(
+            "m",
+            format!(
+                "Template length {} differs from the recommended lengths {} and {}",
+                template_len, limits.recommended_template_len.0, limits.recommended_template_len.1
+            ),
+        ));
     }
 
     // check number of blocks
-    if number_of_blocks > constants::RECOMMENDED_SIZE {
-        anyhow::bail!(
-            "{}: Number of blocks N ({}) is greater than recommended size ({})",
+    if number_of_blocks == 0 {
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            number_of_blocks,
-            constants::RECOMMENDED_SIZE
-        );
+            "N",
+            "Number of blocks N must be at least 1".to_owned(),
+        ));
+    }
+    if number_of_blocks > limits.max_number_of_blocks {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "N",
+            format!(
+                "Number of blocks N ({}) is greater than recommended size ({})",
+                number_of_blocks, limits.max_number_of_blocks
+            ),
+        ));
     }
 
     // construct block size M to get the substrings to be tested
@@ -203,12 +817,26 @@ fn evaluate_test_params(
     let recommended_size = bit_string_length / 100;
 
     if block_size <= recommended_size {
-        anyhow::bail!(
-            "{}: Block size M ({}) is less than or equal to {}. Choose smaller number of blocks",
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            block_size,
-            recommended_size
-        );
+            "M",
+            format!(
+                "Block size M ({}) is less than or equal to {}. Choose smaller number of blocks",
+                block_size, recommended_size
+            ),
+        ));
+    }
+
+    // the template has to fit into a block
+    if template_len > block_size {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Template length ({}) exceeds block size M ({})",
+                template_len, block_size
+            ),
+        ));
     }
 
     log::info!(
@@ -222,54 +850,99 @@ fn evaluate_test_params(
     Ok(block_size)
 }
 
-/// Get pre-computed templates based on passed template length.
+/// Generate all aperiodic templates of given length. A template is aperiodic if it cannot overlap
+/// with a shifted copy of itself. The 2^m candidates are checked as integers, so only the
+/// aperiodic templates are formatted.
 ///
 /// # Arguments
 ///
-/// template_len - Length of templates to be used for the test
+/// template_len - Length of templates to be generated, at most MAX_GENERATED_TEMPLATE_LEN
 ///
 /// # Return
 ///
-/// Ok(templates) - The extracted templates from file
+/// Ok(templates) - The generated templates in ascending order
 /// Err(err) - Some error occured
-fn get_templates(template_len: usize) -> Result<Vec<String>> {
-    log::trace!("non_overlapping_template::get_templates()");
+pub fn generate_aperiodic_templates(template_len: usize) -> Result<Vec<String>> {
+    log::trace!("non_overlapping_template::generate_aperiodic_templates()");
 
-    // check whether template file already exists in /tmp (due to previous runs). Therefore no
-    // unpacking needed anymore
-    let template_file_path =
-        constants::TMP_DIR.to_owned() + "/template" + &template_len.to_string();
-    if !std::path::Path::new(&template_file_path).exists() {
-        // create path to templates to use
-        let template_path = match std::env::current_dir() {
-            Ok(path) => path,
-            Err(err) => anyhow::bail!("Failed to retrieve current working directory: {}", err),
-        };
-
-        let template_archive = template_path.to_string_lossy().into_owned()
-            + constants::TEMPLATE_SUB_PATH
-            + &template_len.to_string()
-            + ".tar.gz";
-
-        // now unpack the archive to tmp directory and read in the particular templates from file
-        utils::untar_archive(&template_archive, constants::TMP_DIR).with_context(|| {
-            format!("Failed to unpack template archive '{}'", &template_archive)
-        })?;
+    // all 2^m possible templates have to be enumerated
+    if template_len == 0 || template_len > constants::MAX_GENERATED_TEMPLATE_LEN {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Cannot generate templates of length {}, the maximum is {}",
+                template_len,
+                constants::MAX_GENERATED_TEMPLATE_LEN
+            ),
+        ));
     }
+    // a template is periodic if its prefix of some length k < m equals its suffix of length k
+    let templates: Vec<String> = (0..(1_u64 << template_len))
+        .filter(|&template| {
+            (1..template_len).all(|k| template >> (template_len - k) != template & ((1 << k) - 1))
+        })
+        .map(|template| format!("{:0width$b}", template, width = template_len))
+        .collect();
 
-    // read the file contents line by line
-    let template_file = std::fs::File::open(&template_file_path)
-        .with_context(|| format!("Failed to open template file '{}'", &template_file_path))?;
+    log::debug!(
+        "{}: Generated {} aperiodic templates of length {}",
+        TEST_NAME,
+        templates.len(),
+        template_len
+    );
 
-    let reader = BufReader::new(template_file);
-    let mut templates = Vec::<String>::new();
+    Ok(templates)
+}
 
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            templates.push(line);
-        }
+/// Get the aperiodic templates of given length from the NIST template tables. If there is no table
+/// for the given length, the templates are generated at runtime in the same order.
+///
+/// # Arguments
+///
+/// template_len - Length of templates to be used for the test
+///
+/// # Return
+///
+/// Ok(templates) - The templates in ascending order
+/// Err(err) - Some error occured
+pub fn get_templates(template_len: usize) -> Result<Vec<String>> {
+    log::trace!("non_overlapping_template::get_templates()");
+
+    // without the "template-tables" feature, all templates are generated at runtime
+    #[cfg(feature = "template-tables")]
+    if (constants::TEMPLATE_TABLE_LEN.0..=constants::TEMPLATE_TABLE_LEN.1).contains(&template_len) {
+        return extract_templates(template_len);
     }
 
+    generate_aperiodic_templates(template_len)
+}
+
+/// Extract the templates of given length from the embedded template tables.
+///
+/// # Arguments
+///
+/// template_len - Length of templates to be used for the test
+///
+/// # Return
+///
+/// Ok(templates) - The extracted templates
+/// Err(err) - Some error occured
+#[cfg(feature = "template-tables")]
+fn extract_templates(template_len: usize) -> Result<Vec<String>> {
+    log::trace!("non_overlapping_template::extract_templates()");
+
+    // each archive contains a single file listing one template per line
+    let table = TEMPLATE_TABLES[template_len - constants::TEMPLATE_TABLE_LEN.0];
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(table));
+    let entry = archive
+        .entries()?
+        .next()
+        .with_context(|| format!("Template table of length {} is empty", template_len))??;
+
+    let templates = parse_templates(BufReader::new(entry))
+        .with_context(|| format!("Template table of length {} is invalid", template_len))?;
+
     log::info!("Extracted {} templates to test with", templates.len());
 
     Ok(templates)
@@ -7,130 +7,1075 @@
 //! window to search for a specific m-bit pattern. As with the test in Section 2.7, if the pattern is not found,
 //! the window slides one bit position. The difference between this test and the test in Section 2.7 is that
 //! when the pattern is found, the window slides only one bit before resuming the search."
+//!
+//! perform_test(), run_test() and their variants with a block size and run_test_streaming()
+//! follow NIST SP 800-22 and its reference implementation with the all-ones template. The
+//! functions ending in "_exhaustive" search for all 2^m templates instead and average their
+//! p-values, which is not the statistic of NIST.
 
+use crate::bit_sequence::BitInput;
+use crate::bit_source::{self, BitSource};
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::progress;
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 
 const TEST_NAME: customtypes::Test = customtypes::Test::OverlappingTemplate;
 
-/// Perform the Overlapping Template Matching Test by determining the p-value.
+/// Perform the Overlapping Template Matching Test of NIST SP 800-22 by determining the p-value.
+/// The bit string is divided into N blocks of M = n / N bits.
 ///
 /// # Arguments
 ///
 /// bit_string - The bit string to be tested for randomness
-/// template_len - Length of templates to be used for test
-/// number_of_blocks - The number of blocks the bit string has to be divided into
+/// template_len - Length m of the all-ones template, 9 in NIST SP 800-22
+/// number_of_blocks - The number of blocks N the bit string has to be divided into
 ///
 /// # Return
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str, template_len: usize, number_of_blocks: usize) -> Result<f64> {
+pub fn perform_test(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+) -> Result<f64> {
     log::trace!("overlapping_template::perform_test()");
 
+    Ok(run_test(bit_string, template_len, number_of_blocks)?.p_value)
+}
+
+/// Perform the Overlapping Template Matching Test of NIST SP 800-22 and return its result. The
+/// bit string is divided into N blocks of M = n / N bits. Use run_test_with_block_size() to pass
+/// M = 1032 of NIST SP 800-22 instead.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length m of the all-ones template, 9 in NIST SP 800-22
+/// number_of_blocks - The number of blocks N the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("overlapping_template::run_test()");
+
+    let length = bit_string.bit_len();
+    if number_of_blocks == 0 || number_of_blocks > length {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "N",
+            format!(
+                "Number of blocks N ({}) must be between 1 and the bit string length ({})",
+                number_of_blocks, length
+            ),
+        ));
+    }
+
+    run_test_with_block_size(bit_string, template_len, length / number_of_blocks)
+}
+
+/// Perform the Overlapping Template Matching Test of NIST SP 800-22 with given block size by
+/// determining the p-value.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length m of the all-ones template, 9 in NIST SP 800-22
+/// block_size - The block size M, 1032 in NIST SP 800-22
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value which indicates whether randomness is given or not
+/// Err(err) - Some error occured
+pub fn perform_test_with_block_size(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    block_size: usize,
+) -> Result<f64> {
+    log::trace!("overlapping_template::perform_test_with_block_size()");
+
+    Ok(run_test_with_block_size(bit_string, template_len, block_size)?.p_value)
+}
+
+/// Perform the Overlapping Template Matching Test of NIST SP 800-22 with given block size and
+/// return its result. The occurences of the all-ones template in each block are classified as 0,
+/// ..., 4 and at least 5, and the classes are compared to their probabilities with a chi_square
+/// test.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length m of the all-ones template, 9 in NIST SP 800-22
+/// block_size - The block size M, 1032 in NIST SP 800-22
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_with_block_size(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    block_size: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("overlapping_template::run_test_with_block_size()");
+
+    run_test_cancellable(
+        bit_string,
+        template_len,
+        block_size,
+        &customtypes::CancellationToken::new(),
+        &customtypes::Limits::default(),
+    )
+}
+
+/// Perform the Overlapping Template Matching Test of NIST SP 800-22 like
+/// run_test_with_block_size() and stop at the next block boundary once the passed token got
+/// cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length m of the all-ones template, 9 in NIST SP 800-22
+/// block_size - The block size M, 1032 in NIST SP 800-22
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input and the template length
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    block_size: usize,
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("overlapping_template::run_test_cancellable()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(
+    let input = utils::evaluate_bit_string(
         TEST_NAME,
         bit_string,
         constants::RECOMMENDED_SIZE_OVERLAPPING_TEMPLATE,
     )
     .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let mut advisories = input.advisories;
 
-    // evaluate the other input and get the block size m
-    let block_size = evaluate_test_params(length, template_len, number_of_blocks)
-        .with_context(|| "Template length does not match defined requirements")?;
+    // evaluate the other input and get the number of blocks N and the class probabilities
+    let number_of_blocks = evaluate_block_size(
+        input.length,
+        template_len,
+        block_size,
+        limits,
+        &mut advisories,
+    )
+    .with_context(|| "Template length or block size does not match defined requirements")?;
+    let probabilities =
+        evaluate_probabilities(template_len, block_size, number_of_blocks, &mut advisories);
 
-    // calculate number of templates to be searched
-    let number_of_templates = 2_usize.pow(template_len.try_into().unwrap());
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
 
-    // calculate theoretical mean and variance
-    let first_fraction = 1.0 / (number_of_templates as f64);
-    let second_fraction =
-        (2.0 * (template_len as f64) - 1.0) / 2.0_f64.powf(2.0 * (template_len as f64));
+    // count the occurences of the all-ones template in each block. The window slides one bit
+    // after a match, so a run of r >= m ones contains r - m + 1 occurences
+    let all_ones = utils::pack_template(TEST_NAME, &"1".repeat(template_len))?;
+    let occurences = utils::try_map_blocks(
+        bit_string,
+        block_size,
+        number_of_blocks,
+        |current_block, block| {
+            cancellation.check(TEST_NAME)?;
 
-    let mean = ((block_size - template_len + 1) as f64) / (number_of_templates as f64);
-    let variance = (block_size as f64) * (first_fraction - second_fraction);
-    log::debug!(
-        "{}: Theoretical mean = {}, Variance = {}",
-        TEST_NAME,
-        mean,
-        variance
-    );
+            let occurences = block.count_pattern(all_ones, template_len, true);
+            crate::logger::verbose_trace!(
+                "{}: Block {}/{}: {} occurences",
+                TEST_NAME,
+                current_block + 1,
+                number_of_blocks,
+                occurences
+            );
 
-    // now iterate over each template and search for it in each substring
-    let mut p_values = Vec::<f64>::new();
+            Ok(occurences)
+        },
+    )?;
 
-    for num in 0..number_of_templates {
-        let template = format!("{:0width$b}", num, width = template_len);
-        let mut template_counters = Vec::<usize>::new();
+    // classify the blocks by their number of occurences, the last class covering at least five
+    let mut nu = [0_usize; constants::CLASSES_OVERLAPPING_TEMPLATE];
+    for occurences in occurences {
+        nu[occurences.min(constants::CLASSES_OVERLAPPING_TEMPLATE - 1)] += 1;
+    }
 
-        // now iterate over blocks 1...N and count occurences of respective template in substring
-        for block in 0..number_of_blocks {
-            let start_index = block * block_size;
-            let end_index = (block + 1) * block_size;
-            let substring = &bit_string[start_index..end_index];
+    Ok(compute_result(
+        &nu,
+        &probabilities,
+        utils::parameter_map(&[
+            ("n", input.length),
+            ("m", template_len),
+            ("M", block_size),
+            ("N", number_of_blocks),
+        ]),
+        advisories,
+        start_time,
+        &span,
+        phase,
+    ))
+}
 
-            let mut counter = 0;
-            let mut index = 0;
+/// Perform the Overlapping Template Matching Test of NIST SP 800-22 on the bits of a source,
+/// which are read in chunks instead of being held in memory. The run of ones within the current
+/// block is carried across the chunks, so the source may be of any length. The bits after the
+/// last complete block are read but not tested.
+///
+/// # Arguments
+///
+/// source - The source of the bits to be tested for randomness
+/// template_len - Length m of the all-ones template, 9 in NIST SP 800-22
+/// block_size - The block size M, 1032 in NIST SP 800-22
+/// limits - The thresholds applied to the input and the template length
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_streaming(
+    source: &mut dyn BitSource,
+    template_len: usize,
+    block_size: usize,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("overlapping_template::run_test_streaming()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
 
-            while let Some(start) = substring[index..].find(&template) {
-                counter += 1;
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("reading");
 
-                // move the index to the next possible occurence
-                index += start + template_len;
+    // classify each complete block by the occurences of the all-ones template. A run of r >= m
+    // ones contains r - m + 1 occurences, so every one extending a run to m or more bits is one
+    let mut nu = [0_usize; constants::CLASSES_OVERLAPPING_TEMPLATE];
+    let mut position_in_block = 0;
+    let mut run = 0;
+    let mut occurences = 0;
+    let length = bit_source::for_each_chunk(source, |bits| {
+        for &bit in bits {
+            run = if bit { run + 1 } else { 0 };
+            if run >= template_len {
+                occurences += 1;
             }
+            position_in_block += 1;
 
-            log::trace!(
-                "{}: Template '{}' in substring '{}' found {} times",
-                TEST_NAME,
-                template,
-                substring,
-                counter
-            );
-            template_counters.push(counter);
-        }
-        // compute chi_square statistics
-        let mut chi_square = 0.0;
-        for counter in &template_counters {
-            chi_square += ((*counter as f64) - mean).powf(2.0) / variance;
+            // the run does not reach into the next block
+            if position_in_block == block_size {
+                nu[occurences.min(constants::CLASSES_OVERLAPPING_TEMPLATE - 1)] += 1;
+                position_in_block = 0;
+                run = 0;
+                occurences = 0;
+            }
         }
-        log::trace!(
-            "{}: Chi_square = {} for template '{}'",
-            TEST_NAME,
-            chi_square,
-            template
-        );
+        Ok(())
+    })?;
+    if length == 0 {
+        return Err(customtypes::NistError::insufficient_length(TEST_NAME, 1, 0));
+    }
+    let mut advisories = Vec::<customtypes::Advisory>::new();
+    utils::check_recommended_size(
+        TEST_NAME,
+        length,
+        constants::RECOMMENDED_SIZE_OVERLAPPING_TEMPLATE,
+        &mut advisories,
+    );
 
-        // now compute p-value for current template with incomplete gamma function
-        let p_value = if chi_square == 0.0 {
-            1.0
-        } else {
-            statrs::function::gamma::gamma_ur((number_of_blocks as f64) * 0.5, chi_square * 0.5)
-        };
-        log::trace!(
-            "{}: p-value = {} for template '{}'",
+    // evaluate the other input and get the number of blocks N and the class probabilities
+    let number_of_blocks =
+        evaluate_block_size(length, template_len, block_size, limits, &mut advisories)
+            .with_context(|| "Template length or block size does not match defined requirements")?;
+    let probabilities =
+        evaluate_probabilities(template_len, block_size, number_of_blocks, &mut advisories);
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    Ok(compute_result(
+        &nu,
+        &probabilities,
+        utils::parameter_map(&[
+            ("n", length),
+            ("m", template_len),
+            ("M", block_size),
+            ("N", number_of_blocks),
+        ]),
+        advisories,
+        start_time,
+        &span,
+        phase,
+    ))
+}
+
+/// Compute the class probabilities of the test and warn if the expected number of blocks of a
+/// class is too small for the chi_square approximation.
+///
+/// # Arguments
+///
+/// template_len - Length m of the all-ones template
+/// block_size - The block size M
+/// number_of_blocks - The number of complete blocks N
+/// advisories - Collects advisories about parameters outside the NIST recommendations
+///
+/// # Return
+///
+/// probabilities - pi_0, ..., pi_5
+fn evaluate_probabilities(
+    template_len: usize,
+    block_size: usize,
+    number_of_blocks: usize,
+    advisories: &mut Vec<customtypes::Advisory>,
+) -> [f64; constants::CLASSES_OVERLAPPING_TEMPLATE] {
+    log::trace!("overlapping_template::evaluate_probabilities()");
+
+    let probabilities = compute_probabilities(template_len, block_size);
+    log::debug!(
+        "{}: Probabilities pi_0, ..., pi_5 = {:?}",
+        TEST_NAME,
+        probabilities
+    );
+
+    let min_expected =
+        (number_of_blocks as f64) * probabilities.iter().copied().fold(f64::INFINITY, f64::min);
+    if min_expected < constants::MIN_EXPECTED_COUNT_OVERLAPPING_TEMPLATE {
+        log::warn!(
+            "{}: Expected number of blocks of a class ({}) is less than {}",
             TEST_NAME,
-            p_value,
-            template
+            min_expected,
+            constants::MIN_EXPECTED_COUNT_OVERLAPPING_TEMPLATE
         );
+        advisories.push(customtypes::Advisory::weak(
+            "N",
+            format!(
+                "Expected number of blocks of a class {:.3} is less than {}",
+                min_expected,
+                constants::MIN_EXPECTED_COUNT_OVERLAPPING_TEMPLATE
+            ),
+        ));
+    }
+
+    probabilities
+}
+
+/// Compute the chi_square statistic and the p-value of the test from the blocks per class and
+/// assemble the result.
+///
+/// # Arguments
+///
+/// nu - The number of blocks v_0, ..., v_5 per class
+/// probabilities - The class probabilities pi_0, ..., pi_5
+/// parameters - The parameters of the test, recorded in its result
+/// advisories - The advisories collected while validating the input
+/// start_time - The time the test got started at
+/// span - The span of the test
+/// phase - The span of the computation phase
+///
+/// # Return
+///
+/// result - The p-value, statistic, parameters, elapsed time and advisories of the test
+fn compute_result(
+    nu: &[usize; constants::CLASSES_OVERLAPPING_TEMPLATE],
+    probabilities: &[f64; constants::CLASSES_OVERLAPPING_TEMPLATE],
+    parameters: std::collections::BTreeMap<String, usize>,
+    advisories: Vec<customtypes::Advisory>,
+    start_time: std::time::Instant,
+    span: &telemetry::Span,
+    phase: telemetry::Span,
+) -> customtypes::TestResult {
+    log::trace!("overlapping_template::compute_result()");
+
+    log::debug!("{}: Blocks per class v_0, ..., v_5 = {:?}", TEST_NAME, nu);
+    let number_of_blocks = nu.iter().sum::<usize>() as f64;
 
-        p_values.push(p_value);
+    // chi_square = sum over i of (v_i - N * pi_i)^2 / (N * pi_i)
+    let chi_square: f64 = nu
+        .iter()
+        .zip(probabilities.iter())
+        .map(|(&count, &probability)| {
+            let expected = number_of_blocks * probability;
+            ((count as f64) - expected).powi(2) / expected
+        })
+        .sum();
+    log::debug!("{}: Value of chi_square: {}", TEST_NAME, chi_square);
+
+    // finally compute p-value with the incomplete gamma function: igamc(K/2, chi_square/2)
+    let p_value = statrs::function::gamma::gamma_ur(
+        ((constants::CLASSES_OVERLAPPING_TEMPLATE - 1) as f64) * 0.5,
+        chi_square * 0.5,
+    );
+    log::info!("{}: p-value = {}", TEST_NAME, p_value);
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories,
+        statistic: chi_square,
+        parameters,
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: nu.to_vec(),
+    };
+    span.record_result(&result);
+
+    result
+}
+
+/// Compute the probabilities pi_0, ..., pi_5 that the all-ones template of length m occurs 0,
+/// ..., 4 and at least 5 times in a block of M bits, as in the reference implementation: with
+/// eta = (M - m + 1) / 2^(m + 1), pi_0 = exp(-eta) and for u >= 1
+/// pi_u = exp(-eta) / 2^u * sum over l = 1, ..., u of eta^l / l! * binomial(u - 1, l - 1).
+/// The example of NIST SP 800-22 uses these probabilities instead of the more exact
+/// PI_VALUES_OVERLAPPING_TEMPLATE, so they reproduce the published p-values. The callers ensure
+/// 1 <= m <= M by evaluate_block_size() beforehand.
+///
+/// # Arguments
+///
+/// template_len - Length m of the all-ones template
+/// block_size - The block size M
+///
+/// # Return
+///
+/// probabilities - pi_0, ..., pi_5
+pub(crate) fn compute_probabilities(
+    template_len: usize,
+    block_size: usize,
+) -> [f64; constants::CLASSES_OVERLAPPING_TEMPLATE] {
+    log::trace!("overlapping_template::compute_probabilities()");
+
+    use statrs::function::gamma::ln_gamma;
+
+    let lambda = ((block_size - template_len + 1) as f64) / 2.0_f64.powf(template_len as f64);
+    let eta = lambda * 0.5;
+
+    let mut probabilities = [0.0; constants::CLASSES_OVERLAPPING_TEMPLATE];
+    probabilities[0] = (-eta).exp();
+    for (u, probability) in probabilities
+        .iter_mut()
+        .enumerate()
+        .take(constants::CLASSES_OVERLAPPING_TEMPLATE - 1)
+        .skip(1)
+    {
+        let u = u as f64;
+        *probability = (1..=u as usize)
+            .map(|l| {
+                let l = l as f64;
+                (-eta - u * std::f64::consts::LN_2 + l * eta.ln() - ln_gamma(l + 1.0) + ln_gamma(u)
+                    - ln_gamma(l)
+                    - ln_gamma(u - l + 1.0))
+                .exp()
+            })
+            .sum();
     }
+    probabilities[constants::CLASSES_OVERLAPPING_TEMPLATE - 1] = 1.0
+        - probabilities[..constants::CLASSES_OVERLAPPING_TEMPLATE - 1]
+            .iter()
+            .sum::<f64>();
+
+    probabilities
+}
+
+/// Perform the Overlapping Template Matching Test with all 2^m templates of given length instead
+/// of the all-ones template, determining the mean of their p-values.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length of templates to be used for test
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value which indicates whether randomness is given or not
+/// Err(err) - Some error occured
+pub fn perform_test_exhaustive(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+) -> Result<f64> {
+    log::trace!("overlapping_template::perform_test_exhaustive()");
+
+    Ok(run_test_exhaustive(bit_string, template_len, number_of_blocks)?.p_value)
+}
+
+/// Perform the Overlapping Template Matching Test with all 2^m templates of given length and
+/// return its result. The p-value is the mean of the p-values of all templates, each computed
+/// from the chi_square value of its occurences per block.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length of templates to be used for test
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_exhaustive(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("overlapping_template::run_test_exhaustive()");
+
+    run_test_exhaustive_with_progress(
+        bit_string,
+        template_len,
+        number_of_blocks,
+        &progress::ignore,
+    )
+}
+
+/// Perform the Overlapping Template Matching Test with all 2^m templates of given length and
+/// report the fraction of templates done to a callback, e.g. to display the progress of a
+/// long-running test.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length of templates to be used for test
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+/// progress - The callback receiving the progress of the test
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_exhaustive_with_progress(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+    progress: &dyn Fn(customtypes::Progress),
+) -> Result<customtypes::TestResult> {
+    log::trace!("overlapping_template::run_test_exhaustive_with_progress()");
+
+    run_test_exhaustive_cancellable(
+        bit_string,
+        template_len,
+        number_of_blocks,
+        progress,
+        &customtypes::CancellationToken::new(),
+        &customtypes::Limits::default(),
+    )
+}
+
+/// Perform the Overlapping Template Matching Test like run_test_exhaustive_with_progress() and
+/// stop at the next block boundary once the passed token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template_len - Length of templates to be used for test
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+/// progress - The callback receiving the progress of the test
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input and the template length
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_exhaustive_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    template_len: usize,
+    number_of_blocks: usize,
+    progress: &dyn Fn(customtypes::Progress),
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("overlapping_template::run_test_exhaustive_cancellable()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(
+        TEST_NAME,
+        bit_string,
+        constants::RECOMMENDED_SIZE_OVERLAPPING_TEMPLATE,
+    )
+    .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let mut advisories = input.advisories;
+
+    // evaluate the other input and get the block size m
+    let block_size = evaluate_test_params(
+        input.length,
+        template_len,
+        number_of_blocks,
+        limits,
+        &mut advisories,
+    )
+    .with_context(|| "Template length does not match defined requirements")?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
 
-    let p_values_mean = p_values.iter().sum::<f64>() / (p_values.len() as f64);
+    // now iterate over each template and search for it in each substring
+    let templates = get_templates(template_len).with_context(|| "Failed to get templates")?;
+    let reporter = progress::ProgressReporter::new(TEST_NAME, templates.len(), progress);
+    let result = compute_results(
+        bit_string,
+        &templates,
+        block_size,
+        number_of_blocks,
+        &reporter,
+        cancellation,
+    )?;
+
+    let p_values_mean = result.p_value_mean();
     log::info!("{}: Mean of p-values = {}", TEST_NAME, p_values_mean);
 
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value: p_values_mean,
+        advisories,
+        statistic: result.chi_square_mean(),
+        parameters: utils::parameter_map(&[
+            ("n", input.length),
+            ("m", template_len),
+            ("M", block_size),
+            ("N", number_of_blocks),
+        ]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
+
+    Ok(result)
+}
+
+/// Perform the Overlapping Template Matching Test for exactly one template instead of all 2^m
+/// possible templates, like the NIST reference implementation does.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// template - The template to be searched for, e.g. constants::REFERENCE_TEMPLATE
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(p-value) - The p-value of the passed template
+/// Err(err) - Some error occured
+pub fn perform_test_single_template(
+    bit_string: &(impl BitInput + ?Sized),
+    template: &str,
+    number_of_blocks: usize,
+) -> Result<f64> {
+    log::trace!("overlapping_template::perform_test_single_template()");
+
+    let result = perform_test_detailed(bit_string, &[template.to_owned()], number_of_blocks)?;
+
+    Ok(result.p_value_mean())
+}
+
+/// Perform the Overlapping Template Matching Test for a chosen set of templates and return the
+/// chi_square value, the p-value and the number of occurences per block of each template. Use
+/// get_templates() to test with all templates of a given length.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// templates - The templates to be searched for. They may differ in length
+/// number_of_blocks - The number of blocks the bit string has to be divided into
+///
+/// # Return
+///
+/// Ok(result) - The results of all passed templates
+/// Err(err) - Some error occured
+pub fn perform_test_detailed(
+    bit_string: &(impl BitInput + ?Sized),
+    templates: &[String],
+    number_of_blocks: usize,
+) -> Result<customtypes::TemplateMatchingResult> {
+    log::trace!("overlapping_template::perform_test_detailed()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(
+        TEST_NAME,
+        bit_string,
+        constants::RECOMMENDED_SIZE_OVERLAPPING_TEMPLATE,
+    )
+    .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let mut advisories = input.advisories;
+
+    // check the passed templates. The longest one determines whether the parameters are valid
+    let max_template_len = evaluate_templates(templates)?;
+
+    // evaluate the other input and get the block size m
+    let block_size = evaluate_test_params(
+        input.length,
+        max_template_len,
+        number_of_blocks,
+        &customtypes::Limits::default(),
+        &mut advisories,
+    )
+    .with_context(|| "Template length does not match defined requirements")?;
+
+    // now iterate over each template and search for it in each substring
+    let reporter = progress::ProgressReporter::new(TEST_NAME, templates.len(), &progress::ignore);
+    let mut result = compute_results(
+        bit_string,
+        templates,
+        block_size,
+        number_of_blocks,
+        &reporter,
+        &customtypes::CancellationToken::new(),
+    )?;
+    result.advisories = advisories;
+    log::info!(
+        "{}: Mean of p-values = {}",
+        TEST_NAME,
+        result.p_value_mean()
+    );
+
     // capture the current time after the test got executed and calculate elapsed time
     let end_time = std::time::Instant::now();
     let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
     log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
 
-    Ok(p_values_mean)
+    Ok(result)
+}
+
+/// Get all 2^m possible templates of given length in ascending order.
+///
+/// # Arguments
+///
+/// template_len - Length of templates to be used for the test
+///
+/// # Return
+///
+/// Ok(templates) - All templates of given length
+/// Err(err) - Some error occured
+pub fn get_templates(template_len: usize) -> Result<Vec<String>> {
+    log::trace!("overlapping_template::get_templates()");
+
+    // all 2^m possible templates have to be enumerated
+    if template_len == 0 || template_len >= usize::BITS as usize {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!("Cannot enumerate templates of length {}", template_len),
+        ));
+    }
+
+    Ok((0..(1_usize << template_len))
+        .map(|num| format!("{:0width$b}", num, width = template_len))
+        .collect())
+}
+
+/// Check passed templates for validity and return the length of the longest one.
+///
+/// # Arguments
+///
+/// templates - The templates to be evaluated
+///
+/// # Return
+///
+/// Ok(max_template_len) - The length of the longest template
+/// Err(err) - Some error occured
+fn evaluate_templates(templates: &[String]) -> Result<usize> {
+    log::trace!("overlapping_template::evaluate_templates()");
+
+    if templates.is_empty() {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "templates",
+            "No templates passed".to_owned(),
+        ));
+    }
+
+    for template in templates {
+        if template.len() < constants::TEMPLATE_LEN.0
+            || template.chars().any(|c| c != '0' && c != '1')
+        {
+            return Err(customtypes::NistError::invalid_parameter(
+                TEST_NAME,
+                "templates",
+                format!(
+                    "Template '{}' is either shorter than {} or contains invalid character(s)",
+                    template,
+                    constants::TEMPLATE_LEN.0
+                ),
+            ));
+        }
+    }
+
+    let max_template_len = templates
+        .iter()
+        .map(|template| template.len())
+        .max()
+        .unwrap_or(0);
+
+    Ok(max_template_len)
+}
+
+/// Search each template in each block and compute its chi_square value and p-value.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// templates - The templates to be searched for
+/// block_size - The block size M
+/// number_of_blocks - The number of blocks N
+/// progress - Reporter receiving the number of templates done
+/// cancellation - The token to stop the computation with
+///
+/// # Return
+///
+/// Ok(result) - The chi_square value, p-value and counts per block of each template
+/// Err(err) - Some error occured
+fn compute_results(
+    bit_string: &(impl BitInput + ?Sized),
+    templates: &[String],
+    block_size: usize,
+    number_of_blocks: usize,
+    progress: &progress::ProgressReporter,
+    cancellation: &customtypes::CancellationToken,
+) -> Result<customtypes::TemplateMatchingResult> {
+    log::trace!("overlapping_template::compute_results()");
+
+    let mut result = customtypes::TemplateMatchingResult::default();
+
+    // check the bit string once. The blocks are read a word at a time for each template instead of
+    // being copied
+    let blocks = utils::blocks(bit_string, block_size)?.take(number_of_blocks);
+
+    // the templates of a batch are processed in parallel with the "parallel" feature
+    let mut done = 0;
+    for batch in templates.chunks(utils::parallel_batch_size()) {
+        let batch_results = utils::try_map(batch, |template| {
+            let pattern = utils::pack_template(TEST_NAME, template)?;
+            let mut template_counters = Vec::<usize>::new();
+
+            // now iterate over blocks 1...N and count occurences of respective template in block.
+            // The window slides only one bit after a match
+            for block in blocks.clone() {
+                cancellation.check(TEST_NAME)?;
+
+                let counter = block.count_pattern(pattern, template.len(), true);
+                crate::logger::verbose_trace!(
+                    "{}: Template '{}' in block '{}' found {} times",
+                    TEST_NAME,
+                    template,
+                    block,
+                    counter
+                );
+                template_counters.push(counter);
+            }
+
+            let (chi_square, p_value) =
+                compute_statistics(template, block_size, number_of_blocks, &template_counters);
+
+            Ok((chi_square, p_value, template_counters))
+        })?;
+
+        for (template, (chi_square, p_value, template_counters)) in batch.iter().zip(batch_results)
+        {
+            result.chi_squares.insert(template.clone(), chi_square);
+            result.p_values.insert(template.clone(), p_value);
+            result.counts.insert(template.clone(), template_counters);
+        }
+        done += batch.len();
+        progress.report(done);
+    }
+
+    Ok(result)
+}
+
+/// Compute the chi_square value and p-value of a template from its number of occurences per
+/// block.
+///
+/// # Arguments
+///
+/// template - The template searched for
+/// block_size - The block size M
+/// number_of_blocks - The number of blocks N
+/// template_counters - The number of occurences of the template in each block
+///
+/// # Return
+///
+/// (chi_square, p_value) - The statistic and p-value of the template
+fn compute_statistics(
+    template: &str,
+    block_size: usize,
+    number_of_blocks: usize,
+    template_counters: &[usize],
+) -> (f64, f64) {
+    let template_len = template.len();
+    let number_of_templates = 2.0_f64.powf(template_len as f64);
+
+    // calculate theoretical mean and variance
+    let first_fraction = 1.0 / number_of_templates;
+    let second_fraction =
+        (2.0 * (template_len as f64) - 1.0) / 2.0_f64.powf(2.0 * (template_len as f64));
+
+    let mean = ((block_size - template_len + 1) as f64) / number_of_templates;
+    let variance = (block_size as f64) * (first_fraction - second_fraction);
+    crate::logger::verbose_trace!(
+        "{}: Theoretical mean = {}, Variance = {} for template '{}'",
+        TEST_NAME,
+        mean,
+        variance,
+        template
+    );
+
+    // compute chi_square statistics
+    let mut chi_square = 0.0;
+    for counter in template_counters {
+        chi_square += ((*counter as f64) - mean).powf(2.0) / variance;
+    }
+    crate::logger::verbose_trace!(
+        "{}: Chi_square = {} for template '{}'",
+        TEST_NAME,
+        chi_square,
+        template
+    );
+
+    // now compute p-value for current template with incomplete gamma function
+    let p_value = if chi_square == 0.0 {
+        1.0
+    } else {
+        statrs::function::gamma::gamma_ur((number_of_blocks as f64) * 0.5, chi_square * 0.5)
+    };
+    crate::logger::verbose_trace!(
+        "{}: p-value = {} for template '{}'",
+        TEST_NAME,
+        p_value,
+        template
+    );
+
+    (chi_square, p_value)
+}
+
+/// Evaluate the parameters of the test of NIST SP 800-22 and return the number of blocks N.
+///
+/// # Arguments
+///
+/// bit_string_length - Length of bit string
+/// template_len - Length m of the all-ones template
+/// block_size - The block size M
+/// limits - The bounds of the template length
+/// advisories - Collects advisories about parameters outside the NIST recommendations
+///
+/// # Return
+///
+/// Ok(number_of_blocks) - The number of complete blocks N
+/// Err(err) - Some error occured
+fn evaluate_block_size(
+    bit_string_length: usize,
+    template_len: usize,
+    block_size: usize,
+    limits: &customtypes::Limits,
+    advisories: &mut Vec<customtypes::Advisory>,
+) -> Result<usize> {
+    log::trace!("overlapping_template::evaluate_block_size()");
+
+    // check whether template length is between thresholds for meaningful results
+    if template_len == 0 || !(limits.template_len.0..=limits.template_len.1).contains(&template_len)
+    {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Passed template length '{}' must be between {} and {}",
+                template_len, limits.template_len.0, limits.template_len.1
+            ),
+        ));
+    }
+    if !(limits.recommended_template_len.0..=limits.recommended_template_len.1)
+        .contains(&template_len)
+    {
+        log::warn!(
+            "{}: Recommended size for template length: {}, {}",
+            TEST_NAME,
+            limits.recommended_template_len.0,
+            limits.recommended_template_len.1
+        );
+        advisories.push(customtypes::Advisory::weak(
+            "m",
+            format!(
+                "Template length {} differs from the recommended lengths {} and {}",
+                template_len, limits.recommended_template_len.0, limits.recommended_template_len.1
+            ),
+        ));
+    }
+
+    // the template has to fit into a block and at least one block into the bit string
+    if block_size < template_len || block_size > bit_string_length {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "M",
+            format!(
+                "Block size M ({}) must be between the template length ({}) and the bit string length ({})",
+                block_size, template_len, bit_string_length
+            ),
+        ));
+    }
+
+    let number_of_blocks = bit_string_length / block_size;
+    log::info!(
+        "{}: Template length = {}, Block size M = {}, Number of blocks N = {}",
+        TEST_NAME,
+        template_len,
+        block_size,
+        number_of_blocks
+    );
+
+    Ok(number_of_blocks)
 }
 
 /// Evaluate passed test parameters and return the resulting block size M.
@@ -140,6 +1085,8 @@ pub fn perform_test(bit_string: &str, template_len: usize, number_of_blocks: usi
 /// bit_string_length - Length of bit string
 /// template_len - Length of template to be searched later in substrings
 /// number_of_blocks - The number of blocks the bitstring has to be divided into
+/// limits - The bounds of the template length and the number of blocks
+/// advisories - Collects advisories about parameters outside the NIST recommendations
 ///
 /// # Return
 ///
@@ -149,38 +1096,59 @@ fn evaluate_test_params(
     bit_string_length: usize,
     template_len: usize,
     number_of_blocks: usize,
+    limits: &customtypes::Limits,
+    advisories: &mut Vec<customtypes::Advisory>,
 ) -> Result<usize> {
-    log::trace!("non_overlapping_template::evaluate_test_params()");
+    log::trace!("overlapping_template::evaluate_test_params()");
 
     // check whether template length is between thresholds for meaningful results
-    if !(constants::TEMPLATE_LEN.0..constants::TEMPLATE_LEN.1 + 1).contains(&template_len) {
-        anyhow::bail!(
-            "{}: Passed template length '{}' must be between {} and {}",
+    if !(limits.template_len.0..=limits.template_len.1).contains(&template_len) {
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            template_len,
-            constants::TEMPLATE_LEN.0,
-            constants::TEMPLATE_LEN.1
-        );
+            "m",
+            format!(
+                "Passed template length '{}' must be between {} and {}",
+                template_len, limits.template_len.0, limits.template_len.1
+            ),
+        ));
     }
 
     // recommended sizes for template lengths: 9, 10. Log a warning if they do not match
-    if template_len < constants::TEMPLATE_LEN.1 - 1 {
+    if !(limits.recommended_template_len.0..=limits.recommended_template_len.1)
+        .contains(&template_len)
+    {
         log::warn!(
             "{}: Recommended size for template length: {}, {}",
             TEST_NAME,
-            constants::TEMPLATE_LEN.1 - 1,
-            constants::TEMPLATE_LEN.1
+            limits.recommended_template_len.0,
+            limits.recommended_template_len.1
         );
+        advisories.push(customtypes::Advisory::weak(
+            "m",
+            format!(
+                "Template length {} differs from the recommended lengths {} and {}",
+                template_len, limits.recommended_template_len.0, limits.recommended_template_len.1
+            ),
+        ));
     }
 
     // check number of blocks
-    if number_of_blocks > constants::RECOMMENDED_SIZE {
-        anyhow::bail!(
-            "{}: Number of blocks N ({}) is greater than recommended size ({})",
+    if number_of_blocks == 0 {
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            number_of_blocks,
-            constants::RECOMMENDED_SIZE
-        );
+            "N",
+            "Number of blocks N must be at least 1".to_owned(),
+        ));
+    }
+    if number_of_blocks > limits.max_number_of_blocks {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "N",
+            format!(
+                "Number of blocks N ({}) is greater than recommended size ({})",
+                number_of_blocks, limits.max_number_of_blocks
+            ),
+        ));
     }
 
     // construct block size M to get the substrings to be tested
@@ -188,12 +1156,26 @@ fn evaluate_test_params(
     let recommended_size = bit_string_length / 100;
 
     if block_size <= recommended_size {
-        anyhow::bail!(
-            "{}: Block size M ({}) is less than or equal to {}. Choose smaller number of blocks",
+        return Err(customtypes::NistError::invalid_parameter(
             TEST_NAME,
-            block_size,
-            recommended_size
-        );
+            "M",
+            format!(
+                "Block size M ({}) is less than or equal to {}. Choose smaller number of blocks",
+                block_size, recommended_size
+            ),
+        ));
+    }
+
+    // the template has to fit into a block
+    if template_len > block_size {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Template length ({}) exceeds block size M ({})",
+                template_len, block_size
+            ),
+        ));
     }
 
     log::info!(
@@ -0,0 +1,196 @@
+//! This module chains transforms applied to the input before testing, e.g. to test one channel of
+//! an interleaved capture or the debiased output of a raw noise source. The transforms are
+//! declared instead of being applied by ad-hoc code, and the applied pipeline is recorded in the
+//! report, so the preprocessing of a result can be reproduced.
+
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::suite;
+
+/// Builder of a sequence of transforms applied in the order they were added
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Pipeline {
+    transforms: Vec<customtypes::Transform>,
+}
+
+impl Pipeline {
+    /// Create a pipeline which passes the input unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the bits of one channel of an input interleaving several channels bit by bit.
+    ///
+    /// # Arguments
+    ///
+    /// channels - The number of interleaved channels
+    /// channel - The index of the channel to be kept, starting at 0
+    pub fn deinterleave(self, channels: usize, channel: usize) -> Self {
+        self.then(customtypes::Transform::Deinterleave { channels, channel })
+    }
+
+    /// Keep every factor-th bit, starting with the first one.
+    ///
+    /// # Arguments
+    ///
+    /// factor - The decimation factor
+    pub fn decimate(self, factor: usize) -> Self {
+        self.then(customtypes::Transform::Decimate { factor })
+    }
+
+    /// Debias the bits with the von Neumann extractor.
+    pub fn von_neumann(self) -> Self {
+        self.then(customtypes::Transform::VonNeumann)
+    }
+
+    /// Invert each bit.
+    pub fn invert(self) -> Self {
+        self.then(customtypes::Transform::Invert)
+    }
+
+    /// Append a transform to the pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// transform - The transform applied after the ones already added
+    pub fn then(mut self, transform: customtypes::Transform) -> Self {
+        self.transforms.push(transform);
+        self
+    }
+
+    /// Get the transforms of the pipeline in the order they are applied.
+    pub fn transforms(&self) -> &[customtypes::Transform] {
+        &self.transforms
+    }
+
+    /// Apply all transforms to passed bit string.
+    ///
+    /// # Arguments
+    ///
+    /// bit_string - The bit string to be transformed
+    ///
+    /// # Return
+    ///
+    /// Ok(bit_string) - The transformed bit string
+    /// Err(err) - Some error occured, e.g. a transform has invalid parameters or left no bits
+    pub fn apply(&self, bit_string: &str) -> Result<String> {
+        log::trace!("Pipeline::apply()");
+
+        if let Some((position, symbol)) = bit_string
+            .char_indices()
+            .find(|&(_, c)| c != '0' && c != '1')
+        {
+            return Err(customtypes::NistError::InvalidBit { position, symbol });
+        }
+
+        let mut bits = bit_string.as_bytes().to_vec();
+        for transform in &self.transforms {
+            bits = apply_transform(*transform, &bits)?;
+            log::debug!("Pipeline: {} left {} bits", transform, bits.len());
+
+            if bits.is_empty() {
+                return Err(customtypes::NistError::InvalidInput {
+                    reason: format!("Pipeline: {} left no bits", transform),
+                });
+            }
+        }
+
+        // only the ASCII characters '0' and '1' were copied, so the bytes are valid UTF-8
+        String::from_utf8(bits).context("Pipeline: Bits are not valid UTF-8")
+    }
+
+    /// Apply the pipeline to passed bit string and run all implemented tests on the result. The
+    /// report records the applied pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// bit_string - The bit string to be transformed and tested for randomness
+    /// config - The configuration of the suite run
+    ///
+    /// # Return
+    ///
+    /// Ok(report) - The results of all tests run on the transformed bit string
+    /// Err(err) - Some error occured
+    pub fn run_all(
+        &self,
+        bit_string: &str,
+        config: &customtypes::SuiteConfig,
+    ) -> Result<customtypes::SuiteReport> {
+        log::trace!("Pipeline::run_all()");
+
+        let transformed = self.apply(bit_string)?;
+        log::info!(
+            "Pipeline '{}' transformed {} bits into {} bits",
+            self,
+            bit_string.len(),
+            transformed.len()
+        );
+
+        let mut report = suite::run_all(&transformed, config)?;
+        report.pipeline = self.transforms.clone();
+
+        Ok(report)
+    }
+}
+
+/// Render the pipeline as its transforms joined by arrows
+impl std::fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let transforms: Vec<String> = self.transforms.iter().map(|t| t.to_string()).collect();
+        write!(f, "{}", transforms.join(" -> "))
+    }
+}
+
+/// Apply a single transform to the ASCII bits of a bit string.
+///
+/// # Arguments
+///
+/// transform - The transform to be applied
+/// bits - The ASCII characters '0' and '1' to be transformed
+///
+/// # Return
+///
+/// Ok(bits) - The transformed bits
+/// Err(err) - The parameters of the transform are invalid
+fn apply_transform(transform: customtypes::Transform, bits: &[u8]) -> Result<Vec<u8>> {
+    log::trace!("pipeline::apply_transform()");
+
+    let transformed = match transform {
+        customtypes::Transform::Deinterleave { channels, channel } => {
+            if channels < 2 || channel >= channels {
+                return Err(customtypes::NistError::InvalidParameter { test: None, name: "channel".to_owned(), reason: format!("Pipeline: Channel ({}) must be less than the number of channels ({}), which must be at least 2",
+                    channel,
+                    channels) });
+            }
+            bits.iter()
+                .skip(channel)
+                .step_by(channels)
+                .copied()
+                .collect()
+        }
+        customtypes::Transform::Decimate { factor } => {
+            if factor < 2 {
+                return Err(customtypes::NistError::InvalidParameter {
+                    test: None,
+                    name: "factor".to_owned(),
+                    reason: format!(
+                        "Pipeline: Decimation factor ({}) must be at least 2",
+                        factor
+                    ),
+                });
+            }
+            bits.iter().step_by(factor).copied().collect()
+        }
+        customtypes::Transform::VonNeumann => bits
+            .chunks_exact(2)
+            .filter(|pair| pair[0] != pair[1])
+            .map(|pair| pair[0])
+            .collect(),
+        customtypes::Transform::Invert => bits
+            .iter()
+            .map(|&bit| if bit == b'1' { b'0' } else { b'1' })
+            .collect(),
+    };
+
+    Ok(transformed)
+}
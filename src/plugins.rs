@@ -0,0 +1,306 @@
+//! This module loads proprietary tests from shared libraries, so vendors can ship in-house tests
+//! whose results appear in the same reports as the tests of NIST SP 800-22.
+//!
+//! A plugin exports the C function `nist_suite_register_tests`, returning the tests it provides:
+//!
+//! ```text
+//! typedef struct {
+//!     const char *name;
+//!     int32_t (*run)(const uint8_t *bits, size_t length, double *p_value, double *statistic);
+//! } PluginTestDescriptor;
+//!
+//! typedef struct {
+//!     uint32_t abi_version;
+//!     const PluginTestDescriptor *tests;
+//!     size_t number_of_tests;
+//! } PluginRegistration;
+//!
+//! PluginRegistration nist_suite_register_tests(void);
+//! ```
+//!
+//! The bits are passed as ASCII characters '0' and '1'. A test returns 0 on success and any
+//! other value on failure. The names and descriptors have to stay valid while the library is
+//! loaded.
+
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::utils;
+use std::collections::BTreeSet;
+use std::ffi::{c_char, CStr};
+use std::sync::Arc;
+
+/// Interface of a test whose results can be added to a report
+pub trait RandomnessTest {
+    /// Get the test the results belong to.
+    fn test(&self) -> customtypes::Test;
+
+    /// Run the test on a bit string.
+    ///
+    /// # Arguments
+    ///
+    /// bit_string - The bit string to be tested for randomness
+    ///
+    /// # Return
+    ///
+    /// Ok(result) - The result of the test
+    /// Err(err) - Some error occured
+    fn run(&self, bit_string: &str) -> Result<customtypes::TestResult>;
+}
+
+/// Function running a test of a plugin
+pub type PluginTestFn = unsafe extern "C" fn(
+    bits: *const u8,
+    length: usize,
+    p_value: *mut f64,
+    statistic: *mut f64,
+) -> i32;
+
+/// Function exported by a plugin to register its tests
+pub type PluginRegistrationFn = unsafe extern "C" fn() -> PluginRegistration;
+
+/// C ABI of a test provided by a plugin
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginTestDescriptor {
+    /// Name of the test as shown in reports, a NUL-terminated UTF-8 string
+    pub name: *const c_char,
+    /// Function running the test
+    pub run: PluginTestFn,
+}
+
+// SAFETY: descriptors are never mutated and their names are valid while the library is loaded,
+// so plugins written in Rust can keep them in statics
+unsafe impl Sync for PluginTestDescriptor {}
+
+/// C ABI of the tests registered by a plugin
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginRegistration {
+    /// The version of the ABI the plugin was built for, constants::PLUGIN_ABI_VERSION
+    pub abi_version: u32,
+    /// Array of the registered tests
+    pub tests: *const PluginTestDescriptor,
+    /// Number of registered tests
+    pub number_of_tests: usize,
+}
+
+/// A test provided by a plugin. It keeps the library loaded as long as it exists
+#[derive(Debug, Clone)]
+pub struct PluginTest {
+    test: customtypes::Test,
+    run: PluginTestFn,
+    _library: Option<Arc<libloading::Library>>,
+}
+
+impl RandomnessTest for PluginTest {
+    fn test(&self) -> customtypes::Test {
+        self.test
+    }
+
+    fn run(&self, bit_string: &str) -> Result<customtypes::TestResult> {
+        log::trace!("PluginTest::run()");
+
+        // capture the current time before executing the actual test
+        let start_time = std::time::Instant::now();
+
+        // plugins rely on the bit string consisting of '0' and '1' only
+        if bit_string.is_empty() {
+            return Err(customtypes::NistError::insufficient_length(self.test, 1, 0));
+        }
+        let input = utils::validate_bit_string(bit_string);
+        if let Some((position, symbol)) = input.first_invalid {
+            return Err(customtypes::NistError::InvalidBit { position, symbol });
+        }
+
+        let mut p_value = f64::NAN;
+        let mut statistic = f64::NAN;
+        // SAFETY: the pointer and length describe the bit string, which outlives the call, and
+        // the output pointers refer to local variables
+        let code = unsafe {
+            (self.run)(
+                bit_string.as_ptr(),
+                bit_string.len(),
+                &mut p_value,
+                &mut statistic,
+            )
+        };
+        if code != 0 {
+            return Err(customtypes::NistError::TestFailed {
+                test: self.test,
+                reason: format!("Plugin failed with code {}", code),
+            });
+        }
+        if !(0.0..=1.0).contains(&p_value) {
+            return Err(customtypes::NistError::TestFailed {
+                test: self.test,
+                reason: format!("Plugin returned invalid p-value {}", p_value),
+            });
+        }
+
+        // capture the current time after the test got executed and calculate elapsed time
+        let end_time = std::time::Instant::now();
+        let elapsed_time = end_time.duration_since(start_time);
+        log::info!(
+            "{} took {:.6} seconds",
+            self.test,
+            elapsed_time.as_secs_f64()
+        );
+
+        Ok(customtypes::TestResult {
+            test: self.test,
+            p_value,
+            advisories: Vec::new(),
+            statistic,
+            parameters: utils::parameter_map(&[("n", input.length)]),
+            elapsed_time,
+            alpha: constants::P_VALUE_THRESHOLD,
+            counts: Vec::new(),
+        })
+    }
+}
+
+/// Load a plugin and get the tests it registers.
+///
+/// # Safety
+///
+/// Loading a library runs its initialization code and the exported function is trusted to
+/// follow the ABI described in the module documentation.
+///
+/// # Arguments
+///
+/// path - The path of the shared library
+///
+/// # Return
+///
+/// Ok(tests) - The tests of the plugin
+/// Err(err) - Some error occured
+pub unsafe fn load(path: &str) -> Result<Vec<PluginTest>> {
+    log::trace!("plugins::load()");
+
+    let library = Arc::new(
+        libloading::Library::new(path)
+            .with_context(|| format!("Failed to load plugin '{}'", path))?,
+    );
+    let register = *library
+        .get::<PluginRegistrationFn>(constants::PLUGIN_REGISTRATION_SYMBOL)
+        .with_context(|| format!("Plugin '{}' does not export a registration function", path))?;
+
+    let tests = register_tests(register, Some(library))
+        .with_context(|| format!("Failed to register tests of plugin '{}'", path))?;
+    log::info!("Loaded {} tests from plugin '{}'", tests.len(), path);
+
+    Ok(tests)
+}
+
+/// Get the tests registered by a registration function, e.g. of a plugin linked statically.
+///
+/// # Safety
+///
+/// The function is trusted to follow the ABI described in the module documentation.
+///
+/// # Arguments
+///
+/// register - The registration function
+///
+/// # Return
+///
+/// Ok(tests) - The registered tests
+/// Err(err) - Some error occured
+pub unsafe fn register(register: PluginRegistrationFn) -> Result<Vec<PluginTest>> {
+    log::trace!("plugins::register()");
+
+    register_tests(register, None)
+}
+
+/// Run tests on a bit string and add their results to a report.
+///
+/// # Arguments
+///
+/// report - The report the results are added to
+/// bit_string - The bit string to be tested for randomness
+/// tests - The tests to be run
+///
+/// # Return
+///
+/// Ok() - All tests were run
+/// Err(err) - Some error occured
+pub fn run_tests(
+    report: &mut customtypes::SuiteReport,
+    bit_string: &str,
+    tests: &[&dyn RandomnessTest],
+) -> Result<()> {
+    log::trace!("plugins::run_tests()");
+
+    for test in tests {
+        report.results.push(test.run(bit_string)?);
+    }
+
+    Ok(())
+}
+
+/// Call a registration function and check the registered tests.
+///
+/// # Arguments
+///
+/// register - The registration function
+/// library - The library exporting the function, kept loaded by the tests
+///
+/// # Return
+///
+/// Ok(tests) - The registered tests
+/// Err(err) - Some error occured
+unsafe fn register_tests(
+    register: PluginRegistrationFn,
+    library: Option<Arc<libloading::Library>>,
+) -> Result<Vec<PluginTest>> {
+    let registration = register();
+    if registration.abi_version != constants::PLUGIN_ABI_VERSION {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!(
+                "Plugin ABI version {} is not supported, expected {}",
+                registration.abi_version,
+                constants::PLUGIN_ABI_VERSION
+            ),
+        });
+    }
+    if registration.number_of_tests > 0 && registration.tests.is_null() {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: "Plugin registered tests without descriptors".to_owned(),
+        });
+    }
+
+    let descriptors = match registration.number_of_tests {
+        0 => &[][..],
+        number_of_tests => std::slice::from_raw_parts(registration.tests, number_of_tests),
+    };
+    let mut names = BTreeSet::<&str>::new();
+    let mut tests = Vec::<PluginTest>::new();
+    for (index, descriptor) in descriptors.iter().enumerate() {
+        if descriptor.name.is_null() {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!("Plugin test {} has no name", index),
+            });
+        }
+        let name = CStr::from_ptr(descriptor.name)
+            .to_str()
+            .with_context(|| format!("Name of plugin test {} is not valid UTF-8", index))?;
+        if customtypes::Test::from_name(name).is_some() || !names.insert(name) {
+            return Err(customtypes::NistError::InvalidInput {
+                reason: format!("Plugin test name '{}' is already in use", name),
+            });
+        }
+
+        // names are copied, as the results may outlive the library. Plugins are loaded once, so
+        // the leaked names do not accumulate
+        let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        log::debug!("Registered plugin test '{}'", name);
+        tests.push(PluginTest {
+            test: customtypes::Test::Plugin(name),
+            run: descriptor.run,
+            _library: library.clone(),
+        });
+    }
+
+    Ok(tests)
+}
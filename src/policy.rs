@@ -0,0 +1,107 @@
+//! This module decides the overall verdict of a report from the results of its tests. The policy
+//! is selected on customtypes::SuiteConfig, so QA processes a fixed significance level does not
+//! fit can choose one of the built-in policies or implement their own without scripting.
+
+use crate::analysis;
+use crate::customtypes;
+
+/// Policy deciding the overall verdict from the results of all tests. The Debug representation
+/// identifies the policy when configurations are compared or hashed, so it has to contain all
+/// parameters of the policy
+pub trait VerdictPolicy: std::fmt::Debug + Send + Sync {
+    /// Decide the verdict.
+    ///
+    /// # Arguments
+    ///
+    /// results - The results of all tests run
+    ///
+    /// # Return
+    ///
+    /// verdict - The overall verdict
+    fn decide(&self, results: &[customtypes::TestResult]) -> customtypes::Verdict;
+}
+
+/// Fail if any test fails, otherwise invalid if any result is invalid. This is the default policy
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AnyFail;
+
+impl VerdictPolicy for AnyFail {
+    fn decide(&self, results: &[customtypes::TestResult]) -> customtypes::Verdict {
+        log::trace!("AnyFail::decide()");
+
+        KOfN { k: 1 }.decide(results)
+    }
+}
+
+/// Fail if at least k of the n tests fail, otherwise invalid if any result is invalid. A single
+/// failure among many tests is expected at a significance level of 0.01, so k = 2 tolerates it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KOfN {
+    /// The number of failed tests failing the report. k = 0 is treated as k = 1, so a report
+    /// without failed tests does not fail
+    pub k: usize,
+}
+
+impl VerdictPolicy for KOfN {
+    fn decide(&self, results: &[customtypes::TestResult]) -> customtypes::Verdict {
+        log::trace!("KOfN::decide()");
+
+        let failed = results
+            .iter()
+            .filter(|result| result.verdict() == "FAIL")
+            .count();
+        log::debug!(
+            "{} of {} tests failed, k = {}",
+            failed,
+            results.len(),
+            self.k
+        );
+
+        if failed >= self.k.max(1) {
+            customtypes::Verdict::Fail
+        } else if results.iter().any(|result| result.is_invalid()) {
+            customtypes::Verdict::Invalid
+        } else {
+            customtypes::Verdict::Pass
+        }
+    }
+}
+
+/// Fail if the q-value of any test is less than alpha, controlling the false discovery rate over
+/// all tests instead of the significance level of each test. Otherwise invalid if any result is
+/// invalid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FdrAdjusted {
+    /// The procedure used to compute the q-values
+    pub method: customtypes::FdrMethod,
+    /// The false discovery rate
+    pub alpha: f64,
+}
+
+impl VerdictPolicy for FdrAdjusted {
+    fn decide(&self, results: &[customtypes::TestResult]) -> customtypes::Verdict {
+        log::trace!("FdrAdjusted::decide()");
+
+        // invalid results are not meaningful and must not fail the report on their own
+        let p_values: Vec<f64> = results
+            .iter()
+            .filter(|result| !result.is_invalid())
+            .map(|result| result.p_value)
+            .collect();
+        let failed = match analysis::q_values(&p_values, self.method) {
+            Ok(q_values) => q_values.iter().any(|&q_value| q_value < self.alpha),
+            Err(err) => {
+                log::debug!("No q-values computed: {}", err);
+                false
+            }
+        };
+
+        if failed {
+            customtypes::Verdict::Fail
+        } else if results.iter().any(|result| result.is_invalid()) {
+            customtypes::Verdict::Invalid
+        } else {
+            customtypes::Verdict::Pass
+        }
+    }
+}
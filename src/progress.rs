@@ -0,0 +1,81 @@
+//! This module reports the progress of long-running tests, e.g. the template matching tests with
+//! hundreds of templates, to a callback passed by the caller. GUIs and CLIs can use it to display
+//! the fraction of work done and the estimated remaining time.
+
+use crate::customtypes;
+
+/// Reporter passing the progress of a test to the callback of the caller. To keep the overhead
+/// low, the callback is only invoked when at least another percent of the work is done
+pub struct ProgressReporter<'a> {
+    test: customtypes::Test,
+    total: usize,
+    start_time: std::time::Instant,
+    last_percent: std::cell::Cell<Option<usize>>,
+    callback: &'a dyn Fn(customtypes::Progress),
+}
+
+impl<'a> ProgressReporter<'a> {
+    /// Create a reporter for a test consisting of a given number of work items, e.g. templates.
+    ///
+    /// # Arguments
+    ///
+    /// test - The test the progress is reported for
+    /// total - The number of work items of the test
+    /// callback - The callback receiving the progress
+    pub fn new(
+        test: customtypes::Test,
+        total: usize,
+        callback: &'a dyn Fn(customtypes::Progress),
+    ) -> Self {
+        ProgressReporter {
+            test,
+            total,
+            start_time: std::time::Instant::now(),
+            last_percent: std::cell::Cell::new(None),
+            callback,
+        }
+    }
+
+    /// Report the number of work items done so far.
+    ///
+    /// # Arguments
+    ///
+    /// done - The number of finished work items
+    pub fn report(&self, done: usize) {
+        let done = done.min(self.total);
+        let fraction = if self.total == 0 {
+            1.0
+        } else {
+            (done as f64) / (self.total as f64)
+        };
+
+        // skip the callback if the percentage did not change since the last report
+        let percent = (fraction * 100.0) as usize;
+        if self.last_percent.get() == Some(percent) {
+            return;
+        }
+        self.last_percent.set(Some(percent));
+
+        // extrapolate the remaining time from the time taken so far
+        let eta = if done == 0 {
+            None
+        } else {
+            let elapsed = self.start_time.elapsed().as_secs_f64();
+            let remaining = elapsed * ((self.total - done) as f64) / (done as f64);
+            Some(std::time::Duration::from_secs_f64(remaining))
+        };
+
+        (self.callback)(customtypes::Progress {
+            test: self.test,
+            fraction,
+            eta,
+        });
+    }
+}
+
+/// Callback ignoring any progress, for callers not interested in it.
+///
+/// # Arguments
+///
+/// _progress - The ignored progress
+pub fn ignore(_progress: customtypes::Progress) {}
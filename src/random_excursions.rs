@@ -0,0 +1,228 @@
+//! This module performs the Random Excursions Test.
+//!
+//! Description of test from NIST SP 800-22:
+//!
+//! "The focus of this test is the number of cycles having exactly K visits in a cumulative sum random
+//! walk. The cumulative sum random walk is derived from partial sums after the (0,1) sequence is
+//! transferred to the appropriate (-1, +1) sequence. A cycle of a random walk consists of a sequence of
+//! steps of unit length taken at random that begin at and return to the origin. The purpose of this test is
+//! to determine if the number of visits to a particular state within a cycle deviates from what one would
+//! expect for a random sequence. This test is actually a series of eight tests (and conclusions), one test
+//! and conclusion for each of the states: -4, -3, -2, -1 and +1, +2, +3, +4."
+
+use crate::bit_sequence::BitInput;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
+use crate::utils;
+use std::collections::BTreeMap;
+
+const TEST_NAME: customtypes::Test = customtypes::Test::RandomExcursions;
+
+/// Perform the Random Excursions Test for the states -4, ..., -1, 1, ..., 4.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(p-values) - The p-value of each state
+/// Err(err) - Some error occured
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized)) -> Result<BTreeMap<i64, f64>> {
+    log::trace!("random_excursions::perform_test()");
+
+    Ok(run_test(bit_string)?.p_values())
+}
+
+/// Perform the Random Excursions Test for the states -4, ..., -1, 1, ..., 4 and return the number
+/// of visits, chi-square statistic and p-value of each state.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The number of cycles and the result of each state
+/// Err(err) - Some error occured, e.g. NistError::InsufficientCycles if the test is not applicable
+pub fn run_test(bit_string: &(impl BitInput + ?Sized)) -> Result<customtypes::ExcursionsResult> {
+    log::trace!("random_excursions::run_test()");
+
+    run_test_cancellable(bit_string, &customtypes::CancellationToken::new())
+}
+
+/// Perform the Random Excursions Test like run_test() and stop once the passed token got
+/// cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// cancellation - The token to stop the test with
+///
+/// # Return
+///
+/// Ok(result) - The number of cycles and the result of each state
+/// Err(err) - Some error occured, e.g. NistError::InsufficientCycles if the test is not
+/// applicable, or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    cancellation: &customtypes::CancellationToken,
+) -> Result<customtypes::ExcursionsResult> {
+    log::trace!("random_excursions::run_test_cancellable()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(
+        TEST_NAME,
+        bit_string,
+        constants::RECOMMENDED_SIZE_EXCURSIONS,
+    )
+    .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+
+    // each partial sum is bounded by the length, so they cannot overflow once the length fits
+    utils::checked_i64(TEST_NAME, length, "partial sums")?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // walk through the partial sums and count the visits of each state within the current cycle.
+    // When the walk returns to zero, the cycle is classified by the number of its visits, i.e.
+    // nu_k(x) counts the cycles with k visits of state x. '1' is a +1 whereas '0' is a -1
+    let max_state = constants::STATES_EXCURSIONS;
+    let mut cycle_visits = vec![0_usize; (2 * max_state + 1) as usize];
+    let mut total_visits = vec![0_usize; cycle_visits.len()];
+    let mut nu = vec![[0_usize; constants::VISIT_CLASSES_EXCURSIONS]; cycle_visits.len()];
+    let mut current_sum: i64 = 0;
+    let mut number_of_cycles = 0;
+
+    let mut end_cycle = |cycle_visits: &mut Vec<usize>| {
+        for (visits, counts) in cycle_visits.iter_mut().zip(nu.iter_mut()) {
+            counts[(*visits).min(constants::VISIT_CLASSES_EXCURSIONS - 1)] += 1;
+            *visits = 0;
+        }
+        number_of_cycles += 1;
+    };
+
+    for (index, bit) in bit_string.bits().enumerate() {
+        cancellation.check_at(TEST_NAME, index)?;
+        if bit {
+            current_sum += 1;
+        } else {
+            current_sum -= 1;
+        }
+
+        if current_sum == 0 {
+            end_cycle(&mut cycle_visits);
+        } else if current_sum.abs() <= max_state {
+            cycle_visits[(current_sum + max_state) as usize] += 1;
+            total_visits[(current_sum + max_state) as usize] += 1;
+        }
+    }
+    // a walk not ending at zero completes its last cycle at the appended zero
+    if current_sum != 0 {
+        end_cycle(&mut cycle_visits);
+    }
+    log::debug!("{}: Number of cycles J = {}", TEST_NAME, number_of_cycles);
+
+    // NIST considers the test not applicable if there are too few cycles
+    utils::check_number_of_cycles(TEST_NAME, number_of_cycles, length)?;
+
+    // compute the p-value of each state: igamc(5/2, chi_square/2) with
+    // chi_square = sum over k of (nu_k(x) - J * pi_k(x))^2 / (J * pi_k(x))
+    let cycles = number_of_cycles as f64;
+    let mut states = BTreeMap::<i64, customtypes::StateResult>::new();
+    for state in (-max_state..=max_state).filter(|&state| state != 0) {
+        let counts = &nu[(state + max_state) as usize];
+        let chi_square: f64 = compute_probabilities(state)
+            .iter()
+            .zip(counts)
+            .map(|(&probability, &count)| {
+                let expected = cycles * probability;
+                ((count as f64) - expected).powi(2) / expected
+            })
+            .sum();
+        let p_value = statrs::function::gamma::gamma_ur(
+            ((constants::VISIT_CLASSES_EXCURSIONS - 1) as f64) * 0.5,
+            chi_square * 0.5,
+        );
+        crate::logger::verbose_trace!(
+            "{}: State {}: nu = {:?}, chi_square = {}, p-value = {}",
+            TEST_NAME,
+            state,
+            counts,
+            chi_square,
+            p_value
+        );
+
+        states.insert(
+            state,
+            customtypes::StateResult {
+                visits: total_visits[(state + max_state) as usize],
+                statistic: chi_square,
+                p_value,
+            },
+        );
+    }
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::ExcursionsResult {
+        test: TEST_NAME,
+        number_of_cycles,
+        states,
+        advisories: input.advisories,
+        elapsed_time,
+    };
+    log::info!("{}: p-values = {:?}", TEST_NAME, result.p_values());
+    span.exit();
+
+    Ok(result)
+}
+
+/// Compute the probabilities pi_k(x) that a state is visited k times within a cycle, the last
+/// class covering at least five visits.
+///
+/// # Arguments
+///
+/// state - The state x
+///
+/// # Return
+///
+/// probabilities - pi_0(x), ..., pi_5(x)
+fn compute_probabilities(state: i64) -> [f64; constants::VISIT_CLASSES_EXCURSIONS] {
+    let x = state.abs() as f64;
+    let stay = 1.0 - 1.0 / (2.0 * x);
+
+    let mut probabilities = [0.0; constants::VISIT_CLASSES_EXCURSIONS];
+    probabilities[0] = stay;
+    for (k, probability) in probabilities
+        .iter_mut()
+        .enumerate()
+        .take(constants::VISIT_CLASSES_EXCURSIONS - 1)
+        .skip(1)
+    {
+        *probability = 1.0 / (4.0 * x * x) * stay.powi(k as i32 - 1);
+    }
+    probabilities[constants::VISIT_CLASSES_EXCURSIONS - 1] =
+        1.0 / (2.0 * x) * stay.powi(constants::VISIT_CLASSES_EXCURSIONS as i32 - 2);
+
+    probabilities
+}
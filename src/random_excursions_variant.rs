@@ -0,0 +1,229 @@
+//! This module performs the Random Excursions Variant Test.
+//!
+//! Description of test from NIST SP 800-22:
+//!
+//! "The focus of this test is the total number of times that a particular state is visited (i.e., occurs) in a
+//! cumulative sum random walk. The purpose of this test is to detect deviations from the expected number
+//! of visits to various states in the random walk. This test is actually a series of eighteen tests (and
+//! conclusions), one test and conclusion for each of the states: -9, -8, ..., -1 and +1, +2, ..., +9."
+
+use crate::bit_sequence::BitInput;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
+use crate::utils;
+use std::collections::BTreeMap;
+
+const TEST_NAME: customtypes::Test = customtypes::Test::RandomExcursionsVariant;
+
+/// Perform the Random Excursions Variant Test for the states -9, ..., -1, 1, ..., 9.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(p-values) - The p-value of each state
+/// Err(err) - Some error occured
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized)) -> Result<BTreeMap<i64, f64>> {
+    log::trace!("random_excursions_variant::perform_test()");
+
+    Ok(run_test(bit_string)?.p_values())
+}
+
+/// Perform the Random Excursions Variant Test for the states -9, ..., -1, 1, ..., 9 and return
+/// the number of visits, statistic and p-value of each state.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The number of cycles and the result of each state
+/// Err(err) - Some error occured
+pub fn run_test(bit_string: &(impl BitInput + ?Sized)) -> Result<customtypes::ExcursionsResult> {
+    log::trace!("random_excursions_variant::run_test()");
+
+    run_test_with_states(bit_string, constants::STATES_EXCURSIONS_VARIANT)
+}
+
+/// Perform the Random Excursions Variant Test for the states -x_max, ..., -1, 1, ..., x_max.
+/// States beyond +-9 show rarer excursion levels, e.g. to study heavy-tailed bias. Their
+/// statistic uses the same variance 2J(4|x| - 2) as the standard states. x_max is bounded by
+/// constants::MAX_STATES_EXCURSIONS_VARIANT.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// max_state - The largest absolute state x_max to be analyzed, 9 in NIST SP 800-22
+///
+/// # Return
+///
+/// Ok(result) - The number of cycles and the result of each state
+/// Err(err) - Some error occured
+pub fn run_test_with_states(
+    bit_string: &(impl BitInput + ?Sized),
+    max_state: i64,
+) -> Result<customtypes::ExcursionsResult> {
+    log::trace!("random_excursions_variant::run_test_with_states()");
+
+    run_test_cancellable(
+        bit_string,
+        max_state,
+        &customtypes::CancellationToken::new(),
+        &customtypes::Limits::default(),
+    )
+}
+
+/// Perform the Random Excursions Variant Test like run_test_with_states() and stop once the passed
+/// token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// max_state - The largest absolute state x_max to be analyzed, 9 in NIST SP 800-22
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input and the largest state
+///
+/// # Return
+///
+/// Ok(result) - The number of cycles and the result of each state
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    max_state: i64,
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::ExcursionsResult> {
+    log::trace!("random_excursions_variant::run_test_cancellable()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(
+        TEST_NAME,
+        bit_string,
+        constants::RECOMMENDED_SIZE_EXCURSIONS,
+    )
+    .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+    let mut advisories = input.advisories;
+
+    // each partial sum is bounded by the length, so they cannot overflow once the length fits
+    utils::checked_i64(TEST_NAME, length, "partial sums")?;
+
+    if !(1..=limits.max_state_excursions_variant).contains(&max_state) {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "max_state",
+            format!(
+                "Largest state ({}) must be between 1 and {}",
+                max_state, limits.max_state_excursions_variant
+            ),
+        ));
+    }
+    if max_state != constants::STATES_EXCURSIONS_VARIANT {
+        log::warn!(
+            "{}: States up to +-{} differ from the standard states up to +-{}",
+            TEST_NAME,
+            max_state,
+            constants::STATES_EXCURSIONS_VARIANT
+        );
+        advisories.push(customtypes::Advisory::weak(
+            "x",
+            format!(
+                "States up to +-{} differ from the standard states up to +-{}",
+                max_state,
+                constants::STATES_EXCURSIONS_VARIANT
+            ),
+        ));
+    }
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // walk through the partial sums, counting the visits of each state and the returns to zero.
+    // '1' is a +1 whereas '0' is a -1
+    let mut visits = vec![0_usize; (2 * max_state + 1) as usize];
+    let mut current_sum: i64 = 0;
+    let mut number_of_cycles = 0;
+    for (index, bit) in bit_string.bits().enumerate() {
+        cancellation.check_at(TEST_NAME, index)?;
+        if bit {
+            current_sum += 1;
+        } else {
+            current_sum -= 1;
+        }
+
+        if current_sum == 0 {
+            number_of_cycles += 1;
+        } else if current_sum.abs() <= max_state {
+            visits[(current_sum + max_state) as usize] += 1;
+        }
+    }
+    // a walk not ending at zero completes its last cycle at the appended zero
+    if current_sum != 0 {
+        number_of_cycles += 1;
+    }
+    log::debug!("{}: Number of cycles J = {}", TEST_NAME, number_of_cycles);
+
+    // NIST considers the test not applicable if there are too few cycles
+    utils::check_number_of_cycles(TEST_NAME, number_of_cycles, length)?;
+
+    // compute the p-value of each state: erfc(|xi(x) - J| / sqrt(2J(4|x| - 2)))
+    let cycles = number_of_cycles as f64;
+    let mut states = BTreeMap::<i64, customtypes::StateResult>::new();
+    for state in (-max_state..=max_state).filter(|&state| state != 0) {
+        let state_visits = visits[(state + max_state) as usize];
+        let statistic = ((state_visits as f64) - cycles).abs()
+            / (2.0 * cycles * (4.0 * (state.abs() as f64) - 2.0)).sqrt();
+        let p_value = statrs::function::erf::erfc(statistic);
+        crate::logger::verbose_trace!(
+            "{}: State {}: visits = {}, p-value = {}",
+            TEST_NAME,
+            state,
+            state_visits,
+            p_value
+        );
+
+        states.insert(
+            state,
+            customtypes::StateResult {
+                visits: state_visits,
+                statistic,
+                p_value,
+            },
+        );
+    }
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::ExcursionsResult {
+        test: TEST_NAME,
+        number_of_cycles,
+        states,
+        advisories,
+        elapsed_time,
+    };
+    log::info!("{}: p-values = {:?}", TEST_NAME, result.p_values());
+    span.exit();
+
+    Ok(result)
+}
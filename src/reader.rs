@@ -0,0 +1,259 @@
+//! This module feeds the streaming variants of the tests from byte streams, e.g. multi-gigabyte
+//! capture files of a random number generator. The bytes are either raw binary data or ASCII '0'
+//! and '1' characters. They are decoded while they are read, so a file is neither held in memory
+//! nor re-encoded into a bit string. With the "mmap" feature, files can be memory-mapped instead
+//! of being read in chunks.
+
+use crate::bit_source::BitSource;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use std::io::Read;
+
+/// Decoder of bytes into bits, keeping the bits of a partially decoded byte for the next call
+#[derive(Debug, Clone)]
+struct Decoder {
+    format: customtypes::InputFormat,
+    /// Byte offset of the next byte to decode
+    position: usize,
+    /// The last decoded byte and the number of its bits not handed out yet
+    pending: (u8, u32),
+}
+
+impl Decoder {
+    /// Create a decoder starting at the first byte of a stream.
+    ///
+    /// # Arguments
+    ///
+    /// format - The encoding of the bits
+    fn new(format: customtypes::InputFormat) -> Self {
+        Decoder {
+            format,
+            position: 0,
+            pending: (0, 0),
+        }
+    }
+
+    /// Check whether bits of the last decoded byte are left.
+    fn has_pending(&self) -> bool {
+        self.pending.1 > 0
+    }
+
+    /// Decode bytes into a buffer until either of them is exhausted. An invalid ASCII symbol ends
+    /// the decoding, but is only reported if no bits were decoded before, so the bits preceding
+    /// it can be read.
+    ///
+    /// # Arguments
+    ///
+    /// bytes - The bytes following the already decoded ones
+    /// buffer - The buffer receiving one bit per element
+    ///
+    /// # Return
+    ///
+    /// Ok((consumed, written)) - The number of bytes decoded and the number of bits written
+    /// Err(err) - The next symbol is neither '0', '1' nor whitespace
+    fn decode(&mut self, bytes: &[u8], buffer: &mut [bool]) -> Result<(usize, usize)> {
+        let mut consumed = 0;
+        let mut written = 0;
+
+        match self.format {
+            customtypes::InputFormat::Binary => loop {
+                // hand out the bits left of the previous byte first
+                while self.pending.1 > 0 && written < buffer.len() {
+                    self.pending.1 -= 1;
+                    buffer[written] = (self.pending.0 >> self.pending.1) & 1 == 1;
+                    written += 1;
+                }
+                if written == buffer.len() || consumed == bytes.len() {
+                    break;
+                }
+                self.pending = (bytes[consumed], 8);
+                consumed += 1;
+            },
+            customtypes::InputFormat::Ascii => {
+                while written < buffer.len() && consumed < bytes.len() {
+                    match bytes[consumed] {
+                        b'0' => {
+                            buffer[written] = false;
+                            written += 1;
+                        }
+                        b'1' => {
+                            buffer[written] = true;
+                            written += 1;
+                        }
+                        symbol if symbol.is_ascii_whitespace() => (),
+                        _ if written > 0 => break,
+                        symbol => {
+                            return Err(customtypes::NistError::InvalidBit {
+                                position: self.position + consumed,
+                                symbol: symbol as char,
+                            })
+                        }
+                    }
+                    consumed += 1;
+                }
+            }
+        }
+        self.position += consumed;
+
+        Ok((consumed, written))
+    }
+}
+
+/// Source reading the bits of a byte stream, e.g. a file or the standard input, in chunks of
+/// constants::READ_BUFFER_SIZE bytes
+#[derive(Debug)]
+pub struct ReaderSource<R: Read> {
+    reader: R,
+    decoder: Decoder,
+    chunk: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl<R: Read> ReaderSource<R> {
+    /// Create a source starting at the current position of a reader. Invalid ASCII symbols are
+    /// reported once they are read.
+    ///
+    /// # Arguments
+    ///
+    /// reader - The reader providing the bytes
+    /// format - The encoding of the bits
+    pub fn new(reader: R, format: customtypes::InputFormat) -> Self {
+        log::trace!("ReaderSource::new()");
+
+        ReaderSource {
+            reader,
+            decoder: Decoder::new(format),
+            chunk: vec![0; constants::READ_BUFFER_SIZE],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Get the number of bytes decoded so far.
+    pub fn position(&self) -> usize {
+        self.decoder.position
+    }
+}
+
+impl ReaderSource<std::fs::File> {
+    /// Open a file to read its bits from the beginning.
+    ///
+    /// # Arguments
+    ///
+    /// file_path - The path to the file
+    /// format - The encoding of the bits
+    ///
+    /// # Return
+    ///
+    /// Ok(source) - The source reading the file
+    /// Err(err) - Some error occured
+    pub fn open(file_path: &str, format: customtypes::InputFormat) -> Result<Self> {
+        log::trace!("ReaderSource::open()");
+
+        let file = std::fs::File::open(file_path)
+            .with_context(|| format!("Failed to open file '{}'", file_path))?;
+
+        Ok(ReaderSource::new(file, format))
+    }
+}
+
+impl<R: Read> BitSource for ReaderSource<R> {
+    fn read_bits(&mut self, buffer: &mut [bool]) -> Result<usize> {
+        let mut written = 0;
+
+        while written < buffer.len() {
+            // refill the chunk once all of its bytes and bits are handed out
+            if self.start == self.end && !self.decoder.has_pending() {
+                let read = loop {
+                    match self.reader.read(&mut self.chunk) {
+                        Ok(read) => break read,
+                        Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(err) => return Err(err).context("Failed to read from byte stream"),
+                    }
+                };
+                if read == 0 {
+                    break;
+                }
+                self.start = 0;
+                self.end = read;
+            }
+
+            // the bits decoded in this call are returned before an invalid symbol is reported
+            let (consumed, bits) = match self
+                .decoder
+                .decode(&self.chunk[self.start..self.end], &mut buffer[written..])
+            {
+                Ok(decoded) => decoded,
+                Err(_) if written > 0 => break,
+                Err(err) => return Err(err),
+            };
+            self.start += consumed;
+            written += bits;
+            if bits == 0 && consumed == 0 {
+                break;
+            }
+        }
+
+        Ok(written)
+    }
+}
+
+/// Source reading the bits of a memory-mapped file. The operating system pages the file in on
+/// demand, so even files larger than the memory can be read without copying them.
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct MmapSource {
+    map: memmap2::Mmap,
+    decoder: Decoder,
+}
+
+#[cfg(feature = "mmap")]
+impl MmapSource {
+    /// Memory-map a file to read its bits from the beginning. The file must not be modified
+    /// while it is mapped.
+    ///
+    /// # Arguments
+    ///
+    /// file_path - The path to the file
+    /// format - The encoding of the bits
+    ///
+    /// # Return
+    ///
+    /// Ok(source) - The source reading the mapped file
+    /// Err(err) - Some error occured
+    pub fn open(file_path: &str, format: customtypes::InputFormat) -> Result<Self> {
+        log::trace!("MmapSource::open()");
+
+        let file = std::fs::File::open(file_path)
+            .with_context(|| format!("Failed to open file '{}'", file_path))?;
+
+        // SAFETY: the mapping is read-only and callers are required not to modify the file while
+        // it is mapped
+        let map = unsafe { memmap2::Mmap::map(&file) }
+            .with_context(|| format!("Failed to memory-map file '{}'", file_path))?;
+        log::debug!("Mapped {} bytes of '{}'", map.len(), file_path);
+
+        Ok(MmapSource {
+            map,
+            decoder: Decoder::new(format),
+        })
+    }
+
+    /// Get the number of bytes decoded so far.
+    pub fn position(&self) -> usize {
+        self.decoder.position
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl BitSource for MmapSource {
+    fn read_bits(&mut self, buffer: &mut [bool]) -> Result<usize> {
+        let (_, written) = self
+            .decoder
+            .decode(&self.map[self.decoder.position..], buffer)?;
+
+        Ok(written)
+    }
+}
@@ -0,0 +1,227 @@
+//! This module embeds the reference sequences of NIST SP 800-22, appendix B: the binary
+//! expansions of e, pi, sqrt(2) and sqrt(3). Running the implemented tests on them and comparing
+//! the p-values with the published ones validates the implementation without the reference
+//! implementation at hand.
+//!
+//! The sequences add about 4 MB to the binary, so the module is only available with the
+//! "reference-data" feature.
+
+use crate::approximate_entropy;
+#[cfg(feature = "matrix-rank")]
+use crate::binary_matrix_rank;
+use crate::constants;
+use crate::cumulative_sums;
+use crate::customtypes;
+use crate::customtypes::Result;
+#[cfg(feature = "spectral")]
+use crate::dft_spectral;
+use crate::frequency_block;
+use crate::frequency_monobit;
+use crate::longest_run;
+use crate::overlapping_template;
+use crate::random_excursions;
+use crate::random_excursions_variant;
+use crate::runs;
+use crate::serial;
+
+const DATA_E: &str = include_str!("tests/testdata/data.e");
+const DATA_PI: &str = include_str!("tests/testdata/data.pi");
+const DATA_SQRT_2: &str = include_str!("tests/testdata/data.sqrt2");
+const DATA_SQRT_3: &str = include_str!("tests/testdata/data.sqrt3");
+
+impl customtypes::ReferenceSequence {
+    /// Get the first constants::REFERENCE_LENGTH bits of the binary expansion the p-values were
+    /// published for.
+    ///
+    /// # Return
+    ///
+    /// bit_string - The bits of the sequence
+    pub fn bit_string(&self) -> String {
+        log::trace!("ReferenceSequence::bit_string()");
+
+        let data = match self {
+            customtypes::ReferenceSequence::E => DATA_E,
+            customtypes::ReferenceSequence::Pi => DATA_PI,
+            customtypes::ReferenceSequence::Sqrt2 => DATA_SQRT_2,
+            customtypes::ReferenceSequence::Sqrt3 => DATA_SQRT_3,
+        };
+
+        // the embedded files are split into lines and contain a few more bits than published for
+        data.chars()
+            .filter(|c| !c.is_whitespace())
+            .take(constants::REFERENCE_LENGTH)
+            .collect()
+    }
+}
+
+/// Run the implemented tests with published p-values on all reference sequences and compare the
+/// p-values. Tests disabled by a feature are left out.
+///
+/// # Return
+///
+/// Ok(checks) - One check per published p-value, in the order of the sequences and tests
+/// Err(err) - Some error occured
+pub fn validate() -> Result<Vec<customtypes::ReferenceCheck>> {
+    log::trace!("reference::validate()");
+
+    let mut checks = Vec::<customtypes::ReferenceCheck>::new();
+    for sequence in customtypes::ReferenceSequence::ALL {
+        let bit_string = sequence.bit_string();
+        for (published_p_value, result) in run_tests(&bit_string, sequence as usize)? {
+            checks.push(customtypes::ReferenceCheck {
+                sequence,
+                published_p_value,
+                result,
+            });
+        }
+    }
+
+    let deviations = checks.iter().filter(|check| !check.matches()).count();
+    for check in checks.iter().filter(|check| !check.matches()) {
+        log::warn!("Deviation from reference: {}", check);
+    }
+    log::info!(
+        "Validated {} p-values against the reference sequences, {} deviate",
+        checks.len(),
+        deviations
+    );
+
+    Ok(checks)
+}
+
+/// Run the tests with published p-values on a reference sequence, using the parameters of the
+/// published tables.
+///
+/// # Arguments
+///
+/// bit_string - The bits of the reference sequence
+/// index - The index of the sequence in the published tables
+///
+/// # Return
+///
+/// Ok(results) - The published p-value and the result of each test
+/// Err(err) - Some error occured
+fn run_tests(bit_string: &str, index: usize) -> Result<Vec<(f64, customtypes::TestResult)>> {
+    log::trace!("reference::run_tests()");
+
+    // the tables use M = 128 for the "Frequency Test within a Block", resulting in far more
+    // blocks than recommended
+    let limits = customtypes::Limits {
+        max_number_of_blocks: constants::REFERENCE_LENGTH,
+        ..Default::default()
+    };
+    let cancellation = customtypes::CancellationToken::new();
+
+    let mut results = vec![
+        (
+            constants::REFERENCE_P_VALUES_MONOBIT[index],
+            frequency_monobit::run_test(bit_string)?,
+        ),
+        (
+            constants::REFERENCE_P_VALUES_BLOCK[index],
+            frequency_block::run_test_cancellable(
+                bit_string,
+                constants::REFERENCE_BLOCK_SIZE,
+                &cancellation,
+                &limits,
+            )?,
+        ),
+        (
+            constants::REFERENCE_P_VALUES_RUNS[index],
+            runs::run_test(bit_string)?,
+        ),
+        (
+            constants::REFERENCE_P_VALUES_LONGEST_RUN[index],
+            longest_run::run_test(bit_string)?,
+        ),
+    ];
+    #[cfg(feature = "matrix-rank")]
+    results.push((
+        constants::REFERENCE_P_VALUES_MATRIX_RANK[index],
+        binary_matrix_rank::run_test(
+            bit_string,
+            constants::MATRIX_ROWS_M,
+            constants::MATRIX_COLUMNS_Q,
+        )?,
+    ));
+    #[cfg(feature = "spectral")]
+    results.push((
+        constants::REFERENCE_P_VALUES_DFT[index],
+        dft_spectral::run_test(bit_string)?,
+    ));
+    results.push((
+        constants::REFERENCE_P_VALUES_CUSUM_FORWARD[index],
+        cumulative_sums::run_test(bit_string, customtypes::Mode::Forward)?,
+    ));
+    results.push((
+        constants::REFERENCE_P_VALUES_CUSUM_BACKWARD[index],
+        cumulative_sums::run_test(bit_string, customtypes::Mode::Backward)?,
+    ));
+    results.push((
+        constants::REFERENCE_P_VALUES_OVERLAPPING[index],
+        overlapping_template::run_test_with_block_size(
+            bit_string,
+            constants::SUITE_TEMPLATE_LEN,
+            constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE,
+        )?,
+    ));
+    results.push((
+        constants::REFERENCE_P_VALUES_APPROXIMATE_ENTROPY[index],
+        approximate_entropy::run_test(
+            bit_string,
+            constants::SUITE_BLOCK_LENGTH_APPROXIMATE_ENTROPY,
+        )?,
+    ));
+    let [serial_1, serial_2] = serial::run_test(bit_string, constants::SUITE_BLOCK_LENGTH_SERIAL)?;
+    results.push((constants::REFERENCE_P_VALUES_SERIAL_1[index], serial_1));
+    results.push((constants::REFERENCE_P_VALUES_SERIAL_2[index], serial_2));
+    results.push((
+        constants::REFERENCE_P_VALUES_EXCURSIONS[index],
+        state_result(
+            &random_excursions::run_test(bit_string)?,
+            constants::REFERENCE_STATE_EXCURSIONS,
+            bit_string.len(),
+        )?,
+    ));
+    results.push((
+        constants::REFERENCE_P_VALUES_EXCURSIONS_VARIANT[index],
+        state_result(
+            &random_excursions_variant::run_test(bit_string)?,
+            constants::REFERENCE_STATE_EXCURSIONS_VARIANT,
+            bit_string.len(),
+        )?,
+    ));
+
+    Ok(results)
+}
+
+/// Get the result of the state the tables publish the p-value of from an excursion test.
+///
+/// # Arguments
+///
+/// result - The result of all states of the excursion test
+/// state - The published state x
+/// length - The length n of the reference sequence
+///
+/// # Return
+///
+/// Ok(result) - The result of the state
+/// Err(err) - The test has no result for the state
+fn state_result(
+    result: &customtypes::ExcursionsResult,
+    state: i64,
+    length: usize,
+) -> Result<customtypes::TestResult> {
+    log::trace!("reference::state_result()");
+
+    // the results are listed in the order of the states
+    result
+        .states
+        .keys()
+        .position(|&key| key == state)
+        .and_then(|index| result.to_test_results(length).into_iter().nth(index))
+        .ok_or_else(|| customtypes::NistError::TestFailed {
+            test: result.test,
+            reason: format!("No result for state x = {}", state),
+        })
+}
@@ -0,0 +1,141 @@
+//! This module exports results and reports to archive them and diff them across builds, e.g. of a
+//! firmware. JSON contains everything a result or report holds, while CSV has one row per result.
+//! Both schemas are stable: fields and columns are only ever appended.
+
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::policy::{self, VerdictPolicy};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+/// Header of the CSV export, one column per field of a result
+const CSV_HEADER: &str =
+    "test,name,p_value,statistic,alpha,verdict,parameters,counts,advisories,elapsed_time";
+
+/// Serialize the result with its verdict and the name of its test. The elapsed time is given in
+/// seconds
+impl Serialize for customtypes::TestResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TestResult", 10)?;
+        state.serialize_field("test", &self.test)?;
+        state.serialize_field("name", self.test.name())?;
+        state.serialize_field("p_value", &self.p_value)?;
+        state.serialize_field("statistic", &self.statistic)?;
+        state.serialize_field("alpha", &self.alpha)?;
+        state.serialize_field("verdict", self.verdict())?;
+        state.serialize_field("parameters", &self.parameters)?;
+        state.serialize_field("counts", &self.counts)?;
+        state.serialize_field("advisories", &self.advisories)?;
+        state.serialize_field("elapsed_time", &self.elapsed_time.as_secs_f64())?;
+        state.end()
+    }
+}
+
+/// Serialize the report with its overall verdict as decided by the default policy
+impl Serialize for customtypes::SuiteReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SuiteReport", 6)?;
+        state.serialize_field("verdict", &policy::AnyFail.decide(&self.results))?;
+        state.serialize_field("results", &self.results)?;
+        state.serialize_field("skipped", &self.skipped)?;
+        state.serialize_field("pipeline", &self.pipeline)?;
+        state.serialize_field("length_advice", &self.length_advice)?;
+        state.serialize_field("cancelled", &self.cancelled)?;
+        state.end()
+    }
+}
+
+/// Serialize the error as its message, including the context it was returned with
+impl Serialize for customtypes::NistError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:#}", self))
+    }
+}
+
+/// Export a result, a list of results or a report as pretty-printed JSON.
+///
+/// # Arguments
+///
+/// value - The result, results or report to export
+///
+/// # Return
+///
+/// Ok(json) - The JSON document
+/// Err(err) - Some error occured
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    log::trace!("report::to_json()");
+
+    serde_json::to_string_pretty(value).context("Failed to serialize to JSON")
+}
+
+/// Export results as CSV with a header row and one row per result. Parameters are written as
+/// "key=value" pairs and counts as values, both separated by ";". Advisories are written as
+/// "parameter: message" separated by ";". Fields are quoted if needed.
+///
+/// # Arguments
+///
+/// results - The results to export, e.g. the results of a report
+///
+/// # Return
+///
+/// csv - The CSV document
+pub fn to_csv(results: &[customtypes::TestResult]) -> String {
+    log::trace!("report::to_csv()");
+
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for result in results {
+        let parameters: Vec<String> = result
+            .parameters
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        let counts: Vec<String> = result
+            .counts
+            .iter()
+            .map(|count| count.to_string())
+            .collect();
+        let advisories: Vec<String> = result
+            .advisories
+            .iter()
+            .map(|advisory| format!("{}: {}", advisory.parameter, advisory.message))
+            .collect();
+
+        let row = [
+            format!("{:?}", result.test),
+            result.test.name().to_owned(),
+            result.p_value.to_string(),
+            result.statistic.to_string(),
+            result.alpha.to_string(),
+            result.verdict().to_owned(),
+            parameters.join(";"),
+            counts.join(";"),
+            advisories.join(";"),
+            result.elapsed_time.as_secs_f64().to_string(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| quote_csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quote a CSV field if it contains a separator, a quote or a line break. Quotes are doubled.
+///
+/// # Arguments
+///
+/// field - The field to quote
+///
+/// # Return
+///
+/// field - The field as written to the CSV document
+fn quote_csv_field(field: &str) -> String {
+    log::trace!("report::quote_csv_field()");
+
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
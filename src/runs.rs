@@ -9,10 +9,13 @@
 //! ones and zeros of various lengths is as expected for a random sequence. In particular, this test determines
 //! whether the oscillation between such zeros and ones is too fast or too slow."
 
+use crate::bit_sequence::{self, BitInput, WORD_BITS};
+use crate::bit_source::{self, BitSource};
 use crate::constants;
 use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
 use crate::utils;
-use anyhow::{Context, Result};
 
 const TEST_NAME: customtypes::Test = customtypes::Test::Runs;
 
@@ -26,19 +29,225 @@ const TEST_NAME: customtypes::Test = customtypes::Test::Runs;
 ///
 /// Ok(p-value) - The p-value which indicates whether randomness is given or not
 /// Err(err) - Some error occured
-pub fn perform_test(bit_string: &str) -> Result<f64> {
+pub fn perform_test(bit_string: &(impl BitInput + ?Sized)) -> Result<f64> {
     log::trace!("runs::perform_test()");
 
+    Ok(run_test(bit_string)?.p_value)
+}
+
+/// Perform the Runs test and return its result.
+///
+/// # Arguments
+///
+/// bit_string -  The bit string to be tested for randomness
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(bit_string: &(impl BitInput + ?Sized)) -> Result<customtypes::TestResult> {
+    log::trace!("runs::run_test()");
+
+    run_test_with_limits(bit_string, &customtypes::Limits::default())
+}
+
+/// Perform the Runs test like run_test() with thresholds overriding the NIST recommendations.
+///
+/// # Arguments
+///
+/// bit_string -  The bit string to be tested for randomness
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_with_limits(
+    bit_string: &(impl BitInput + ?Sized),
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("runs::run_test_with_limits()");
+
+    run_test_cancellable(bit_string, &customtypes::CancellationToken::new(), limits)
+}
+
+/// Perform the Runs test like run_test_with_limits() and stop once the passed token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// cancellation - The token to stop the test with
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    cancellation: &customtypes::CancellationToken,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("runs::run_test_cancellable()");
+
     // capture the current time before executing the actual test
     let start_time = std::time::Instant::now();
 
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
     // check if bit string contains invalid characters
-    let length = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE)
-        .with_context(|| "Invalid character(s) in passed bit string detected")?
-        as f64;
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, limits.recommended_size)
+        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let pre_test_proportion = check_applicability(&input)?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // compute observed runs test statistics V_n(obs), i.e. one more than the number of
+    // positions where a bit differs from its successor
+    let v_n_observed = count_changes(bit_string, input.length, cancellation)? + 1;
+
+    Ok(compute_result(
+        input,
+        pre_test_proportion,
+        v_n_observed,
+        start_time,
+        &span,
+        phase,
+    ))
+}
+
+/// Perform the Runs test on the bits of a source, which are read in chunks instead of being held
+/// in memory. The ones and the runs are counted in the same pass, so the source may be of any
+/// length.
+///
+/// # Arguments
+///
+/// source - The source of the bits to be tested for randomness
+/// limits - The thresholds applied to the input
+///
+/// # Return
+///
+/// Ok(result) - The p-value, statistic, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test_streaming(
+    source: &mut dyn BitSource,
+    limits: &customtypes::Limits,
+) -> Result<customtypes::TestResult> {
+    log::trace!("runs::run_test_streaming()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("reading");
+
+    // count the ones and the runs chunk by chunk. The previous bit is carried across the chunks
+    let mut ones = 0;
+    let mut v_n_observed: usize = 1;
+    let mut previous = None;
+    let length = bit_source::for_each_chunk(source, |bits| {
+        for &bit in bits {
+            if bit {
+                ones += 1;
+            }
+            if previous.is_some_and(|previous| previous != bit) {
+                v_n_observed += 1;
+            }
+            previous = Some(bit);
+        }
+        Ok(())
+    })?;
+    let mut input = customtypes::ValidatedInput {
+        length,
+        ones,
+        ..Default::default()
+    };
+    if input.length == 0 {
+        return Err(customtypes::NistError::insufficient_length(TEST_NAME, 1, 0));
+    }
+    utils::check_recommended_size(
+        TEST_NAME,
+        input.length,
+        limits.recommended_size,
+        &mut input.advisories,
+    );
+    let pre_test_proportion = check_applicability(&input)?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    Ok(compute_result(
+        input,
+        pre_test_proportion,
+        v_n_observed,
+        start_time,
+        &span,
+        phase,
+    ))
+}
+
+/// Count the positions where a bit differs from its successor a word at a time. Each word is
+/// compared with itself shifted by one bit, with the first bit of the next word shifted in.
+///
+/// # Arguments
+///
+/// bit_string - The validated bit string
+/// length - The length of the bit string
+/// cancellation - The token to stop the count with
+///
+/// # Return
+///
+/// Ok(changes) - The number of changes between consecutive bits
+/// Err(err) - The test got cancelled
+fn count_changes(
+    bit_string: &(impl BitInput + ?Sized),
+    length: usize,
+    cancellation: &customtypes::CancellationToken,
+) -> Result<usize> {
+    log::trace!("runs::count_changes()");
+
+    let mut changes = 0;
+    let mut remaining = length;
+    let mut words = bit_string.words().peekable();
+
+    while let Some(word) = words.next() {
+        cancellation.check_at(TEST_NAME, length - remaining)?;
+        let next_bit = words.peek().map_or(0, |next| next >> (WORD_BITS - 1));
+        let differences = word ^ ((word << 1) | next_bit);
+
+        // only pairs of valid bits count, the last bit of the sequence has no successor
+        let pairs = if remaining > WORD_BITS {
+            WORD_BITS
+        } else {
+            remaining - 1
+        };
+        changes += (differences & bit_sequence::leading_mask(pairs)).count_ones() as usize;
+        remaining = remaining.saturating_sub(WORD_BITS);
+    }
+
+    Ok(changes)
+}
+
+/// Check whether the proportion of ones allows to perform the test and return it.
+///
+/// # Arguments
+///
+/// input - The length and number of ones of the bit string
+///
+/// # Return
+///
+/// Ok(pre_test_proportion) - The proportion of ones
+/// Err(err) - The proportion deviates too much from 1/2
+fn check_applicability(input: &customtypes::ValidatedInput) -> Result<f64> {
+    log::trace!("runs::check_applicability()");
 
     // determine the number of ones in given bit string and compute pre-test proportion = #ones/length
-    let count_ones = bit_string.chars().filter(|&c| c == '1').count() as f64;
+    let length = input.length as f64;
+    let count_ones = input.ones as f64;
 
     let pre_test_proportion = count_ones / length;
     log::debug!(
@@ -54,25 +263,41 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
     let requirement = (pre_test_proportion - 0.5).abs();
 
     if requirement >= tau {
-        anyhow::bail!(
-            "{} is not applicable! Tau ({}) < Requirement ({})",
-            TEST_NAME,
-            tau,
-            requirement
-        );
+        return Err(customtypes::NistError::NotApplicable {
+            test: TEST_NAME,
+            reason: format!("Tau ({}) < Requirement ({})", tau, requirement),
+        });
     }
 
-    // compute observed runs test statistics V_n(obs). Therefore compare current bit with
-    // consecutive one. If not equal, add 1 to counter, otherwise do nothing
-    let mut v_n_observed = 1;
-    let bytes = bit_string.as_bytes();
+    Ok(pre_test_proportion)
+}
+
+/// Compute the p-value from the number of runs.
+///
+/// # Arguments
+///
+/// input - The length and advisories of the bit string
+/// pre_test_proportion - The proportion of ones
+/// v_n_observed - The observed number of runs V_n(obs)
+/// start_time - The time the test started at
+/// span - The span of the test
+/// phase - The span of the computation phase, exited once the p-value is known
+///
+/// # Return
+///
+/// result - The p-value, statistic, parameters, elapsed time and advisories of the test
+fn compute_result(
+    input: customtypes::ValidatedInput,
+    pre_test_proportion: f64,
+    v_n_observed: usize,
+    start_time: std::time::Instant,
+    span: &telemetry::Span,
+    phase: telemetry::Span,
+) -> customtypes::TestResult {
+    log::trace!("runs::compute_result()");
 
-    for bit in 0..bytes.len() - 1 {
-        if bytes[bit] != bytes[bit + 1] {
-            v_n_observed += 1;
-        }
-    }
     log::debug!("{}: v_n_observed value: {}", TEST_NAME, v_n_observed);
+    let length = input.length as f64;
 
     // finally, compute p-value with complementary error function
     let constant = pre_test_proportion * (1.0 - pre_test_proportion);
@@ -88,10 +313,28 @@ pub fn perform_test(bit_string: &str) -> Result<f64> {
     let p_value = statrs::function::erf::erfc(numerator / denominator);
     log::info!("{}: p-value = {}", TEST_NAME, p_value);
 
+    phase.exit();
+
     // capture the current time after the test got executed and calculate elapsed time
     let end_time = std::time::Instant::now();
-    let elapsed_time = end_time.duration_since(start_time).as_secs_f64();
-    log::info!("{} took {:.6} seconds", TEST_NAME, elapsed_time);
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let result = customtypes::TestResult {
+        test: TEST_NAME,
+        p_value,
+        advisories: input.advisories,
+        statistic: v_n_observed as f64,
+        parameters: utils::parameter_map(&[("n", input.length)]),
+        elapsed_time,
+        alpha: constants::P_VALUE_THRESHOLD,
+        counts: Vec::new(),
+    };
+    span.record_result(&result);
 
-    Ok(p_value)
+    result
 }
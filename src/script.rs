@@ -0,0 +1,166 @@
+//! This module computes the overall verdict of a report by a user-provided rhai script, for QA
+//! processes a fixed significance level does not fit, e.g. "fail only if two or more tests fail,
+//! or any p < 1e-6".
+//!
+//! The script sees the variables `results`, an array with one map per result, and `skipped`, the
+//! number of skipped tests. Each map contains `test`, `p_value`, `statistic`, `passed`,
+//! `invalid`, `verdict` and `parameters`. The script evaluates to either a bool, true meaning
+//! "PASS", or one of the verdicts "PASS", "FAIL" and "INVALID":
+//!
+//! ```text
+//! let failed = results.filter(|r| !r.passed).len();
+//! failed < 2 && results.all(|r| r.p_value >= 1e-6)
+//! ```
+
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::Result;
+
+/// A compiled verdict script, which can be evaluated on any number of reports
+#[derive(Debug)]
+pub struct VerdictScript {
+    engine: rhai::Engine,
+    ast: rhai::AST,
+}
+
+impl VerdictScript {
+    /// Compile a verdict script.
+    ///
+    /// # Arguments
+    ///
+    /// script - The source code of the script
+    ///
+    /// # Return
+    ///
+    /// Ok(script) - The compiled script
+    /// Err(err) - The script contains a syntax error
+    pub fn new(script: &str) -> Result<Self> {
+        log::trace!("VerdictScript::new()");
+
+        let mut engine = rhai::Engine::new();
+        // a script running in a loop must not block the suite forever
+        engine.set_max_operations(constants::SCRIPT_MAX_OPERATIONS);
+
+        let ast =
+            engine
+                .compile(script)
+                .map_err(|err| customtypes::NistError::InvalidParameter {
+                    test: None,
+                    name: "script".to_owned(),
+                    reason: format!("Failed to compile verdict script: {}", err),
+                })?;
+
+        Ok(VerdictScript { engine, ast })
+    }
+
+    /// Evaluate the script on a report. As for SuiteReport::verdict(), the verdict is qualified
+    /// by the number of skipped tests.
+    ///
+    /// # Arguments
+    ///
+    /// report - The report to compute the verdict for
+    ///
+    /// # Return
+    ///
+    /// Ok(verdict) - The verdict computed by the script
+    /// Err(err) - The script failed or evaluated to an unknown verdict
+    pub fn evaluate(&self, report: &customtypes::SuiteReport) -> Result<String> {
+        log::trace!("VerdictScript::evaluate()");
+
+        let results: rhai::Array = report
+            .results
+            .iter()
+            .map(|result| rhai::Dynamic::from_map(result_map(result)))
+            .collect();
+        let mut scope = rhai::Scope::new();
+        scope.push("results", results);
+        scope.push("skipped", report.skipped.len() as rhai::INT);
+
+        let value = self
+            .engine
+            .eval_ast_with_scope::<rhai::Dynamic>(&mut scope, &self.ast)
+            .map_err(|err| customtypes::NistError::InvalidParameter {
+                test: None,
+                name: "script".to_owned(),
+                reason: format!("Failed to evaluate verdict script: {}", err),
+            })?;
+
+        let verdict = if value.is_bool() {
+            match value.as_bool() {
+                Ok(true) => "PASS".to_owned(),
+                _ => "FAIL".to_owned(),
+            }
+        } else if value.is_string() {
+            value.into_string().unwrap_or_default()
+        } else {
+            return Err(customtypes::NistError::InvalidParameter {
+                test: None,
+                name: "script".to_owned(),
+                reason: format!(
+                    "Verdict script evaluated to {}, expected a bool or a verdict",
+                    value.type_name()
+                ),
+            });
+        };
+        if !["PASS", "FAIL", "INVALID"].contains(&verdict.as_str()) {
+            return Err(customtypes::NistError::InvalidParameter { test: None, name: "script".to_owned(), reason: format!("Verdict script evaluated to unknown verdict '{}', expected PASS, FAIL or INVALID",
+                verdict) });
+        }
+        log::debug!("Verdict script evaluated to {}", verdict);
+
+        if report.skipped.is_empty() {
+            Ok(verdict)
+        } else {
+            Ok(format!(
+                "{} (qualified, {} skipped)",
+                verdict,
+                report.skipped.len()
+            ))
+        }
+    }
+}
+
+/// Compile a verdict script and evaluate it on a report.
+///
+/// # Arguments
+///
+/// report - The report to compute the verdict for
+/// script - The source code of the script
+///
+/// # Return
+///
+/// Ok(verdict) - The verdict computed by the script
+/// Err(err) - Some error occured
+pub fn evaluate_verdict(report: &customtypes::SuiteReport, script: &str) -> Result<String> {
+    log::trace!("script::evaluate_verdict()");
+
+    VerdictScript::new(script)?.evaluate(report)
+}
+
+/// Convert a result into the map the script sees.
+///
+/// # Arguments
+///
+/// result - The result of a test
+///
+/// # Return
+///
+/// map - The fields of the result
+fn result_map(result: &customtypes::TestResult) -> rhai::Map {
+    let parameters: rhai::Map = result
+        .parameters
+        .iter()
+        .map(|(name, value)| (name.as_str().into(), (*value as rhai::INT).into()))
+        .collect();
+
+    let mut map = rhai::Map::new();
+    map.insert("test".into(), result.test.name().into());
+    map.insert("p_value".into(), result.p_value.into());
+    map.insert("statistic".into(), result.statistic.into());
+    map.insert("passed".into(), result.passed().into());
+    map.insert("invalid".into(), result.is_invalid().into());
+    map.insert("verdict".into(), result.verdict().into());
+    map.insert("parameters".into(), parameters.into());
+
+    map
+}
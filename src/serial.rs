@@ -0,0 +1,261 @@
+//! This module performs the Serial Test.
+//!
+//! Description of test from NIST SP 800-22:
+//!
+//! "The focus of this test is the frequency of all possible overlapping m-bit patterns across the entire
+//! sequence. The purpose of this test is to determine whether the number of occurrences of the 2^m m-bit
+//! overlapping patterns is approximately the same as would be expected for a random sequence. Random
+//! sequences have uniformity; that is, every m-bit pattern has the same chance of appearing as every other
+//! m-bit pattern."
+
+use crate::bit_sequence::BitInput;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use crate::telemetry;
+use crate::utils;
+
+const TEST_NAME: customtypes::Test = customtypes::Test::Serial;
+
+/// Perform the Serial Test by determining both p-values.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_length - The block length m
+///
+/// # Return
+///
+/// Ok(result) - The p-values of del psi^2_m and del^2 psi^2_m
+/// Err(err) - Some error occured
+pub fn perform_test(
+    bit_string: &(impl BitInput + ?Sized),
+    block_length: usize,
+) -> Result<customtypes::SerialResult> {
+    log::trace!("serial::perform_test()");
+
+    let [first, second] = run_test(bit_string, block_length)?;
+
+    Ok(customtypes::SerialResult {
+        p_value_1: first.p_value,
+        p_value_2: second.p_value,
+    })
+}
+
+/// Perform the Serial Test and return its results. The statistic of the first result is
+/// del psi^2_m, the one of the second result del^2 psi^2_m.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_length - The block length m
+///
+/// # Return
+///
+/// Ok(results) - The p-values, statistics, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured
+pub fn run_test(
+    bit_string: &(impl BitInput + ?Sized),
+    block_length: usize,
+) -> Result<[customtypes::TestResult; 2]> {
+    log::trace!("serial::run_test()");
+
+    run_test_cancellable(
+        bit_string,
+        block_length,
+        &customtypes::CancellationToken::new(),
+    )
+}
+
+/// Perform the Serial Test like run_test() and stop once the passed token got cancelled.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// block_length - The block length m
+/// cancellation - The token to stop the test with
+///
+/// # Return
+///
+/// Ok(results) - The p-values, statistics, parameters, elapsed time and advisories of the test
+/// Err(err) - Some error occured or the test got cancelled
+pub fn run_test_cancellable(
+    bit_string: &(impl BitInput + ?Sized),
+    block_length: usize,
+    cancellation: &customtypes::CancellationToken,
+) -> Result<[customtypes::TestResult; 2]> {
+    log::trace!("serial::run_test_cancellable()");
+
+    // capture the current time before executing the actual test
+    let start_time = std::time::Instant::now();
+
+    // open the span of the test and of its first phase
+    let span = telemetry::Span::test(TEST_NAME);
+    let phase = telemetry::Span::phase("validation");
+
+    // check if bit string contains invalid characters
+    let input = utils::evaluate_bit_string(TEST_NAME, bit_string, constants::RECOMMENDED_SIZE)
+        .with_context(|| "Invalid character(s) in passed bit string detected")?;
+    let length = input.length;
+    evaluate_block_length(length, block_length)?;
+
+    phase.exit();
+    let phase = telemetry::Span::phase("computation");
+
+    // compute psi^2 of the overlapping patterns of length m, m-1 and m-2, wrapping around the end
+    // of the sequence
+    let psi_squared = (0..3)
+        .map(|offset| compute_psi_squared(bit_string, length, block_length - offset, cancellation))
+        .collect::<Result<Vec<f64>>>()?;
+    let del_psi_squared = psi_squared[0] - psi_squared[1];
+    let del_2_psi_squared = psi_squared[0] - 2.0 * psi_squared[1] + psi_squared[2];
+    log::debug!(
+        "{}: psi^2 = {:?}, del psi^2 = {}, del^2 psi^2 = {}",
+        TEST_NAME,
+        psi_squared,
+        del_psi_squared,
+        del_2_psi_squared
+    );
+
+    // p-value1 = igamc(2^(m-2), del psi^2 / 2), p-value2 = igamc(2^(m-3), del^2 psi^2 / 2)
+    let p_value_1 = compute_p_value(del_psi_squared, block_length as i32 - 2);
+    let p_value_2 = compute_p_value(del_2_psi_squared, block_length as i32 - 3);
+    log::info!(
+        "{}: p-value1 = {}, p-value2 = {}",
+        TEST_NAME,
+        p_value_1,
+        p_value_2
+    );
+
+    phase.exit();
+
+    // capture the current time after the test got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "{} took {:.6} seconds",
+        TEST_NAME,
+        elapsed_time.as_secs_f64()
+    );
+
+    let results = [(p_value_1, del_psi_squared), (p_value_2, del_2_psi_squared)].map(
+        |(p_value, statistic)| customtypes::TestResult {
+            test: TEST_NAME,
+            p_value,
+            advisories: input.advisories.clone(),
+            statistic,
+            parameters: utils::parameter_map(&[("n", length), ("m", block_length)]),
+            elapsed_time,
+            alpha: constants::P_VALUE_THRESHOLD,
+            counts: Vec::new(),
+        },
+    );
+    for result in &results {
+        span.record_result(result);
+    }
+
+    Ok(results)
+}
+
+/// Check the block length m for validity.
+///
+/// # Arguments
+///
+/// length - The length n of the bit string
+/// block_length - The block length m
+///
+/// # Return
+///
+/// Ok() - The block length is valid
+/// Err(err) - Some error occured
+fn evaluate_block_length(length: usize, block_length: usize) -> Result<()> {
+    log::trace!("serial::evaluate_block_length()");
+
+    if !(constants::MIN_BLOCK_LENGTH_SERIAL..=constants::MAX_BLOCK_LENGTH_SERIAL)
+        .contains(&block_length)
+    {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Block length m ({}) must be between {} and {}",
+                block_length,
+                constants::MIN_BLOCK_LENGTH_SERIAL,
+                constants::MAX_BLOCK_LENGTH_SERIAL
+            ),
+        ));
+    }
+    if length <= block_length {
+        return Err(customtypes::NistError::invalid_parameter(
+            TEST_NAME,
+            "m",
+            format!(
+                "Length ({}) must exceed block length m ({})",
+                length, block_length
+            ),
+        ));
+    }
+
+    // NIST recommends m < floor(log2(n)) - 2, larger m lead to unreliable p-values
+    if block_length + 2 >= length.ilog2() as usize {
+        log::warn!(
+            "{}: Block length m ({}) should be less than log2(n) - 2 for length {}",
+            TEST_NAME,
+            block_length,
+            length
+        );
+    }
+
+    Ok(())
+}
+
+/// Compute psi^2_m = 2^m / n * sum of the squared pattern counts - n. psi^2_0 is 0.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// length - The length n of the bit string
+/// pattern_len - The length m of the patterns
+/// cancellation - The token to stop the count of the patterns with
+///
+/// # Return
+///
+/// Ok(psi_squared) - The value of psi^2_m
+/// Err(err) - The test got cancelled
+fn compute_psi_squared(
+    bit_string: &(impl BitInput + ?Sized),
+    length: usize,
+    pattern_len: usize,
+    cancellation: &customtypes::CancellationToken,
+) -> Result<f64> {
+    if pattern_len == 0 {
+        return Ok(0.0);
+    }
+
+    let n = length as f64;
+    let sum_of_squares: f64 =
+        utils::count_overlapping_patterns(TEST_NAME, bit_string, pattern_len, cancellation)?
+            .iter()
+            .map(|&count| (count as f64).powi(2))
+            .sum();
+
+    Ok(2.0_f64.powi(pattern_len as i32) / n * sum_of_squares - n)
+}
+
+/// Compute the p-value igamc(2^exponent, statistic / 2) of a difference of psi^2.
+///
+/// # Arguments
+///
+/// statistic - The difference del psi^2_m or del^2 psi^2_m
+/// exponent - The exponent of the degrees of freedom, m-2 or m-3
+///
+/// # Return
+///
+/// p_value - The p-value of the difference
+fn compute_p_value(statistic: f64, exponent: i32) -> f64 {
+    if statistic <= 0.0 {
+        return 1.0;
+    }
+
+    statrs::function::gamma::gamma_ur(2.0_f64.powi(exponent), statistic * 0.5)
+}
@@ -0,0 +1,300 @@
+//! This module reads the output of the NIST reference implementation (STS) and compares it with a
+//! report of this crate, so labs can qualify the implementation against the reference tool.
+//!
+//! The reference implementation writes the directory experiments/AlgorithmTesting with the file
+//! finalAnalysisReport.txt and one subdirectory per test containing results.txt, which lists one
+//! p-value per line. Only runs on a single sequence can be compared with a report.
+
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::{Context, Result};
+use std::path::Path;
+
+/// Read the output of the reference implementation. Result files of tests which were not run are
+/// missing and left out.
+///
+/// # Arguments
+///
+/// directory - The directory containing finalAnalysisReport.txt, e.g.
+/// experiments/AlgorithmTesting
+///
+/// # Return
+///
+/// Ok(output) - The parsed rows of the final analysis report and p-values of the result files
+/// Err(err) - Some error occured
+pub fn read_output(directory: &str) -> Result<customtypes::StsOutput> {
+    log::trace!("sts::read_output()");
+
+    let directory = Path::new(directory);
+    let report_path = directory.join(constants::STS_FINAL_ANALYSIS_REPORT);
+    let contents = std::fs::read_to_string(&report_path)
+        .with_context(|| format!("Failed to read '{}'", report_path.display()))?;
+
+    let mut output = customtypes::StsOutput {
+        rows: parse_final_analysis_report(&contents)
+            .with_context(|| format!("Invalid report '{}'", report_path.display()))?,
+        ..Default::default()
+    };
+
+    for test in customtypes::Test::ALL {
+        let sts_name = match test.sts_name() {
+            Some(sts_name) => sts_name,
+            None => continue,
+        };
+        let results_path = directory.join(sts_name).join(constants::STS_RESULTS_FILE);
+        if !results_path.is_file() {
+            log::debug!("{}: No result file '{}'", test, results_path.display());
+            continue;
+        }
+
+        let contents = std::fs::read_to_string(&results_path)
+            .with_context(|| format!("Failed to read '{}'", results_path.display()))?;
+        let p_values = parse_results(&contents)
+            .with_context(|| format!("Invalid result file '{}'", results_path.display()))?;
+        output.p_values.insert(test, p_values);
+    }
+
+    log::info!(
+        "Read {} rows and result files of {} tests from '{}'",
+        output.rows.len(),
+        output.p_values.len(),
+        directory.display()
+    );
+
+    Ok(output)
+}
+
+/// Parse the contents of finalAnalysisReport.txt. Header and summary lines are skipped.
+///
+/// # Arguments
+///
+/// contents - The contents of the file
+///
+/// # Return
+///
+/// Ok(rows) - The rows of the report in their order
+/// Err(err) - Some error occured
+pub fn parse_final_analysis_report(contents: &str) -> Result<Vec<customtypes::StsRow>> {
+    log::trace!("sts::parse_final_analysis_report()");
+
+    let mut rows = Vec::<customtypes::StsRow>::new();
+    for (index, line) in contents.lines().enumerate() {
+        // rows start with the histogram C1 ... C10, any other line is a header or a summary
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < constants::STS_NUMBER_OF_BINS {
+            continue;
+        }
+        let mut histogram = [0; constants::STS_NUMBER_OF_BINS];
+        if tokens
+            .iter()
+            .zip(histogram.iter_mut())
+            .any(|(token, count)| match token.parse::<usize>() {
+                Ok(value) => {
+                    *count = value;
+                    false
+                }
+                Err(_) => true,
+            })
+        {
+            continue;
+        }
+
+        let row = parse_row(histogram, &tokens[constants::STS_NUMBER_OF_BINS..])
+            .with_context(|| format!("Invalid row in line {}", index + 1))?;
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// Parse the contents of a results.txt, containing one p-value per line.
+///
+/// # Arguments
+///
+/// contents - The contents of the file
+///
+/// # Return
+///
+/// Ok(p_values) - The p-values in their order
+/// Err(err) - Some error occured
+pub fn parse_results(contents: &str) -> Result<Vec<f64>> {
+    log::trace!("sts::parse_results()");
+
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse::<f64>()
+                .with_context(|| format!("Invalid p-value '{}'", line))
+        })
+        .collect()
+}
+
+/// Compare the output of the reference implementation on a single sequence with a report on the
+/// same sequence. The p-values of each test are compared in their order, so the Cusum test has
+/// to be run in forward mode first.
+///
+/// # Arguments
+///
+/// report - The report of this crate
+/// output - The output of the reference implementation
+///
+/// # Return
+///
+/// Ok(discrepancies) - The differences found, in the order of customtypes::Test
+/// Err(err) - The reference implementation was run on more than one sequence
+pub fn compare(
+    report: &customtypes::SuiteReport,
+    output: &customtypes::StsOutput,
+) -> Result<Vec<customtypes::Discrepancy>> {
+    log::trace!("sts::compare()");
+
+    if let Some(row) = output.rows.iter().find(|row| row.sequences > 1) {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!(
+                "{}: Reference output covers {} sequences, but a report covers a single one",
+                row.test, row.sequences
+            ),
+        });
+    }
+
+    let mut discrepancies = Vec::<customtypes::Discrepancy>::new();
+    for test in customtypes::Test::ALL
+        .into_iter()
+        .filter(|test| test.sts_name().is_some())
+    {
+        let results: Vec<&customtypes::TestResult> = report
+            .results
+            .iter()
+            .filter(|result| result.test == test)
+            .collect();
+        // rows without any sequence belong to tests not applicable to the sequence
+        let rows: Vec<&customtypes::StsRow> = output
+            .rows
+            .iter()
+            .filter(|row| row.test == test && row.sequences == 1)
+            .collect();
+        let p_values = output.p_values.get(&test).map_or(&[][..], Vec::as_slice);
+
+        let count = results.len().max(rows.len()).max(p_values.len());
+        for index in 0..count {
+            let mut push = |kind| {
+                discrepancies.push(customtypes::Discrepancy { test, index, kind });
+            };
+
+            let result = match results.get(index) {
+                Some(result) => result,
+                None => {
+                    push(customtypes::DiscrepancyKind::MissingInReport);
+                    continue;
+                }
+            };
+            let row = rows.get(index);
+            let p_value = p_values.get(index);
+            if row.is_none() && p_value.is_none() {
+                push(customtypes::DiscrepancyKind::MissingInReference);
+                continue;
+            }
+
+            if let Some(&reference) = p_value {
+                if (reference - result.p_value).abs() > constants::STS_TOLERANCE {
+                    push(customtypes::DiscrepancyKind::PValue {
+                        reference,
+                        report: result.p_value,
+                    });
+                }
+            }
+            if let Some(row) = row {
+                let report_bin = ((result.p_value * constants::STS_NUMBER_OF_BINS as f64) as usize)
+                    .min(constants::STS_NUMBER_OF_BINS - 1);
+                if let Some(reference) = row.histogram.iter().position(|&count| count == 1) {
+                    if reference != report_bin {
+                        push(customtypes::DiscrepancyKind::Bin {
+                            reference,
+                            report: report_bin,
+                        });
+                    }
+                }
+                let reference = row.passed == 1;
+                if reference != result.passed() {
+                    push(customtypes::DiscrepancyKind::Passed {
+                        reference,
+                        report: result.passed(),
+                    });
+                }
+            }
+        }
+    }
+
+    for discrepancy in &discrepancies {
+        log::warn!("Discrepancy to reference output: {}", discrepancy);
+    }
+    log::info!(
+        "Compared {} results with the reference output: {} discrepancies",
+        report.results.len(),
+        discrepancies.len()
+    );
+
+    Ok(discrepancies)
+}
+
+/// Parse the columns of a row following the histogram.
+///
+/// # Arguments
+///
+/// histogram - The already parsed histogram C1 ... C10
+/// tokens - The remaining columns: p-value, proportion and test, each possibly followed by '*'
+///
+/// # Return
+///
+/// Ok(row) - The parsed row
+/// Err(err) - Some error occured
+fn parse_row(
+    histogram: [usize; constants::STS_NUMBER_OF_BINS],
+    tokens: &[&str],
+) -> Result<customtypes::StsRow> {
+    // failing values are marked by '*', either separated or attached to the value
+    let tokens: Vec<&str> = tokens
+        .iter()
+        .map(|token| token.trim_end_matches('*'))
+        .filter(|token| !token.is_empty())
+        .collect();
+    if tokens.len() != 3 {
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!(
+                "Expected p-value, proportion and test, got {} columns",
+                tokens.len()
+            ),
+        });
+    }
+
+    // the uniformity is only assessed for enough sequences, otherwise "----" is written
+    let uniformity_p_value = match tokens[0] {
+        "----" => None,
+        value => Some(
+            value
+                .parse::<f64>()
+                .with_context(|| format!("Invalid p-value '{}'", value))?,
+        ),
+    };
+    let (passed, sequences) = tokens[1]
+        .split_once('/')
+        .with_context(|| format!("Invalid proportion '{}'", tokens[1]))?;
+    let passed = passed
+        .parse::<usize>()
+        .with_context(|| format!("Invalid proportion '{}'", tokens[1]))?;
+    let sequences = sequences
+        .parse::<usize>()
+        .with_context(|| format!("Invalid proportion '{}'", tokens[1]))?;
+    let test = customtypes::Test::from_sts_name(tokens[2])
+        .with_context(|| format!("Unknown test '{}'", tokens[2]))?;
+
+    Ok(customtypes::StsRow {
+        test,
+        histogram,
+        uniformity_p_value,
+        passed,
+        sequences,
+    })
+}
@@ -0,0 +1,651 @@
+//! This module runs all implemented tests of NIST SP 800-22 on one bit string. Tests requiring a
+//! longer bit string than passed are skipped and recorded in the report, so short inputs still
+//! get a qualified verdict from the tests they support. All other tests depend on the
+//! "Frequency Monobit Test", so they are skipped as well if it fails. The excursion tests are not
+//! applicable to a random walk with too few cycles and skipped in this case. Tests failing with an
+//! error and the tests left when a run gets cancelled are recorded as skipped as well, so the
+//! results completed so far are kept.
+
+use crate::approximate_entropy;
+#[cfg(feature = "matrix-rank")]
+use crate::binary_matrix_rank;
+use crate::bit_sequence::{BitInput, BitSequence};
+use crate::constants;
+use crate::cumulative_sums;
+use crate::customtypes;
+use crate::customtypes::Result;
+#[cfg(feature = "spectral")]
+use crate::dft_spectral;
+use crate::frequency_block;
+use crate::frequency_monobit;
+use crate::longest_run;
+use crate::non_overlapping_template;
+use crate::overlapping_template;
+use crate::progress;
+use crate::random_excursions;
+use crate::random_excursions_variant;
+use crate::runs;
+use crate::serial;
+use crate::tables;
+use crate::telemetry;
+use crate::utils;
+use rand::RngCore;
+
+/// Run all implemented tests in the order of NIST SP 800-22 on passed bit string. Tests whose
+/// required length exceeds the bit string length are skipped. If the "Frequency Monobit Test"
+/// fails, all other tests are skipped unless disabled in the configuration.
+/// Tests which are not implemented yet are not skipped, the report only lists their advice for the
+/// bit string length.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// config - The configuration of the suite run
+///
+/// # Return
+///
+/// Ok(report) - The results of all tests run and the skipped tests
+/// Err(err) - Some error occured or a result violates the configured compliance mode
+pub fn run_all(
+    bit_string: &(impl BitInput + ?Sized),
+    config: &customtypes::SuiteConfig,
+) -> Result<customtypes::SuiteReport> {
+    log::trace!("suite::run_all()");
+
+    run_all_with_control(
+        bit_string,
+        config,
+        &customtypes::ExecutionControl::default(),
+    )
+}
+
+/// Run all implemented tests like run_all(), reporting the progress of each test to the callback
+/// of the passed control and stopping once its token got cancelled, e.g. to keep a GUI or a
+/// service responsive while 10^8 bits get tested. A cancelled run returns the results completed
+/// so far like run_selected_with_control().
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// config - The configuration of the suite run
+/// control - The progress callback and cancellation token of the caller
+///
+/// # Return
+///
+/// Ok(report) - The results of all tests run and the skipped tests, possibly cancelled
+/// Err(err) - The input is invalid or a result violates the configured compliance mode
+pub fn run_all_with_control(
+    bit_string: &(impl BitInput + ?Sized),
+    config: &customtypes::SuiteConfig,
+    control: &customtypes::ExecutionControl,
+) -> Result<customtypes::SuiteReport> {
+    log::trace!("suite::run_all_with_control()");
+
+    let tests: Vec<customtypes::Test> = customtypes::Test::ALL
+        .into_iter()
+        .filter(|&test| test.is_nist() && required_length(test, &config.limits).is_some())
+        .collect();
+    let mut report = run_selected_with_control(bit_string, &tests, config, control)?;
+
+    // tests which are not implemented yet are not skipped, only their length advice is reported
+    report.length_advice = customtypes::Test::ALL
+        .into_iter()
+        .filter(|&test| test.is_nist() && required_length(test, &config.limits).is_none())
+        .filter_map(|test| tables::length_advice(test, bit_string.bit_len()).ok())
+        .collect();
+
+    Ok(report)
+}
+
+/// Run all implemented tests like run_all() on bits pulled straight from a random number
+/// generator, e.g. to test a DRBG implementation in CI without writing its output to a file. To
+/// run only some of the tests, pull the bits by BitSequence::from_rng() and pass them to
+/// run_selected().
+///
+/// # Arguments
+///
+/// rng - The random number generator to be tested
+/// number_of_bits - The number of bits to pull from the generator
+/// config - The configuration of the suite run
+///
+/// # Return
+///
+/// Ok(report) - The results of all tests run and the skipped tests
+/// Err(err) - Some error occured, e.g. the generator failed, or a result violates the configured
+/// compliance mode
+pub fn test_rng<R: RngCore + ?Sized>(
+    rng: &mut R,
+    number_of_bits: usize,
+    config: &customtypes::SuiteConfig,
+) -> Result<customtypes::SuiteReport> {
+    log::trace!("suite::test_rng()");
+
+    let bits = BitSequence::from_rng(rng, number_of_bits)?;
+    log::info!(
+        "Pulled {} bits from the random number generator",
+        bits.len()
+    );
+
+    run_all(&bits, config)
+}
+
+/// Run the passed tests like run_all(), e.g. to restrict a run to the tests of interest. The tests
+/// are run in the order of NIST SP 800-22 regardless of the order passed. If the "Frequency
+/// Monobit Test" is not passed, the other tests do not depend on it.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// tests - The tests to be run
+/// config - The configuration of the suite run
+///
+/// # Return
+///
+/// Ok(report) - The results of all tests run and the skipped tests
+/// Err(err) - Some error occured, a passed test is not part of the suite or a result violates the
+/// configured compliance mode
+pub fn run_selected(
+    bit_string: &(impl BitInput + ?Sized),
+    tests: &[customtypes::Test],
+    config: &customtypes::SuiteConfig,
+) -> Result<customtypes::SuiteReport> {
+    log::trace!("suite::run_selected()");
+
+    run_selected_with_control(
+        bit_string,
+        tests,
+        config,
+        &customtypes::ExecutionControl::default(),
+    )
+}
+
+/// Run the passed tests like run_selected(), reporting the progress of each test to the callback
+/// of the passed control and stopping once its token got cancelled. The token is checked between
+/// the tests and by the long-running tests within them. A cancelled run returns the results
+/// completed so far, the report is marked as cancelled and lists the remaining tests as skipped.
+/// Errors of a single test are listed as skipped as well instead of aborting the run.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// tests - The tests to be run
+/// config - The configuration of the suite run
+/// control - The progress callback and cancellation token of the caller
+///
+/// # Return
+///
+/// Ok(report) - The results of all tests run and the skipped tests, possibly cancelled
+/// Err(err) - The input is invalid, a passed test is not part of the suite or a result violates
+/// the configured compliance mode
+pub fn run_selected_with_control(
+    bit_string: &(impl BitInput + ?Sized),
+    tests: &[customtypes::Test],
+    config: &customtypes::SuiteConfig,
+    control: &customtypes::ExecutionControl,
+) -> Result<customtypes::SuiteReport> {
+    log::trace!("suite::run_selected_with_control()");
+
+    // tests which are not implemented yet or disabled are not part of the suite
+    if let Some(test) = tests
+        .iter()
+        .find(|&&test| !test.is_nist() || required_length(test, &config.limits).is_none())
+    {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: Some(*test),
+            name: "test".to_owned(),
+            reason: "Not part of the suite".to_owned(),
+        });
+    }
+
+    // capture the current time before executing the tests
+    let start_time = std::time::Instant::now();
+    let _span = telemetry::Span::suite();
+
+    utils::check_limits(config)?;
+
+    // check the bit string once instead of letting the first test fail
+    if bit_string.bit_len() == 0 {
+        if let Some(test) = customtypes::Test::ALL
+            .into_iter()
+            .find(|test| tests.contains(test))
+        {
+            let required = required_length(test, &config.limits).unwrap_or(1);
+            return Err(customtypes::NistError::insufficient_length(
+                test, required, 0,
+            ));
+        }
+    }
+    let input = bit_string.validate();
+    if let Some((position, symbol)) = input.first_invalid {
+        return Err(customtypes::NistError::InvalidBit { position, symbol });
+    }
+
+    // strict mode refuses the run before any test computed its result
+    if config.compliance_mode == customtypes::ComplianceMode::Strict {
+        for &test in tests {
+            if required_length(test, &config.limits)
+                .is_some_and(|required_length| input.length >= required_length)
+            {
+                check_recommendations(test, input.length, config)?;
+            }
+        }
+    }
+
+    let mut report = customtypes::SuiteReport::default();
+    let mut monobit_failed = false;
+    for test in customtypes::Test::ALL
+        .into_iter()
+        .filter(|test| tests.contains(test))
+    {
+        // tests which are not implemented yet or disabled are not part of the suite
+        let required_length = match required_length(test, &config.limits) {
+            Some(required_length) => required_length,
+            None => continue,
+        };
+
+        // once the run got cancelled, the remaining tests are listed as skipped
+        if report.cancelled || control.cancellation.is_cancelled() {
+            report.cancelled = true;
+            report.skipped.push(customtypes::SkippedTest {
+                test,
+                reason: customtypes::SkipReason::Cancelled,
+            });
+            continue;
+        }
+
+        if monobit_failed {
+            report.skipped.push(customtypes::SkippedTest {
+                test,
+                reason: customtypes::SkipReason::MonobitFailed,
+            });
+            continue;
+        }
+
+        if input.length < required_length {
+            log::warn!(
+                "{}: Skipped, bit string length {} is less than the required {} bits",
+                test,
+                input.length,
+                required_length
+            );
+            report.skipped.push(customtypes::SkippedTest {
+                test,
+                reason: customtypes::SkipReason::TooShort { required_length },
+            });
+            continue;
+        }
+
+        let results = match run_test(
+            test,
+            bit_string,
+            input.length,
+            &config.limits,
+            &config.params,
+            control,
+        ) {
+            Ok(results) => results,
+            Err(err) => {
+                let reason = match err.root() {
+                    // the excursion tests are not applicable to a random walk with too few cycles
+                    cycles @ customtypes::NistError::InsufficientCycles {
+                        number_of_cycles,
+                        required_cycles,
+                        ..
+                    } => {
+                        log::warn!("{}: Skipped, {}", test, cycles);
+                        customtypes::SkipReason::TooFewCycles {
+                            number_of_cycles: *number_of_cycles,
+                            required_cycles: *required_cycles,
+                        }
+                    }
+                    customtypes::NistError::Cancelled { .. } => {
+                        log::warn!("{}: Cancelled, skipping the remaining tests", test);
+                        report.cancelled = true;
+                        customtypes::SkipReason::Cancelled
+                    }
+                    // an error of one test does not discard the results of the others
+                    _ => {
+                        log::error!("{}: {:#}", test, err);
+                        customtypes::SkipReason::Error { error: err }
+                    }
+                };
+                report
+                    .skipped
+                    .push(customtypes::SkippedTest { test, reason });
+                continue;
+            }
+        };
+
+        for result in results {
+            let result = utils::check_compliance(config, result)?;
+
+            // the other tests depend on a balanced proportion of ones and zeros
+            if test == customtypes::Test::FrequencyMonobit
+                && !result.passed()
+                && config.stop_on_monobit_failure
+            {
+                log::warn!(
+                    "{}: Failed with p-value {}, skipping the remaining tests",
+                    test,
+                    result.p_value
+                );
+                monobit_failed = true;
+            }
+            report.results.push(result);
+        }
+    }
+
+    // capture the current time after the tests got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "Suite ran {} tests and skipped {} in {:.6} seconds: {}",
+        report.results.len(),
+        report.skipped.len(),
+        elapsed_time.as_secs_f64(),
+        report.verdict_with(config.verdict_policy.as_ref())
+    );
+
+    Ok(report)
+}
+
+/// Check the bit string length and the parameters passed for a test against the NIST
+/// recommendations before the test is run. Advisories depending on the computation of a test,
+/// e.g. on its expected counts, are still refused by utils::check_compliance() afterwards.
+///
+/// # Arguments
+///
+/// test - The test to be run
+/// length - The length n of the bit string
+/// config - The configuration containing the limits and the passed parameters
+///
+/// # Return
+///
+/// Ok() - The length and the parameters meet the recommendations
+/// Err(err) - The length or a parameter violates the recommendations
+fn check_recommendations(
+    test: customtypes::Test,
+    length: usize,
+    config: &customtypes::SuiteConfig,
+) -> Result<()> {
+    log::trace!("suite::check_recommendations()");
+
+    let limits = &config.limits;
+    let recommended_length = match test {
+        customtypes::Test::LongestRun => limits.min_length_longest_run,
+        customtypes::Test::BinaryMatrixRank => constants::RECOMMENDED_SIZE_MATRIX_TEST,
+        customtypes::Test::DFTSpectral => constants::RECOMMENDED_SIZE_DFT,
+        customtypes::Test::OverlappingTemplate => constants::RECOMMENDED_SIZE_OVERLAPPING_TEMPLATE,
+        customtypes::Test::Serial | customtypes::Test::ApproximateEntropy => {
+            constants::RECOMMENDED_SIZE
+        }
+        customtypes::Test::RandomExcursions | customtypes::Test::RandomExcursionsVariant => {
+            constants::RECOMMENDED_SIZE_EXCURSIONS
+        }
+        _ => limits.recommended_size,
+    };
+    if length < recommended_length {
+        return Err(customtypes::NistError::invalid_parameter(
+            test,
+            "n",
+            format!(
+                "Refused in strict mode because the bit string length {} is less than the recommended {} bits",
+                length, recommended_length
+            )
+        ));
+    }
+
+    let template_len = match test {
+        customtypes::Test::FrequencyBlock => {
+            if let Some(block_size) = config
+                .params
+                .block_size
+                .filter(|&block_size| block_size < limits.recommended_block_size)
+            {
+                return Err(customtypes::NistError::invalid_parameter(
+                    test,
+                    "M",
+                    format!(
+                        "Refused in strict mode because the block size {} is less than the recommended {} bits",
+                        block_size, limits.recommended_block_size
+                    )
+                ));
+            }
+            None
+        }
+        customtypes::Test::NonOverlappingTemplate => config.params.template_len,
+        customtypes::Test::OverlappingTemplate => config.params.overlapping_template_len,
+        _ => None,
+    };
+    if let Some(template_len) = template_len.filter(|template_len| {
+        !(limits.recommended_template_len.0..=limits.recommended_template_len.1)
+            .contains(template_len)
+    }) {
+        return Err(customtypes::NistError::invalid_parameter(
+            test,
+            "m",
+            format!(
+                "Refused in strict mode because the template length {} differs from the recommended lengths {} and {}",
+                template_len, limits.recommended_template_len.0, limits.recommended_template_len.1
+            )
+        ));
+    }
+
+    Ok(())
+}
+
+/// Get the bit string length the suite requires to run a test.
+///
+/// # Arguments
+///
+/// test - The test to get the required length for
+/// limits - The thresholds of the suite run
+///
+/// # Return
+///
+/// Some(required_length) - The minimum bit string length of the test
+/// None - The test is not implemented or disabled by a feature and not part of the suite
+pub fn required_length(test: customtypes::Test, limits: &customtypes::Limits) -> Option<usize> {
+    match test {
+        customtypes::Test::FrequencyMonobit
+        | customtypes::Test::FrequencyBlock
+        | customtypes::Test::Runs
+        | customtypes::Test::NonOverlappingTemplate
+        | customtypes::Test::CumulativeSums => Some(limits.recommended_size),
+        customtypes::Test::LongestRun => Some(limits.min_length_longest_run),
+        customtypes::Test::BinaryMatrixRank if cfg!(feature = "matrix-rank") => {
+            Some(constants::RECOMMENDED_SIZE_MATRIX_TEST)
+        }
+        customtypes::Test::DFTSpectral if cfg!(feature = "spectral") => {
+            Some(constants::RECOMMENDED_SIZE_DFT)
+        }
+        customtypes::Test::OverlappingTemplate => {
+            Some(constants::RECOMMENDED_SIZE_OVERLAPPING_TEMPLATE)
+        }
+        customtypes::Test::Serial => Some(constants::MIN_LENGTH_SERIAL),
+        customtypes::Test::ApproximateEntropy => Some(constants::MIN_LENGTH_APPROXIMATE_ENTROPY),
+        customtypes::Test::RandomExcursions | customtypes::Test::RandomExcursionsVariant => {
+            Some(constants::RECOMMENDED_SIZE_EXCURSIONS)
+        }
+        _ => None,
+    }
+}
+
+/// Run a single test of the suite with the parameters the suite chooses for it unless they are
+/// overridden.
+///
+/// # Arguments
+///
+/// test - The test to be run
+/// bit_string - The bit string to be tested for randomness
+/// length - The length of the bit string
+/// limits - The thresholds of the suite run
+/// params - The parameters overriding the ones chosen by the suite
+/// control - The progress callback and cancellation token of the caller
+///
+/// # Return
+///
+/// Ok(results) - The results of the test, e.g. both modes of the Cumulative Sums test
+/// Err(err) - Some error occured or the test got cancelled
+pub(crate) fn run_test(
+    test: customtypes::Test,
+    bit_string: &(impl BitInput + ?Sized),
+    length: usize,
+    limits: &customtypes::Limits,
+    params: &customtypes::TestParams,
+    control: &customtypes::ExecutionControl,
+) -> Result<Vec<customtypes::TestResult>> {
+    log::trace!("suite::run_test()");
+
+    // tests without progress reports of their own are reported when started and finished
+    let own_progress = matches!(
+        test,
+        customtypes::Test::BinaryMatrixRank
+            | customtypes::Test::DFTSpectral
+            | customtypes::Test::NonOverlappingTemplate
+    );
+    let reporter = progress::ProgressReporter::new(test, 1, control.progress);
+    if !own_progress {
+        reporter.report(0);
+    }
+
+    let cancellation = &control.cancellation;
+    let results = match test {
+        customtypes::Test::FrequencyMonobit => {
+            vec![frequency_monobit::run_test_cancellable(
+                bit_string,
+                cancellation,
+                limits,
+            )?]
+        }
+        customtypes::Test::FrequencyBlock => {
+            // choose the smallest block size which keeps the number of blocks within the limit
+            let block_size = params.block_size.unwrap_or_else(|| {
+                (length / limits.max_number_of_blocks + 1).max(limits.recommended_block_size)
+            });
+            vec![frequency_block::run_test_cancellable(
+                bit_string,
+                block_size,
+                cancellation,
+                limits,
+            )?]
+        }
+        customtypes::Test::Runs => {
+            vec![runs::run_test_cancellable(
+                bit_string,
+                cancellation,
+                limits,
+            )?]
+        }
+        customtypes::Test::LongestRun => vec![longest_run::run_test_cancellable(
+            bit_string,
+            customtypes::RunBit::Ones,
+            cancellation,
+            limits,
+        )?],
+        #[cfg(feature = "matrix-rank")]
+        customtypes::Test::BinaryMatrixRank => vec![binary_matrix_rank::run_test_cancellable(
+            bit_string,
+            constants::MATRIX_ROWS_M,
+            constants::MATRIX_COLUMNS_Q,
+            control.progress,
+            cancellation,
+        )?],
+        #[cfg(feature = "spectral")]
+        customtypes::Test::DFTSpectral => vec![dft_spectral::run_test_cancellable(
+            bit_string,
+            control.progress,
+            cancellation,
+        )?],
+        customtypes::Test::NonOverlappingTemplate => {
+            let (template_len, number_of_blocks) =
+                match (params.template_len, params.number_of_template_blocks) {
+                    (Some(template_len), Some(number_of_blocks)) => {
+                        (template_len, number_of_blocks)
+                    }
+                    (template_len, number_of_blocks) => {
+                        let tuned = non_overlapping_template::tune_params(length, limits)?;
+                        (
+                            template_len.unwrap_or(tuned.0),
+                            number_of_blocks.unwrap_or(tuned.1),
+                        )
+                    }
+                };
+            vec![non_overlapping_template::run_test_cancellable(
+                bit_string,
+                template_len,
+                number_of_blocks,
+                control.progress,
+                cancellation,
+                limits,
+            )?]
+        }
+        customtypes::Test::OverlappingTemplate => vec![overlapping_template::run_test_cancellable(
+            bit_string,
+            params
+                .overlapping_template_len
+                .unwrap_or(constants::SUITE_TEMPLATE_LEN),
+            constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE,
+            cancellation,
+            limits,
+        )?],
+        customtypes::Test::Serial => {
+            // the largest block length m < floor(log2(n)) - 2, up to the default of NIST
+            let block_length = params.block_length_serial.unwrap_or_else(|| {
+                (length.ilog2() as usize - 3).min(constants::SUITE_BLOCK_LENGTH_SERIAL)
+            });
+            serial::run_test_cancellable(bit_string, block_length, cancellation)?.to_vec()
+        }
+        customtypes::Test::ApproximateEntropy => {
+            // the largest block length m < floor(log2(n)) - 5, up to the default of NIST
+            let block_length = params.block_length_approximate_entropy.unwrap_or_else(|| {
+                (length.ilog2() as usize - 6).min(constants::SUITE_BLOCK_LENGTH_APPROXIMATE_ENTROPY)
+            });
+            vec![approximate_entropy::run_test_cancellable(
+                bit_string,
+                block_length,
+                cancellation,
+            )?]
+        }
+        customtypes::Test::CumulativeSums => vec![
+            cumulative_sums::run_test_cancellable(
+                bit_string,
+                customtypes::Mode::Forward,
+                cancellation,
+                limits,
+            )?,
+            cumulative_sums::run_test_cancellable(
+                bit_string,
+                customtypes::Mode::Backward,
+                cancellation,
+                limits,
+            )?,
+        ],
+        customtypes::Test::RandomExcursions => {
+            random_excursions::run_test_cancellable(bit_string, cancellation)?
+                .to_test_results(length)
+        }
+        customtypes::Test::RandomExcursionsVariant => {
+            random_excursions_variant::run_test_cancellable(
+                bit_string,
+                constants::STATES_EXCURSIONS_VARIANT,
+                cancellation,
+                limits,
+            )?
+            .to_test_results(length)
+        }
+        _ => {
+            return Err(customtypes::NistError::InvalidParameter {
+                test: Some(test),
+                name: "test".to_owned(),
+                reason: "Not part of the suite".to_owned(),
+            })
+        }
+    };
+
+    if !own_progress {
+        reporter.report(1);
+    }
+
+    Ok(results)
+}
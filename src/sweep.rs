@@ -0,0 +1,168 @@
+//! This module runs the parameter-sensitive tests at several scales in one call, as defects of a
+//! generator are often only visible at specific block sizes or pattern lengths. Swept are the
+//! block size M of the "Frequency Test within a Block", the template length m of the
+//! "Non-overlapping Template Matching" test and the block length m of the "Approximate Entropy"
+//! test. Scales the bit string is too short for are skipped and recorded in the report.
+
+use crate::approximate_entropy;
+use crate::bit_sequence::BitInput;
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::Result;
+use crate::frequency_block;
+use crate::non_overlapping_template;
+use crate::progress;
+use crate::utils;
+
+/// Run the parameter-sensitive tests at all scales of a sweep configuration.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be tested for randomness
+/// sweep - The scales each test is run at
+/// config - The configuration applied to each run, e.g. the limits and compliance mode
+///
+/// # Return
+///
+/// Ok(report) - The results at all scales the tests could be run at and the skipped scales
+/// Err(err) - Some error occured
+pub fn run_sweep(
+    bit_string: &(impl BitInput + ?Sized),
+    sweep: &customtypes::SweepConfig,
+    config: &customtypes::SuiteConfig,
+) -> Result<customtypes::SweepReport> {
+    log::trace!("sweep::run_sweep()");
+
+    // capture the current time before executing the tests
+    let start_time = std::time::Instant::now();
+
+    utils::check_limits(config)?;
+
+    // check the bit string once instead of letting each scale fail on its own
+    if bit_string.bit_len() == 0 {
+        // reported for the first test swept
+        let test = if !sweep.block_sizes.is_empty() {
+            customtypes::Test::FrequencyBlock
+        } else if !sweep.template_lens.is_empty() {
+            customtypes::Test::NonOverlappingTemplate
+        } else {
+            customtypes::Test::ApproximateEntropy
+        };
+        return Err(customtypes::NistError::insufficient_length(test, 1, 0));
+    }
+    let input = bit_string.validate();
+    if let Some((position, symbol)) = input.first_invalid {
+        return Err(customtypes::NistError::InvalidBit { position, symbol });
+    }
+
+    let cancellation = customtypes::CancellationToken::new();
+    let mut report = customtypes::SweepReport::default();
+
+    for &block_size in &sweep.block_sizes {
+        let result = frequency_block::run_test_cancellable(
+            bit_string,
+            block_size,
+            &cancellation,
+            &config.limits,
+        );
+        record(
+            &mut report,
+            config,
+            customtypes::Test::FrequencyBlock,
+            "M",
+            block_size,
+            result,
+        );
+    }
+
+    for &template_len in &sweep.template_lens {
+        let result = non_overlapping_template::run_test_cancellable(
+            bit_string,
+            template_len,
+            constants::SUITE_NUMBER_OF_TEMPLATE_BLOCKS,
+            &progress::ignore,
+            &cancellation,
+            &config.limits,
+        );
+        record(
+            &mut report,
+            config,
+            customtypes::Test::NonOverlappingTemplate,
+            "m",
+            template_len,
+            result,
+        );
+    }
+
+    for &block_length in &sweep.block_lengths {
+        let result = approximate_entropy::run_test(bit_string, block_length);
+        record(
+            &mut report,
+            config,
+            customtypes::Test::ApproximateEntropy,
+            "m",
+            block_length,
+            result,
+        );
+    }
+
+    // capture the current time after the tests got executed and calculate elapsed time
+    let end_time = std::time::Instant::now();
+    let elapsed_time = end_time.duration_since(start_time);
+    log::info!(
+        "Sweep ran {} scales and skipped {} in {:.6} seconds",
+        report.results.len(),
+        report.skipped.len(),
+        elapsed_time.as_secs_f64()
+    );
+
+    Ok(report)
+}
+
+/// Add the outcome of a test at one scale to the report. Errors, including results refused by
+/// the compliance mode, skip the scale instead of aborting the sweep.
+///
+/// # Arguments
+///
+/// report - The report the outcome is added to
+/// config - The configuration containing the compliance mode
+/// test - The test run
+/// parameter - The name of the swept parameter
+/// value - The value of the swept parameter
+/// result - The outcome of the test
+fn record(
+    report: &mut customtypes::SweepReport,
+    config: &customtypes::SuiteConfig,
+    test: customtypes::Test,
+    parameter: &'static str,
+    value: usize,
+    result: Result<customtypes::TestResult>,
+) {
+    log::trace!("sweep::record()");
+
+    match result.and_then(|result| utils::check_compliance(config, result)) {
+        Ok(result) => {
+            log::debug!(
+                "{}: p-value = {} for {} = {}",
+                test,
+                result.p_value,
+                parameter,
+                value
+            );
+            report.results.push(customtypes::SweepResult {
+                parameter,
+                value,
+                result,
+            });
+        }
+        Err(err) => {
+            log::warn!("{}: Skipped {} = {}: {:#}", test, parameter, value, err);
+            report.skipped.push(customtypes::SweepSkip {
+                test,
+                parameter,
+                value,
+                reason: format!("{:#}", err),
+            });
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! This module exposes the pre-computed tables of NIST SP 800-22 the tests apply for a given bit
+//! string length, so reports built outside of this crate can show the constants actually used.
+
+use crate::constants;
+use crate::customtypes;
+use crate::customtypes::Result;
+use crate::longest_run;
+use crate::utils;
+use std::collections::BTreeMap;
+
+/// Get the block size M, number of blocks N, thresholds and probabilities pi_i the "Longest Run of
+/// Ones in a Block" test applies to a bit string of given length.
+///
+/// # Arguments
+///
+/// length - The length n of the bit string
+///
+/// # Return
+///
+/// Ok(config) - The configuration chosen for the length
+/// Err(err) - The length is less than constants::MIN_LENGTH
+pub fn longest_run_config(length: usize) -> Result<customtypes::LongestRunConfig<'static>> {
+    log::trace!("tables::longest_run_config()");
+
+    longest_run::get_longest_run_config(length, constants::MIN_LENGTH)
+}
+
+/// Get the probabilities of full rank, full rank - 1 and any lower rank the "Binary Matrix Rank"
+/// test applies. Like the reference implementation, they are computed exactly for 32 x 32
+/// matrices and used for any matrix size.
+///
+/// # Return
+///
+/// probabilities - The probabilities of the three rank classes
+pub fn rank_probabilities() -> [f64; 3] {
+    log::trace!("tables::rank_probabilities()");
+
+    compute_rank_probabilities(constants::MATRIX_ROWS_M, constants::MATRIX_COLUMNS_Q)
+}
+
+/// Compute the exact probabilities of full rank, full rank - 1 and any lower rank of a random
+/// binary matrix of given size, to compare the probabilities of 32 x 32 matrices the test applies
+/// with other matrix sizes.
+///
+/// # Arguments
+///
+/// rows - The number of rows M
+/// columns - The number of columns Q
+///
+/// # Return
+///
+/// Ok(probabilities) - The probabilities of the three rank classes
+/// Err(err) - The matrix has less than two rows or columns
+pub fn expected_rank_probabilities(rows: usize, columns: usize) -> Result<[f64; 3]> {
+    log::trace!("tables::expected_rank_probabilities()");
+
+    if rows < 2 || columns < 2 {
+        return Err(customtypes::NistError::invalid_parameter(
+            customtypes::Test::BinaryMatrixRank,
+            "M",
+            format!(
+                "Matrix needs at least 2 rows and columns! Actual size: {} x {}",
+                rows, columns
+            ),
+        ));
+    }
+
+    Ok(compute_rank_probabilities(rows, columns))
+}
+
+/// Compute the exact probabilities of the three rank classes of a random binary matrix.
+///
+/// # Arguments
+///
+/// rows - The number of rows M, at least 2
+/// columns - The number of columns Q, at least 2
+///
+/// # Return
+///
+/// probabilities - The probabilities of full rank, full rank - 1 and any lower rank
+fn compute_rank_probabilities(rows: usize, columns: usize) -> [f64; 3] {
+    // p_r = 2^(r(Q + M - r) - MQ) * prod_{i=0}^{r-1} (1 - 2^(i-Q))(1 - 2^(i-M)) / (1 - 2^(i-r))
+    let probability = |rank: usize| -> f64 {
+        let exponent = (rank * (columns + rows - rank)) as f64 - (rows * columns) as f64;
+        (0..rank).fold(exponent.exp2(), |product, i| {
+            let i = i as f64;
+            product * (1.0 - (i - columns as f64).exp2()) * (1.0 - (i - rows as f64).exp2())
+                / (1.0 - (i - rank as f64).exp2())
+        })
+    };
+    let full_rank = rows.min(columns);
+    let full_rank_probability = probability(full_rank);
+    let full_rank_minus_one_probability = probability(full_rank - 1);
+    let probabilities = [
+        full_rank_probability,
+        full_rank_minus_one_probability,
+        1.0 - full_rank_probability - full_rank_minus_one_probability,
+    ];
+    log::debug!(
+        "Rank probabilities of {} x {} matrices: {:?}",
+        rows,
+        columns,
+        probabilities
+    );
+
+    probabilities
+}
+
+/// Get the block length L, number of initialization blocks Q, expected value and variance the
+/// "Maurer's Universal Statistical" test applies to a bit string of given length.
+///
+/// # Arguments
+///
+/// length - The length n of the bit string
+///
+/// # Return
+///
+/// Ok(config) - The configuration chosen for the length
+/// Err(err) - The length is less than the first row of the table
+pub fn universal_config(length: usize) -> Result<customtypes::UniversalConfig> {
+    log::trace!("tables::universal_config()");
+
+    // the rows are sorted by length, so the last row not exceeding the length applies
+    let row = match constants::UNIVERSAL_MIN_LENGTHS
+        .iter()
+        .rposition(|(min_length, _)| *min_length <= length)
+    {
+        Some(row) => row,
+        None => {
+            return Err(customtypes::NistError::insufficient_length(
+                customtypes::Test::MaurersUniversalStatistical,
+                constants::RECOMMENDED_SIZE_UNIVERSAL,
+                length,
+            ))
+        }
+    };
+
+    let block_length = constants::UNIVERSAL_MIN_LENGTHS[row].1;
+    let config = customtypes::UniversalConfig {
+        block_length,
+        init_blocks: constants::UNIVERSAL_INIT_BLOCKS_FACTOR * (1 << block_length),
+        expected_value: constants::UNIVERSAL_EXPECTED_VALUES[row],
+        variance: constants::UNIVERSAL_VARIANCES[row],
+    };
+    log::debug!(
+        "Universal configuration for length {}: {:?}",
+        length,
+        config
+    );
+
+    Ok(config)
+}
+
+/// Check whether a bit string length meets the recommended sample size of the "Maurer's
+/// Universal Statistical" or "Linear Complexity" test and get the parameters L and Q or M and N
+/// chosen for it. The suite attaches the advice to the tests it skips until they are implemented.
+///
+/// # Arguments
+///
+/// test - Either customtypes::Test::MaurersUniversalStatistical or Test::LinearComplexity
+/// length - The length n of the bit string
+///
+/// # Return
+///
+/// Ok(advice) - The recommended length and chosen parameters
+/// Err(err) - No advice is available for passed test
+pub fn length_advice(test: customtypes::Test, length: usize) -> Result<customtypes::LengthAdvice> {
+    log::trace!("tables::length_advice()");
+
+    let (recommended_length, parameters) = match test {
+        customtypes::Test::MaurersUniversalStatistical => {
+            let parameters = match universal_config(length) {
+                Ok(config) => {
+                    utils::parameter_map(&[("L", config.block_length), ("Q", config.init_blocks)])
+                }
+                Err(_) => BTreeMap::new(),
+            };
+            (constants::RECOMMENDED_SIZE_UNIVERSAL, parameters)
+        }
+        customtypes::Test::LinearComplexity => {
+            let block_size = constants::LINEAR_COMPLEXITY_BLOCK_SIZE;
+            let parameters = match length / block_size {
+                0 => BTreeMap::new(),
+                number_of_blocks => {
+                    utils::parameter_map(&[("M", block_size), ("N", number_of_blocks)])
+                }
+            };
+            (constants::RECOMMENDED_SIZE_LINEAR_COMPLEXITY, parameters)
+        }
+        _ => {
+            return Err(customtypes::NistError::invalid_parameter(
+                test,
+                "test",
+                "No length advice available".to_owned(),
+            ))
+        }
+    };
+
+    let advice = customtypes::LengthAdvice {
+        test,
+        length,
+        recommended_length,
+        parameters,
+    };
+    if !advice.is_adequate() {
+        log::warn!(
+            "{}: Bit string length {} is less than the recommended {} bits",
+            test,
+            length,
+            recommended_length
+        );
+    }
+
+    Ok(advice)
+}
@@ -0,0 +1,87 @@
+//! This module emits structured telemetry with the "tracing" feature. Each test opens a span with
+//! its name, which contains one span per phase (e.g. validation and computation) and records the
+//! statistic, p-value and parameters of the result when the test is done. Without the feature,
+//! all spans are no-ops.
+
+use crate::customtypes;
+
+/// An entered span. The span is exited when the value gets dropped or exit() is called
+#[derive(Debug)]
+pub struct Span {
+    #[cfg(feature = "tracing")]
+    inner: tracing::span::EnteredSpan,
+}
+
+impl Span {
+    /// Enter the span of a suite run containing the spans of the single tests.
+    pub fn suite() -> Self {
+        Span {
+            #[cfg(feature = "tracing")]
+            inner: tracing::info_span!("suite").entered(),
+        }
+    }
+
+    /// Enter the span of a single test. The fields of the result are recorded by
+    /// record_result().
+    ///
+    /// # Arguments
+    ///
+    /// test - The test the span is created for
+    pub fn test(test: customtypes::Test) -> Self {
+        #[cfg(not(feature = "tracing"))]
+        let _ = test;
+
+        Span {
+            #[cfg(feature = "tracing")]
+            inner: tracing::info_span!(
+                "test",
+                name = ?test,
+                p_value = tracing::field::Empty,
+                statistic = tracing::field::Empty,
+                parameters = tracing::field::Empty,
+                advisories = tracing::field::Empty,
+                elapsed_seconds = tracing::field::Empty,
+            )
+            .entered(),
+        }
+    }
+
+    /// Enter the span of a phase within the current test.
+    ///
+    /// # Arguments
+    ///
+    /// name - The name of the phase, e.g. "validation" or "computation"
+    pub fn phase(name: &'static str) -> Self {
+        #[cfg(not(feature = "tracing"))]
+        let _ = name;
+
+        Span {
+            #[cfg(feature = "tracing")]
+            inner: tracing::debug_span!("phase", name).entered(),
+        }
+    }
+
+    /// Record the result of a test in its span.
+    ///
+    /// # Arguments
+    ///
+    /// result - The result of the test the span belongs to
+    pub fn record_result(&self, result: &customtypes::TestResult) {
+        #[cfg(not(feature = "tracing"))]
+        let _ = result;
+
+        #[cfg(feature = "tracing")]
+        {
+            self.inner.record("p_value", result.p_value);
+            self.inner.record("statistic", result.statistic);
+            self.inner
+                .record("parameters", tracing::field::debug(&result.parameters));
+            self.inner.record("advisories", result.advisories.len());
+            self.inner
+                .record("elapsed_seconds", result.elapsed_time.as_secs_f64());
+        }
+    }
+
+    /// Exit the span before the end of the enclosing scope, e.g. to start the next phase.
+    pub fn exit(self) {}
+}
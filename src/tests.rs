@@ -1,8 +1,23 @@
+#[cfg(test)]
+#[allow(dead_code, clippy::needless_borrow)]
 mod test_helper;
 
 #[cfg(test)]
+#[allow(clippy::needless_late_init)]
 mod utils_tests;
 
+#[cfg(test)]
+mod customtypes_tests;
+
+#[cfg(test)]
+mod baseline_tests;
+
+#[cfg(test)]
+mod bit_sequence_tests;
+
+#[cfg(test)]
+mod cache_tests;
+
 #[cfg(test)]
 mod frequency_monobit_tests;
 
@@ -14,15 +29,90 @@ mod runs_tests;
 
 #[cfg(test)]
 mod longest_run_tests;
-/*
-#[cfg(test)]
+
+#[cfg(all(test, feature = "matrix-rank"))]
 mod binary_matrix_rank_tests;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "spectral"))]
 mod dft_spectral_tests;
-*/
+
 #[cfg(test)]
 mod non_overlapping_template_tests;
 
+#[cfg(test)]
+mod overlapping_template_tests;
+
+#[cfg(test)]
+mod reader_tests;
+
+#[cfg(all(test, feature = "serde"))]
+mod report_tests;
+
 #[cfg(test)]
 mod cumulative_sums_tests;
+
+#[cfg(test)]
+mod diagnostics_tests;
+
+#[cfg(test)]
+mod byte_frequency_tests;
+
+#[cfg(test)]
+mod cross_correlation_tests;
+
+#[cfg(test)]
+mod analysis_tests;
+
+#[cfg(test)]
+mod adversarial_tests;
+
+#[cfg(test)]
+mod tables_tests;
+
+#[cfg(test)]
+mod suite_tests;
+
+#[cfg(test)]
+mod health_tests;
+
+#[cfg(all(test, feature = "reference-data"))]
+mod reference_tests;
+
+#[cfg(test)]
+mod sts_tests;
+
+#[cfg(all(test, feature = "scripting"))]
+mod script_tests;
+
+#[cfg(all(test, feature = "plugins"))]
+mod plugins_tests;
+
+#[cfg(test)]
+mod policy_tests;
+
+#[cfg(test)]
+mod batch_tests;
+
+#[cfg(test)]
+mod bit_source_tests;
+
+#[cfg(test)]
+mod approximate_entropy_tests;
+
+#[cfg(test)]
+mod sweep_tests;
+
+#[cfg(test)]
+mod random_excursions_variant_tests;
+
+#[cfg(test)]
+mod pipeline_tests;
+
+#[cfg(test)]
+mod capture_tests;
+
+#[cfg(test)]
+mod random_excursions_tests;
+
+#[cfg(test)]
+mod serial_tests;
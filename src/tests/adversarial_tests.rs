@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "matrix-rank")]
+    use crate::binary_matrix_rank;
+    use crate::byte_frequency;
+    use crate::cross_correlation;
+    use crate::cumulative_sums;
+    use crate::customtypes;
+    #[cfg(feature = "spectral")]
+    use crate::dft_spectral;
+    use crate::frequency_block;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::longest_run;
+    use crate::non_overlapping_template;
+    use crate::overlapping_template;
+    use crate::runs;
+
+    const LOGLEVEL: &str = "Debug";
+    const ADVERSARIAL_BIT_STRINGS: [&str; 7] = ["", "0", "1", "01", "0é1", "0 1", "10\n"];
+    const BIT_STRING_128: &str = "11001100000101010110110001001100111000000000001001001101010100010001001111010110100000001101011111001100111001101101100010110010";
+
+    /// Run all tests without parameters on the passed bit string. None of them may panic.
+    fn run_all(bit_string: &str) {
+        let _ = frequency_monobit::perform_test(bit_string);
+        let _ = frequency_block::perform_test(bit_string, 3);
+        let _ = runs::perform_test(bit_string);
+        let _ = longest_run::perform_test_both(bit_string);
+        #[cfg(feature = "matrix-rank")]
+        let _ = binary_matrix_rank::perform_test(bit_string, 32, 32);
+        #[cfg(feature = "spectral")]
+        let _ = dft_spectral::perform_test(bit_string);
+        let _ = non_overlapping_template::perform_test_single_template(bit_string, "001", 1);
+        let _ = overlapping_template::perform_test_single_template(bit_string, "001", 1);
+        let _ = cumulative_sums::perform_test(bit_string, customtypes::Mode::Forward);
+        let _ = cumulative_sums::perform_test(bit_string, customtypes::Mode::Backward);
+        let _ = byte_frequency::perform_test(bit_string);
+        let _ = cross_correlation::perform_test(bit_string, bit_string, 1);
+    }
+
+    #[test]
+    fn test_adversarial_bit_strings() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        for bit_string in ADVERSARIAL_BIT_STRINGS {
+            run_all(bit_string);
+
+            // none of those inputs is a valid bit string for a test
+            if !bit_string.chars().all(|c| c == '0' || c == '1') || bit_string.is_empty() {
+                assert!(frequency_monobit::perform_test(bit_string).is_err());
+                assert!(runs::perform_test(bit_string).is_err());
+            }
+        }
+        run_all(BIT_STRING_128);
+        run_all(&BIT_STRING_128[..127]);
+    }
+
+    #[test]
+    fn test_adversarial_parameters() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // block sizes
+        assert!(frequency_block::perform_test(BIT_STRING_128, 0).is_err());
+        assert!(frequency_block::perform_test(BIT_STRING_128, usize::MAX).is_err());
+
+        // matrix dimensions
+        #[cfg(feature = "matrix-rank")]
+        {
+            assert!(binary_matrix_rank::perform_test(BIT_STRING_128, 0, 32).is_err());
+            assert!(binary_matrix_rank::perform_test(BIT_STRING_128, 32, 0).is_err());
+            assert!(binary_matrix_rank::perform_test(BIT_STRING_128, 1, 1).is_err());
+            assert!(binary_matrix_rank::perform_test(BIT_STRING_128, 32, 32).is_err());
+            assert!(binary_matrix_rank::perform_test(BIT_STRING_128, usize::MAX, 2).is_err());
+        }
+
+        // number of blocks and template lengths
+        assert!(non_overlapping_template::perform_test(BIT_STRING_128, 2, 0).is_err());
+        assert!(non_overlapping_template::perform_test(BIT_STRING_128, 0, 1).is_err());
+        assert!(non_overlapping_template::perform_test(BIT_STRING_128, usize::MAX, 1).is_err());
+        assert!(
+            non_overlapping_template::perform_test_single_template(BIT_STRING_128, "0é", 1)
+                .is_err()
+        );
+        assert!(overlapping_template::perform_test(BIT_STRING_128, 2, 0).is_err());
+        assert!(overlapping_template::perform_test(BIT_STRING_128, usize::MAX, 1).is_err());
+        assert!(overlapping_template::perform_test(BIT_STRING_128, 2, usize::MAX).is_err());
+        assert!(overlapping_template::perform_test_exhaustive(BIT_STRING_128, 2, 0).is_err());
+        assert!(
+            overlapping_template::perform_test_exhaustive(BIT_STRING_128, usize::MAX, 1).is_err()
+        );
+        assert!(overlapping_template::get_templates(0).is_err());
+        assert!(overlapping_template::get_templates(usize::MAX).is_err());
+        assert!(non_overlapping_template::generate_aperiodic_templates(usize::MAX).is_err());
+
+        // offsets
+        assert!(
+            cross_correlation::perform_test(BIT_STRING_128, BIT_STRING_128, usize::MAX).is_err()
+        );
+        assert!(cross_correlation::perform_test(BIT_STRING_128, "1", 0).is_ok());
+    }
+}
@@ -0,0 +1,294 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis;
+    use crate::bit_sequence::BitSequence;
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const NUMBER_OF_SUBSEQUENCES: usize = 100;
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+
+    #[test]
+    fn test_uniformity_p_value() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // one p-value per bin is perfectly uniform
+        let p_values: Vec<f64> = (0..10).map(|bin| (bin as f64) * 0.1 + 0.05).collect();
+        assert_eq!(analysis::uniformity_p_value(&p_values).unwrap(), 1.0);
+        assert_eq!(analysis::passing_proportion(&p_values).unwrap(), 1.0);
+
+        // a p-value of 1.0 belongs to the last bin
+        let mut p_values_edge = p_values.clone();
+        p_values_edge[9] = 1.0;
+        assert_eq!(analysis::uniformity_p_value(&p_values_edge).unwrap(), 1.0);
+
+        // all p-values in the same bin
+        let p_values = vec![0.005; 100];
+        assert!(
+            analysis::uniformity_p_value(&p_values).unwrap()
+                < constants::UNIFORMITY_P_VALUE_THRESHOLD
+        );
+        assert_eq!(analysis::passing_proportion(&p_values).unwrap(), 0.0);
+
+        assert!(analysis::uniformity_p_value(&[]).is_err());
+        assert!(analysis::uniformity_p_value(&[0.5, 1.5]).is_err());
+        assert!(analysis::passing_proportion(&[]).is_err());
+    }
+
+    #[test]
+    fn test_uniformity_with_bins() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let p_values: Vec<f64> = (0..20).map(|index| (index as f64) * 0.05 + 0.01).collect();
+
+        // coarse binning
+        let result = analysis::uniformity_with_bins(&p_values, 4).unwrap();
+        assert_eq!(result.bin_counts, vec![5, 5, 5, 5]);
+        assert_eq!(result.chi_square, 0.0);
+        assert_eq!(result.p_value, 1.0);
+
+        // the default binning matches uniformity_p_value()
+        let result = analysis::uniformity_with_bins(&p_values, constants::UNIFORMITY_BINS).unwrap();
+        assert_eq!(result.bin_counts.len(), constants::UNIFORMITY_BINS);
+        assert_eq!(
+            result.p_value,
+            analysis::uniformity_p_value(&p_values).unwrap()
+        );
+
+        // skewed p-values
+        let p_values: Vec<f64> = (0..20).map(|index| (index as f64) * 0.01).collect();
+        let result = analysis::uniformity_with_bins(&p_values, 2).unwrap();
+        assert_eq!(result.bin_counts, vec![20, 0]);
+        assert_eq!(result.chi_square, 20.0);
+        assert!(result.p_value < constants::UNIFORMITY_P_VALUE_THRESHOLD);
+
+        assert!(analysis::uniformity_with_bins(&p_values, 0).is_err());
+        assert!(analysis::uniformity_with_bins(&p_values, 1).is_err());
+        assert!(analysis::uniformity_with_bins(&[], 4).is_err());
+    }
+
+    #[test]
+    fn test_q_values() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // expected q-values of the Benjamini-Hochberg step-up procedure
+        let p_values = [0.01, 0.04, 0.03, 0.005, 0.2];
+        let expected = [0.025, 0.05, 0.05, 0.025, 0.2];
+        let q_values =
+            analysis::q_values(&p_values, customtypes::FdrMethod::BenjaminiHochberg).unwrap();
+        for (q_value, expected) in q_values.iter().zip(expected) {
+            assert!((q_value - expected).abs() < 1e-12);
+        }
+
+        // Storey: no p-value above lambda, so one is counted and pi_0 = 1 / (m * (1 - lambda))
+        let q_values = analysis::q_values(&p_values, customtypes::FdrMethod::Storey).unwrap();
+        for (q_value, expected) in q_values.iter().zip(expected) {
+            assert!((q_value - expected * 0.4).abs() < 1e-12);
+        }
+
+        // the example of Benjamini and Hochberg (1995), q-values as computed by R's
+        // p.adjust(p, "BH") and by qvalue(p, lambda = 0.5) with pi_0 = 4 / 7.5
+        let p_values = [
+            0.0001, 0.0004, 0.0019, 0.0095, 0.0201, 0.0278, 0.0298, 0.0344, 0.0459, 0.3240, 0.4262,
+            0.5719, 0.6528, 0.7590, 1.0,
+        ];
+        let expected = [
+            0.0015, 0.003, 0.0095, 0.035625, 0.0603, 0.063857, 0.063857, 0.0645, 0.0765, 0.486,
+            0.581182, 0.714875, 0.753231, 0.813214, 1.0,
+        ];
+        let bh = analysis::q_values(&p_values, customtypes::FdrMethod::BenjaminiHochberg).unwrap();
+        let storey = analysis::q_values(&p_values, customtypes::FdrMethod::Storey).unwrap();
+        for ((bh, storey), expected) in bh.iter().zip(&storey).zip(expected) {
+            assert!((bh - expected).abs() < 1e-6);
+            assert!((storey - expected * 4.0 / 7.5).abs() < 1e-6);
+        }
+
+        // Storey with uniform p-values estimates pi_0 close to 1 and is never larger than BH
+        let p_values: Vec<f64> = (1..=100).map(|index| (index as f64) / 100.0).collect();
+        let bh = analysis::q_values(&p_values, customtypes::FdrMethod::BenjaminiHochberg).unwrap();
+        let storey = analysis::q_values(&p_values, customtypes::FdrMethod::Storey).unwrap();
+        assert!(bh.iter().all(|&q_value| (q_value - 1.0).abs() < 1e-12));
+        assert!(storey.iter().zip(&bh).all(|(s, b)| s <= b));
+
+        assert!(analysis::q_values(&[], customtypes::FdrMethod::Storey).is_err());
+        assert!(analysis::q_values(&[-0.1], customtypes::FdrMethod::Storey).is_err());
+    }
+
+    #[test]
+    fn test_two_level_test() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let results = analysis::two_level_test(
+            customtypes::Test::FrequencyMonobit,
+            &pi_bit_string,
+            NUMBER_OF_SUBSEQUENCES,
+            0.01,
+        )
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].number_of_sequences, NUMBER_OF_SUBSEQUENCES);
+        assert!(results[0].proportion() >= 0.96);
+        assert!(results[0].uniformity.p_value >= constants::UNIFORMITY_P_VALUE_THRESHOLD);
+
+        // the subsequences are judged at the passed significance level
+        let strict = analysis::two_level_test(
+            customtypes::Test::FrequencyMonobit,
+            &pi_bit_string,
+            NUMBER_OF_SUBSEQUENCES,
+            0.5,
+        )
+        .unwrap();
+        assert_eq!(strict[0].alpha, 0.5);
+        assert!(strict[0].passed_sequences < results[0].passed_sequences);
+        assert_eq!(strict[0].uniformity.p_value, results[0].uniformity.p_value);
+
+        let sha_3_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + SHA_3_FILE;
+        let sha_3_sequence =
+            BitSequence::from_bit_string(&utils::read_random_numbers(&sha_3_file).unwrap())
+                .unwrap();
+        let results = analysis::two_level_test(
+            customtypes::Test::Runs,
+            &sha_3_sequence,
+            NUMBER_OF_SUBSEQUENCES,
+            0.01,
+        )
+        .unwrap();
+        assert!(results[0].proportion() >= 0.96);
+        assert!(results[0].uniformity.p_value >= constants::UNIFORMITY_P_VALUE_THRESHOLD);
+
+        // subsequences with a bias towards ones all fail
+        let biased: BitSequence = sha_3_sequence
+            .iter()
+            .enumerate()
+            .map(|(index, bit)| index % 4 == 0 || bit)
+            .collect();
+        let results = analysis::two_level_test(
+            customtypes::Test::FrequencyMonobit,
+            &biased,
+            NUMBER_OF_SUBSEQUENCES,
+            0.01,
+        )
+        .unwrap();
+        assert_eq!(results[0].passed_sequences, 0);
+        assert!(results[0].uniformity.p_value < constants::UNIFORMITY_P_VALUE_THRESHOLD);
+
+        // invalid numbers of subsequences, bit strings and significance levels
+        let runs = customtypes::Test::Runs;
+        assert!(analysis::two_level_test(runs, &pi_bit_string, 0, 0.01).is_err());
+        assert!(analysis::two_level_test(runs, "0101", 5, 0.01).is_err());
+        assert!(analysis::two_level_test(runs, "01a1", 2, 0.01).is_err());
+        assert!(analysis::two_level_test(runs, &pi_bit_string, 2, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_proportion_interval() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the example of section 4.2.1 of NIST SP 800-22: 0.99 +- 0.0094392
+        let (lower, upper) = analysis::proportion_interval(1000, 0.01);
+        assert!((lower - 0.9805608).abs() < 1e-6);
+        assert!((upper - 0.9994392).abs() < 1e-6);
+
+        // the bounds stay within [0, 1] for few sequences
+        assert_eq!(analysis::proportion_interval(10, 0.01).1, 1.0);
+        assert_eq!(analysis::proportion_interval(1, 0.5).0, 0.0);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let sequences: Vec<&str> = pi_bit_string
+            .as_bytes()
+            .chunks_exact(pi_bit_string.len() / NUMBER_OF_SUBSEQUENCES)
+            .take(NUMBER_OF_SUBSEQUENCES)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .collect();
+
+        let results =
+            analysis::evaluate(customtypes::Test::FrequencyMonobit, &sequences, 0.01).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].number_of_sequences, NUMBER_OF_SUBSEQUENCES);
+        assert_eq!(results[0].uniformity.bin_counts.iter().sum::<usize>(), 100);
+        assert!(results[0].passed());
+        assert!(results[0].to_string().ends_with(&format!(
+            " {}/100     Frequency Monobit Test",
+            results[0].passed_sequences
+        )));
+
+        // the results match the ones of the two-level test
+        let two_level = analysis::two_level_test(
+            customtypes::Test::FrequencyMonobit,
+            &pi_bit_string,
+            NUMBER_OF_SUBSEQUENCES,
+            0.01,
+        )
+        .unwrap();
+        assert_eq!(results[0].passed_sequences, two_level[0].passed_sequences);
+        assert_eq!(
+            results[0].uniformity.p_value,
+            two_level[0].uniformity.p_value
+        );
+
+        // both modes of the Cumulative Sums test are evaluated on their own
+        let results =
+            analysis::evaluate(customtypes::Test::CumulativeSums, &sequences, 0.01).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.passed()));
+
+        // a bias towards ones fails the proportion and the uniformity
+        let biased: Vec<String> = sequences
+            .iter()
+            .map(|sequence| {
+                sequence
+                    .chars()
+                    .enumerate()
+                    .map(|(index, c)| if index % 4 == 0 { '1' } else { c })
+                    .collect()
+            })
+            .collect();
+        let biased: Vec<&str> = biased.iter().map(String::as_str).collect();
+        let results =
+            analysis::evaluate(customtypes::Test::FrequencyMonobit, &biased, 0.01).unwrap();
+        assert_eq!(results[0].passed_sequences, 0);
+        assert!(!results[0].proportion_passed());
+        assert!(!results[0].uniformity_passed());
+        assert!(results[0].to_string().contains("0/100   *"));
+
+        // invalid significance levels, tests and sequences
+        let monobit = customtypes::Test::FrequencyMonobit;
+        assert!(matches!(
+            analysis::evaluate(monobit, &sequences, 0.0)
+                .unwrap_err()
+                .root(),
+            customtypes::NistError::InvalidParameter { .. }
+        ));
+        assert!(analysis::evaluate(monobit, &sequences, 1.0).is_err());
+        assert!(analysis::evaluate::<str>(monobit, &[], 0.01).is_err());
+        assert!(analysis::evaluate(monobit, &["0101"], 0.01).is_err());
+        assert!(analysis::evaluate(customtypes::Test::RandomExcursions, &sequences, 0.01).is_err());
+    }
+}
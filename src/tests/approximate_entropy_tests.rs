@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use crate::approximate_entropy;
+    use crate::approximate_entropy::WindowedApproximateEntropy;
+    use crate::logger;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_NIST_1: &str = "0100110101"; // example from NIST Paper. ApEn should be 0.190954
+    const AP_EN_NIST_1: f64 = 0.190954;
+    const P_VALUE_NIST_1: f64 = 0.261961;
+    const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+
+    #[test]
+    fn test_approximate_entropy() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let result = approximate_entropy::run_test(BIT_STRING_NIST_1, 3).unwrap();
+        assert!((result.statistic - AP_EN_NIST_1).abs() < 1e-6);
+        assert!((result.p_value - P_VALUE_NIST_1).abs() < 1e-6);
+
+        // test e in its binary representation
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        assert!(approximate_entropy::perform_test(&e_bit_string, 10).unwrap() >= 0.01);
+
+        // a window holding the whole sequence has to give the same result
+        let mut window = WindowedApproximateEntropy::new(10000, 4).unwrap();
+        for bit in e_bit_string[..10000].chars() {
+            window.feed(bit == '1');
+        }
+        let result = approximate_entropy::run_test(&e_bit_string[..10000], 4).unwrap();
+        assert!((window.approximate_entropy().unwrap() - result.statistic).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_approximate_entropy_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(approximate_entropy::perform_test("", 2).is_err());
+        assert!(approximate_entropy::perform_test(INVALID_BIT_STRING, 2).is_err());
+        assert!(approximate_entropy::perform_test(BIT_STRING_NIST_1, 0).is_err());
+        assert!(approximate_entropy::perform_test(BIT_STRING_NIST_1, 10).is_err());
+    }
+
+    #[test]
+    fn test_windowed_approximate_entropy() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let mut window = WindowedApproximateEntropy::new(10, 3).unwrap();
+        assert!(window.is_empty());
+        for bit in BIT_STRING_NIST_1.chars() {
+            window.feed(bit == '1');
+        }
+        assert!(window.is_full());
+        let result = window.result().unwrap();
+        assert!((result.statistic - AP_EN_NIST_1).abs() < 1e-6);
+        assert!((result.p_value - P_VALUE_NIST_1).abs() < 1e-6);
+        assert!(result.advisories.is_empty());
+
+        // sliding over e has to give the same entropy as computing the last window from scratch
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let e_bit_string = &e_bit_string[..10000];
+
+        let mut sliding = WindowedApproximateEntropy::new(1000, 2).unwrap();
+        for bit in e_bit_string.chars() {
+            sliding.feed(bit == '1');
+        }
+        let mut fresh = WindowedApproximateEntropy::new(1000, 2).unwrap();
+        for bit in e_bit_string[9000..].chars() {
+            fresh.feed(bit == '1');
+        }
+        assert_eq!(sliding.len(), 1000);
+        assert!(
+            (sliding.approximate_entropy().unwrap() - fresh.approximate_entropy().unwrap()).abs()
+                < 1e-12
+        );
+        assert!(sliding.result().unwrap().p_value >= 0.01);
+    }
+
+    #[test]
+    fn test_windowed_approximate_entropy_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(WindowedApproximateEntropy::new(10, 0).is_err());
+        assert!(WindowedApproximateEntropy::new(3, 3).is_err());
+        assert!(WindowedApproximateEntropy::new(1 << 30, 21).is_err());
+
+        // the window needs at least m+1 bits
+        let mut window = WindowedApproximateEntropy::new(10, 3).unwrap();
+        for bit in "010".chars() {
+            window.feed(bit == '1');
+        }
+        assert!(window.approximate_entropy().is_none());
+        assert!(window.result().is_err());
+
+        // a partially filled window is reported
+        window.feed(true);
+        assert_eq!(window.result().unwrap().advisories.len(), 1);
+    }
+}
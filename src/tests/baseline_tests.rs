@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::cumulative_sums;
+    use crate::customtypes;
+    use crate::frequency_block;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::runs;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const BASELINE_PI_FILE: &str = "/src/tests/testdata/baseline_pi";
+    const BASELINE_TMP_FILE: &str = "/tmp/baseline_roundtrip";
+    const BASELINE_SCHEMA_FILE: &str = "/tmp/baseline_schema";
+    const TOLERANCE: f64 = 1e-9;
+
+    fn create_pi_report() -> customtypes::SuiteReport {
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+
+        customtypes::SuiteReport {
+            results: vec![
+                frequency_monobit::run_test(&pi_bit_string).unwrap(),
+                frequency_block::run_test(&pi_bit_string, 10200).unwrap(),
+                runs::run_test(&pi_bit_string).unwrap(),
+                cumulative_sums::run_test(&pi_bit_string, customtypes::Mode::Forward).unwrap(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_baseline() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // pin the results of pi against the golden file
+        let report = create_pi_report();
+        let baseline_pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + BASELINE_PI_FILE;
+        report.assert_matches_baseline(&baseline_pi_file).unwrap();
+
+        // a written baseline matches the report it was written from
+        report.write_baseline(BASELINE_TMP_FILE).unwrap();
+        report.assert_matches_baseline(BASELINE_TMP_FILE).unwrap();
+
+        // deviations beyond the tolerance are reported
+        let mut deviating = report.clone();
+        deviating.results[2].p_value += 1e-6;
+        let error = deviating
+            .assert_matches_baseline(&baseline_pi_file)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Runs Test"));
+
+        let mut missing = report.clone();
+        missing.results.pop();
+        assert!(missing.assert_matches_baseline(&baseline_pi_file).is_err());
+        assert!(report
+            .assert_matches_baseline("/non-existing-dir/baseline")
+            .is_err());
+    }
+
+    #[test]
+    fn test_baseline_schema() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let report = create_pi_report();
+        report.write_baseline(BASELINE_SCHEMA_FILE).unwrap();
+        let contents = std::fs::read_to_string(BASELINE_SCHEMA_FILE).unwrap();
+        assert!(contents.starts_with(&format!(
+            "{}{}\n",
+            constants::SCHEMA_VERSION_PREFIX,
+            constants::REPORT_SCHEMA_VERSION
+        )));
+
+        // the order the tests were run in does not change the written file
+        let mut reversed = report.clone();
+        reversed.results.reverse();
+        reversed.write_baseline(BASELINE_SCHEMA_FILE).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(BASELINE_SCHEMA_FILE).unwrap(),
+            contents
+        );
+        reversed
+            .assert_matches_baseline(BASELINE_SCHEMA_FILE)
+            .unwrap();
+
+        // files without schema version are parsed as version 1
+        let legacy: String = contents
+            .lines()
+            .filter(|line| !line.starts_with(constants::SCHEMA_VERSION_PREFIX))
+            .map(|line| line.to_owned() + "\n")
+            .collect();
+        std::fs::write(BASELINE_SCHEMA_FILE, legacy).unwrap();
+        report
+            .assert_matches_baseline(BASELINE_SCHEMA_FILE)
+            .unwrap();
+
+        // files of newer schema versions are refused
+        let newer = contents.replacen(
+            &format!(
+                "{}{}",
+                constants::SCHEMA_VERSION_PREFIX,
+                constants::REPORT_SCHEMA_VERSION
+            ),
+            &format!(
+                "{}{}",
+                constants::SCHEMA_VERSION_PREFIX,
+                constants::REPORT_SCHEMA_VERSION + 1
+            ),
+            1,
+        );
+        std::fs::write(BASELINE_SCHEMA_FILE, newer).unwrap();
+        assert!(report
+            .assert_matches_baseline(BASELINE_SCHEMA_FILE)
+            .is_err());
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let report = create_pi_report();
+        let result = &report.results[0];
+
+        let mut other = result.clone();
+        other.p_value += TOLERANCE / 2.0;
+        other.elapsed_time += std::time::Duration::from_secs(1);
+        assert!(result.approx_eq(&other, TOLERANCE));
+
+        other.statistic += TOLERANCE * 2.0;
+        assert!(!result.approx_eq(&other, TOLERANCE));
+
+        // different tests never match
+        assert!(!result.approx_eq(&report.results[1], 1.0));
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis;
+    use crate::cumulative_sums;
+    use crate::customtypes;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::policy;
+    use crate::utils;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    const LOGLEVEL: &str = "Debug";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const NUMBER_OF_SEQUENCES: usize = 100;
+    const SEQUENCE_LENGTH: usize = 10000;
+
+    fn create_reports() -> BTreeMap<usize, customtypes::SuiteReport> {
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+
+        (0..NUMBER_OF_SEQUENCES)
+            .map(|sequence| {
+                let bit_string =
+                    &pi_bit_string[sequence * SEQUENCE_LENGTH..(sequence + 1) * SEQUENCE_LENGTH];
+                let report = customtypes::SuiteReport {
+                    results: vec![
+                        frequency_monobit::run_test(bit_string).unwrap(),
+                        cumulative_sums::run_test(bit_string, customtypes::Mode::Forward).unwrap(),
+                        cumulative_sums::run_test(bit_string, customtypes::Mode::Backward).unwrap(),
+                    ],
+                    ..Default::default()
+                };
+                (sequence, report)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_merge() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let config = customtypes::SuiteConfig::default();
+        let reports = create_reports();
+        let whole = customtypes::BatchReport::new(&config, reports.clone()).unwrap();
+        assert_eq!(whole.analyses.len(), 3);
+
+        // the analyses of both modes of the Cusum test are kept apart
+        let backward: Vec<f64> = reports
+            .values()
+            .map(|report| report.results[2].p_value)
+            .collect();
+        let analysis = &whole.analyses[2];
+        assert_eq!(analysis.test, customtypes::Test::CumulativeSums);
+        assert_eq!(analysis.index, 1);
+        assert_eq!(analysis.number_of_sequences, NUMBER_OF_SEQUENCES);
+        assert_eq!(
+            analysis.proportion,
+            analysis::passing_proportion(&backward).unwrap()
+        );
+        assert_eq!(
+            analysis.uniformity_p_value,
+            analysis::uniformity_p_value(&backward).unwrap()
+        );
+
+        // merging the batches of two machines equals testing all sequences on one machine
+        let (first, second): (BTreeMap<_, _>, BTreeMap<_, _>) = reports
+            .into_iter()
+            .partition(|(sequence, _)| sequence % 2 == 0);
+        let mut merged = customtypes::BatchReport::new(&config, first).unwrap();
+        let second = customtypes::BatchReport::new(&config, second).unwrap();
+        assert_ne!(merged.analyses, whole.analyses);
+        merged.merge(second.clone()).unwrap();
+        assert_eq!(merged, whole);
+
+        // overlapping batches and different configurations are refused
+        assert!(merged.merge(second.clone()).is_err());
+        assert_eq!(merged, whole);
+
+        // a batch with invalid p-values leaves the merged batch unchanged
+        let mut invalid = second.reports[&1].clone();
+        invalid.results[0].p_value = 1.5;
+        let invalid = customtypes::BatchReport {
+            config: config.clone(),
+            reports: BTreeMap::from([(NUMBER_OF_SEQUENCES, invalid)]),
+            analyses: Vec::new(),
+        };
+        assert!(merged.merge(invalid).is_err());
+        assert_eq!(merged, whole);
+        let other_config = customtypes::SuiteConfig {
+            verdict_policy: Arc::new(policy::KOfN { k: 2 }),
+            ..Default::default()
+        };
+        let mut other = customtypes::BatchReport::new(&other_config, BTreeMap::new()).unwrap();
+        assert!(other.merge(second).is_err());
+    }
+
+    #[test]
+    fn test_report_merge() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let mut reports = create_reports().into_values();
+        let mut report = reports.next().unwrap();
+        let mut monobit = report.clone();
+        monobit.results.truncate(1);
+        let mut cusum = report.clone();
+        cusum.results.remove(0);
+        cusum.skipped.push(customtypes::SkippedTest {
+            test: customtypes::Test::OverlappingTemplate,
+            reason: customtypes::SkipReason::TooShort {
+                required_length: 1000000,
+            },
+        });
+
+        // reports of different tests on the same sequence are combined
+        monobit.merge(cusum.clone()).unwrap();
+        report.skipped = cusum.skipped.clone();
+        assert_eq!(monobit, report);
+        assert!(monobit.merge(cusum).is_err());
+
+        // a test cannot be both run and skipped
+        let mut skipped_monobit = customtypes::SuiteReport::default();
+        skipped_monobit.skipped.push(customtypes::SkippedTest {
+            test: customtypes::Test::FrequencyMonobit,
+            reason: customtypes::SkipReason::TooShort {
+                required_length: 100,
+            },
+        });
+        assert!(report.clone().merge(skipped_monobit.clone()).is_err());
+        assert!(skipped_monobit.merge(report).is_err());
+    }
+}
@@ -3,10 +3,11 @@ mod tests {
     use crate::binary_matrix_rank;
     use crate::constants;
     use crate::logger;
+    use crate::tables;
     use crate::utils;
 
     const LOGLEVEL: &str = "Debug";
-    const BIT_STRING_1: &str = "01011001001010101101"; // example from NIST Paper. p-value should be 0.741948
+    const BIT_STRING_1: &str = "01011001001010101101"; // example from NIST Paper. p-value should be 0.741948 with the probabilities rounded to four digits
     const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
     const PI_FILE: &str = "/src/tests/testdata/data.pi";
     const E_FILE: &str = "/src/tests/testdata/data.e";
@@ -14,12 +15,13 @@ mod tests {
     const SQRT_3_FILE: &str = "/src/tests/testdata/data.sqrt3";
     const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
 
-    // XXX Fix binary matrix rank
     #[test]
     fn test_binary_matrix_rank() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
 
-        assert!(binary_matrix_rank::perform_test(BIT_STRING_1, 3, 3).unwrap() >= 0.01);
+        assert!(
+            (binary_matrix_rank::perform_test(BIT_STRING_1, 3, 3).unwrap() - 0.741908).abs() < 1e-6
+        );
 
         // test pi, e, sqrt(2) and sqrt(3) in their respective binary representations
         let pi_file = std::env::current_dir()
@@ -39,6 +41,8 @@ mod tests {
                 >= 0.01
         );
 
+        // like the reference implementation, the exact rank probabilities reproduce the published
+        // p-value of e
         let e_file = std::env::current_dir()
             .unwrap()
             .to_str()
@@ -46,6 +50,14 @@ mod tests {
             .to_owned()
             + E_FILE;
         let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let distribution = binary_matrix_rank::run_test_with_distribution(
+            &e_bit_string[..constants::REFERENCE_LENGTH],
+            constants::MATRIX_ROWS_M,
+            constants::MATRIX_COLUMNS_Q,
+        )
+        .unwrap();
+        assert_eq!(distribution.result.parameters["N"], 976);
+        assert!((distribution.result.p_value - 0.306156).abs() < 1e-6);
         assert!(
             binary_matrix_rank::perform_test(
                 &e_bit_string,
@@ -108,6 +120,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_binary_matrix_rank_distribution() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // example from NIST Paper: one matrix of full rank 3 and one of rank 2. The remaining two
+        // bits are discarded
+        let distribution =
+            binary_matrix_rank::run_test_with_distribution(BIT_STRING_1, 3, 3).unwrap();
+        assert_eq!(distribution.observed, [1, 1, 0]);
+        assert_eq!(distribution.result.parameters["N"], 2);
+        assert_eq!(distribution.rank_counts.values().sum::<usize>(), 2);
+        assert_eq!(distribution.probabilities, tables::rank_probabilities());
+        assert!((distribution.expected().iter().sum::<f64>() - 2.0).abs() < 1e-9);
+        assert_eq!(
+            distribution.result.p_value,
+            binary_matrix_rank::perform_test(BIT_STRING_1, 3, 3).unwrap()
+        );
+
+        // rows spanning two words: equal rows have rank 1, rows differing in the last column
+        // have rank 2
+        let row = "10".repeat(32) + "1";
+        let mut last_differs = row.clone();
+        last_differs.replace_range(64.., "0");
+        let bit_string = [row.as_str(), &row, &row, &last_differs].concat();
+        let distribution =
+            binary_matrix_rank::run_test_with_distribution(&bit_string, 2, 65).unwrap();
+        assert_eq!(distribution.rank_counts.get(&1), Some(&1));
+        assert_eq!(distribution.rank_counts.get(&2), Some(&1));
+        assert_eq!(distribution.observed, [1, 1, 0]);
+    }
+
     #[test]
     fn test_binary_matrix_rank_error_cases() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
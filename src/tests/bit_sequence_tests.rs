@@ -0,0 +1,311 @@
+#[cfg(test)]
+mod tests {
+    use crate::approximate_entropy;
+    use crate::bit_sequence::{BitInput, BitSequence};
+    use crate::cumulative_sums;
+    use crate::customtypes;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::longest_run;
+    use crate::runs;
+    use crate::suite;
+    use crate::utils;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_1: &str = "1011000011";
+    const BIT_STRING_1_REVERSED: &str = "1100001101";
+    const BIT_STRING_1_COMPLEMENTED: &str = "0100111100";
+    const BIT_STRING_1_REVERSED_COMPLEMENTED: &str = "0011110010";
+    const INVALID_BIT_STRING: &str = "10110a0011";
+    const SEED: u64 = 42;
+    const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+
+    #[test]
+    fn test_bit_sequence() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let sequence = BitSequence::from_bit_string(BIT_STRING_1).unwrap();
+        assert_eq!(sequence.len(), 10);
+        assert_eq!(sequence.count_ones(), 5);
+        assert_eq!(sequence.get(0), Some(true));
+        assert_eq!(sequence.get(1), Some(false));
+        assert_eq!(sequence.get(10), None);
+        assert_eq!(sequence.to_bit_string(), BIT_STRING_1);
+
+        assert!(BitSequence::from_bit_string("").unwrap().is_empty());
+        assert!(BitSequence::from_bit_string(INVALID_BIT_STRING).is_err());
+
+        // the sequence has to survive a round trip spanning multiple words
+        let sha_3_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + SHA_3_FILE;
+        let sha_3_bit_string = utils::read_random_numbers(&sha_3_file).unwrap();
+        let sequence = BitSequence::from_bit_string(&sha_3_bit_string).unwrap();
+        assert_eq!(sequence.to_bit_string(), sha_3_bit_string);
+        assert_eq!(
+            sequence.count_ones(),
+            sha_3_bit_string.chars().filter(|&c| c == '1').count()
+        );
+    }
+
+    #[test]
+    fn test_bit_sequence_views() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let sequence = BitSequence::from_bit_string(BIT_STRING_1).unwrap();
+
+        assert_eq!(sequence.reversed().to_bit_string(), BIT_STRING_1_REVERSED);
+        assert_eq!(
+            sequence.complemented().to_bit_string(),
+            BIT_STRING_1_COMPLEMENTED
+        );
+        assert_eq!(
+            sequence.reversed().complemented().to_bit_string(),
+            BIT_STRING_1_REVERSED_COMPLEMENTED
+        );
+        assert_eq!(sequence.complemented().count_ones(), 5);
+        assert_eq!(sequence.reversed().reversed(), sequence);
+        assert_eq!(sequence.complemented().complemented(), sequence);
+        assert_ne!(sequence.reversed(), sequence);
+
+        // the longest runs of zeros are the longest runs of ones of the complemented sequence
+        let sha_3_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + SHA_3_FILE;
+        let sha_3_bit_string = utils::read_random_numbers(&sha_3_file).unwrap();
+        let sequence = BitSequence::from_bit_string(&sha_3_bit_string).unwrap();
+        let result = longest_run::perform_test_both(&sha_3_bit_string).unwrap();
+        assert_eq!(
+            longest_run::perform_test(&sequence.complemented().to_bit_string()).unwrap(),
+            result.p_value_zeros
+        );
+        assert!(longest_run::perform_test(&sequence.reversed().to_bit_string()).unwrap() >= 0.01);
+    }
+
+    #[test]
+    fn test_bit_sequence_from_bytes() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the most significant bit of each byte comes first, also across words
+        let bytes: Vec<u8> = (0..=9).map(|i| 0xb0 | i).collect();
+        let sequence = BitSequence::from_bytes(&bytes);
+        let bit_string: String = bytes.iter().map(|byte| format!("{:08b}", byte)).collect();
+        assert_eq!(sequence.len(), 80);
+        assert_eq!(sequence.to_bit_string(), bit_string);
+
+        assert_eq!(BitSequence::from(&bytes[..]), sequence);
+        assert_eq!(BitSequence::from(bytes), sequence);
+        assert_eq!(
+            sequence.iter().collect::<BitSequence>().to_bit_string(),
+            bit_string
+        );
+        assert!(BitSequence::from_bytes(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_bit_sequence_encodings() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // 0x01 0xb0 0xff in both bit orders
+        let bytes = [0x01, 0xb0, 0xff];
+        let msb_first = "000000011011000011111111";
+        let lsb_first = "100000000000110111111111";
+        assert_eq!(
+            BitSequence::from_bytes_msb(&bytes).to_bit_string(),
+            msb_first
+        );
+        assert_eq!(
+            BitSequence::from_bytes_lsb(&bytes).to_bit_string(),
+            lsb_first
+        );
+        assert_eq!(
+            BitSequence::from_bytes_with_order(&bytes, customtypes::BitOrder::default()),
+            BitSequence::from_bytes(&bytes)
+        );
+        assert_eq!(
+            BitSequence::from_bytes_with_order(&bytes, customtypes::BitOrder::LsbFirst)
+                .to_bit_string(),
+            lsb_first
+        );
+
+        // whitespace is skipped, hex digits are accepted in both cases
+        for (hex_string, base64_string) in [("01b0ff", "AbD/"), ("01 B0\nfF\n", "Ab\nD/\n")] {
+            assert_eq!(
+                BitSequence::from_hex(hex_string, customtypes::BitOrder::MsbFirst)
+                    .unwrap()
+                    .to_bit_string(),
+                msb_first
+            );
+            assert_eq!(
+                BitSequence::from_base64(base64_string, customtypes::BitOrder::LsbFirst)
+                    .unwrap()
+                    .to_bit_string(),
+                lsb_first
+            );
+        }
+        assert!(BitSequence::from_hex("", customtypes::BitOrder::MsbFirst)
+            .unwrap()
+            .is_empty());
+
+        // invalid input is reported as such
+        for error in [
+            BitSequence::from_hex("01b", customtypes::BitOrder::MsbFirst).unwrap_err(),
+            BitSequence::from_hex("01bg", customtypes::BitOrder::MsbFirst).unwrap_err(),
+            BitSequence::from_base64("AbD", customtypes::BitOrder::MsbFirst).unwrap_err(),
+            BitSequence::from_base64("A*D/", customtypes::BitOrder::MsbFirst).unwrap_err(),
+        ] {
+            assert!(matches!(
+                error.root(),
+                customtypes::NistError::InvalidInput { .. }
+            ));
+        }
+
+        // the sequences can be passed to all tests
+        let sequence = BitSequence::from_hex(
+            "c90fdaa22168c234c4c6628b80dc1cd1",
+            customtypes::BitOrder::MsbFirst,
+        )
+        .unwrap();
+        assert_eq!(
+            frequency_monobit::perform_test(&sequence).unwrap(),
+            frequency_monobit::perform_test(&sequence.to_bit_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bit_sequence_from_rng() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the bits equal the bytes written by the generator, surplus bits are dropped
+        let mut bytes = [0; 9];
+        StdRng::seed_from_u64(SEED).fill_bytes(&mut bytes);
+        let bit_string: String = bytes.iter().map(|byte| format!("{:08b}", byte)).collect();
+        for number_of_bits in [0, 13, 64, 70, 72] {
+            let sequence =
+                BitSequence::from_rng(&mut StdRng::seed_from_u64(SEED), number_of_bits).unwrap();
+            assert_eq!(sequence.len(), number_of_bits);
+            assert_eq!(sequence.to_bit_string(), bit_string[..number_of_bits]);
+            assert_eq!(
+                sequence.complemented().count_ones(),
+                bit_string[..number_of_bits].matches('0').count()
+            );
+        }
+    }
+
+    /// Pack the bits of an input one at a time as reference for the words of the input
+    fn pack_bits(input: &(impl BitInput + ?Sized)) -> Vec<u64> {
+        let bits: Vec<bool> = input.bits().collect();
+
+        bits.chunks(64)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0, |word, (index, &bit)| {
+                    word | ((bit as u64) << (63 - index))
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bit_sequence_words() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // every byte value is packed by the multiplication without carries
+        for byte in 0..=u8::MAX {
+            let bit_string = format!("{:08b}", byte);
+            assert_eq!(
+                bit_string.words().collect::<Vec<u64>>(),
+                vec![(byte as u64) << 56]
+            );
+        }
+
+        // the words of bit strings and views equal the bits packed one at a time, including the
+        // zero padding of the last word
+        let mut rng = StdRng::seed_from_u64(SEED);
+        for length in [0, 1, 7, 63, 64, 65, 127, 200, 1000] {
+            let bit_string: String = (0..length)
+                .map(|_| if rng.next_u32() % 2 == 1 { '1' } else { '0' })
+                .collect();
+            assert_eq!(
+                bit_string.words().collect::<Vec<u64>>(),
+                pack_bits(&bit_string)
+            );
+
+            let sequence = BitSequence::from_bit_string(&bit_string).unwrap();
+            for view in [
+                sequence.clone(),
+                sequence.reversed(),
+                sequence.complemented(),
+                sequence.reversed().complemented(),
+            ] {
+                assert_eq!(view.words().collect::<Vec<u64>>(), pack_bits(&view));
+
+                // ranges not starting at a word are expanded the same way
+                let expected = view.to_bit_string();
+                for range in [0..length, length / 3..length - length / 4] {
+                    assert_eq!(view.ascii(range.clone()), &expected[range]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit_sequence_input() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the tests have to yield the same results for a packed sequence as for its bit string
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let sequence = BitSequence::from_bit_string(&e_bit_string).unwrap();
+
+        assert_eq!(
+            frequency_monobit::perform_test(&sequence).unwrap(),
+            frequency_monobit::perform_test(&e_bit_string).unwrap()
+        );
+        assert_eq!(
+            runs::perform_test(&sequence).unwrap(),
+            runs::perform_test(&e_bit_string).unwrap()
+        );
+        assert_eq!(
+            longest_run::perform_test(&sequence).unwrap(),
+            longest_run::perform_test(&e_bit_string).unwrap()
+        );
+        assert_eq!(
+            approximate_entropy::perform_test(&sequence, 10).unwrap(),
+            approximate_entropy::perform_test(&e_bit_string, 10).unwrap()
+        );
+        assert_eq!(
+            cumulative_sums::perform_test(&sequence, customtypes::Mode::Backward).unwrap(),
+            cumulative_sums::perform_test(&e_bit_string, customtypes::Mode::Backward).unwrap()
+        );
+
+        let config = customtypes::SuiteConfig::default();
+        let packed_report = suite::run_all(&sequence, &config).unwrap();
+        let report = suite::run_all(&e_bit_string, &config).unwrap();
+        assert_eq!(packed_report.results.len(), report.results.len());
+        for (packed_result, result) in packed_report.results.iter().zip(&report.results) {
+            assert_eq!(packed_result.test, result.test);
+            assert_eq!(packed_result.p_value, result.p_value);
+        }
+
+        // views are read as seen, e.g. reversed
+        assert_eq!(
+            cumulative_sums::perform_test(&sequence.reversed(), customtypes::Mode::Forward)
+                .unwrap(),
+            cumulative_sums::perform_test(&e_bit_string, customtypes::Mode::Backward).unwrap()
+        );
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::bit_sequence::BitSequence;
+    use crate::bit_source::{BitSequenceSource, BitSource, BitStringSource};
+    use crate::customtypes;
+    use crate::logger;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_1: &str = "1011000011";
+    const INVALID_BIT_STRING: &str = "10110a0011";
+
+    #[test]
+    fn test_bit_source() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // read in chunks not dividing the length
+        let mut source = BitStringSource::new(BIT_STRING_1);
+        let mut buffer = [false; 4];
+        let mut bits = Vec::<bool>::new();
+        loop {
+            let count = source.read_bits(&mut buffer).unwrap();
+            if count == 0 {
+                break;
+            }
+            bits.extend_from_slice(&buffer[..count]);
+        }
+        assert_eq!(
+            bits.into_iter().collect::<BitSequence>().to_bit_string(),
+            BIT_STRING_1
+        );
+
+        // sequences are read as seen through their view
+        let sequence = BitSequence::from_bit_string(BIT_STRING_1).unwrap();
+        let mut source = BitSequenceSource::new(&sequence.reversed());
+        let mut buffer = [false; 16];
+        assert_eq!(source.read_bits(&mut buffer).unwrap(), 10);
+        assert_eq!(
+            buffer[..10].iter().copied().collect::<BitSequence>(),
+            sequence.reversed()
+        );
+        assert_eq!(source.read_bits(&mut buffer).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_bit_source_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the bits before the invalid character can be read
+        let mut source = BitStringSource::new(INVALID_BIT_STRING);
+        let mut buffer = [false; 5];
+        assert_eq!(source.read_bits(&mut buffer).unwrap(), 5);
+        let err = source.read_bits(&mut buffer).unwrap_err();
+        assert_eq!(
+            err.root(),
+            &customtypes::NistError::InvalidBit {
+                position: 5,
+                symbol: 'a'
+            }
+        );
+    }
+}
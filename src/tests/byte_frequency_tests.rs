@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use crate::byte_frequency;
+    use crate::logger;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const INVALID_BIT_STRING: &str = "0101011110101010a0101010";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+    const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+
+    #[test]
+    fn test_byte_frequency() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // each byte value exactly once results in a perfect fit. Trailing bits are discarded
+        let all_bytes: Vec<u8> = (0..=u8::MAX).collect();
+        let bit_string = utils::hex_bytes_to_bit_string(all_bytes).unwrap() + "101";
+        let result = byte_frequency::perform_test_detailed(&bit_string).unwrap();
+        assert_eq!(result.chi_square, 0.0);
+        assert_eq!(result.p_value, 1.0);
+        assert_eq!(result.expected, 1.0);
+        assert!(result.counts.iter().all(|&count| count == 1));
+
+        // one byte value dominates while others are missing
+        let mut bytes: Vec<u8> = (0..=u8::MAX).cycle().take(256 * 40).collect();
+        bytes
+            .iter_mut()
+            .filter(|byte| (0x30..0x40).contains(*byte))
+            .for_each(|byte| *byte = 0xaa);
+        let bit_string = utils::hex_bytes_to_bit_string(bytes).unwrap();
+        let result = byte_frequency::perform_test_detailed(&bit_string).unwrap();
+        assert!(result.p_value < 0.01);
+        assert_eq!(result.most_over_represented, 0xaa);
+        assert_eq!(result.most_under_represented, 0x30);
+        assert_eq!(result.counts[0xaa], 680);
+        assert_eq!(result.counts[0x3c], 0);
+
+        // test pi, e and sha3 in their respective binary representations
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        assert!(byte_frequency::perform_test(&pi_bit_string).unwrap() >= 0.01);
+
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        assert!(byte_frequency::perform_test(&e_bit_string).unwrap() >= 0.01);
+
+        let sha_3_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + SHA_3_FILE;
+        let sha_3_bit_string = utils::read_random_numbers(&sha_3_file).unwrap();
+        assert!(byte_frequency::perform_test(&sha_3_bit_string).unwrap() >= 0.01);
+    }
+
+    #[test]
+    fn test_byte_frequency_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let mut success: bool;
+
+        // pass empty string
+        match byte_frequency::perform_test("") {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass invalid bit string
+        match byte_frequency::perform_test(INVALID_BIT_STRING) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass less than one byte
+        match byte_frequency::perform_test("0110101") {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use crate::cache;
+    use crate::customtypes;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::runs;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_1: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
+    const BIT_STRING_2: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111001";
+
+    fn run_suite(bit_string: &str) -> customtypes::Result<customtypes::SuiteReport> {
+        Ok(customtypes::SuiteReport {
+            results: vec![
+                frequency_monobit::run_test(bit_string)?,
+                runs::run_test(bit_string)?,
+            ],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_report_cache() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let cache = cache::ReportCache::new();
+        let config = customtypes::SuiteConfig::default();
+        let runs = std::cell::Cell::new(0);
+        let counting_run = |bit_string| {
+            runs.set(runs.get() + 1);
+            run_suite(bit_string)
+        };
+
+        // the second run on identical input is served from the cache
+        let report = cache
+            .get_or_run(BIT_STRING_1, &config, || counting_run(BIT_STRING_1))
+            .unwrap();
+        let cached = cache
+            .clone()
+            .get_or_run(BIT_STRING_1, &config, || counting_run(BIT_STRING_1))
+            .unwrap();
+        assert_eq!(report, cached);
+        assert_eq!(runs.get(), 1);
+        assert_eq!(cache.len(), 1);
+
+        // different input or configuration is run again
+        cache
+            .get_or_run(BIT_STRING_2, &config, || counting_run(BIT_STRING_2))
+            .unwrap();
+        let strict = customtypes::SuiteConfig {
+            compliance_mode: customtypes::ComplianceMode::Strict,
+            ..Default::default()
+        };
+        cache
+            .get_or_run(BIT_STRING_1, &strict, || counting_run(BIT_STRING_1))
+            .unwrap();
+        assert_eq!(runs.get(), 3);
+        assert_eq!(cache.len(), 3);
+
+        // failed runs are not cached
+        assert!(cache.get_or_run("", &config, || counting_run("")).is_err());
+        assert_eq!(cache.len(), 3);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_input_hash() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let config = customtypes::SuiteConfig::default();
+        assert_eq!(
+            cache::input_hash(BIT_STRING_1, &config),
+            cache::input_hash(String::from(BIT_STRING_1).as_str(), &config.clone())
+        );
+        assert_ne!(
+            cache::input_hash(BIT_STRING_1, &config),
+            cache::input_hash(BIT_STRING_2, &config)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use crate::capture;
+    use crate::logger;
+
+    const LOGLEVEL: &str = "Debug";
+    const CSV_FILE: &str = "/src/tests/testdata/capture.csv";
+    const VCD_FILE: &str = "/src/tests/testdata/capture.vcd";
+    const SAMPLE_RATE: f64 = 1e6;
+    const BITS_CHANNEL_0: &str = "11011100";
+
+    fn file_path(file: &str) -> String {
+        std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + file
+    }
+
+    #[test]
+    fn test_read_capture_csv() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let csv_file = file_path(CSV_FILE);
+        let sequence = capture::read_capture(&csv_file, "Channel 0", SAMPLE_RATE).unwrap();
+        assert_eq!(sequence.to_bit_string(), BITS_CHANNEL_0);
+        let sequence = capture::read_capture(&csv_file, "Channel 1", SAMPLE_RATE).unwrap();
+        assert_eq!(sequence.to_bit_string(), "00001111");
+
+        // oversampling repeats each bit
+        let sequence = capture::read_capture(&csv_file, "Channel 0", 2.0 * SAMPLE_RATE).unwrap();
+        assert_eq!(sequence.to_bit_string(), "1111001111110000");
+    }
+
+    #[test]
+    fn test_read_capture_vcd() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the channel is selected by its reference or its identifier code
+        let vcd_file = file_path(VCD_FILE);
+        let sequence = capture::read_capture(&vcd_file, "D0", SAMPLE_RATE).unwrap();
+        assert_eq!(sequence.to_bit_string(), BITS_CHANNEL_0);
+        let sequence = capture::read_capture(&vcd_file, "!", SAMPLE_RATE).unwrap();
+        assert_eq!(sequence.to_bit_string(), BITS_CHANNEL_0);
+        let sequence = capture::read_capture(&vcd_file, "D1", SAMPLE_RATE).unwrap();
+        assert_eq!(sequence.to_bit_string(), "00011111");
+
+        // the timescale is applied to the timestamps
+        let vcd =
+            "$timescale 10ns $end $var wire 1 ! D0 $end $enddefinitions $end #0 1! #100 0! #200";
+        let sequence = capture::parse_vcd(vcd, "D0", SAMPLE_RATE).unwrap();
+        assert_eq!(sequence.to_bit_string(), "10");
+    }
+
+    #[test]
+    fn test_read_capture_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let csv_file = file_path(CSV_FILE);
+        let vcd_file = file_path(VCD_FILE);
+
+        // unknown file or channel
+        assert!(
+            capture::read_capture("/tmp/does_not_exist.csv", "Channel 0", SAMPLE_RATE).is_err()
+        );
+        assert!(capture::read_capture(&csv_file, "Channel 2", SAMPLE_RATE).is_err());
+        assert!(capture::read_capture(&vcd_file, "D2", SAMPLE_RATE).is_err());
+
+        // channels of several bits cannot be sampled
+        assert!(capture::read_capture(&vcd_file, "BUS", SAMPLE_RATE).is_err());
+
+        // invalid sample rates or captures too short for a single sample
+        assert!(capture::read_capture(&csv_file, "Channel 0", 0.0).is_err());
+        assert!(capture::read_capture(&csv_file, "Channel 0", f64::NAN).is_err());
+        assert!(capture::read_capture(&csv_file, "Channel 0", 100.0).is_err());
+
+        // malformed captures
+        assert!(capture::parse_csv("", "Channel 0", SAMPLE_RATE).is_err());
+        assert!(capture::parse_csv("Time [s],Channel 0\n", "Channel 0", SAMPLE_RATE).is_err());
+        assert!(
+            capture::parse_csv("Time [s],Channel 0\n0.0,2\n1.0,0", "Channel 0", SAMPLE_RATE)
+                .is_err()
+        );
+        assert!(
+            capture::parse_csv("Time [s],Channel 0\n1.0,1\n0.0,0", "Channel 0", SAMPLE_RATE)
+                .is_err()
+        );
+        assert!(
+            capture::parse_csv("Time [s],Channel 0\n0.0\n1.0,0", "Channel 0", SAMPLE_RATE).is_err()
+        );
+        assert!(capture::parse_vcd(
+            "$timescale 1 xs $end $var wire 1 ! D0 $end $enddefinitions $end #0 1! #8",
+            "D0",
+            SAMPLE_RATE
+        )
+        .is_err());
+        assert!(capture::parse_vcd(
+            "$timescale 1 us $end $var wire 1 ! D0 $end $enddefinitions $end #0 x! #8",
+            "D0",
+            SAMPLE_RATE
+        )
+        .is_err());
+        assert!(capture::parse_vcd(
+            "$timescale 1 us $end $var wire 1 ! D0 $end $enddefinitions $end #8 1! #0",
+            "D0",
+            SAMPLE_RATE
+        )
+        .is_err());
+        assert!(capture::parse_vcd(
+            "$timescale 1 us $end $var wire 1 ! D0 $end $enddefinitions $end 1! #8",
+            "D0",
+            SAMPLE_RATE
+        )
+        .is_err());
+    }
+}
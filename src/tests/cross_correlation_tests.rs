@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::cross_correlation;
+    use crate::logger;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_1: &str = "1100101101";
+    const BIT_STRING_1_COMPLEMENTED: &str = "0011010010";
+    const INVALID_BIT_STRING: &str = "1100a01101";
+    const MAX_OFFSET: usize = 16;
+    const SHIFT: usize = 5;
+    const NUMBER_OF_BITS: usize = 100000;
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+
+    #[test]
+    fn test_cross_correlation() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let result = cross_correlation::perform_test(BIT_STRING_1, BIT_STRING_1, 2).unwrap();
+        assert_eq!(result.correlations.len(), 5);
+        assert_eq!(result.correlations[&0], 1.0);
+        assert_eq!(result.worst_offset, 0);
+
+        let result =
+            cross_correlation::perform_test(BIT_STRING_1, BIT_STRING_1_COMPLEMENTED, 2).unwrap();
+        assert_eq!(result.correlations[&0], -1.0);
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let pi_bit_string = &pi_bit_string[..NUMBER_OF_BITS];
+
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let e_bit_string = &e_bit_string[..NUMBER_OF_BITS];
+
+        // independent sequences are not correlated
+        let result =
+            cross_correlation::perform_test(pi_bit_string, e_bit_string, MAX_OFFSET).unwrap();
+        assert_eq!(result.p_values.len(), 2 * MAX_OFFSET + 1);
+        assert!(result.p_value >= constants::P_VALUE_THRESHOLD);
+
+        // a delayed copy is detected at the delay
+        let delayed = "0".repeat(SHIFT) + &pi_bit_string[..NUMBER_OF_BITS - SHIFT];
+        let result = cross_correlation::perform_test(pi_bit_string, &delayed, MAX_OFFSET).unwrap();
+        assert!(result.p_value < constants::P_VALUE_THRESHOLD);
+        assert_eq!(result.worst_offset, SHIFT as isize);
+        assert!(result.correlations[&(SHIFT as isize)] > result.bounds[&(SHIFT as isize)]);
+
+        // a weak coupling: every fourth bit of the second channel copies the first channel
+        let coupled: String = e_bit_string
+            .chars()
+            .zip(pi_bit_string.chars())
+            .enumerate()
+            .map(|(index, (e, pi))| if index % 4 == 0 { pi } else { e })
+            .collect();
+        let result = cross_correlation::perform_test(pi_bit_string, &coupled, MAX_OFFSET).unwrap();
+        assert!(result.p_value < constants::P_VALUE_THRESHOLD);
+        assert_eq!(result.worst_offset, 0);
+    }
+
+    #[test]
+    fn test_cross_correlation_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let mut success: bool;
+
+        // pass empty string
+        match cross_correlation::perform_test("", BIT_STRING_1, 2) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass invalid bit string
+        match cross_correlation::perform_test(BIT_STRING_1, INVALID_BIT_STRING, 2) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass too large offset
+        match cross_correlation::perform_test(BIT_STRING_1, BIT_STRING_1, 6) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+    }
+}
@@ -1,9 +1,12 @@
 #[cfg(test)]
 mod tests {
+    use crate::bit_sequence::BitSequence;
     use crate::cumulative_sums;
     use crate::customtypes;
     use crate::logger;
     use crate::utils;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
 
     const LOGLEVEL: &str = "Debug";
     const BIT_STRING_NIST_1: &str = "1011010111";
@@ -19,6 +22,7 @@ mod tests {
     const SQRT_2_FILE: &str = "/src/tests/testdata/data.sqrt2";
     const SQRT_3_FILE: &str = "/src/tests/testdata/data.sqrt3";
     const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+    const SEED: u64 = 42;
 
     #[test]
     fn test_cumulative_sums() {
@@ -149,6 +153,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cumulative_sums_path() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let path =
+            cumulative_sums::run_test_with_path(BIT_STRING_NIST_1, customtypes::Mode::Forward)
+                .unwrap();
+        assert_eq!(path.partial_sums, vec![1, 0, 1, 2, 1, 2, 1, 2, 3, 4]);
+        assert_eq!(path.excursions.result.p_value, P_VALUE_NIST_1);
+        assert_eq!(
+            path.excursions.maximum,
+            customtypes::WalkPoint { index: 10, sum: 4 }
+        );
+        assert_eq!(
+            path.excursions.minimum,
+            customtypes::WalkPoint { index: 0, sum: 0 }
+        );
+        assert_eq!(path.excursions.maximal_excursion(), path.excursions.maximum);
+
+        // the backward walk reaches its maximum for the first time at k = 8
+        let mut points = Vec::<customtypes::WalkPoint>::new();
+        let excursions = cumulative_sums::run_test_with_sink(
+            BIT_STRING_NIST_1,
+            customtypes::Mode::Backward,
+            &mut |point| points.push(point),
+        )
+        .unwrap();
+        assert_eq!(points.len(), BIT_STRING_NIST_1.len());
+        assert_eq!(points[0], customtypes::WalkPoint { index: 1, sum: 1 });
+        assert_eq!(
+            excursions.maximum,
+            customtypes::WalkPoint { index: 8, sum: 4 }
+        );
+
+        let path =
+            cumulative_sums::run_test_with_path(BIT_STRING_ONLY_ZEROS, customtypes::Mode::Forward)
+                .unwrap();
+        assert_eq!(
+            path.excursions.maximal_excursion(),
+            customtypes::WalkPoint {
+                index: 100,
+                sum: -100
+            }
+        );
+    }
+
+    #[test]
+    fn test_cumulative_sums_words() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // walking a byte at a time yields the results of walking the path one bit at a time in
+        // both modes, including a partial last byte and word
+        let mut rng = StdRng::seed_from_u64(SEED);
+        for length in [100, 127, 128, 131, 1000, 4097] {
+            let bit_string: String = (0..length)
+                .map(|_| if rng.next_u32() % 2 == 1 { '1' } else { '0' })
+                .collect();
+            let sequence = BitSequence::from_bit_string(&bit_string).unwrap();
+
+            for backward in [false, true] {
+                let mode = || {
+                    if backward {
+                        customtypes::Mode::Backward
+                    } else {
+                        customtypes::Mode::Forward
+                    }
+                };
+                let expected =
+                    cumulative_sums::run_test_with_sink(&bit_string, mode(), &mut |_| {})
+                        .unwrap()
+                        .result;
+                let result = cumulative_sums::run_test(&bit_string, mode()).unwrap();
+                assert_eq!(result.statistic, expected.statistic);
+                assert_eq!(result.p_value, expected.p_value);
+                assert_eq!(
+                    cumulative_sums::run_test(&sequence.complemented(), mode())
+                        .unwrap()
+                        .p_value,
+                    expected.p_value
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_cumulative_sums_error_cases() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::customtypes;
+    use crate::customtypes::Context;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::longest_run;
+    use crate::runs;
+    use crate::serial;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_NIST: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
+    const BIT_STRING_ONLY_ZEROS: &str = "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
+
+    #[test]
+    fn test_report_display() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let passed = frequency_monobit::run_test(BIT_STRING_NIST).unwrap();
+        let failed = frequency_monobit::run_test(BIT_STRING_ONLY_ZEROS).unwrap();
+        assert_eq!(passed.verdict(), "PASS");
+        assert_eq!(failed.verdict(), "FAIL");
+
+        // each result is one row starting with the test name
+        let row = passed.to_string();
+        assert!(row.starts_with("Frequency Monobit Test "));
+        assert!(row.contains(&format!("{:.6}", passed.p_value)));
+        assert!(row.contains("PASS"));
+
+        let report = customtypes::SuiteReport {
+            results: vec![passed, failed, runs::run_test(BIT_STRING_NIST).unwrap()],
+            ..Default::default()
+        };
+        let table = report.to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Test "));
+        assert!(lines[2].contains("FAIL"));
+        assert!(lines[3].starts_with("Runs Test "));
+        assert_eq!(report.verdict(), "FAIL");
+
+        // the columns are aligned
+        let verdict_column = lines[0].find("verdict").unwrap();
+        assert!(lines[1..]
+            .iter()
+            .all(|line| line[verdict_column..].starts_with("PASS")
+                || line[verdict_column..].starts_with("FAIL")));
+    }
+
+    #[test]
+    fn test_result_alpha() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the NIST example has a p-value of 0.109599, so it passes up to alpha = 0.1 only
+        let result = frequency_monobit::run_test(BIT_STRING_NIST).unwrap();
+        assert_eq!(result.alpha, constants::P_VALUE_THRESHOLD);
+        assert!(result.passed());
+        assert!(result.passed_at(0.1));
+        assert!(!result.passed_at(0.2));
+
+        let strict = result.clone().with_alpha(0.2);
+        assert!(!strict.passed());
+        assert_eq!(strict.verdict(), "FAIL");
+        assert!(strict.to_string().contains("FAIL"));
+
+        // the significance level is not part of the regression comparison
+        assert!(strict.approx_eq(&result, 0.0));
+    }
+
+    #[test]
+    fn test_nist_error() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // too short bit strings, invalid characters and bad parameters are told apart by the root
+        // below the added context
+        let error = longest_run::run_test(&BIT_STRING_NIST[..100]).unwrap_err();
+        assert_eq!(
+            error.root(),
+            &customtypes::NistError::InsufficientLength {
+                test: customtypes::Test::LongestRun,
+                required: 128,
+                actual: 100
+            }
+        );
+
+        let error = frequency_monobit::run_test("0110a1").unwrap_err();
+        assert_eq!(
+            error.root(),
+            &customtypes::NistError::InvalidBit {
+                position: 4,
+                symbol: 'a'
+            }
+        );
+
+        let error = serial::run_test(BIT_STRING_NIST, 0).unwrap_err();
+        match error.root() {
+            customtypes::NistError::InvalidParameter {
+                test: Some(customtypes::Test::Serial),
+                name,
+                ..
+            } => assert_eq!(name, "m"),
+            _ => panic!("Expected an invalid parameter, got {:?}", error),
+        }
+
+        let error = runs::run_test(BIT_STRING_ONLY_ZEROS).unwrap_err();
+        assert!(matches!(
+            error.root(),
+            customtypes::NistError::NotApplicable {
+                test: customtypes::Test::Runs,
+                ..
+            }
+        ));
+
+        // the messages name the test
+        assert_eq!(
+            customtypes::NistError::insufficient_length(customtypes::Test::LongestRun, 128, 100)
+                .to_string(),
+            "'Longest Run of Ones in a Block Test': Bit string needs at least 128 bits! Actual length: 100"
+        );
+        assert_eq!(
+            customtypes::NistError::insufficient_length(customtypes::Test::Runs, 1, 0).to_string(),
+            "'Runs Test': Bit string is empty"
+        );
+        assert_eq!(
+            customtypes::NistError::invalid_parameter(
+                customtypes::Test::Serial,
+                "m",
+                "Block length m (0) must be between 2 and 16".to_owned()
+            )
+            .to_string(),
+            "'Serial Test': Block length m (0) must be between 2 and 16"
+        );
+
+        // errors of dependencies keep their source, the alternate form lists all messages
+        let error = utils::read_random_numbers("/nonexistent/data.pi").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to open file '/nonexistent/data.pi'"
+        );
+        match error.root() {
+            customtypes::NistError::Io { source } => assert_eq!(
+                source
+                    .get()
+                    .downcast_ref::<std::io::Error>()
+                    .map(|error| error.kind()),
+                Some(std::io::ErrorKind::NotFound)
+            ),
+            _ => panic!("Expected an I/O error, got {:?}", error),
+        }
+        assert!(std::error::Error::source(&error).is_some());
+        assert!(format!("{:#}", error)
+            .starts_with("Failed to open file '/nonexistent/data.pi': No such file"));
+
+        // added context keeps a NistError as its root, a missing value is invalid input
+        let error = Err::<(), _>(customtypes::NistError::Cancelled {
+            test: customtypes::Test::Runs,
+        })
+        .context("Suite stopped")
+        .unwrap_err();
+        assert_eq!(
+            error.root(),
+            &customtypes::NistError::Cancelled {
+                test: customtypes::Test::Runs
+            }
+        );
+        assert_eq!(
+            None::<usize>.context("Value is missing"),
+            Err(customtypes::NistError::InvalidInput {
+                reason: "Value is missing".to_owned()
+            })
+        );
+    }
+}
@@ -1,11 +1,12 @@
 #[cfg(test)]
 mod tests {
+    use crate::customtypes;
     use crate::dft_spectral;
     use crate::logger;
     use crate::utils;
 
     const LOGLEVEL: &str = "Debug";
-    const BIT_STRING_1: &str = "1001010011"; // example from NIST Paper. p-value should be 0.029523
+    const BIT_STRING_1: &str = "1001010011"; // example from NIST Paper
     const BIT_STRING_2: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
     const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
     const PI_FILE: &str = "/src/tests/testdata/data.pi";
@@ -18,9 +19,14 @@ mod tests {
     fn test_dft_spectral() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
 
-        //  XXX Fix dicrete fourier transform
-        assert!(dft_spectral::perform_test(BIT_STRING_1).unwrap() != 1.00);
-        assert!(dft_spectral::perform_test(BIT_STRING_2).unwrap() != 1.00);
+        // the examples of NIST SP 800-22, sections 2.6.4 and 2.6.8. The paper lists the p-values
+        // 0.029523 and 0.168669, which do not follow from its formulas: all five peaks of the
+        // first example are below T = 5.473328. The published p-values of the reference sequences
+        // in appendix B are reproduced, see reference_tests
+        let result = dft_spectral::run_test(BIT_STRING_1).unwrap();
+        assert!((result.statistic - 0.725476).abs() < 1e-6);
+        assert!((result.p_value - 0.468160).abs() < 1e-6);
+        assert!((dft_spectral::perform_test(BIT_STRING_2).unwrap() - 0.646355).abs() < 1e-6);
 
         // test pi, e, sqrt(2) and sqrt(3) in their respective binary representations
         let pi_file = std::env::current_dir()
@@ -69,6 +75,41 @@ mod tests {
         assert!(dft_spectral::perform_test(&sha_3_bit_string).unwrap() >= 0.01);
     }
 
+    #[test]
+    fn test_dft_spectral_cancellation() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the progress reaches 1.0 and the result equals the one of run_test()
+        let reports = std::cell::RefCell::new(Vec::<customtypes::Progress>::new());
+        let token = customtypes::CancellationToken::new();
+        let result = dft_spectral::run_test_cancellable(
+            BIT_STRING_2,
+            &|progress| reports.borrow_mut().push(progress),
+            &token,
+        )
+        .unwrap();
+        assert!(result.approx_eq(&dft_spectral::run_test(BIT_STRING_2).unwrap(), 0.0));
+        assert_eq!(reports.borrow().first().unwrap().fraction, 0.0);
+        assert_eq!(reports.borrow().last().unwrap().fraction, 1.0);
+
+        // cancelling stops the test before the transform
+        reports.borrow_mut().clear();
+        token.cancel();
+        let error = dft_spectral::run_test_cancellable(
+            BIT_STRING_2,
+            &|progress| reports.borrow_mut().push(progress),
+            &token,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.root(),
+            &customtypes::NistError::Cancelled {
+                test: customtypes::Test::DFTSpectral
+            }
+        );
+        assert!(reports.borrow().is_empty());
+    }
+
     #[test]
     fn test_dft_spectral_error_cases() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
@@ -0,0 +1,257 @@
+#[cfg(test)]
+mod tests {
+    use crate::cumulative_sums;
+    use crate::customtypes;
+    use crate::diagnostics;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_1: &str = "000011110101100";
+    const SEED: u64 = 42;
+    const BLOCK_SIZE: usize = 4;
+    const NUMBER_OF_BITS: usize = 100000;
+    const NUMBER_OF_BLOCKS: usize = 10;
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+
+    /// Force every fourth bit of the passed bit string to the given bit.
+    fn force_every_fourth_bit(bit_string: &str, bit: char) -> String {
+        bit_string
+            .chars()
+            .enumerate()
+            .map(|(index, c)| if index % 4 == 0 { bit } else { c })
+            .collect()
+    }
+
+    #[test]
+    fn test_shuffle_blocks() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let shuffled = diagnostics::shuffle_blocks(BIT_STRING_1, 4, SEED).unwrap();
+        assert_eq!(shuffled.len(), BIT_STRING_1.len());
+        // the trailing bits which do not fill a whole block stay in place
+        assert!(shuffled.ends_with("100"));
+
+        // each block is still present
+        for block in ["0000", "1111", "0101"] {
+            assert!((0..3).any(|index| &shuffled[index * 4..(index + 1) * 4] == block));
+        }
+
+        // the same seed leads to the same permutation
+        assert_eq!(
+            diagnostics::shuffle_blocks(BIT_STRING_1, 4, SEED).unwrap(),
+            shuffled
+        );
+
+        // invalid block sizes
+        assert!(diagnostics::shuffle_blocks(BIT_STRING_1, 0, SEED).is_err());
+        assert!(diagnostics::shuffle_blocks(BIT_STRING_1, 16, SEED).is_err());
+    }
+
+    #[test]
+    fn test_block_shuffle_control() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let bit_string = &pi_bit_string[..NUMBER_OF_BITS];
+
+        // long-range structure: the first half is biased towards ones, the second half towards
+        // zeros. The failure disappears after shuffling
+        let drifting = force_every_fourth_bit(&bit_string[..NUMBER_OF_BITS / 2], '1')
+            + force_every_fourth_bit(&bit_string[NUMBER_OF_BITS / 2..], '0').as_str();
+        let result = diagnostics::block_shuffle_control(&drifting, BLOCK_SIZE, SEED, |bits| {
+            cumulative_sums::perform_test(bits, customtypes::Mode::Forward)
+        })
+        .unwrap();
+        assert!(result.original < 0.01);
+        assert!(result.shuffled >= 0.01);
+
+        // marginal bias: the failure persists after shuffling
+        let biased = force_every_fourth_bit(bit_string, '1');
+        let result = diagnostics::block_shuffle_control(
+            &biased,
+            BLOCK_SIZE,
+            SEED,
+            frequency_monobit::perform_test,
+        )
+        .unwrap();
+        assert!(result.original < 0.01);
+        assert!(result.shuffled < 0.01);
+
+        // errors of the test are passed through
+        assert!(
+            diagnostics::block_shuffle_control("", BLOCK_SIZE, SEED, |bits| {
+                frequency_monobit::perform_test(bits)
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_phase_analysis() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert_eq!(
+            diagnostics::split_phases(BIT_STRING_1, 4).unwrap(),
+            vec!["0101", "0110", "0100", "011"]
+        );
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let pi_bit_string = &pi_bit_string[..NUMBER_OF_BITS];
+
+        let analysis = diagnostics::phase_analysis(pi_bit_string, 4).unwrap();
+        assert_eq!(analysis.phases.len(), 4);
+        assert!(analysis.phases.iter().all(|phase| phase.results.len() == 3));
+        assert!(analysis.diverging_phases().is_empty());
+        assert!(analysis.homogeneity_p_value >= 0.01);
+
+        // a bit stuck at every fourth position only shows in phase 0
+        let stuck = force_every_fourth_bit(pi_bit_string, '1');
+        let analysis = diagnostics::phase_analysis(&stuck, 4).unwrap();
+        assert_eq!(analysis.diverging_phases(), vec![0]);
+        assert_eq!(analysis.phases[0].ones, NUMBER_OF_BITS / 4);
+        assert_eq!(analysis.phases[0].results.len(), 1);
+        assert_eq!(analysis.phases[1].results.len(), 3);
+        assert!(analysis.homogeneity_p_value < 0.01);
+    }
+
+    #[test]
+    fn test_phase_analysis_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(diagnostics::split_phases(BIT_STRING_1, 0).is_err());
+        assert!(diagnostics::split_phases(BIT_STRING_1, 16).is_err());
+        assert!(diagnostics::phase_analysis(BIT_STRING_1, 1).is_err());
+        assert!(diagnostics::phase_analysis(&"0a".repeat(500), 4).is_err());
+
+        // each phase needs at least 100 bits
+        let bit_string = "01".repeat(150);
+        assert!(diagnostics::phase_analysis(&bit_string, 4).is_err());
+    }
+
+    #[test]
+    fn test_bit_position_bias() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // 0x0f and 0xf0: each bit is set in one of the two bytes
+        let positions = diagnostics::bit_position_bias("0000111111110000101").unwrap();
+        assert_eq!(positions.len(), 8);
+        assert!(positions.iter().all(|position| position.ones == 1));
+        assert!(positions.iter().all(|position| !position.is_biased()));
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let pi_bit_string = &pi_bit_string[..NUMBER_OF_BITS];
+
+        let positions = diagnostics::bit_position_bias(pi_bit_string).unwrap();
+        assert!(positions.iter().all(|position| !position.is_biased()));
+        assert!(positions
+            .iter()
+            .all(
+                |position| position.confidence_interval.0 < position.frequency
+                    && position.frequency < position.confidence_interval.1
+            ));
+
+        // forcing every fourth bit to 0 clears bits 7 and 3 of each byte
+        let stuck = force_every_fourth_bit(pi_bit_string, '0');
+        let positions = diagnostics::bit_position_bias(&stuck).unwrap();
+        let biased: Vec<usize> = positions
+            .iter()
+            .filter(|position| position.is_biased())
+            .map(|position| position.bit)
+            .collect();
+        assert_eq!(biased, vec![3, 7]);
+        assert_eq!(positions[7].ones, 0);
+        assert!(positions[7].confidence_interval.0 >= 0.0);
+        assert!(positions[7].p_value < 0.01);
+    }
+
+    #[test]
+    fn test_bit_position_bias_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(diagnostics::bit_position_bias("").is_err());
+        assert!(diagnostics::bit_position_bias("0101010").is_err());
+        assert!(diagnostics::bit_position_bias("0101010a").is_err());
+    }
+
+    #[test]
+    fn test_jackknife() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let bit_string = &pi_bit_string[..NUMBER_OF_BITS];
+
+        // a random sequence passes with and without each block
+        let analysis =
+            diagnostics::jackknife(bit_string, NUMBER_OF_BLOCKS, frequency_monobit::run_test)
+                .unwrap();
+        assert!(analysis.result.passed());
+        assert_eq!(analysis.blocks.len(), NUMBER_OF_BLOCKS);
+        assert_eq!(analysis.blocks[3].range, 30000..40000);
+        assert!(analysis.decisive_blocks().is_empty());
+
+        // a single anomalous region: leaving out the biased block 3 lets the test pass
+        let block_size = NUMBER_OF_BITS / NUMBER_OF_BLOCKS;
+        let anomalous = bit_string[..3 * block_size].to_owned()
+            + force_every_fourth_bit(&bit_string[3 * block_size..4 * block_size], '1').as_str()
+            + &bit_string[4 * block_size..];
+        let analysis =
+            diagnostics::jackknife(&anomalous, NUMBER_OF_BLOCKS, frequency_monobit::run_test)
+                .unwrap();
+        assert!(!analysis.result.passed());
+        assert_eq!(analysis.decisive_blocks(), vec![3]);
+        assert_eq!(analysis.most_influential(1)[0].block, 3);
+        assert_eq!(
+            analysis.most_influential(NUMBER_OF_BLOCKS + 1).len(),
+            NUMBER_OF_BLOCKS
+        );
+
+        // a bias spread over the whole sequence persists without any of the blocks
+        let biased = force_every_fourth_bit(bit_string, '1');
+        let analysis =
+            diagnostics::jackknife(&biased, NUMBER_OF_BLOCKS, frequency_monobit::run_test).unwrap();
+        assert!(!analysis.result.passed());
+        assert!(analysis.decisive_blocks().is_empty());
+    }
+
+    #[test]
+    fn test_jackknife_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(diagnostics::jackknife("01a1", 2, frequency_monobit::run_test).is_err());
+        assert!(diagnostics::jackknife(BIT_STRING_1, 1, frequency_monobit::run_test).is_err());
+        assert!(diagnostics::jackknife(BIT_STRING_1, 16, frequency_monobit::run_test).is_err());
+
+        // errors of the test are passed through
+        assert!(diagnostics::jackknife("", 2, frequency_monobit::run_test).is_err());
+        assert!(diagnostics::jackknife(BIT_STRING_1, 2, |bits| {
+            frequency_monobit::run_test(&bits[bits.len()..])
+        })
+        .is_err());
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::customtypes;
     use crate::frequency_block;
     use crate::logger;
     use crate::utils;
@@ -90,6 +91,84 @@ mod tests {
         assert!(frequency_block::perform_test(&sha_3_bit_string, 10250).unwrap() >= 0.01);
     }
 
+    #[test]
+    fn test_frequency_block_result() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // example from NIST paper: chi_square = 1 with N = 3 blocks of size M = 3
+        let result = frequency_block::run_test(BIT_STRING_NIST_1, 3).unwrap();
+        assert!((result.statistic - 1.0).abs() < 1e-12);
+        assert_eq!(result.parameters["n"], BIT_STRING_NIST_1.len());
+        assert_eq!(result.parameters["M"], 3);
+        assert_eq!(result.parameters["N"], 3);
+        assert!(result.elapsed_time > std::time::Duration::ZERO);
+    }
+
+    #[test]
+    fn test_frequency_block_advisories() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the NIST example violates the recommendations for n and M, but is still valid
+        let result = frequency_block::run_test(BIT_STRING_NIST_1, 3).unwrap();
+        assert_eq!(result.test, customtypes::Test::FrequencyBlock);
+        assert_eq!(result.p_value, P_VALUE_NIST_1);
+        assert!(!result.is_invalid());
+        assert_eq!(result.advisories.len(), 2);
+        assert_eq!(result.advisories[0].parameter, "n");
+        assert_eq!(result.advisories[1].parameter, "M");
+        assert!(result
+            .advisories
+            .iter()
+            .all(|advisory| advisory.severity == customtypes::Severity::Weak));
+
+        // following the recommendations does not produce any advisory
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let result = frequency_block::run_test(&pi_bit_string, 10200).unwrap();
+        assert!(result.advisories.is_empty());
+    }
+
+    #[test]
+    fn test_frequency_block_cancellation() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // an untouched token does not change the result
+        let token = customtypes::CancellationToken::new();
+        assert_eq!(
+            frequency_block::run_test_cancellable(
+                BIT_STRING_NIST_2,
+                10,
+                &token,
+                &customtypes::Limits::default()
+            )
+            .unwrap()
+            .p_value,
+            P_VALUE_NIST_2
+        );
+
+        // a cancelled token stops the test with a typed error
+        token.clone().cancel();
+        assert!(token.is_cancelled());
+        let error = frequency_block::run_test_cancellable(
+            BIT_STRING_NIST_2,
+            10,
+            &token,
+            &customtypes::Limits::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.root(),
+            &customtypes::NistError::Cancelled {
+                test: customtypes::Test::FrequencyBlock
+            }
+        );
+    }
+
     #[test]
     fn test_frequency_block_error_cases() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use crate::customtypes;
+    use crate::health;
+    use crate::logger;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const INVALID_BIT_STRING: &str = "1100110000010101011011000100110011100000000000100100110101010001000100a111010110100000001101011111001100111001101101100010110010";
+
+    #[test]
+    fn test_repetition_count() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the alarm is raised once when the run reaches the cutoff
+        let mut test = health::RepetitionCountTest::<3>::new();
+        let alarms: Vec<bool> = [1, 1, 0, 0, 0, 0, 1]
+            .iter()
+            .map(|&s| test.feed(s))
+            .collect();
+        assert_eq!(alarms, [false, false, false, false, true, false, false]);
+    }
+
+    #[test]
+    fn test_adaptive_proportion() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the first sample of each window of 4 is the reference
+        let mut test = health::AdaptiveProportionTest::<4, 3>::new();
+        let alarms: Vec<bool> = [1, 0, 1, 1, 0, 1, 0, 1]
+            .iter()
+            .map(|&s| test.feed(s))
+            .collect();
+        assert_eq!(
+            alarms,
+            [false, false, false, true, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_health_monitor() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi = utils::read_random_numbers(&pi_file).unwrap();
+
+        let monitor = health::check_bit_string(&pi).unwrap();
+        assert!(monitor.is_healthy());
+        assert_eq!(monitor.samples(), pi.len() as u64);
+
+        // a stuck source raises both alarms
+        let stuck = pi[..1000].to_owned() + "0".repeat(1000).as_str();
+        let monitor = health::check_bit_string(&stuck).unwrap();
+        let alarms: Vec<health::HealthAlarm> = monitor.alarms().copied().collect();
+        assert_eq!(alarms.len(), 2);
+        assert_eq!(alarms[0].test, health::HealthTest::RepetitionCount);
+        assert_eq!(alarms[0].sample, 0);
+        assert!(alarms[0].position < 1020);
+        assert_eq!(alarms[1].test, health::HealthTest::AdaptiveProportion);
+
+        // the log keeps the last alarms only, but all of them are counted
+        let mut monitor = health::HealthMonitor::<2, 8, 8, 2>::new();
+        let results: Vec<bool> = (0..10).map(|_| monitor.feed(1).is_err()).collect();
+        assert_eq!(results.iter().filter(|&&alarm| alarm).count(), 2);
+        assert_eq!(monitor.alarm_count(), 2);
+        for _ in 0..8 {
+            let _ = monitor.feed(0);
+            let _ = monitor.feed(1);
+        }
+        assert_eq!(monitor.alarm_count(), 2);
+
+        // the third alarm replaces the oldest one in the log
+        assert!(monitor.feed(1).is_err());
+        assert_eq!(monitor.alarm_count(), 3);
+        let tests: Vec<health::HealthTest> = monitor.alarms().map(|alarm| alarm.test).collect();
+        assert_eq!(
+            tests,
+            [
+                health::HealthTest::AdaptiveProportion,
+                health::HealthTest::RepetitionCount
+            ]
+        );
+
+        assert_eq!(
+            health::check_bit_string("").unwrap_err(),
+            health::HealthError::Empty
+        );
+        let error = health::check_bit_string(INVALID_BIT_STRING).unwrap_err();
+        assert_eq!(
+            error,
+            health::HealthError::InvalidBit {
+                position: 70,
+                symbol: 'a'
+            }
+        );
+        assert_eq!(
+            customtypes::NistError::from(error),
+            customtypes::NistError::InvalidBit {
+                position: 70,
+                symbol: 'a'
+            }
+        );
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::customtypes;
     use crate::logger;
     use crate::longest_run;
     use crate::utils;
@@ -78,6 +79,112 @@ mod tests {
         assert!(longest_run::perform_test(&sha_3_bit_string).unwrap() >= 0.01);
     }
 
+    #[test]
+    fn test_longest_run_counts() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the NIST example observes v_0 = 4, v_1 = 9, v_2 = 3 and v_3 = 0 blocks of M = 8 bits
+        let result = longest_run::run_test(BIT_STRING_NIST_1).unwrap();
+        assert_eq!(result.counts, vec![4, 9, 3, 0]);
+        assert_eq!(result.counts.iter().sum::<usize>(), result.parameters["N"]);
+    }
+
+    #[test]
+    fn test_longest_run_words() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // 6272 bits are split into 49 blocks of M = 128 bits, i.e. two words each. The runs of
+        // ones of length 4 to 10 cross the boundary of the words, so they are only found if the
+        // trailing ones of the first word are joined with the leading ones of the second word
+        let bit_string: String = (0..49)
+            .map(|block| {
+                let run_length = 4 + block % 7;
+                let start = 64 - run_length / 2;
+                format!(
+                    "{}{}{}",
+                    "0".repeat(start),
+                    "1".repeat(run_length),
+                    "0".repeat(128 - start - run_length)
+                )
+            })
+            .collect();
+        let result = longest_run::run_test(&bit_string).unwrap();
+        assert_eq!(result.parameters["M"], 128);
+        // v_0 counts runs <= 4 and v_5 runs >= 9
+        assert_eq!(result.counts, vec![7, 7, 7, 7, 7, 14]);
+
+        // the zeros of the complement cross the boundary the same way
+        let complement: String = bit_string
+            .chars()
+            .map(|bit| if bit == '1' { '0' } else { '1' })
+            .collect();
+        let result =
+            longest_run::run_test_with_run_bit(&complement, customtypes::RunBit::Zeros).unwrap();
+        assert_eq!(result.counts, vec![7, 7, 7, 7, 7, 14]);
+    }
+
+    #[test]
+    fn test_longest_run_zeros() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // runs of zeros of a bit string equal runs of ones of its complement
+        let complement: String = BIT_STRING_NIST_1
+            .chars()
+            .map(|bit| if bit == '1' { '0' } else { '1' })
+            .collect();
+        assert_eq!(
+            longest_run::perform_test_with_run_bit(&complement, customtypes::RunBit::Zeros)
+                .unwrap(),
+            P_VALUE_NIST_1
+        );
+
+        let result = longest_run::perform_test_both(BIT_STRING_NIST_1).unwrap();
+        assert_eq!(result.p_value_ones, P_VALUE_NIST_1);
+        assert_eq!(
+            result.p_value_zeros,
+            longest_run::perform_test(&complement).unwrap()
+        );
+
+        // long bursts of zeros are only visible in the runs of zeros
+        let result = longest_run::perform_test_both(BIT_STRING_NON_RANDOM).unwrap();
+        assert!(result.p_value_zeros < 0.01);
+
+        let sha_3_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + SHA_3_FILE;
+        let sha_3_bit_string = utils::read_random_numbers(&sha_3_file).unwrap();
+        let result = longest_run::perform_test_both(&sha_3_bit_string).unwrap();
+        assert!(result.p_value_ones >= 0.01);
+        assert!(result.p_value_zeros >= 0.01);
+    }
+
+    #[test]
+    fn test_longest_run_limits() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // 64 bits are refused by default, but run with 8 blocks once the minimum length is lowered
+        let bit_string = &BIT_STRING_NIST_1[..64];
+        assert!(longest_run::perform_test(bit_string).is_err());
+
+        let limits = customtypes::Limits {
+            min_length_longest_run: 64,
+            ..Default::default()
+        };
+        let result = longest_run::run_test_cancellable(
+            bit_string,
+            customtypes::RunBit::Ones,
+            &customtypes::CancellationToken::new(),
+            &limits,
+        )
+        .unwrap();
+        assert_eq!(result.parameters["M"], 8);
+        assert_eq!(result.parameters["N"], 8);
+        assert!(result.advisories.is_empty());
+    }
+
     #[test]
     fn test_longest_run_error_cases() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
@@ -97,5 +204,11 @@ mod tests {
             Err(_) => success = false,
         };
         assert!(!success);
+
+        match longest_run::perform_test_both(INVALID_BIT_STRING) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
     }
 }
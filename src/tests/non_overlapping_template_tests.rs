@@ -1,8 +1,10 @@
 #[cfg(test)]
 mod tests {
     use crate::constants;
+    use crate::customtypes;
     use crate::logger;
     use crate::non_overlapping_template;
+    use crate::progress;
     use crate::utils;
 
     const LOGLEVEL: &str = "Debug";
@@ -17,6 +19,19 @@ mod tests {
     const SQRT_2_FILE: &str = "/src/tests/testdata/data.sqrt2";
     const SQRT_3_FILE: &str = "/src/tests/testdata/data.sqrt3";
     const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+    const TEMPLATES_LEN_3: [&str; 4] = ["001", "011", "100", "110"];
+    const TEMPLATE_NIST_1: &str = "001";
+    const P_VALUE_NIST_1: f64 = 0.344154;
+    const CUSTOM_TEMPLATES_FILE: &str = "/src/tests/testdata/templates_custom";
+    const INVALID_TEMPLATES_FILE: &str = "/src/tests/testdata/templates_invalid_char";
+    const INVALID_UTF8_TEMPLATES_FILE: &str = "/src/tests/testdata/templates_invalid_utf8";
+    const CUSTOM_TEMPLATES: [&str; 3] = ["001", "10101010", "000000001"];
+    #[cfg(feature = "template-tables")]
+    const TEMPLATE_LEN_GENERATED_MAX: usize = 12;
+    #[cfg(feature = "template-tables")]
+    const TEMPLATE_SUB_PATH: &str = "/templates/template";
+    #[cfg(feature = "template-tables")]
+    const TEMPLATE_DEST_DIR: &str = "/tmp/generated_templates";
 
     #[test]
     fn test_non_overlapping_template() {
@@ -76,6 +91,408 @@ mod tests {
         assert!(non_overlapping_template::perform_test(&sha_3_bit_string, 10, 8).unwrap() >= 0.01);
     }
 
+    #[test]
+    fn test_non_overlapping_template_custom_templates() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // example from NIST paper with template B = 001
+        let p_value = non_overlapping_template::perform_test_with_templates(
+            BIT_STRING_NIST_1,
+            &[TEMPLATE_NIST_1.to_owned()],
+            2,
+        )
+        .unwrap();
+        assert!((p_value - P_VALUE_NIST_1).abs() < 1e-6);
+
+        // single template mode returns the p-value of exactly that template
+        let p_value = non_overlapping_template::perform_test_single_template(
+            BIT_STRING_NIST_1,
+            TEMPLATE_NIST_1,
+            2,
+        )
+        .unwrap();
+        assert!((p_value - P_VALUE_NIST_1).abs() < 1e-6);
+
+        // read templates from file
+        let templates_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + CUSTOM_TEMPLATES_FILE;
+        let templates = non_overlapping_template::read_templates(&templates_file).unwrap();
+        assert_eq!(templates, CUSTOM_TEMPLATES);
+
+        let sha_3_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + SHA_3_FILE;
+        let sha_3_bit_string = utils::read_random_numbers(&sha_3_file).unwrap();
+        assert!(
+            non_overlapping_template::perform_test_with_templates(&sha_3_bit_string, &templates, 8)
+                .unwrap()
+                >= 0.01
+        );
+        assert!(
+            non_overlapping_template::perform_test_single_template(
+                &sha_3_bit_string,
+                constants::REFERENCE_TEMPLATE,
+                8
+            )
+            .unwrap()
+                >= 0.01
+        );
+
+        let mut success: bool;
+
+        // pass no templates
+        match non_overlapping_template::perform_test_with_templates(BIT_STRING_NIST_1, &[], 2) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass invalid templates
+        match non_overlapping_template::perform_test_with_templates(
+            BIT_STRING_NIST_1,
+            &["0".to_owned()],
+            2,
+        ) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        match non_overlapping_template::perform_test_with_templates(
+            BIT_STRING_NIST_1,
+            &["01a".to_owned()],
+            2,
+        ) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass template longer than block size
+        match non_overlapping_template::perform_test_with_templates(
+            BIT_STRING_NIST_1,
+            &["00000000001".to_owned()],
+            2,
+        ) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // read invalid or non-existing template files
+        let invalid_templates_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + INVALID_TEMPLATES_FILE;
+        match non_overlapping_template::read_templates(&invalid_templates_file) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // a line which is not UTF-8 fails the file instead of ending it early
+        let invalid_utf8_templates_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + INVALID_UTF8_TEMPLATES_FILE;
+        let error =
+            non_overlapping_template::read_templates(&invalid_utf8_templates_file).unwrap_err();
+        assert!(format!("{:#}", error).contains("Failed to read line 3"));
+
+        match non_overlapping_template::read_templates("/non-existing-dir/templates") {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+    }
+
+    #[test]
+    fn test_non_overlapping_template_detailed() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // example from NIST paper: template B = 001 occurs twice in the first and once in the
+        // second block
+        let result = non_overlapping_template::perform_test_detailed(
+            BIT_STRING_NIST_1,
+            &[TEMPLATE_NIST_1.to_owned()],
+            2,
+        )
+        .unwrap();
+        assert_eq!(result.counts[TEMPLATE_NIST_1], vec![2, 1]);
+        assert!((result.p_values[TEMPLATE_NIST_1] - P_VALUE_NIST_1).abs() < 1e-6);
+        assert_eq!(
+            result.to_string(),
+            "001    2    1   2.133333   0.344154 PASS\n"
+        );
+
+        // the mean of the per-template p-values is the p-value of the exhaustive test
+        let templates = non_overlapping_template::get_templates(4).unwrap();
+        let result = non_overlapping_template::perform_test_detailed(
+            BIT_STRING_RANDOM_PATTERN,
+            &templates,
+            3,
+        )
+        .unwrap();
+        assert_eq!(result.p_values.len(), templates.len());
+        assert!(result.counts.values().all(|counts| counts.len() == 3));
+        assert_eq!(
+            result.p_value_mean(),
+            non_overlapping_template::perform_test(BIT_STRING_RANDOM_PATTERN, 4, 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_non_overlapping_template_template_set() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the tables of the reference implementation contain the generated templates
+        let templates = customtypes::TemplateSet::Aperiodic(constants::SUITE_TEMPLATE_LEN)
+            .templates()
+            .unwrap();
+        assert_eq!(templates.len(), 148);
+        assert_eq!(templates[0], constants::REFERENCE_TEMPLATE);
+        assert_eq!(
+            templates,
+            non_overlapping_template::generate_aperiodic_templates(constants::SUITE_TEMPLATE_LEN)
+                .unwrap()
+        );
+        assert_eq!(
+            customtypes::TemplateSet::Aperiodic(constants::TEMPLATE_TABLE_LEN.1)
+                .templates()
+                .unwrap()
+                .len(),
+            17622
+        );
+
+        // all aperiodic templates give the result of run_test()
+        let result = non_overlapping_template::run_test_with_template_set(
+            BIT_STRING_RANDOM_PATTERN,
+            &customtypes::TemplateSet::Aperiodic(4),
+            3,
+            &progress::ignore,
+            &customtypes::CancellationToken::new(),
+            &customtypes::Limits::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            result.p_value,
+            non_overlapping_template::perform_test(BIT_STRING_RANDOM_PATTERN, 4, 3).unwrap()
+        );
+
+        // custom templates restrict the run, the longest one is reported as "m"
+        let result = non_overlapping_template::run_test_with_template_set(
+            BIT_STRING_NIST_1,
+            &customtypes::TemplateSet::Custom(vec![TEMPLATE_NIST_1.to_owned()]),
+            2,
+            &progress::ignore,
+            &customtypes::CancellationToken::new(),
+            &customtypes::Limits::default(),
+        )
+        .unwrap();
+        assert!((result.p_value - P_VALUE_NIST_1).abs() < 1e-6);
+        assert_eq!(result.parameters["m"], 3);
+
+        let templates: Vec<String> = CUSTOM_TEMPLATES
+            .iter()
+            .map(|template| template.to_string())
+            .collect();
+        let result = non_overlapping_template::run_test_with_template_set(
+            BIT_STRING_RANDOM_PATTERN,
+            &customtypes::TemplateSet::Custom(templates.clone()),
+            1,
+            &progress::ignore,
+            &customtypes::CancellationToken::new(),
+            &customtypes::Limits::default(),
+        )
+        .unwrap();
+        assert_eq!(result.parameters["m"], 9);
+        assert_eq!(
+            result.p_value,
+            non_overlapping_template::perform_test_with_templates(
+                BIT_STRING_RANDOM_PATTERN,
+                &templates,
+                1
+            )
+            .unwrap()
+        );
+
+        // custom templates are checked like templates read from a file
+        for templates in [vec![], vec!["1a1".to_owned()], vec!["1".to_owned()]] {
+            let template_set = customtypes::TemplateSet::Custom(templates);
+            assert!(template_set.templates().is_err());
+            assert!(non_overlapping_template::run_test_with_template_set(
+                BIT_STRING_RANDOM_PATTERN,
+                &template_set,
+                1,
+                &progress::ignore,
+                &customtypes::CancellationToken::new(),
+                &customtypes::Limits::default(),
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_non_overlapping_template_advisories() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // short templates and bit strings are statistically weak, but not invalid
+        let result = non_overlapping_template::run_test(BIT_STRING_RANDOM_PATTERN, 4, 3).unwrap();
+        assert_eq!(result.test, customtypes::Test::NonOverlappingTemplate);
+        assert!(!result.is_invalid());
+        assert!(result
+            .advisories
+            .iter()
+            .any(|advisory| advisory.parameter == "n"
+                && advisory.severity == customtypes::Severity::Weak));
+        assert!(result
+            .advisories
+            .iter()
+            .any(|advisory| advisory.parameter == "m"
+                && advisory.severity == customtypes::Severity::Weak));
+        assert_eq!(
+            result.p_value,
+            non_overlapping_template::perform_test(BIT_STRING_RANDOM_PATTERN, 4, 3).unwrap()
+        );
+
+        // the advisories are reported by the detailed test as well
+        let templates = non_overlapping_template::get_templates(4).unwrap();
+        let detailed = non_overlapping_template::perform_test_detailed(
+            BIT_STRING_RANDOM_PATTERN,
+            &templates,
+            3,
+        )
+        .unwrap();
+        assert_eq!(detailed.advisories, result.advisories);
+    }
+
+    #[test]
+    fn test_non_overlapping_template_progress() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let reports = std::cell::RefCell::new(Vec::<customtypes::Progress>::new());
+        let result = non_overlapping_template::run_test_with_progress(
+            BIT_STRING_RANDOM_PATTERN,
+            4,
+            3,
+            &|progress| reports.borrow_mut().push(progress),
+        )
+        .unwrap();
+        assert_eq!(
+            result.p_value,
+            non_overlapping_template::perform_test(BIT_STRING_RANDOM_PATTERN, 4, 3).unwrap()
+        );
+
+        // one report per aperiodic template of length 4, ending with the whole work done
+        let reports = reports.into_inner();
+        assert_eq!(
+            reports.len(),
+            non_overlapping_template::get_templates(4).unwrap().len()
+        );
+        assert!(reports
+            .iter()
+            .all(|progress| progress.test == customtypes::Test::NonOverlappingTemplate));
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].fraction < pair[1].fraction));
+        assert_eq!(reports.last().unwrap().fraction, 1.0);
+        assert_eq!(reports.last().unwrap().eta, Some(std::time::Duration::ZERO));
+    }
+
+    #[test]
+    fn test_non_overlapping_template_cancellation() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let token = customtypes::CancellationToken::new();
+        token.cancel();
+
+        // cancelling stops the test before the first template is done
+        let reports = std::cell::RefCell::new(Vec::<customtypes::Progress>::new());
+        let error = non_overlapping_template::run_test_cancellable(
+            BIT_STRING_RANDOM_PATTERN,
+            4,
+            3,
+            &|progress| reports.borrow_mut().push(progress),
+            &token,
+            &customtypes::Limits::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.root(),
+            &customtypes::NistError::Cancelled {
+                test: customtypes::Test::NonOverlappingTemplate
+            }
+        );
+        assert!(reports.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_generate_aperiodic_templates() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert_eq!(
+            non_overlapping_template::generate_aperiodic_templates(3).unwrap(),
+            TEMPLATES_LEN_3
+        );
+
+        // generated templates have to match the pre-computed ones
+        #[cfg(feature = "template-tables")]
+        for template_len in constants::TEMPLATE_LEN.0..=TEMPLATE_LEN_GENERATED_MAX {
+            let template_file = format!(
+                "{}{}{}.tar.gz",
+                std::env::current_dir().unwrap().to_str().unwrap(),
+                TEMPLATE_SUB_PATH,
+                template_len
+            );
+            let template_dir = format!("{}{}", TEMPLATE_DEST_DIR, template_len);
+            std::fs::create_dir_all(&template_dir).unwrap();
+            utils::untar_archive(&template_file, &template_dir).unwrap();
+
+            let contents =
+                std::fs::read_to_string(format!("{}/template{}", template_dir, template_len))
+                    .unwrap();
+            let templates: Vec<String> = contents.lines().map(|line| line.to_owned()).collect();
+
+            assert_eq!(
+                non_overlapping_template::generate_aperiodic_templates(template_len).unwrap(),
+                templates
+            );
+            let _ = std::fs::remove_dir_all(&template_dir);
+        }
+
+        // templates longer than the pre-computed ones
+        assert!(non_overlapping_template::generate_aperiodic_templates(
+            constants::TEMPLATE_LEN.1 + 1
+        )
+        .unwrap()
+        .iter()
+        .all(|template| template.len() == constants::TEMPLATE_LEN.1 + 1));
+
+        // templates beyond the practical maximum are refused instead of enumerating 2^m of them
+        let error = non_overlapping_template::generate_aperiodic_templates(
+            constants::MAX_GENERATED_TEMPLATE_LEN + 1,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error.root(),
+            customtypes::NistError::InvalidParameter { .. }
+        ));
+        assert!(
+            non_overlapping_template::generate_aperiodic_templates(usize::BITS as usize).is_err()
+        );
+    }
+
     #[test]
     fn test_non_overlapping_template_error_cases() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
@@ -129,4 +546,51 @@ mod tests {
         };
         assert!(!success);
     }
+
+    #[test]
+    fn test_non_overlapping_template_tune_params() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the suite parameters fit the recommended length
+        let limits = customtypes::Limits::default();
+        assert_eq!(
+            non_overlapping_template::tune_params(1000000, &limits).unwrap(),
+            (
+                constants::SUITE_TEMPLATE_LEN,
+                constants::SUITE_NUMBER_OF_TEMPLATE_BLOCKS
+            )
+        );
+
+        // short bit strings get fewer blocks first and shorter templates then
+        assert_eq!(
+            non_overlapping_template::tune_params(50, &limits).unwrap(),
+            (9, 5)
+        );
+        assert_eq!(
+            non_overlapping_template::tune_params(5, &limits).unwrap(),
+            (5, 1)
+        );
+        let (template_len, number_of_blocks) =
+            non_overlapping_template::tune_params(BIT_STRING_SAME_PATTERN.len(), &limits).unwrap();
+        assert!(non_overlapping_template::perform_test(
+            BIT_STRING_SAME_PATTERN,
+            template_len,
+            number_of_blocks
+        )
+        .is_ok());
+
+        // the number of blocks respects overridden limits
+        let limits = customtypes::Limits {
+            max_number_of_blocks: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            non_overlapping_template::tune_params(1000000, &limits).unwrap(),
+            (9, 4)
+        );
+
+        // nothing fits
+        assert!(non_overlapping_template::tune_params(0, &limits).is_err());
+        assert!(non_overlapping_template::tune_params(1, &limits).is_err());
+    }
 }
@@ -0,0 +1,320 @@
+#[cfg(test)]
+mod tests {
+    use crate::bit_sequence::BitSequence;
+    use crate::bit_source::{BitSequenceSource, BitStringSource};
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::overlapping_template;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_SAME_PATTERN: &str = "1101101101101101101101101101101101101101101101101101101101101101101101101101101101101101101101101101";
+    const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+    const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+
+    #[test]
+    fn test_overlapping_template() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // eta = 1 for m = 9 and M = 1032, the probabilities are close to the more exact ones
+        let probabilities = overlapping_template::compute_probabilities(
+            constants::SUITE_TEMPLATE_LEN,
+            constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE,
+        );
+        assert!((probabilities[0] - (-1.0_f64).exp()).abs() < 1e-12);
+        assert!((probabilities[1] - 0.5 * (-1.0_f64).exp()).abs() < 1e-12);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        for (probability, exact) in probabilities
+            .iter()
+            .zip(constants::PI_VALUES_OVERLAPPING_TEMPLATE)
+        {
+            assert!((probability - exact).abs() < 0.005);
+        }
+
+        // the example of section 2.8.8 of NIST SP 800-22 on the first 10^6 bits of e
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let result = overlapping_template::run_test_with_block_size(
+            &e_bit_string[..1000000],
+            constants::SUITE_TEMPLATE_LEN,
+            constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE,
+        )
+        .unwrap();
+        assert_eq!(result.counts, vec![329, 164, 150, 111, 78, 136]);
+        assert_eq!(result.parameters["N"], 968);
+        assert!((result.statistic - 8.965859).abs() < 1e-6);
+        assert!((result.p_value - 0.110434).abs() < 1e-6);
+        assert!(result.advisories.is_empty());
+
+        // a packed sequence gives the same result
+        let sequence = BitSequence::from_bit_string(&e_bit_string[..1000000]).unwrap();
+        assert_eq!(
+            overlapping_template::perform_test_with_block_size(
+                &sequence,
+                constants::SUITE_TEMPLATE_LEN,
+                constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE
+            )
+            .unwrap(),
+            result.p_value
+        );
+
+        // N = 968 blocks of n / N = 1033 bits differ from the published blocks of 1032 bits
+        let result = overlapping_template::run_test(
+            &e_bit_string[..1000000],
+            constants::SUITE_TEMPLATE_LEN,
+            968,
+        )
+        .unwrap();
+        assert_eq!(result.parameters["M"], 1033);
+        assert_eq!(result.parameters["N"], 968);
+
+        // every block of a repeated pattern contains the template equally often
+        let result = overlapping_template::run_test(BIT_STRING_SAME_PATTERN, 2, 10).unwrap();
+        assert_eq!(result.counts, vec![0, 0, 0, 10, 0, 0]);
+        assert!(result.p_value <= 0.01);
+        assert!(result
+            .advisories
+            .iter()
+            .any(|advisory| advisory.parameter == "N"));
+
+        // the template has to fit into a block and a block into the bit string
+        assert!(overlapping_template::perform_test(BIT_STRING_SAME_PATTERN, 9, 20).is_err());
+        assert!(overlapping_template::perform_test(BIT_STRING_SAME_PATTERN, 2, 0).is_err());
+        assert!(overlapping_template::perform_test(BIT_STRING_SAME_PATTERN, 2, 101).is_err());
+        assert!(overlapping_template::perform_test(BIT_STRING_SAME_PATTERN, 1, 10).is_err());
+        assert!(overlapping_template::perform_test(INVALID_BIT_STRING, 2, 5).is_err());
+        assert!(
+            overlapping_template::perform_test_with_block_size(BIT_STRING_SAME_PATTERN, 9, 8)
+                .is_err()
+        );
+        assert!(overlapping_template::perform_test_with_block_size(
+            BIT_STRING_SAME_PATTERN,
+            2,
+            101
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_overlapping_template_single_template() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(
+            overlapping_template::perform_test_single_template(BIT_STRING_SAME_PATTERN, "011", 2)
+                .unwrap()
+                <= 0.01
+        );
+
+        // test pi, e and sha3 in their respective binary representations
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        assert!(
+            overlapping_template::perform_test_single_template(
+                &pi_bit_string,
+                constants::REFERENCE_TEMPLATE,
+                8
+            )
+            .unwrap()
+                >= 0.01
+        );
+
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        assert!(
+            overlapping_template::perform_test_single_template(
+                &e_bit_string,
+                constants::REFERENCE_TEMPLATE,
+                8
+            )
+            .unwrap()
+                >= 0.01
+        );
+
+        let sha_3_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + SHA_3_FILE;
+        let sha_3_bit_string = utils::read_random_numbers(&sha_3_file).unwrap();
+        assert!(
+            overlapping_template::perform_test_single_template(
+                &sha_3_bit_string,
+                constants::REFERENCE_TEMPLATE,
+                8
+            )
+            .unwrap()
+                >= 0.01
+        );
+    }
+
+    #[test]
+    fn test_overlapping_template_detailed() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert_eq!(overlapping_template::get_templates(3).unwrap().len(), 8);
+        assert_eq!(overlapping_template::get_templates(3).unwrap()[3], "011");
+
+        // test a subset of templates of different lengths
+        let templates = vec!["011".to_owned(), constants::REFERENCE_TEMPLATE.to_owned()];
+        let result =
+            overlapping_template::perform_test_detailed(BIT_STRING_SAME_PATTERN, &templates, 2)
+                .unwrap();
+        assert_eq!(result.p_values.len(), 2);
+        assert_eq!(result.chi_squares.len(), 2);
+        assert!(result.p_values["011"] <= 0.01);
+        assert_eq!(result.counts["011"].len(), 2);
+        assert_eq!(result.counts[constants::REFERENCE_TEMPLATE], vec![0, 0]);
+
+        // the mean over all templates has to match the result of perform_test_exhaustive()
+        let templates = overlapping_template::get_templates(3).unwrap();
+        let result =
+            overlapping_template::perform_test_detailed(BIT_STRING_SAME_PATTERN, &templates, 2)
+                .unwrap();
+        assert_eq!(result.p_values.len(), 8);
+        assert_eq!(
+            result.p_value_mean(),
+            overlapping_template::perform_test_exhaustive(BIT_STRING_SAME_PATTERN, 3, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_overlapping_template_streaming() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the streaming variant agrees with run_test_with_block_size(). Pi is read in several
+        // chunks, so the runs of ones have to be carried across them
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        assert!(pi_bit_string.len() > constants::STREAM_CHUNK_SIZE);
+        let limits = customtypes::Limits::default();
+        let expected = overlapping_template::run_test_with_block_size(
+            &pi_bit_string,
+            constants::SUITE_TEMPLATE_LEN,
+            constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE,
+        )
+        .unwrap();
+        let result = overlapping_template::run_test_streaming(
+            &mut BitStringSource::new(&pi_bit_string),
+            constants::SUITE_TEMPLATE_LEN,
+            constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE,
+            &limits,
+        )
+        .unwrap();
+        assert_eq!(result.counts, expected.counts);
+        assert_eq!(result.statistic, expected.statistic);
+        assert_eq!(result.p_value, expected.p_value);
+        assert_eq!(result.parameters, expected.parameters);
+        assert_eq!(result.advisories, expected.advisories);
+
+        // occurences overlapping each other are all counted, but none across blocks
+        let bit_string = format!("{}{}", "1".repeat(30), "0".repeat(30));
+        let sequence = BitSequence::from_bit_string(&bit_string).unwrap();
+        let expected = overlapping_template::run_test(&bit_string, 9, 3).unwrap();
+        let result = overlapping_template::run_test_streaming(
+            &mut BitSequenceSource::new(&sequence),
+            9,
+            20,
+            &limits,
+        )
+        .unwrap();
+        assert_eq!(result.counts, vec![1, 0, 1, 0, 0, 1]);
+        assert_eq!(result.counts, expected.counts);
+        assert_eq!(result.p_value, expected.p_value);
+
+        // the template has to fit into a block and the source must not contain invalid bits
+        assert!(overlapping_template::run_test_streaming(
+            &mut BitStringSource::new(BIT_STRING_SAME_PATTERN),
+            constants::SUITE_TEMPLATE_LEN,
+            BIT_STRING_SAME_PATTERN.len() + 1,
+            &limits,
+        )
+        .is_err());
+        assert!(overlapping_template::run_test_streaming(
+            &mut BitStringSource::new(INVALID_BIT_STRING),
+            constants::SUITE_TEMPLATE_LEN,
+            10,
+            &limits,
+        )
+        .is_err());
+        assert!(overlapping_template::run_test_streaming(
+            &mut BitStringSource::new(""),
+            constants::SUITE_TEMPLATE_LEN,
+            constants::BLOCK_SIZE_OVERLAPPING_TEMPLATE,
+            &limits,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_overlapping_template_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let mut success: bool;
+
+        // pass empty string
+        match overlapping_template::perform_test_single_template("", "111", 2) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass invalid bit string
+        match overlapping_template::perform_test_single_template(INVALID_BIT_STRING, "111", 2) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass invalid templates
+        match overlapping_template::perform_test_single_template(BIT_STRING_SAME_PATTERN, "1a1", 2)
+        {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        match overlapping_template::perform_test_single_template(BIT_STRING_SAME_PATTERN, "1", 2) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass no templates at all
+        match overlapping_template::perform_test_detailed(BIT_STRING_SAME_PATTERN, &[], 2) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+
+        // pass invalid number of blocks size
+        match overlapping_template::perform_test_exhaustive(BIT_STRING_SAME_PATTERN, 3, 120) {
+            Ok(_) => success = true,
+            Err(_) => success = false,
+        };
+        assert!(!success);
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use crate::customtypes;
+    use crate::logger;
+    use crate::pipeline::Pipeline;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
+
+    #[test]
+    fn test_pipeline_transforms() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert_eq!(Pipeline::new().apply("0110").unwrap(), "0110");
+        assert_eq!(
+            Pipeline::new().deinterleave(2, 1).apply("011011").unwrap(),
+            "101"
+        );
+        assert_eq!(Pipeline::new().decimate(3).apply("0110111").unwrap(), "001");
+        assert_eq!(
+            Pipeline::new().von_neumann().apply("01100011101").unwrap(),
+            "011"
+        );
+        assert_eq!(Pipeline::new().invert().apply("0110").unwrap(), "1001");
+
+        // the transforms are applied in the order they were added
+        let pipeline = Pipeline::new().deinterleave(2, 0).von_neumann().invert();
+        assert_eq!(pipeline.apply("00101101").unwrap(), "10");
+        assert_eq!(
+            pipeline.transforms(),
+            &[
+                customtypes::Transform::Deinterleave {
+                    channels: 2,
+                    channel: 0
+                },
+                customtypes::Transform::VonNeumann,
+                customtypes::Transform::Invert,
+            ]
+        );
+        assert_eq!(
+            pipeline.to_string(),
+            "deinterleave(0/2) -> von-neumann -> invert"
+        );
+        assert_eq!(
+            Pipeline::new()
+                .invert()
+                .von_neumann()
+                .apply("00101101")
+                .unwrap(),
+            Pipeline::new()
+                .von_neumann()
+                .invert()
+                .apply("00101101")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pipeline_run_all() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // test pi in its binary representation
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+
+        let pipeline = Pipeline::new().decimate(2).invert();
+        let config = customtypes::SuiteConfig::default();
+        let report = pipeline.run_all(&pi_bit_string[..200000], &config).unwrap();
+        assert!(!report.results.is_empty());
+        assert_eq!(report.pipeline, pipeline.transforms());
+        assert!(report
+            .to_string()
+            .starts_with("Pipeline: decimate(2) -> invert\n"));
+
+        // reports of differently preprocessed sequences cannot be merged
+        let mut other = customtypes::SuiteReport::default();
+        assert!(other.merge(report).is_err());
+    }
+
+    #[test]
+    fn test_pipeline_errors() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(Pipeline::new().apply(INVALID_BIT_STRING).is_err());
+        assert!(Pipeline::new().deinterleave(1, 0).apply("0110").is_err());
+        assert!(Pipeline::new().deinterleave(2, 2).apply("0110").is_err());
+        assert!(Pipeline::new().decimate(1).apply("0110").is_err());
+
+        // transforms leaving no bits
+        assert!(Pipeline::new().von_neumann().apply("0011").is_err());
+        assert!(Pipeline::new().deinterleave(4, 3).apply("01").is_err());
+    }
+}
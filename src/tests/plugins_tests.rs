@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::customtypes;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::plugins::{self, RandomnessTest};
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
+
+    /// Fraction of ones as p-value, failing for bit strings of odd length
+    unsafe extern "C" fn fraction_of_ones(
+        bits: *const u8,
+        length: usize,
+        p_value: *mut f64,
+        statistic: *mut f64,
+    ) -> i32 {
+        if length % 2 == 1 {
+            return 1;
+        }
+        let bits = std::slice::from_raw_parts(bits, length);
+        let ones = bits.iter().filter(|&&bit| bit == b'1').count();
+        *p_value = ones as f64 / length as f64;
+        *statistic = ones as f64;
+
+        0
+    }
+
+    static TESTS: [plugins::PluginTestDescriptor; 1] = [plugins::PluginTestDescriptor {
+        name: c"Fraction of Ones Test".as_ptr(),
+        run: fraction_of_ones,
+    }];
+
+    static DUPLICATE_TESTS: [plugins::PluginTestDescriptor; 2] = [
+        plugins::PluginTestDescriptor {
+            name: c"Fraction of Ones Test".as_ptr(),
+            run: fraction_of_ones,
+        },
+        plugins::PluginTestDescriptor {
+            name: c"Fraction of Ones Test".as_ptr(),
+            run: fraction_of_ones,
+        },
+    ];
+
+    static BUILT_IN_NAME_TESTS: [plugins::PluginTestDescriptor; 1] =
+        [plugins::PluginTestDescriptor {
+            name: c"Runs Test".as_ptr(),
+            run: fraction_of_ones,
+        }];
+
+    extern "C" fn register_tests() -> plugins::PluginRegistration {
+        plugins::PluginRegistration {
+            abi_version: constants::PLUGIN_ABI_VERSION,
+            tests: TESTS.as_ptr(),
+            number_of_tests: TESTS.len(),
+        }
+    }
+
+    extern "C" fn register_future_abi() -> plugins::PluginRegistration {
+        plugins::PluginRegistration {
+            abi_version: constants::PLUGIN_ABI_VERSION + 1,
+            tests: TESTS.as_ptr(),
+            number_of_tests: TESTS.len(),
+        }
+    }
+
+    extern "C" fn register_duplicates() -> plugins::PluginRegistration {
+        plugins::PluginRegistration {
+            abi_version: constants::PLUGIN_ABI_VERSION,
+            tests: DUPLICATE_TESTS.as_ptr(),
+            number_of_tests: DUPLICATE_TESTS.len(),
+        }
+    }
+
+    extern "C" fn register_built_in_name() -> plugins::PluginRegistration {
+        plugins::PluginRegistration {
+            abi_version: constants::PLUGIN_ABI_VERSION,
+            tests: BUILT_IN_NAME_TESTS.as_ptr(),
+            number_of_tests: BUILT_IN_NAME_TESTS.len(),
+        }
+    }
+
+    #[test]
+    fn test_plugins() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let tests = unsafe { plugins::register(register_tests) }.unwrap();
+        assert_eq!(tests.len(), 1);
+        assert_eq!(
+            tests[0].test(),
+            customtypes::Test::Plugin("Fraction of Ones Test")
+        );
+        assert!(!tests[0].test().is_nist());
+
+        // the results of plugin tests appear in the same report as the built-in tests
+        let mut report = customtypes::SuiteReport {
+            results: vec![frequency_monobit::run_test(BIT_STRING).unwrap()],
+            ..Default::default()
+        };
+        plugins::run_tests(&mut report, BIT_STRING, &[&tests[0]]).unwrap();
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(report.results[1].p_value, 0.42);
+        assert_eq!(report.results[1].statistic, 42.0);
+        assert_eq!(report.results[1].parameters["n"], 100);
+        assert!(report
+            .to_string()
+            .contains("Fraction of Ones Test                          0.420000"));
+
+        // failing tests and invalid input are reported
+        assert!(tests[0].run(&BIT_STRING[1..]).is_err());
+        assert!(tests[0].run("").is_err());
+        assert!(tests[0].run("0101a").is_err());
+    }
+
+    #[test]
+    fn test_plugins_invalid_registration() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(unsafe { plugins::register(register_future_abi) }.is_err());
+        assert!(unsafe { plugins::register(register_duplicates) }.is_err());
+        assert!(unsafe { plugins::register(register_built_in_name) }.is_err());
+        assert!(unsafe { plugins::load("/non-existing-dir/libplugin.so") }.is_err());
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use crate::cache;
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::policy::{self, VerdictPolicy};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
+
+    fn create_results(p_values: &[f64]) -> Vec<customtypes::TestResult> {
+        p_values
+            .iter()
+            .map(|&p_value| customtypes::TestResult {
+                test: customtypes::Test::FrequencyMonobit,
+                p_value,
+                advisories: Vec::new(),
+                statistic: 0.0,
+                parameters: BTreeMap::new(),
+                elapsed_time: std::time::Duration::ZERO,
+                alpha: constants::P_VALUE_THRESHOLD,
+                counts: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Policy passing any report, as users may implement it
+    #[derive(Debug)]
+    struct AlwaysPass;
+
+    impl policy::VerdictPolicy for AlwaysPass {
+        fn decide(&self, _results: &[customtypes::TestResult]) -> customtypes::Verdict {
+            customtypes::Verdict::Pass
+        }
+    }
+
+    #[test]
+    fn test_verdict_policies() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let one_failure = create_results(&[0.5, 0.005, 0.3, 0.8]);
+        assert_eq!(
+            policy::AnyFail.decide(&one_failure),
+            customtypes::Verdict::Fail
+        );
+        assert_eq!(
+            policy::KOfN { k: 2 }.decide(&one_failure),
+            customtypes::Verdict::Pass
+        );
+        assert_eq!(
+            policy::KOfN { k: 2 }.decide(&create_results(&[0.005, 0.003, 0.3])),
+            customtypes::Verdict::Fail
+        );
+
+        // k = 0 acts like k = 1 instead of failing every report
+        assert_eq!(
+            policy::KOfN { k: 0 }.decide(&create_results(&[0.5, 0.3])),
+            customtypes::Verdict::Pass
+        );
+        assert_eq!(
+            policy::KOfN { k: 0 }.decide(&one_failure),
+            customtypes::Verdict::Fail
+        );
+
+        // the q-value of a single low p-value among several tests exceeds alpha
+        let fdr = policy::FdrAdjusted {
+            method: customtypes::FdrMethod::BenjaminiHochberg,
+            alpha: 0.01,
+        };
+        assert_eq!(fdr.decide(&one_failure), customtypes::Verdict::Pass);
+        assert_eq!(
+            fdr.decide(&create_results(&[0.5, 0.0001, 0.3, 0.8])),
+            customtypes::Verdict::Fail
+        );
+        assert_eq!(fdr.decide(&[]), customtypes::Verdict::Pass);
+
+        // invalid results make the verdict invalid unless the report fails
+        let mut invalid = create_results(&[0.5, 0.3]);
+        invalid[0]
+            .advisories
+            .push(customtypes::Advisory::invalid("n", "Too short".to_owned()));
+        assert_eq!(
+            policy::AnyFail.decide(&invalid),
+            customtypes::Verdict::Invalid
+        );
+        assert_eq!(fdr.decide(&invalid), customtypes::Verdict::Invalid);
+        assert_eq!(customtypes::Verdict::Invalid.to_string(), "INVALID");
+
+        // the verdict of a report is qualified by the skipped tests
+        let report = customtypes::SuiteReport {
+            results: one_failure,
+            skipped: vec![customtypes::SkippedTest {
+                test: customtypes::Test::OverlappingTemplate,
+                reason: customtypes::SkipReason::TooShort {
+                    required_length: 1000000,
+                },
+            }],
+            ..Default::default()
+        };
+        assert_eq!(report.verdict(), "FAIL (qualified, 1 skipped)");
+        assert_eq!(
+            report.verdict_with(&policy::KOfN { k: 2 }),
+            "PASS (qualified, 1 skipped)"
+        );
+        assert_eq!(
+            report.verdict_with(&AlwaysPass),
+            "PASS (qualified, 1 skipped)"
+        );
+    }
+
+    #[test]
+    fn test_verdict_policy_config() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // configurations differ by the parameters of their policies
+        let default = customtypes::SuiteConfig::default();
+        let any_fail = customtypes::SuiteConfig {
+            verdict_policy: Arc::new(policy::AnyFail),
+            ..Default::default()
+        };
+        let two_of_n = customtypes::SuiteConfig {
+            verdict_policy: Arc::new(policy::KOfN { k: 2 }),
+            ..Default::default()
+        };
+        let three_of_n = customtypes::SuiteConfig {
+            verdict_policy: Arc::new(policy::KOfN { k: 3 }),
+            ..Default::default()
+        };
+        assert_eq!(default, any_fail);
+        assert_ne!(default, two_of_n);
+        assert_ne!(two_of_n, three_of_n);
+        assert_eq!(two_of_n, two_of_n.clone());
+        assert_eq!(
+            cache::input_hash(BIT_STRING, &default),
+            cache::input_hash(BIT_STRING, &any_fail)
+        );
+        assert_ne!(
+            cache::input_hash(BIT_STRING, &default),
+            cache::input_hash(BIT_STRING, &two_of_n)
+        );
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use crate::customtypes;
+    use crate::logger;
+    use crate::random_excursions;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+
+    fn read_e() -> String {
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        utils::read_random_numbers(&e_file).unwrap()
+    }
+
+    #[test]
+    fn test_random_excursions() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // test e in its binary representation, NIST reports J = 1490 and these p-values
+        let e_bit_string = read_e();
+        let result = random_excursions::run_test(&e_bit_string).unwrap();
+        assert_eq!(result.test, customtypes::Test::RandomExcursions);
+        assert_eq!(result.number_of_cycles, 1490);
+        assert_eq!(result.states.len(), 8);
+        assert!(result.advisories.is_empty());
+
+        let expected = [
+            (-4, 0.573306),
+            (-3, 0.197996),
+            (-2, 0.164011),
+            (-1, 0.007779),
+            (1, 0.786868),
+            (2, 0.440912),
+            (3, 0.797854),
+            (4, 0.778186),
+        ];
+        for (state, p_value) in expected {
+            assert!((result.states[&state].p_value - p_value).abs() < 1e-6);
+        }
+        assert_eq!(
+            random_excursions::perform_test(&e_bit_string).unwrap(),
+            result.p_values()
+        );
+
+        // one result per state as listed by the NIST reference implementation
+        let results = result.to_test_results(e_bit_string.len());
+        assert_eq!(results.len(), 8);
+        assert_eq!(results[0].parameters["-x"], 4);
+        assert_eq!(results[0].parameters["J"], 1490);
+        assert_eq!(results[7].parameters["x"], 4);
+        assert_eq!(results[3].p_value, result.states[&-1].p_value);
+        assert!(!results[3].passed());
+    }
+
+    #[test]
+    fn test_random_excursions_errors() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // empty or invalid bit strings
+        assert!(random_excursions::run_test("").is_err());
+        assert!(random_excursions::run_test(INVALID_BIT_STRING).is_err());
+
+        // too few cycles make the test not applicable instead of failed
+        let e_bit_string = read_e();
+        let err = random_excursions::run_test(&e_bit_string[..10000]).unwrap_err();
+        match err.root() {
+            customtypes::NistError::InsufficientCycles {
+                test,
+                number_of_cycles,
+                required_cycles,
+            } => {
+                assert_eq!(*test, customtypes::Test::RandomExcursions);
+                assert_eq!(*required_cycles, 500);
+                assert!(*number_of_cycles < 500);
+            }
+            _ => panic!("Expected too few cycles, got {:?}", err),
+        }
+    }
+}
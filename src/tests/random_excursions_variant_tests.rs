@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::random_excursions_variant;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+
+    fn read_e() -> String {
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        utils::read_random_numbers(&e_file).unwrap()
+    }
+
+    #[test]
+    fn test_random_excursions_variant() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // test e in its binary representation, NIST reports J = 1490
+        let e_bit_string = read_e();
+        let result = random_excursions_variant::run_test(&e_bit_string).unwrap();
+        assert_eq!(result.number_of_cycles, 1490);
+        assert_eq!(result.states.len(), 18);
+        assert!(result.advisories.is_empty());
+        assert_eq!(result.states[&-9].visits, 1450);
+        assert!((result.states[&-9].p_value - 0.858946).abs() < 1e-6);
+        assert_eq!(result.states[&-1].visits, 1502);
+        assert!((result.states[&-1].p_value - 0.826009).abs() < 1e-6);
+        assert_eq!(result.states[&1].visits, 1409);
+        assert!((result.states[&1].p_value - 0.137861).abs() < 1e-6);
+        assert!(random_excursions_variant::perform_test(&e_bit_string)
+            .unwrap()
+            .values()
+            .all(|&p_value| p_value >= 0.01));
+    }
+
+    #[test]
+    fn test_random_excursions_variant_extended_states() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let e_bit_string = read_e();
+        let standard = random_excursions_variant::run_test(&e_bit_string).unwrap();
+        let extended = random_excursions_variant::run_test_with_states(&e_bit_string, 12).unwrap();
+        assert_eq!(extended.states.len(), 24);
+        assert_eq!(extended.number_of_cycles, standard.number_of_cycles);
+        assert_eq!(extended.advisories.len(), 1);
+        for (state, result) in &standard.states {
+            assert_eq!(extended.states[state], *result);
+        }
+        assert!(extended.states.contains_key(&-12) && extended.states.contains_key(&12));
+    }
+
+    #[test]
+    fn test_random_excursions_variant_errors() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // empty or invalid bit strings
+        assert!(random_excursions_variant::run_test("").is_err());
+        assert!(random_excursions_variant::run_test(INVALID_BIT_STRING).is_err());
+
+        // no states to analyze
+        let e_bit_string = read_e();
+        assert!(random_excursions_variant::run_test_with_states(&e_bit_string, 0).is_err());
+
+        // too many states, bounded by the limits instead of the bit string length
+        let err = random_excursions_variant::run_test_with_states(
+            &e_bit_string,
+            constants::MAX_STATES_EXCURSIONS_VARIANT + 1,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            customtypes::NistError::InvalidParameter { .. }
+        ));
+        let limits = customtypes::Limits {
+            max_state_excursions_variant: 12,
+            ..Default::default()
+        };
+        assert!(random_excursions_variant::run_test_cancellable(
+            &e_bit_string,
+            13,
+            &customtypes::CancellationToken::new(),
+            &limits,
+        )
+        .is_err());
+
+        // too few cycles
+        let err = random_excursions_variant::run_test(&e_bit_string[..10000]).unwrap_err();
+        assert!(matches!(
+            err.root(),
+            customtypes::NistError::InsufficientCycles { .. }
+        ));
+    }
+}
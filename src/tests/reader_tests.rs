@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod tests {
+    use crate::bit_sequence::BitSequence;
+    use crate::bit_source::{self, BitSource};
+    use crate::cumulative_sums;
+    use crate::customtypes;
+    use crate::frequency_block;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::reader::ReaderSource;
+    use crate::runs;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const ASCII_BYTES: &[u8] = b"1011 0000\n11\n";
+    const BINARY_BYTES: [u8; 2] = [0xb0, 0xc3];
+    const INVALID_ASCII_BYTES: &[u8] = b"10\na1";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+    const BLOCK_SIZE: usize = 20000;
+
+    /// Read all bits of a source with a buffer of given size.
+    fn read_all(source: &mut dyn BitSource, buffer_size: usize) -> String {
+        let mut buffer = vec![false; buffer_size];
+        let mut bits = Vec::<bool>::new();
+        loop {
+            let count = source.read_bits(&mut buffer).unwrap();
+            if count == 0 {
+                break;
+            }
+            bits.extend_from_slice(&buffer[..count]);
+        }
+        bits.into_iter().collect::<BitSequence>().to_bit_string()
+    }
+
+    #[test]
+    fn test_reader() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // whitespace between ASCII bits is skipped, the bits of a byte may span several reads
+        let mut source = ReaderSource::new(ASCII_BYTES, customtypes::InputFormat::Ascii);
+        assert_eq!(read_all(&mut source, 3), "1011000011");
+        assert_eq!(source.position(), ASCII_BYTES.len());
+
+        let mut source = ReaderSource::new(&BINARY_BYTES[..], customtypes::InputFormat::Binary);
+        assert_eq!(read_all(&mut source, 5), "1011000011000011");
+        assert_eq!(
+            read_all(
+                &mut ReaderSource::new(&BINARY_BYTES[..], customtypes::InputFormat::Binary),
+                16
+            ),
+            BitSequence::from_bytes(&BINARY_BYTES).to_bit_string()
+        );
+    }
+
+    #[test]
+    fn test_reader_streaming_tests() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let limits = customtypes::Limits::default();
+        let open = || ReaderSource::open(&e_file, customtypes::InputFormat::Ascii).unwrap();
+
+        // the streaming tests give the same results as the tests on the whole bit string
+        let expected = frequency_monobit::run_test(&e_bit_string).unwrap();
+        let result = frequency_monobit::run_test_streaming(&mut open(), &limits).unwrap();
+        assert_eq!(result.p_value, expected.p_value);
+        assert_eq!(result.parameters, expected.parameters);
+
+        let expected = runs::run_test(&e_bit_string).unwrap();
+        let result = runs::run_test_streaming(&mut open(), &limits).unwrap();
+        assert_eq!(result.p_value, expected.p_value);
+        assert_eq!(result.statistic, expected.statistic);
+
+        let expected =
+            cumulative_sums::run_test(&e_bit_string, customtypes::Mode::Forward).unwrap();
+        let result =
+            cumulative_sums::run_test_streaming(&mut open(), customtypes::Mode::Forward, &limits)
+                .unwrap();
+        assert_eq!(result.p_value, expected.p_value);
+        assert_eq!(result.statistic, expected.statistic);
+
+        let expected =
+            cumulative_sums::run_test(&e_bit_string, customtypes::Mode::Backward).unwrap();
+        let result =
+            cumulative_sums::run_test_streaming(&mut open(), customtypes::Mode::Backward, &limits)
+                .unwrap();
+        assert_eq!(result.p_value, expected.p_value);
+        assert_eq!(result.statistic, expected.statistic);
+
+        let expected = frequency_block::run_test(&e_bit_string, BLOCK_SIZE).unwrap();
+        let result = frequency_block::run_test_streaming(&mut open(), BLOCK_SIZE, &limits).unwrap();
+        assert_eq!(result.p_value, expected.p_value);
+        assert_eq!(result.parameters, expected.parameters);
+
+        // raw bytes are read most significant bit first
+        let bytes: Vec<u8> = e_bit_string
+            .as_bytes()
+            .chunks_exact(8)
+            .map(|chunk| u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 2).unwrap())
+            .collect();
+        let mut source = ReaderSource::new(bytes.as_slice(), customtypes::InputFormat::Binary);
+        let result = runs::run_test_streaming(&mut source, &limits).unwrap();
+        assert_eq!(
+            result.p_value,
+            runs::perform_test(&BitSequence::from_bytes(&bytes)).unwrap()
+        );
+        assert_eq!(source.position(), bytes.len());
+
+        // the backward partial sums of a short walk
+        let mut source = ReaderSource::new(&b"0011111"[..], customtypes::InputFormat::Ascii);
+        let result =
+            cumulative_sums::run_test_streaming(&mut source, customtypes::Mode::Backward, &limits)
+                .unwrap();
+        assert_eq!(
+            result.statistic,
+            cumulative_sums::run_test("0011111", customtypes::Mode::Backward)
+                .unwrap()
+                .statistic
+        );
+    }
+
+    #[test]
+    fn test_reader_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the bits before an invalid symbol can be read, then the symbol is reported
+        let mut source = ReaderSource::new(INVALID_ASCII_BYTES, customtypes::InputFormat::Ascii);
+        let mut buffer = [false; 4];
+        assert_eq!(source.read_bits(&mut buffer).unwrap(), 2);
+        let err = source.read_bits(&mut buffer).unwrap_err();
+        assert_eq!(
+            err.root(),
+            &customtypes::NistError::InvalidBit {
+                position: 3,
+                symbol: 'a'
+            }
+        );
+
+        // invalid symbols abort the streaming tests
+        let mut source = ReaderSource::new(INVALID_ASCII_BYTES, customtypes::InputFormat::Ascii);
+        assert!(bit_source::for_each_chunk(&mut source, |_| Ok(())).is_err());
+
+        // empty sources and unsuitable parameters
+        let limits = customtypes::Limits::default();
+        let mut source = ReaderSource::new(&b""[..], customtypes::InputFormat::Binary);
+        assert!(frequency_monobit::run_test_streaming(&mut source, &limits).is_err());
+        let mut source = ReaderSource::new(&[0xff; 16][..], customtypes::InputFormat::Binary);
+        assert!(runs::run_test_streaming(&mut source, &limits).is_err());
+        let mut source = ReaderSource::new(&BINARY_BYTES[..], customtypes::InputFormat::Binary);
+        assert!(frequency_block::run_test_streaming(&mut source, 0, &limits).is_err());
+        let mut source = ReaderSource::new(&BINARY_BYTES[..], customtypes::InputFormat::Binary);
+        assert!(frequency_block::run_test_streaming(&mut source, 16, &limits).is_err());
+        assert!(ReaderSource::open("/nonexistent", customtypes::InputFormat::Binary).is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_reader_mmap() {
+        use crate::reader::MmapSource;
+
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // a mapped file gives the same bits as the file read in chunks
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let mut source = MmapSource::open(&e_file, customtypes::InputFormat::Ascii).unwrap();
+        let limits = customtypes::Limits::default();
+        assert_eq!(
+            frequency_monobit::run_test_streaming(&mut source, &limits)
+                .unwrap()
+                .p_value,
+            frequency_monobit::run_test_streaming(
+                &mut ReaderSource::open(&e_file, customtypes::InputFormat::Ascii).unwrap(),
+                &limits
+            )
+            .unwrap()
+            .p_value
+        );
+        assert_eq!(
+            source.position(),
+            std::fs::metadata(&e_file).unwrap().len() as usize
+        );
+
+        let binary_file = std::env::temp_dir().join("reader_tests_mmap");
+        std::fs::write(&binary_file, BINARY_BYTES).unwrap();
+        let mut source = MmapSource::open(
+            binary_file.to_str().unwrap(),
+            customtypes::InputFormat::Binary,
+        )
+        .unwrap();
+        assert_eq!(read_all(&mut source, 3), "1011000011000011");
+        let _ = std::fs::remove_file(&binary_file);
+
+        assert!(MmapSource::open("/nonexistent", customtypes::InputFormat::Binary).is_err());
+    }
+}
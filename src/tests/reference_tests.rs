@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::reference;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+
+    #[test]
+    fn test_reference_sequence() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        for sequence in customtypes::ReferenceSequence::ALL {
+            let bit_string = sequence.bit_string();
+            assert_eq!(bit_string.len(), constants::REFERENCE_LENGTH);
+            assert!(bit_string.chars().all(|c| c == '0' || c == '1'));
+        }
+
+        // the embedded sequence is the prefix of the test data
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        assert!(pi_bit_string.starts_with(&customtypes::ReferenceSequence::Pi.bit_string()));
+        assert_eq!(customtypes::ReferenceSequence::Sqrt2.to_string(), "sqrt(2)");
+    }
+
+    #[test]
+    fn test_validate() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let checks = reference::validate().unwrap();
+        let tests_per_sequence = 12
+            + usize::from(cfg!(feature = "matrix-rank"))
+            + usize::from(cfg!(feature = "spectral"));
+        assert_eq!(checks.len(), 4 * tests_per_sequence);
+
+        // every test reproduces the published p-values
+        for check in &checks {
+            assert!(check.matches(), "{}", check);
+        }
+    }
+}
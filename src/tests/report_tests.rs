@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::customtypes;
+    use crate::frequency_block;
+    use crate::logger;
+    use crate::report;
+    use crate::suite;
+    use crate::utils;
+    use std::collections::BTreeMap;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_NIST: &str = "0110011010";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const SHORT_LENGTH: usize = 5000;
+
+    fn read_pi() -> String {
+        let file_path = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+
+        utils::read_random_numbers(&file_path).unwrap()
+    }
+
+    #[test]
+    fn test_report_json() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let result = frequency_block::run_test(BIT_STRING_NIST, 3).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&report::to_json(&result).unwrap()).unwrap();
+        assert_eq!(json["test"], "FrequencyBlock");
+        assert_eq!(json["name"], "Frequency Test within a Block");
+        assert_eq!(json["p_value"], result.p_value);
+        assert_eq!(json["statistic"], result.statistic);
+        assert_eq!(json["alpha"], constants::P_VALUE_THRESHOLD);
+        assert_eq!(json["verdict"], result.verdict());
+        assert_eq!(json["parameters"]["M"], 3);
+        assert_eq!(json["parameters"]["n"], 10);
+        assert_eq!(json["advisories"][0]["severity"], "Weak");
+        assert_eq!(json["advisories"][0]["parameter"], "n");
+        assert!(json["elapsed_time"].is_f64());
+
+        // a report contains its verdict, the results, the skipped tests and the pipeline
+        let report = suite::run_all(
+            &read_pi()[..SHORT_LENGTH],
+            &customtypes::SuiteConfig::default(),
+        )
+        .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&report::to_json(&report).unwrap()).unwrap();
+        assert_eq!(json["verdict"], "PASS");
+        assert_eq!(
+            json["results"].as_array().unwrap().len(),
+            report.results.len()
+        );
+        assert_eq!(json["results"][0]["test"], "FrequencyMonobit");
+        assert_eq!(
+            json["skipped"].as_array().unwrap().len(),
+            report.skipped.len()
+        );
+        assert!(json["skipped"][0]["reason"]["TooShort"]["required_length"].is_u64());
+        assert_eq!(json["pipeline"], serde_json::json!([]));
+
+        // the results of a report can be exported on their own
+        let json: serde_json::Value =
+            serde_json::from_str(&report::to_json(&report.results).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!(report.results));
+    }
+
+    #[test]
+    fn test_report_csv() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let result = customtypes::TestResult {
+            test: customtypes::Test::FrequencyBlock,
+            p_value: 0.801252,
+            advisories: vec![customtypes::Advisory::weak(
+                "n",
+                "Bit string length n = 10 is below 100, the \"recommended\" minimum".to_owned(),
+            )],
+            statistic: 1.0,
+            parameters: BTreeMap::from([("M".to_owned(), 3), ("n".to_owned(), 10)]),
+            elapsed_time: std::time::Duration::from_millis(500),
+            alpha: constants::P_VALUE_THRESHOLD,
+            counts: vec![2, 1],
+        };
+        let failed = customtypes::TestResult {
+            p_value: 0.005,
+            advisories: Vec::new(),
+            counts: Vec::new(),
+            ..result.clone()
+        };
+
+        assert_eq!(
+            report::to_csv(&[result, failed]),
+            "test,name,p_value,statistic,alpha,verdict,parameters,counts,advisories,elapsed_time\n\
+             FrequencyBlock,Frequency Test within a Block,0.801252,1,0.01,PASS,M=3;n=10,2;1,\
+             \"n: Bit string length n = 10 is below 100, the \"\"recommended\"\" minimum\",0.5\n\
+             FrequencyBlock,Frequency Test within a Block,0.005,1,0.01,FAIL,M=3;n=10,,,0.5\n"
+        );
+        assert_eq!(
+            report::to_csv(&[]),
+            "test,name,p_value,statistic,alpha,verdict,parameters,counts,advisories,elapsed_time\n"
+        );
+    }
+}
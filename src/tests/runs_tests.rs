@@ -1,8 +1,13 @@
 #[cfg(test)]
 mod tests {
+    use crate::bit_sequence::BitSequence;
+    use crate::bit_source::BitStringSource;
+    use crate::customtypes;
     use crate::logger;
     use crate::runs;
     use crate::utils;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
 
     const LOGLEVEL: &str = "Debug";
     const BIT_STRING_NIST_1: &str = "1001101011";
@@ -20,6 +25,7 @@ mod tests {
     const SQRT_2_FILE: &str = "/src/tests/testdata/data.sqrt2";
     const SQRT_3_FILE: &str = "/src/tests/testdata/data.sqrt3";
     const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+    const SEED: u64 = 42;
 
     #[test]
     fn test_runs() {
@@ -83,6 +89,35 @@ mod tests {
         assert!(runs::perform_test(&sha_3_bit_string).unwrap() >= 0.01);
     }
 
+    #[test]
+    fn test_runs_words() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // counting the runs a word at a time yields the results of reading one bit at a time,
+        // including runs across words and a partial last word
+        let mut rng = StdRng::seed_from_u64(SEED);
+        for length in [100, 127, 128, 129, 1000, 4097] {
+            let bit_string: String = (0..length)
+                .map(|_| if rng.next_u32() % 2 == 1 { '1' } else { '0' })
+                .collect();
+            let expected = runs::run_test_streaming(
+                &mut BitStringSource::new(&bit_string),
+                &customtypes::Limits::default(),
+            )
+            .unwrap();
+
+            let result = runs::run_test(&bit_string).unwrap();
+            assert_eq!(result.statistic, expected.statistic);
+            assert_eq!(result.p_value, expected.p_value);
+
+            let sequence = BitSequence::from_bit_string(&bit_string).unwrap();
+            assert_eq!(
+                runs::run_test(&sequence.reversed()).unwrap().p_value,
+                expected.p_value
+            );
+        }
+    }
+
     #[test]
     fn test_runs_error_cases() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::script;
+    use std::collections::BTreeMap;
+
+    const LOGLEVEL: &str = "Debug";
+    const TWO_FAILURES_SCRIPT: &str = "let failed = results.filter(|r| !r.passed).len();\n\
+                                       failed < 2 && results.all(|r| r.p_value >= 1e-6)";
+
+    fn create_report(p_values: &[f64]) -> customtypes::SuiteReport {
+        customtypes::SuiteReport {
+            results: p_values
+                .iter()
+                .map(|&p_value| customtypes::TestResult {
+                    test: customtypes::Test::FrequencyMonobit,
+                    p_value,
+                    advisories: Vec::new(),
+                    statistic: 0.0,
+                    parameters: BTreeMap::from([("n".to_owned(), 100)]),
+                    elapsed_time: std::time::Duration::ZERO,
+                    alpha: constants::P_VALUE_THRESHOLD,
+                    counts: Vec::new(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_verdict_script() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // one failed test is tolerated, two failed tests or a tiny p-value are not
+        let script = script::VerdictScript::new(TWO_FAILURES_SCRIPT).unwrap();
+        let report = create_report(&[0.5, 0.005, 0.3]);
+        assert_eq!(report.verdict(), "FAIL");
+        assert_eq!(script.evaluate(&report).unwrap(), "PASS");
+        assert_eq!(
+            script
+                .evaluate(&create_report(&[0.5, 0.005, 0.003]))
+                .unwrap(),
+            "FAIL"
+        );
+        assert_eq!(
+            script.evaluate(&create_report(&[0.5, 1e-7])).unwrap(),
+            "FAIL"
+        );
+
+        // scripts may return a verdict and access all fields of the results
+        assert_eq!(
+            script::evaluate_verdict(
+                &report,
+                "if results.some(|r| r.parameters.n < 1000) { \"INVALID\" } else { \"PASS\" }"
+            )
+            .unwrap(),
+            "INVALID"
+        );
+        assert_eq!(
+            script::evaluate_verdict(&report, "results[0].test").unwrap_err().to_string(),
+            "Verdict script evaluated to unknown verdict 'Frequency Monobit Test', expected PASS, FAIL or INVALID"
+        );
+
+        // skipped tests qualify the verdict
+        let mut skipped = report.clone();
+        skipped.skipped.push(customtypes::SkippedTest {
+            test: customtypes::Test::OverlappingTemplate,
+            reason: customtypes::SkipReason::TooShort {
+                required_length: 1000000,
+            },
+        });
+        assert_eq!(
+            script.evaluate(&skipped).unwrap(),
+            "PASS (qualified, 1 skipped)"
+        );
+        assert_eq!(
+            script::evaluate_verdict(&skipped, "skipped == 0").unwrap(),
+            "FAIL (qualified, 1 skipped)"
+        );
+    }
+
+    #[test]
+    fn test_verdict_script_errors() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let report = create_report(&[0.5]);
+        assert!(script::VerdictScript::new("results.len( <").is_err());
+        assert!(script::evaluate_verdict(&report, "42").is_err());
+        assert!(script::evaluate_verdict(&report, "unknown_variable").is_err());
+
+        // endless scripts are aborted
+        assert!(script::evaluate_verdict(&report, "loop { }").is_err());
+    }
+}
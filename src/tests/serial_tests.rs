@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use crate::customtypes;
+    use crate::logger;
+    use crate::serial;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const BIT_STRING_NIST: &str = "0011011101";
+    const INVALID_BIT_STRING: &str = "001101a101";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+    const E_LENGTH: usize = 1000000;
+
+    #[test]
+    fn test_serial() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // example of NIST SP 800-22, section 2.11.4
+        let results = serial::run_test(BIT_STRING_NIST, 3).unwrap();
+        assert!((results[0].statistic - 1.6).abs() < 1e-9);
+        assert!((results[1].statistic - 0.8).abs() < 1e-9);
+        assert!((results[0].p_value - 0.808792).abs() < 1e-6);
+        assert!((results[1].p_value - 0.670320).abs() < 1e-6);
+        assert!(results
+            .iter()
+            .all(|result| result.test == customtypes::Test::Serial));
+        assert_eq!(results[0].parameters["m"], 3);
+
+        // example of NIST SP 800-22, section 2.11.8: the first 1,000,000 bits of e
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let result = serial::perform_test(&e_bit_string[..E_LENGTH], 16).unwrap();
+        assert!((result.p_value_1 - 0.766182).abs() < 1e-6);
+        assert!((result.p_value_2 - 0.462921).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_serial_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert!(serial::perform_test("", 2).is_err());
+        assert!(serial::perform_test(INVALID_BIT_STRING, 2).is_err());
+
+        // the second p-value needs m >= 2 and the counts of 2^m patterns are bounded
+        assert!(serial::perform_test(BIT_STRING_NIST, 1).is_err());
+        assert!(serial::perform_test(BIT_STRING_NIST, 21).is_err());
+        assert!(serial::perform_test(BIT_STRING_NIST, 10).is_err());
+    }
+}
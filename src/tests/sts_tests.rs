@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use crate::cumulative_sums;
+    use crate::customtypes;
+    use crate::frequency_block;
+    use crate::frequency_monobit;
+    use crate::logger;
+    use crate::runs;
+    use crate::sts;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const STS_PI_DIRECTORY: &str = "/src/tests/testdata/sts_pi";
+    const PUBLISHED_LENGTH: usize = 1000000;
+
+    fn create_pi_report() -> customtypes::SuiteReport {
+        let pi_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + PI_FILE;
+        let pi_bit_string = utils::read_random_numbers(&pi_file).unwrap();
+        let pi_bit_string = &pi_bit_string[..PUBLISHED_LENGTH];
+
+        // the reference implementation was run with M = 128
+        let limits = customtypes::Limits {
+            max_number_of_blocks: PUBLISHED_LENGTH,
+            ..Default::default()
+        };
+        customtypes::SuiteReport {
+            results: vec![
+                frequency_monobit::run_test(pi_bit_string).unwrap(),
+                frequency_block::run_test_cancellable(
+                    pi_bit_string,
+                    128,
+                    &customtypes::CancellationToken::new(),
+                    &limits,
+                )
+                .unwrap(),
+                runs::run_test(pi_bit_string).unwrap(),
+                cumulative_sums::run_test(pi_bit_string, customtypes::Mode::Forward).unwrap(),
+                cumulative_sums::run_test(pi_bit_string, customtypes::Mode::Backward).unwrap(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_final_analysis_report() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let contents = " C1  C2  C3  C4  C5  C6  C7  C8  C9 C10  P-VALUE  PROPORTION  STATISTICAL TEST\n\
+                          5   3   6   4   7   5   2   8   6   4  0.534146     49/50      Frequency\n\
+                         12   4   5   3   6   4   5   4   4   3  0.000954 *   46/50  *   Rank\n";
+        let rows = sts::parse_final_analysis_report(contents).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].test, customtypes::Test::FrequencyMonobit);
+        assert_eq!(rows[0].histogram, [5, 3, 6, 4, 7, 5, 2, 8, 6, 4]);
+        assert_eq!(rows[0].uniformity_p_value, Some(0.534146));
+        assert_eq!((rows[0].passed, rows[0].sequences), (49, 50));
+        assert_eq!(rows[1].test, customtypes::Test::BinaryMatrixRank);
+        assert_eq!((rows[1].passed, rows[1].sequences), (46, 50));
+
+        // rows with unknown tests or malformed columns are refused
+        assert!(sts::parse_final_analysis_report(
+            "  0   0   0   0   0   1   0   0   0   0     ----       1/1       Unknown\n"
+        )
+        .is_err());
+        assert!(sts::parse_final_analysis_report(
+            "  0   0   0   0   0   1   0   0   0   0     ----       1       Frequency\n"
+        )
+        .is_err());
+
+        assert_eq!(
+            sts::parse_results("0.628308\n 0.663369 \n\n").unwrap(),
+            vec![0.628308, 0.663369]
+        );
+        assert!(sts::parse_results("0.628308\nnan?\n").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let sts_pi_directory = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + STS_PI_DIRECTORY;
+        let output = sts::read_output(&sts_pi_directory).unwrap();
+        assert_eq!(output.rows.len(), 6);
+        assert_eq!(
+            output.p_values[&customtypes::Test::CumulativeSums],
+            vec![0.628308, 0.663369]
+        );
+
+        // the output of the reference implementation on pi is reproduced
+        let report = create_pi_report();
+        assert!(sts::compare(&report, &output).unwrap().is_empty());
+
+        // deviating and missing p-values are listed
+        let mut deviating = report.clone();
+        deviating.results[2].p_value = 0.005;
+        deviating.results.remove(4);
+        deviating.results.push(customtypes::TestResult {
+            test: customtypes::Test::LongestRun,
+            ..report.results[0].clone()
+        });
+        let discrepancies = sts::compare(&deviating, &output).unwrap();
+        let kinds: Vec<(customtypes::Test, usize, customtypes::DiscrepancyKind)> = discrepancies
+            .into_iter()
+            .map(|discrepancy| (discrepancy.test, discrepancy.index, discrepancy.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    customtypes::Test::Runs,
+                    0,
+                    customtypes::DiscrepancyKind::PValue {
+                        reference: 0.419268,
+                        report: 0.005
+                    }
+                ),
+                (
+                    customtypes::Test::Runs,
+                    0,
+                    customtypes::DiscrepancyKind::Bin {
+                        reference: 4,
+                        report: 0
+                    }
+                ),
+                (
+                    customtypes::Test::Runs,
+                    0,
+                    customtypes::DiscrepancyKind::Passed {
+                        reference: true,
+                        report: false
+                    }
+                ),
+                (
+                    customtypes::Test::LongestRun,
+                    0,
+                    customtypes::DiscrepancyKind::MissingInReference
+                ),
+                (
+                    customtypes::Test::CumulativeSums,
+                    1,
+                    customtypes::DiscrepancyKind::MissingInReport
+                ),
+            ]
+        );
+
+        // a run on several sequences cannot be compared with a single report
+        let mut several = output.clone();
+        several.rows[0].sequences = 50;
+        assert!(sts::compare(&report, &several).is_err());
+        assert!(sts::read_output("/non-existing-dir").is_err());
+    }
+}
@@ -0,0 +1,548 @@
+#[cfg(test)]
+mod tests {
+    use crate::bit_sequence::BitSequence;
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::suite;
+    use crate::utils;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    const LOGLEVEL: &str = "Debug";
+    const PI_FILE: &str = "/src/tests/testdata/data.pi";
+    const SHA_3_FILE: &str = "/src/tests/testdata/data.sha3";
+    const SHORT_LENGTH: usize = 5000;
+    const SEED: u64 = 42;
+    const INVALID_BIT_STRING: &str = "1100110000010101011011000100110011100000000000100100110101010001000100a111010110100000001101011111001100111001101101100010110010";
+
+    fn read_file(file: &str) -> String {
+        let file_path = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + file;
+
+        utils::read_random_numbers(&file_path).unwrap()
+    }
+
+    fn read_pi() -> String {
+        read_file(PI_FILE)
+    }
+
+    #[cfg(all(feature = "spectral", feature = "matrix-rank"))]
+    #[test]
+    fn test_run_all_degraded() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // 5,000 bits are too short for the Binary Matrix Rank and Overlapping Template tests
+        let pi = read_pi();
+        let report =
+            suite::run_all(&pi[..SHORT_LENGTH], &customtypes::SuiteConfig::default()).unwrap();
+
+        let tests: Vec<customtypes::Test> =
+            report.results.iter().map(|result| result.test).collect();
+        assert_eq!(
+            tests,
+            vec![
+                customtypes::Test::FrequencyMonobit,
+                customtypes::Test::FrequencyBlock,
+                customtypes::Test::Runs,
+                customtypes::Test::LongestRun,
+                customtypes::Test::DFTSpectral,
+                customtypes::Test::NonOverlappingTemplate,
+                customtypes::Test::Serial,
+                customtypes::Test::Serial,
+                customtypes::Test::ApproximateEntropy,
+                customtypes::Test::CumulativeSums,
+                customtypes::Test::CumulativeSums,
+            ]
+        );
+        assert_eq!(
+            report.skipped,
+            vec![
+                customtypes::SkippedTest {
+                    test: customtypes::Test::BinaryMatrixRank,
+                    reason: customtypes::SkipReason::TooShort {
+                        required_length: 38912
+                    },
+                },
+                customtypes::SkippedTest {
+                    test: customtypes::Test::OverlappingTemplate,
+                    reason: customtypes::SkipReason::TooShort {
+                        required_length: 1000000
+                    },
+                },
+                customtypes::SkippedTest {
+                    test: customtypes::Test::RandomExcursions,
+                    reason: customtypes::SkipReason::TooShort {
+                        required_length: 1000000
+                    },
+                },
+                customtypes::SkippedTest {
+                    test: customtypes::Test::RandomExcursionsVariant,
+                    reason: customtypes::SkipReason::TooShort {
+                        required_length: 1000000
+                    },
+                },
+            ]
+        );
+        assert_eq!(report.results[1].parameters["N"], 98);
+        assert_eq!(report.results[6].parameters["m"], 9);
+        assert_eq!(report.results[8].parameters["m"], 6);
+        assert!(report.verdict().ends_with(" (qualified, 4 skipped)"));
+
+        // tests which are not implemented yet only get the advice for the length
+        assert_eq!(
+            report
+                .length_advice
+                .iter()
+                .map(|advice| advice.test)
+                .collect::<Vec<customtypes::Test>>(),
+            vec![
+                customtypes::Test::MaurersUniversalStatistical,
+                customtypes::Test::LinearComplexity
+            ]
+        );
+        assert!(report
+            .length_advice
+            .iter()
+            .all(|advice| !advice.is_adequate()));
+
+        // skipped tests are listed after the results
+        let table = report.to_string();
+        let last_line = table.lines().last().unwrap();
+        assert!(last_line.starts_with("Random Excursions Variant Test "));
+        assert!(last_line.contains("SKIPPED"));
+        assert!(last_line.ends_with("n < 1000000"));
+    }
+
+    #[test]
+    fn test_run_all_monobit_failure() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // forcing every 16th bit to one fails the Frequency Monobit Test, but keeps the
+        // proportion of ones close enough to 1/2 for the Runs Test
+        let biased: String = read_pi()[..SHORT_LENGTH]
+            .chars()
+            .enumerate()
+            .map(|(index, c)| if index % 16 == 0 { '1' } else { c })
+            .collect();
+        let mut config = customtypes::SuiteConfig::default();
+        let report = suite::run_all(&biased, &config).unwrap();
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].test, customtypes::Test::FrequencyMonobit);
+        assert!(!report.results[0].passed());
+        assert!(report.verdict().starts_with("FAIL"));
+
+        // the dependent tests are skipped regardless of their required length
+        assert!(report.skipped.iter().any(|skipped| skipped.test
+            == customtypes::Test::OverlappingTemplate
+            && skipped.reason == customtypes::SkipReason::MonobitFailed));
+        assert!(report
+            .skipped
+            .iter()
+            .all(|skipped| skipped.reason == customtypes::SkipReason::MonobitFailed));
+        assert!(report
+            .to_string()
+            .lines()
+            .last()
+            .unwrap()
+            .ends_with("monobit failed"));
+
+        // a significance level below the p-value lets the Frequency Monobit Test pass
+        let p_value = report.results[0].p_value;
+        config.alpha = p_value / 2.0;
+        let report = suite::run_all(&biased, &config).unwrap();
+        assert!(report.results.len() > 1);
+        assert!(report.results[0].passed());
+        assert!(report
+            .results
+            .iter()
+            .all(|result| result.alpha == p_value / 2.0));
+        config.alpha = constants::P_VALUE_THRESHOLD;
+
+        // all tests are run if the short-circuit is disabled
+        config.stop_on_monobit_failure = false;
+        let report = suite::run_all(&biased, &config).unwrap();
+        assert!(report.results.len() > 1);
+        assert!(report
+            .skipped
+            .iter()
+            .all(|skipped| matches!(skipped.reason, customtypes::SkipReason::TooShort { .. })));
+    }
+
+    #[test]
+    fn test_run_all_excursions() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the random walk of the SHA-3 output has J = 282 cycles, so the excursion tests are not
+        // applicable and skipped instead of failing the suite
+        let report =
+            suite::run_all(&read_file(SHA_3_FILE), &customtypes::SuiteConfig::default()).unwrap();
+        assert!(report.results.iter().all(|result| !matches!(
+            result.test,
+            customtypes::Test::RandomExcursions | customtypes::Test::RandomExcursionsVariant
+        )));
+        assert_eq!(
+            report.skipped,
+            vec![
+                customtypes::SkippedTest {
+                    test: customtypes::Test::RandomExcursions,
+                    reason: customtypes::SkipReason::TooFewCycles {
+                        number_of_cycles: 282,
+                        required_cycles: 500
+                    },
+                },
+                customtypes::SkippedTest {
+                    test: customtypes::Test::RandomExcursionsVariant,
+                    reason: customtypes::SkipReason::TooFewCycles {
+                        number_of_cycles: 282,
+                        required_cycles: 500
+                    },
+                },
+            ]
+        );
+        assert!(report
+            .to_string()
+            .lines()
+            .last()
+            .unwrap()
+            .ends_with("J < 500"));
+    }
+
+    #[test]
+    fn test_required_length() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let limits = customtypes::Limits::default();
+        assert_eq!(
+            suite::required_length(customtypes::Test::LongestRun, &limits),
+            Some(128)
+        );
+        assert_eq!(
+            suite::required_length(customtypes::Test::LinearComplexity, &limits),
+            None
+        );
+        assert_eq!(
+            suite::required_length(customtypes::Test::Serial, &limits),
+            Some(32)
+        );
+        assert_eq!(
+            suite::required_length(customtypes::Test::ApproximateEntropy, &limits),
+            Some(128)
+        );
+
+        // tests disabled by a feature are not part of the suite
+        assert_eq!(
+            suite::required_length(customtypes::Test::DFTSpectral, &limits).is_some(),
+            cfg!(feature = "spectral")
+        );
+        assert_eq!(
+            suite::required_length(customtypes::Test::BinaryMatrixRank, &limits).is_some(),
+            cfg!(feature = "matrix-rank")
+        );
+    }
+
+    #[test]
+    fn test_run_selected() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the tests run in the order of the suite, not in the passed one
+        let pi = read_pi();
+        let config = customtypes::SuiteConfig::default();
+        let report = suite::run_selected(
+            &pi[..SHORT_LENGTH],
+            &[customtypes::Test::Runs, customtypes::Test::FrequencyBlock],
+            &config,
+        )
+        .unwrap();
+        let tests: Vec<customtypes::Test> =
+            report.results.iter().map(|result| result.test).collect();
+        assert_eq!(
+            tests,
+            vec![customtypes::Test::FrequencyBlock, customtypes::Test::Runs]
+        );
+        assert!(report.skipped.is_empty());
+
+        // parameters override the ones chosen by the suite
+        let config = customtypes::SuiteConfig {
+            params: customtypes::TestParams {
+                block_size: Some(100),
+                block_length_serial: Some(4),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let report = suite::run_selected(
+            &pi[..SHORT_LENGTH],
+            &[customtypes::Test::FrequencyBlock, customtypes::Test::Serial],
+            &config,
+        )
+        .unwrap();
+        assert_eq!(report.results[0].parameters["M"], 100);
+        assert_eq!(report.results[1].parameters["m"], 4);
+
+        // tests outside the suite cannot be selected
+        assert!(suite::run_selected(
+            &pi[..SHORT_LENGTH],
+            &[customtypes::Test::LinearComplexity],
+            &config
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_selected_with_control() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let pi = read_pi();
+        let config = customtypes::SuiteConfig::default();
+        let tests = [
+            customtypes::Test::FrequencyMonobit,
+            customtypes::Test::FrequencyBlock,
+            customtypes::Test::Runs,
+        ];
+
+        // every test reports its start and its end
+        let reports = std::cell::RefCell::new(Vec::<customtypes::Progress>::new());
+        let control = customtypes::ExecutionControl {
+            progress: &|progress| reports.borrow_mut().push(progress),
+            ..Default::default()
+        };
+        let report =
+            suite::run_selected_with_control(&pi[..SHORT_LENGTH], &tests, &config, &control)
+                .unwrap();
+        assert_eq!(report.results.len(), 3);
+        let fractions: Vec<(customtypes::Test, f64)> = reports
+            .borrow()
+            .iter()
+            .map(|progress| (progress.test, progress.fraction))
+            .collect();
+        assert_eq!(
+            fractions,
+            tests
+                .iter()
+                .flat_map(|&test| [(test, 0.0), (test, 1.0)])
+                .collect::<Vec<_>>()
+        );
+
+        // cancelling from the callback stops the run before the next test and keeps the results
+        let token = customtypes::CancellationToken::new();
+        let callback_token = token.clone();
+        let control = customtypes::ExecutionControl {
+            progress: &|progress| {
+                if progress.test == customtypes::Test::FrequencyBlock && progress.fraction == 1.0 {
+                    callback_token.cancel();
+                }
+            },
+            cancellation: token.clone(),
+        };
+        let report =
+            suite::run_selected_with_control(&pi[..SHORT_LENGTH], &tests, &config, &control)
+                .unwrap();
+        assert!(report.cancelled);
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(
+            report.skipped,
+            vec![customtypes::SkippedTest {
+                test: customtypes::Test::Runs,
+                reason: customtypes::SkipReason::Cancelled,
+            }]
+        );
+        assert!(report.verdict().ends_with(" (cancelled, 1 skipped)"));
+
+        // a token cancelled at the start of a test stops it within its loop
+        let token = customtypes::CancellationToken::new();
+        let callback_token = token.clone();
+        let control = customtypes::ExecutionControl {
+            progress: &|progress| {
+                if progress.test == customtypes::Test::Runs && progress.fraction == 0.0 {
+                    callback_token.cancel();
+                }
+            },
+            cancellation: token.clone(),
+        };
+        let report =
+            suite::run_selected_with_control(&pi[..SHORT_LENGTH], &tests, &config, &control)
+                .unwrap();
+        assert!(report.cancelled);
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(report.skipped[0].reason, customtypes::SkipReason::Cancelled);
+
+        // a token cancelled in advance stops the run before the first test
+        let report = suite::run_all_with_control(&pi[..SHORT_LENGTH], &config, &control).unwrap();
+        assert!(report.cancelled);
+        assert!(report.results.is_empty());
+
+        // a passed deadline stops the run before the first test as well
+        let control = customtypes::ExecutionControl {
+            cancellation: customtypes::CancellationToken::with_deadline(std::time::Instant::now()),
+            ..Default::default()
+        };
+        let report = suite::run_all_with_control(&pi[..SHORT_LENGTH], &config, &control).unwrap();
+        assert!(report.cancelled);
+        assert!(report.results.is_empty());
+        assert!(report
+            .skipped
+            .iter()
+            .all(|skipped| skipped.reason == customtypes::SkipReason::Cancelled));
+
+        // an error of one test is recorded instead of discarding the other results
+        let mut config = customtypes::SuiteConfig::default();
+        config.params.block_size = Some(0);
+        let report = suite::run_selected(&pi[..SHORT_LENGTH], &tests, &config).unwrap();
+        assert!(!report.cancelled);
+        assert_eq!(report.results.len(), 2);
+        assert!(matches!(
+            &report.skipped[0],
+            customtypes::SkippedTest {
+                test: customtypes::Test::FrequencyBlock,
+                reason: customtypes::SkipReason::Error { error },
+            } if matches!(error.root(), customtypes::NistError::InvalidParameter { .. })
+        ));
+    }
+
+    /// Generator failing to produce any output, e.g. a DRBG without entropy
+    struct FailingRng;
+
+    impl RngCore for FailingRng {
+        fn next_u32(&mut self) -> u32 {
+            panic!("FailingRng does not produce any output")
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            panic!("FailingRng does not produce any output")
+        }
+
+        fn fill_bytes(&mut self, _dest: &mut [u8]) {
+            panic!("FailingRng does not produce any output")
+        }
+
+        fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> Result<(), rand::Error> {
+            Err(rand::Error::new("no entropy"))
+        }
+    }
+
+    #[test]
+    fn test_test_rng() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let config = customtypes::SuiteConfig::default();
+        let report =
+            suite::test_rng(&mut StdRng::seed_from_u64(SEED), SHORT_LENGTH, &config).unwrap();
+        assert!(report.verdict().starts_with("PASS"));
+
+        // the report equals the one of the generator's output tested by run_all()
+        let mut bytes = vec![0; SHORT_LENGTH / 8];
+        StdRng::seed_from_u64(SEED).fill_bytes(&mut bytes);
+        let expected = suite::run_all(&BitSequence::from_bytes(&bytes), &config).unwrap();
+        assert_eq!(report.results.len(), expected.results.len());
+        assert!(report
+            .results
+            .iter()
+            .zip(&expected.results)
+            .all(|(result, expected)| result.approx_eq(expected, 0.0)));
+        assert_eq!(report.skipped, expected.skipped);
+
+        assert!(suite::test_rng(&mut StdRng::seed_from_u64(SEED), 0, &config).is_err());
+        assert!(suite::test_rng(&mut FailingRng, SHORT_LENGTH, &config).is_err());
+    }
+
+    #[test]
+    fn test_run_all_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let config = customtypes::SuiteConfig::default();
+        assert_eq!(
+            suite::run_all("", &config).unwrap_err().root(),
+            &customtypes::NistError::insufficient_length(
+                customtypes::Test::FrequencyMonobit,
+                config.limits.recommended_size,
+                0
+            )
+        );
+        assert!(matches!(
+            suite::run_selected("", &[customtypes::Test::LinearComplexity], &config)
+                .unwrap_err()
+                .root(),
+            customtypes::NistError::InvalidParameter { .. }
+        ));
+        assert!(matches!(
+            suite::run_all(INVALID_BIT_STRING, &config).unwrap_err(),
+            customtypes::NistError::InvalidBit { .. }
+        ));
+
+        // strict mode refuses overridden limits before running any test
+        let mut strict = customtypes::SuiteConfig {
+            compliance_mode: customtypes::ComplianceMode::Strict,
+            ..Default::default()
+        };
+        assert!(suite::run_all(&read_pi()[..SHORT_LENGTH], &strict).is_ok());
+        strict.limits.recommended_size = 50;
+        assert!(suite::run_all(&read_pi()[..SHORT_LENGTH], &strict).is_err());
+        strict.limits = customtypes::Limits::default();
+
+        // limits out of range are refused in permissive mode instead of dividing by zero blocks
+        let zero_blocks = customtypes::SuiteConfig {
+            limits: customtypes::Limits {
+                max_number_of_blocks: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            suite::run_selected(
+                &read_pi()[..SHORT_LENGTH],
+                &[customtypes::Test::FrequencyBlock],
+                &zero_blocks
+            )
+            .unwrap_err()
+            .root(),
+            customtypes::NistError::InvalidParameter { name, .. } if name == "limits"
+        ));
+
+        // strict mode refuses lengths and parameters below the recommendations before running any
+        // test, too
+        let started = std::cell::Cell::new(false);
+        let control = customtypes::ExecutionControl {
+            progress: &|_| started.set(true),
+            ..Default::default()
+        };
+        let error = suite::run_selected_with_control(
+            &read_pi()[..50],
+            &[customtypes::Test::Runs, customtypes::Test::Serial],
+            &strict,
+            &control,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error.root(),
+            customtypes::NistError::InvalidParameter { test: Some(customtypes::Test::Serial), name, .. }
+                if name == "n"
+        ));
+        strict.params.block_size = Some(15);
+        let error =
+            suite::run_all_with_control(&read_pi()[..SHORT_LENGTH], &strict, &control).unwrap_err();
+        assert!(matches!(
+            error.root(),
+            customtypes::NistError::InvalidParameter { test: Some(customtypes::Test::FrequencyBlock), name, .. }
+                if name == "M"
+        ));
+        assert!(!started.get());
+
+        // permissive mode runs the test and attaches the advisory instead
+        let permissive = customtypes::SuiteConfig {
+            params: strict.params.clone(),
+            ..Default::default()
+        };
+        let report = suite::run_selected(
+            &read_pi()[..1000],
+            &[customtypes::Test::FrequencyBlock],
+            &permissive,
+        )
+        .unwrap();
+        assert_eq!(report.results[0].advisories[0].parameter, "M");
+    }
+}
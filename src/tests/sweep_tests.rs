@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use crate::customtypes;
+    use crate::logger;
+    use crate::sweep;
+    use crate::utils;
+
+    const LOGLEVEL: &str = "Debug";
+    const INVALID_BIT_STRING: &str = "010101111010101010101010101010a0101010101010100101010101";
+    const E_FILE: &str = "/src/tests/testdata/data.e";
+
+    #[test]
+    fn test_sweep() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let e_file = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            + E_FILE;
+        let e_bit_string = utils::read_random_numbers(&e_file).unwrap();
+        let e_bit_string = &e_bit_string[..100000];
+
+        // a block size of 128 exceeds the maximum number of blocks and is skipped
+        let config = customtypes::SweepConfig {
+            block_sizes: vec![128, 2000, 10000],
+            template_lens: vec![3, 4],
+            block_lengths: vec![2, 5],
+        };
+        let report =
+            sweep::run_sweep(e_bit_string, &config, &customtypes::SuiteConfig::default()).unwrap();
+        assert_eq!(report.results.len(), 6);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].test, customtypes::Test::FrequencyBlock);
+        assert_eq!(
+            (report.skipped[0].parameter, report.skipped[0].value),
+            ("M", 128)
+        );
+
+        let p_values = report.p_values(customtypes::Test::FrequencyBlock);
+        assert_eq!(
+            p_values.keys().copied().collect::<Vec<usize>>(),
+            vec![2000, 10000]
+        );
+        assert_eq!(
+            report.p_values(customtypes::Test::ApproximateEntropy).len(),
+            2
+        );
+        assert!(report
+            .results
+            .iter()
+            .all(|sweep| sweep.result.parameters[sweep.parameter] == sweep.value));
+        assert!(report
+            .results
+            .iter()
+            .all(|sweep| sweep.result.p_value >= 0.01));
+
+        // scales refused by the strict mode are skipped as well
+        let strict = customtypes::SuiteConfig {
+            compliance_mode: customtypes::ComplianceMode::Strict,
+            ..Default::default()
+        };
+        let report = sweep::run_sweep(e_bit_string, &config, &strict).unwrap();
+        assert!(report.results.len() < 6);
+        assert_eq!(report.results.len() + report.skipped.len(), 7);
+    }
+
+    #[test]
+    fn test_sweep_error_cases() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let config = customtypes::SweepConfig::default();
+        assert!(sweep::run_sweep("", &config, &customtypes::SuiteConfig::default()).is_err());
+        assert!(sweep::run_sweep(
+            INVALID_BIT_STRING,
+            &config,
+            &customtypes::SuiteConfig::default()
+        )
+        .is_err());
+    }
+}
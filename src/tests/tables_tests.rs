@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use crate::constants;
+    use crate::customtypes;
+    use crate::logger;
+    use crate::tables;
+
+    const LOGLEVEL: &str = "Debug";
+
+    #[test]
+    fn test_longest_run_config() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let config = tables::longest_run_config(128).unwrap();
+        assert_eq!(config.block_size, 8);
+        assert_eq!(config.number_of_blocks, 16);
+        assert_eq!(config.thresholds, (1, 4));
+        assert_eq!(config.pi_values, constants::MIN_PI_VALUES);
+
+        let config = tables::longest_run_config(6272).unwrap();
+        assert_eq!((config.block_size, config.number_of_blocks), (128, 49));
+
+        let config = tables::longest_run_config(1_000_000).unwrap();
+        assert_eq!((config.block_size, config.number_of_blocks), (10000, 100));
+        let config = tables::longest_run_config(constants::MAX_LENGTH).unwrap();
+        assert_eq!(config.number_of_blocks, constants::MAX_SIZE_N);
+        assert_eq!(config.pi_values.len(), 7);
+
+        assert!(tables::longest_run_config(127).is_err());
+    }
+
+    #[test]
+    fn test_rank_probabilities() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let probabilities = tables::rank_probabilities();
+        assert!((probabilities[0] - 0.288788).abs() < 1e-6);
+        assert!((probabilities[1] - 0.577576).abs() < 1e-6);
+        assert!((probabilities[2] - 0.133636).abs() < 1e-6);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_expected_rank_probabilities() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // NIST SP 800-22 rounds the probabilities of 32 x 32 matrices to 0.2888, 0.5776 and 0.1336
+        let probabilities = tables::expected_rank_probabilities(32, 32).unwrap();
+        assert_eq!(probabilities, tables::rank_probabilities());
+        for (probability, rounded) in probabilities.iter().zip([0.2888, 0.5776, 0.1336]) {
+            assert!((probability - rounded).abs() < 1e-4);
+        }
+
+        let probabilities = tables::expected_rank_probabilities(3, 3).unwrap();
+        assert!((probabilities[0] - 0.328125).abs() < 1e-9);
+        assert!((probabilities[1] - 0.574219).abs() < 1e-6);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        assert!(tables::expected_rank_probabilities(1, 32).is_err());
+    }
+
+    #[test]
+    fn test_universal_config() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let config = tables::universal_config(387_840).unwrap();
+        assert_eq!(config.block_length, 6);
+        assert_eq!(config.init_blocks, 640);
+        assert_eq!(config.expected_value, 5.2177052);
+        assert_eq!(config.variance, 2.954);
+
+        // one bit short of the next row keeps the previous block length
+        let config = tables::universal_config(904_959).unwrap();
+        assert_eq!(config.block_length, 6);
+        let config = tables::universal_config(1_000_000).unwrap();
+        assert_eq!((config.block_length, config.init_blocks), (7, 1280));
+        let config = tables::universal_config(usize::MAX).unwrap();
+        assert_eq!(config.block_length, 16);
+
+        assert!(tables::universal_config(387_839).is_err());
+    }
+
+    #[test]
+    fn test_length_advice() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let advice =
+            tables::length_advice(customtypes::Test::MaurersUniversalStatistical, 1_000_000)
+                .unwrap();
+        assert!(advice.is_adequate());
+        assert_eq!(advice.parameters["L"], 7);
+        assert_eq!(advice.parameters["Q"], 1280);
+        assert_eq!(advice.advisory(), None);
+
+        // too short for any row of the universal table
+        let advice =
+            tables::length_advice(customtypes::Test::MaurersUniversalStatistical, 100_000).unwrap();
+        assert!(!advice.is_adequate());
+        assert!(advice.parameters.is_empty());
+        assert_eq!(
+            advice.advisory().unwrap().severity,
+            customtypes::Severity::Invalid
+        );
+
+        // the linear complexity test still runs on shorter bit strings
+        let advice = tables::length_advice(customtypes::Test::LinearComplexity, 100_000).unwrap();
+        assert_eq!(advice.parameters["M"], 500);
+        assert_eq!(advice.parameters["N"], 200);
+        let advisory = advice.advisory().unwrap();
+        assert_eq!(advisory.severity, customtypes::Severity::Weak);
+        assert_eq!(advisory.parameter, "n");
+        assert!(advisory.message.contains("M = 500, N = 200"));
+
+        assert!(tables::length_advice(customtypes::Test::FrequencyMonobit, 100_000).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use crate::customtypes::{Context, Result};
 use rand::Rng;
 use sha3::{Digest, Sha3_512};
 use std::io::{BufWriter, Write};
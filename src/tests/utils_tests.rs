@@ -1,9 +1,11 @@
 #[cfg(test)]
 mod tests {
+    use crate::bit_sequence::BitSequence;
+    use crate::constants;
     use crate::customtypes;
+    use crate::frequency_block;
     use crate::logger;
     use crate::utils;
-    use std::io::Read;
 
     const LOGLEVEL: &str = "Trace";
     const TEST_NAME: customtypes::Test = customtypes::Test::FrequencyMonobit;
@@ -19,10 +21,192 @@ mod tests {
     const BIT_STRING_FILE: &str = "/src/tests/testdata/random_bit_string";
     const INVALID_CHAR_IN_FILE: &str = "/src/tests/testdata/random_invalid_char";
     const INVALID_FILE: &str = "/non-existing-dir/random_numbers";
+    #[cfg(feature = "template-tables")]
     const TEMPLATE_FILE: &str = "/templates/template2.tar.gz";
+    #[cfg(feature = "template-tables")]
     const ARCHIVE_DEST_DIR: &str = "/tmp";
+    #[cfg(feature = "template-tables")]
     const TEMPLATE_FILE_DEST: &str = "/tmp/template2";
 
+    #[test]
+    fn test_check_compliance() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let strict = customtypes::SuiteConfig {
+            compliance_mode: customtypes::ComplianceMode::Strict,
+            ..Default::default()
+        };
+        let permissive = customtypes::SuiteConfig::default();
+        assert_eq!(
+            permissive.compliance_mode,
+            customtypes::ComplianceMode::Permissive
+        );
+
+        // block size M = 8 is below the recommended 20 bits
+        let weak = frequency_block::run_test(BIT_STRING_2, 8).unwrap();
+        assert!(!weak.advisories.is_empty());
+        assert!(utils::check_compliance(&strict, weak.clone()).is_err());
+        assert_eq!(
+            utils::check_compliance(&permissive, weak.clone()).unwrap(),
+            weak
+        );
+
+        // results without any advisory pass in both modes
+        let compliant = customtypes::TestResult {
+            test: TEST_NAME,
+            p_value: 0.5,
+            advisories: Vec::new(),
+            statistic: 0.0,
+            parameters: utils::parameter_map(&[("n", BIT_STRING_1.len())]),
+            elapsed_time: std::time::Duration::ZERO,
+            alpha: constants::P_VALUE_THRESHOLD,
+            counts: Vec::new(),
+        };
+        assert!(utils::check_compliance(&strict, compliant.clone()).is_ok());
+        assert!(utils::check_compliance(&permissive, compliant).is_ok());
+    }
+
+    #[test]
+    fn test_check_limits() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let mut config = customtypes::SuiteConfig::default();
+        assert!(!config.limits.is_overridden());
+        assert!(utils::check_limits(&config).is_ok());
+
+        // a lower recommended block size silences the advisory of M = 8 in permissive mode
+        config.limits.recommended_block_size = 8;
+        assert!(config.limits.is_overridden());
+        assert!(utils::check_limits(&config).is_ok());
+        let result = frequency_block::run_test_cancellable(
+            BIT_STRING_2,
+            8,
+            &customtypes::CancellationToken::new(),
+            &config.limits,
+        )
+        .unwrap();
+        assert!(result
+            .advisories
+            .iter()
+            .all(|advisory| advisory.parameter != "M"));
+
+        // strict mode refuses any override
+        config.compliance_mode = customtypes::ComplianceMode::Strict;
+        assert!(utils::check_limits(&config).is_err());
+        assert!(utils::check_compliance(&config, result).is_err());
+
+        // limits out of range are refused in permissive mode as well
+        config.compliance_mode = customtypes::ComplianceMode::Permissive;
+        for limits in [
+            customtypes::Limits {
+                max_number_of_blocks: 0,
+                ..Default::default()
+            },
+            customtypes::Limits {
+                template_len: (0, 21),
+                ..Default::default()
+            },
+            customtypes::Limits {
+                template_len: (10, 9),
+                ..Default::default()
+            },
+            customtypes::Limits {
+                max_state_excursions_variant: 0,
+                ..Default::default()
+            },
+        ] {
+            config.limits = limits;
+            assert!(matches!(
+                utils::check_limits(&config),
+                Err(customtypes::NistError::InvalidParameter { name, .. }) if name == "limits"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_check_limits_alpha() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        let mut config = customtypes::SuiteConfig {
+            compliance_mode: customtypes::ComplianceMode::Strict,
+            alpha: constants::MIN_P_VALUE_THRESHOLD,
+            ..Default::default()
+        };
+        assert!(utils::check_limits(&config).is_ok());
+
+        // results are judged at the configured significance level
+        let result = customtypes::TestResult {
+            test: TEST_NAME,
+            p_value: 0.005,
+            advisories: Vec::new(),
+            statistic: 0.0,
+            parameters: utils::parameter_map(&[("n", BIT_STRING_1.len())]),
+            elapsed_time: std::time::Duration::ZERO,
+            alpha: constants::P_VALUE_THRESHOLD,
+            counts: Vec::new(),
+        };
+        assert!(!result.passed());
+        let result = utils::check_compliance(&config, result).unwrap();
+        assert_eq!(result.alpha, constants::MIN_P_VALUE_THRESHOLD);
+        assert!(result.passed());
+
+        // levels outside of [0.001, 0.01] are refused in strict mode only
+        config.alpha = 0.05;
+        assert!(utils::check_limits(&config).is_err());
+        assert!(utils::check_compliance(&config, result).is_err());
+        config.compliance_mode = customtypes::ComplianceMode::Permissive;
+        assert!(utils::check_limits(&config).is_ok());
+
+        // alpha has to be a probability in any mode
+        for alpha in [0.0, 1.0, -0.5, f64::NAN] {
+            config.alpha = alpha;
+            assert!(utils::check_limits(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn test_checked_i64() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        assert_eq!(
+            utils::checked_i64(TEST_NAME, BIT_STRING_1.len(), "partial sums"),
+            Ok(48)
+        );
+
+        let error = utils::checked_i64(TEST_NAME, usize::MAX, "partial sums").unwrap_err();
+        assert_eq!(
+            error,
+            customtypes::NistError::Overflow {
+                test: TEST_NAME,
+                operation: "partial sums".to_owned()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "'Frequency Monobit Test': Arithmetic overflow in partial sums"
+        );
+    }
+
+    #[test]
+    fn test_count_overlapping_patterns() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the example of section 2.11.4 of NIST SP 800-22 for m = 3
+        let cancellation = customtypes::CancellationToken::new();
+        assert_eq!(
+            utils::count_overlapping_patterns(TEST_NAME, "0011011101", 3, &cancellation).unwrap(),
+            vec![0, 1, 1, 2, 1, 2, 2, 1]
+        );
+
+        // the counts of all 2^m patterns have to fit into memory
+        for pattern_len in [0, constants::MAX_PATTERN_LEN + 1, usize::BITS as usize] {
+            assert!(matches!(
+                utils::count_overlapping_patterns(TEST_NAME, BIT_STRING_1, pattern_len, &cancellation),
+                Err(customtypes::NistError::InvalidParameter { name, .. }) if name == "m"
+            ));
+        }
+    }
+
     #[test]
     fn test_hex_bytes_to_bit_string() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
@@ -46,17 +230,85 @@ mod tests {
         assert!(!success);
     }
 
+    #[test]
+    fn test_validate_bit_string() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the lanes of eight characters count the ones of the valid prefix and the first invalid
+        // symbol is found within its lane, also if it is no ASCII character
+        for (position, symbol) in [(0, 'X'), (7, '2'), (8, ' '), (13, 'ä'), (33, '\n')] {
+            let mut bit_string = BIT_STRING_2[..position].to_owned();
+            bit_string.push(symbol);
+            bit_string.push_str(&BIT_STRING_2[position..]);
+
+            let input = utils::validate_bit_string(&bit_string);
+            assert_eq!(input.length, bit_string.len());
+            assert_eq!(input.first_invalid, Some((position, symbol)));
+            assert_eq!(
+                input.ones,
+                BIT_STRING_2[..position]
+                    .chars()
+                    .filter(|&c| c == '1')
+                    .count()
+            );
+        }
+    }
+
     #[test]
     fn test_evaluate_bit_string() {
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
 
         assert_eq!(
-            utils::evaluate_bit_string(TEST_NAME, BIT_STRING_1, BIT_STRING_1.len()).unwrap(),
+            utils::evaluate_bit_string(TEST_NAME, BIT_STRING_1, BIT_STRING_1.len())
+                .unwrap()
+                .length,
             BIT_STRING_1.len()
         );
         assert_eq!(
             utils::evaluate_bit_string(TEST_NAME, BIT_STRING_2, BIT_STRING_2.len()).unwrap(),
-            BIT_STRING_2.len()
+            customtypes::ValidatedInput {
+                length: BIT_STRING_2.len(),
+                ones: BIT_STRING_2.chars().filter(|&c| c == '1').count(),
+                first_invalid: None,
+                advisories: Vec::new(),
+            }
+        );
+
+        // the first invalid symbol is reported with its byte position
+        let input = utils::validate_bit_string(INVALID_BIT_STRING);
+        assert_eq!(input.first_invalid, Some((33, 'X')));
+        assert_eq!(
+            utils::validate_bit_string("01é1").first_invalid,
+            Some((2, 'é'))
+        );
+        assert_eq!(utils::validate_bit_string("0110").ones, 2);
+
+        // a too short bit string results in an advisory
+        let input = utils::evaluate_bit_string(TEST_NAME, BIT_STRING_1, NUMBER_OF_BYTES).unwrap();
+        assert_eq!(input.advisories.len(), 1);
+        assert_eq!(input.advisories[0].severity, customtypes::Severity::Weak);
+        assert_eq!(input.advisories[0].parameter, "n");
+        let error = utils::evaluate_bit_string(TEST_NAME, INVALID_BIT_STRING, NUMBER_OF_BYTES)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'X'") && error.contains("33"));
+
+        // multibyte characters are reported with their byte offset in a typed error
+        let error = utils::evaluate_bit_string(TEST_NAME, "0110é0", NUMBER_OF_BYTES).unwrap_err();
+        assert_eq!(
+            error.root(),
+            &customtypes::NistError::InvalidBit {
+                position: 4,
+                symbol: 'é'
+            }
+        );
+        assert!(utils::ensure_ascii("0110 01").is_ok());
+        assert_eq!(
+            utils::ensure_ascii("01€0").unwrap_err(),
+            customtypes::NistError::InvalidBit {
+                position: 2,
+                symbol: '€'
+            }
         );
 
         let mut success: bool;
@@ -127,8 +379,11 @@ mod tests {
         assert!(!success);
     }
 
+    #[cfg(feature = "template-tables")]
     #[test]
     fn test_untar_archive() {
+        use std::io::Read;
+
         logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
 
         if std::path::Path::new(TEMPLATE_FILE_DEST).exists() {
@@ -168,4 +423,94 @@ mod tests {
         let _ = std::fs::remove_file(TEMPLATE_FILE_DEST);
         assert!(!std::path::Path::new(TEMPLATE_FILE_DEST).exists());
     }
+
+    #[test]
+    fn test_blocks() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // trailing bits not filling a whole block are discarded
+        let blocks: Vec<String> = utils::blocks(BIT_STRING_1, 5)
+            .unwrap()
+            .map(|block| block.to_string())
+            .collect();
+        assert_eq!(blocks.len(), BIT_STRING_1.len() / 5);
+        assert_eq!(blocks[0], "10101");
+        assert!(blocks.iter().all(|block| block.len() == 5));
+        assert_eq!(blocks.concat(), BIT_STRING_1[..45]);
+
+        assert_eq!(utils::blocks(BIT_STRING_1, 8).unwrap().count(), 6);
+        assert_eq!(utils::blocks(BIT_STRING_1, 49).unwrap().count(), 0);
+        assert_eq!(utils::blocks("", 1).unwrap().count(), 0);
+
+        assert!(utils::blocks(BIT_STRING_1, 0).is_err());
+        assert!(utils::blocks("0é10", 2).is_err());
+
+        // a packed sequence is split into the same blocks
+        let sequence = BitSequence::from_bit_string(BIT_STRING_1).unwrap();
+        assert!(utils::blocks(&sequence, 5)
+            .unwrap()
+            .zip(blocks.iter())
+            .all(|(block, expected)| block.to_string() == *expected));
+
+        // blocks are read a word at a time, the window of a non-overlapping search moves past
+        // each match
+        let block = utils::blocks(BIT_STRING_1, 5).unwrap().next().unwrap();
+        let packed_block = utils::blocks(&sequence, 5).unwrap().next().unwrap();
+        assert_eq!(block.len(), 5);
+        assert_eq!(block.words().collect::<Vec<u64>>(), vec![0b10101 << 59]);
+        assert!(block.words().eq(packed_block.words()));
+        assert_eq!(block.count_ones(), 3);
+        assert_eq!(packed_block.count_ones(), 3);
+        assert_eq!(block.count_pattern(0b101, 3, true), 2);
+        assert_eq!(packed_block.count_pattern(0b101, 3, true), 2);
+        assert_eq!(block.count_pattern(0b101, 3, false), 1);
+        let pattern = utils::pack_template(customtypes::Test::OverlappingTemplate, "101").unwrap();
+        assert_eq!(pattern, 0b101);
+        assert!(utils::pack_template(customtypes::Test::OverlappingTemplate, "").is_err());
+        assert!(utils::pack_template(customtypes::Test::OverlappingTemplate, "1021").is_err());
+    }
+
+    #[test]
+    fn test_try_map_blocks() {
+        logger::init_logger(LOGLEVEL).expect("Could not initialize logger");
+
+        // the results are in the order of the blocks, regardless of the "parallel" feature
+        let expected: Vec<(usize, String)> = utils::blocks(BIT_STRING_1, 5)
+            .unwrap()
+            .map(|block| block.to_string())
+            .enumerate()
+            .collect();
+        let blocks = utils::try_map_blocks(BIT_STRING_1, 5, expected.len(), |index, block| {
+            Ok((index, block.to_string()))
+        })
+        .unwrap();
+        assert_eq!(blocks, expected);
+
+        let sequence = BitSequence::from_bit_string(BIT_STRING_1).unwrap();
+        let ones =
+            utils::try_map_blocks(&sequence, 5, 3, |_, block| Ok(block.count_ones())).unwrap();
+        assert_eq!(ones, vec![3, 2, 0]);
+
+        // a failing block fails the whole map
+        assert!(utils::try_map_blocks(BIT_STRING_1, 5, 9, |index, _| {
+            if index == 7 {
+                return Err(customtypes::NistError::InvalidInput {
+                    reason: format!("Block {} failed", index),
+                });
+            }
+            Ok(index)
+        })
+        .is_err());
+
+        assert!(utils::try_map_blocks(BIT_STRING_1, 0, 1, |index, _| Ok(index)).is_err());
+        assert!(utils::try_map_blocks(BIT_STRING_1, 5, 10, |index, _| Ok(index)).is_err());
+        assert!(utils::try_map_blocks("0é10", 2, 1, |index, _| Ok(index)).is_err());
+
+        let items = [3, 1, 2];
+        assert_eq!(
+            utils::try_map(&items, |item| Ok(item * 2)).unwrap(),
+            vec![6, 2, 4]
+        );
+        assert!(utils::parallel_batch_size() >= 1);
+    }
 }
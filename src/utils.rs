@@ -1,10 +1,60 @@
 //! This module contains useful functions to support the statistical tests from the NIST suite.
 
+use crate::bit_sequence::{BitInput, Block};
+use crate::constants;
 use crate::customtypes;
-use anyhow::{Context, Result};
+use crate::customtypes::{Context, Result};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
+/// Scan passed bit string once and collect its length, number of ones and the first invalid
+/// symbol, if any.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to scan
+///
+/// # Return
+///
+/// input - The collected information about the bit string
+pub fn validate_bit_string(bit_string: &str) -> customtypes::ValidatedInput {
+    log::trace!("utils::validate_bit_string()");
+
+    let mut input = customtypes::ValidatedInput {
+        length: bit_string.len(),
+        ..Default::default()
+    };
+
+    // scan eight characters at a time. A lane of '0' and '1' characters only differs from
+    // "00000000" in the lowest bit of each byte, which is the bit of the character
+    let mut scanned = 0;
+    for lane in bit_string.as_bytes().chunks_exact(8) {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(lane);
+        let lane = u64::from_be_bytes(bytes);
+        if lane & !0x0101_0101_0101_0101 != 0x3030_3030_3030_3030 {
+            break;
+        }
+        input.ones += (lane & 0x0101_0101_0101_0101).count_ones() as usize;
+        scanned += 8;
+    }
+
+    // scan the rest one character at a time, starting with the lane of the first invalid symbol
+    for (position, c) in bit_string[scanned..].char_indices() {
+        match c {
+            '1' => input.ones += 1,
+            '0' => (),
+            _ => {
+                input.first_invalid = Some((scanned + position, c));
+                break;
+            }
+        }
+    }
+
+    input
+}
+
 /// Evaluate passed bit string.
 ///
 /// # Arguments
@@ -15,25 +65,59 @@ use std::io::{BufRead, BufReader};
 ///
 /// # Return
 ///
-/// Ok(length) - Return length of bit string if everything is okay
+/// Ok(input) - Return length, number of ones and advisories of bit string if everything is okay
 /// Err(err) - Some error occured
 pub fn evaluate_bit_string(
     test_name: customtypes::Test,
-    bit_string: &str,
+    bit_string: &(impl BitInput + ?Sized),
     recommended_size: usize,
-) -> Result<usize> {
+) -> Result<customtypes::ValidatedInput> {
     log::trace!("utils::evaluate_bit_string()");
 
     // check validity of passed bit string
-    if bit_string.is_empty() || bit_string.chars().any(|c| c != '0' && c != '1') {
-        anyhow::bail!(
-            "{}: Bit string is either empty or contains invalid character(s)",
-            test_name
-        );
+    if bit_string.bit_len() == 0 {
+        return Err(customtypes::NistError::insufficient_length(test_name, 1, 0));
     }
 
-    let length = bit_string.len();
-    log::debug!("{}: Bit string has the length {}", test_name, length);
+    let mut input = bit_string.validate();
+    if let Some((position, symbol)) = input.first_invalid {
+        let error = customtypes::NistError::InvalidBit { position, symbol };
+        let message = format!("{}: {}", test_name, error);
+        return Err(error).context(message);
+    }
+
+    log::debug!(
+        "{}: Bit string has the length {} and contains {} ones",
+        test_name,
+        input.length,
+        input.ones
+    );
+
+    check_recommended_size(
+        test_name,
+        input.length,
+        recommended_size,
+        &mut input.advisories,
+    );
+
+    Ok(input)
+}
+
+/// Check whether the bit string has the recommended size and add an advisory otherwise.
+///
+/// # Arguments
+///
+/// test_name - The test the bit string is passed to
+/// length - The length n of the bit string
+/// recommended_size - The recommended minimum length of the test
+/// advisories - Collects the advisory about the length
+pub fn check_recommended_size(
+    test_name: customtypes::Test,
+    length: usize,
+    recommended_size: usize,
+    advisories: &mut Vec<customtypes::Advisory>,
+) {
+    log::trace!("utils::check_recommended_size()");
 
     // If bit string has not the recommended size, it is not an error but log a warning anyways
     if length < recommended_size {
@@ -42,9 +126,569 @@ pub fn evaluate_bit_string(
             test_name,
             recommended_size
         );
+        advisories.push(customtypes::Advisory::weak(
+            "n",
+            format!(
+                "Bit string length {} is less than the recommended {} bits",
+                length, recommended_size
+            ),
+        ));
+    }
+}
+
+/// Make sure passed bit string only consists of ASCII characters, so it can be sliced by byte
+/// index without splitting a multibyte UTF-8 character.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to check
+///
+/// # Return
+///
+/// Ok() - The bit string only consists of ASCII characters
+/// Err(err) - The byte position and the first non-ASCII character
+pub fn ensure_ascii(bit_string: &str) -> Result<(), customtypes::NistError> {
+    log::trace!("utils::ensure_ascii()");
+
+    // the check of all bytes at once is much faster than decoding the characters
+    if bit_string.is_ascii() {
+        return Ok(());
+    }
+
+    match bit_string.char_indices().find(|(_, c)| !c.is_ascii()) {
+        Some((position, symbol)) => Err(customtypes::NistError::InvalidBit { position, symbol }),
+        None => Ok(()),
+    }
+}
+
+/// Convert a count, e.g. the bit string length, into a signed integer for sums which can become
+/// negative. Values beyond i64::MAX are rejected instead of wrapping.
+///
+/// # Arguments
+///
+/// test_name - The name of the test the conversion is made for
+/// value - The count to be converted
+/// operation - Description of the computation, used in the error
+///
+/// # Return
+///
+/// Ok(value) - The converted count
+/// Err(err) - The count does not fit into an i64
+pub fn checked_i64(
+    test_name: customtypes::Test,
+    value: usize,
+    operation: &str,
+) -> Result<i64, customtypes::NistError> {
+    log::trace!("utils::checked_i64()");
+
+    i64::try_from(value).map_err(|_| customtypes::NistError::Overflow {
+        test: test_name,
+        operation: operation.to_owned(),
+    })
+}
+
+/// Check whether the random walk of an excursion test has enough cycles J. NIST considers the
+/// test not applicable if J < max(0.005 * sqrt(n), 500).
+///
+/// # Arguments
+///
+/// test_name - The name of the excursion test
+/// number_of_cycles - The number of cycles J of the random walk
+/// length - The length n of the bit string
+///
+/// # Return
+///
+/// Ok() - The random walk has enough cycles
+/// Err(err) - The random walk has too few cycles
+pub fn check_number_of_cycles(
+    test_name: customtypes::Test,
+    number_of_cycles: usize,
+    length: usize,
+) -> Result<(), customtypes::NistError> {
+    log::trace!("utils::check_number_of_cycles()");
+
+    let required_cycles = ((constants::MIN_CYCLES_FACTOR_EXCURSIONS * (length as f64).sqrt())
+        as usize)
+        .max(constants::MIN_CYCLES_EXCURSIONS);
+    if number_of_cycles < required_cycles {
+        return Err(customtypes::NistError::InsufficientCycles {
+            test: test_name,
+            number_of_cycles,
+            required_cycles,
+        });
+    }
+
+    Ok(())
+}
+
+/// Count the overlapping patterns of a length, wrapping around the end of the sequence as the
+/// "Serial" and "Approximate Entropy" tests prescribe.
+///
+/// # Arguments
+///
+/// test_name - The test the patterns are counted for
+/// bit_string - The bit string, already evaluated by evaluate_bit_string()
+/// pattern_len - The length of the patterns
+/// cancellation - The token to stop the count with
+///
+/// # Return
+///
+/// Ok(counts) - The number of occurences of each pattern, indexed by its value
+/// Err(err) - The pattern length is out of range or the test got cancelled
+pub fn count_overlapping_patterns(
+    test_name: customtypes::Test,
+    bit_string: &(impl BitInput + ?Sized),
+    pattern_len: usize,
+    cancellation: &customtypes::CancellationToken,
+) -> Result<Vec<usize>> {
+    log::trace!("utils::count_overlapping_patterns()");
+
+    // the counts of all 2^m patterns are kept
+    if !(1..=constants::MAX_PATTERN_LEN).contains(&pattern_len) {
+        return Err(customtypes::NistError::invalid_parameter(
+            test_name,
+            "m",
+            format!(
+                "Pattern length {} must be between 1 and {}",
+                pattern_len,
+                constants::MAX_PATTERN_LEN
+            ),
+        ));
+    }
+
+    let mask = (1 << pattern_len) - 1;
+    let mut counts = vec![0; 1 << pattern_len];
+    let mut pattern = 0;
+
+    // the first m-1 bits are appended to the end
+    let wrapped = bit_string
+        .bits()
+        .chain(bit_string.bits().take(pattern_len - 1));
+    for (index, bit) in wrapped.enumerate() {
+        cancellation.check_at(test_name, index)?;
+        pattern = ((pattern << 1) | usize::from(bit)) & mask;
+        if index + 1 >= pattern_len {
+            counts[pattern] += 1;
+        }
+    }
+
+    Ok(counts)
+}
+
+/// Pack a template of '0' and '1' characters into a word, e.g. to count its occurences in a block
+/// by Block::count_pattern().
+///
+/// # Arguments
+///
+/// test_name - The test searching for the template
+/// template - The template to be packed
+///
+/// # Return
+///
+/// Ok(pattern) - The bits of the template in the least significant positions
+/// Err(err) - The template is empty, longer than a word or contains invalid character(s)
+pub fn pack_template(test_name: customtypes::Test, template: &str) -> Result<u64> {
+    log::trace!("utils::pack_template()");
+
+    if template.is_empty()
+        || template.len() > u64::BITS as usize
+        || template.bytes().any(|bit| bit != b'0' && bit != b'1')
+    {
+        return Err(customtypes::NistError::invalid_parameter(
+            test_name,
+            "templates",
+            format!(
+                "Template '{}' is not a bit string of 1 to {} bits",
+                template,
+                u64::BITS
+            ),
+        ));
+    }
+
+    Ok(template
+        .bytes()
+        .fold(0, |pattern, bit| (pattern << 1) | u64::from(bit == b'1')))
+}
+
+/// Split a bit string into consecutive, non-overlapping blocks of given size. Trailing bits which
+/// do not fill a whole block are discarded, as NIST SP 800-22 prescribes for block-based tests.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be split, already evaluated by evaluate_bit_string()
+/// block_size - The size of each block
+///
+/// # Return
+///
+/// Ok(blocks) - Iterator over all complete blocks, which borrow the bit string. It can be cloned
+/// to iterate over the blocks again without checking the bit string again
+/// Err(err) - Some error occured
+pub fn blocks<T: BitInput + ?Sized>(
+    bit_string: &T,
+    block_size: usize,
+) -> Result<impl Iterator<Item = Block<'_, T>> + Clone> {
+    log::trace!("utils::blocks()");
+
+    if block_size == 0 {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "M".to_owned(),
+            reason: "Block size must be at least 1".to_owned(),
+        });
+    }
+    bit_string.ensure_ascii()?;
+
+    let length = bit_string.bit_len();
+    let discarded = length % block_size;
+    if discarded > 0 {
+        log::debug!(
+            "Discarding {} trailing bits not filling a block of size {}",
+            discarded,
+            block_size
+        );
     }
 
-    Ok(length)
+    // only ASCII bytes are split, so each block is valid UTF-8
+    Ok((0..length / block_size)
+        .map(move |block| Block::new(bit_string, block * block_size..(block + 1) * block_size)))
+}
+
+/// Apply a function to the first blocks of a bit string, on all cores if the "parallel" feature
+/// is enabled. The results are in the order of the blocks, so reducing them sequentially gives
+/// bit-identical results with and without the feature.
+///
+/// # Arguments
+///
+/// bit_string - The bit string to be split, already evaluated by evaluate_bit_string()
+/// block_size - The size of each block
+/// number_of_blocks - The number of blocks to be processed, at most the number of complete blocks
+/// f - The function receiving the index and the bits of a block
+///
+/// # Return
+///
+/// Ok(results) - The result of each block
+/// Err(err) - Some error occured, e.g. the function failed on a block
+pub fn try_map_blocks<T, R, F>(
+    bit_string: &T,
+    block_size: usize,
+    number_of_blocks: usize,
+    f: F,
+) -> Result<Vec<R>>
+where
+    T: BitInput + ?Sized,
+    R: Send,
+    F: Fn(usize, Block<'_, T>) -> Result<R> + Sync + Send,
+{
+    log::trace!("utils::try_map_blocks()");
+
+    if block_size == 0 {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "M".to_owned(),
+            reason: "Block size must be at least 1".to_owned(),
+        });
+    }
+    bit_string.ensure_ascii()?;
+    if number_of_blocks > bit_string.bit_len() / block_size {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "N".to_owned(),
+            reason: format!(
+                "Number of blocks ({}) exceeds the {} complete blocks of size {}",
+                number_of_blocks,
+                bit_string.bit_len() / block_size,
+                block_size
+            ),
+        });
+    }
+
+    try_map_range(0..number_of_blocks, |block| {
+        f(
+            block,
+            Block::new(bit_string, block * block_size..(block + 1) * block_size),
+        )
+    })
+}
+
+/// Apply a function to each item, on all cores if the "parallel" feature is enabled. The results
+/// are in the order of the items.
+///
+/// # Arguments
+///
+/// items - The items to be processed, e.g. templates
+/// f - The function receiving an item
+///
+/// # Return
+///
+/// Ok(results) - The result of each item
+/// Err(err) - The function failed on an item
+pub fn try_map<I, R, F>(items: &[I], f: F) -> Result<Vec<R>>
+where
+    I: Sync,
+    R: Send,
+    F: Fn(&I) -> Result<R> + Sync + Send,
+{
+    try_map_range(0..items.len(), |index| f(&items[index]))
+}
+
+/// Get the number of items to be processed at once between two progress reports: the number of
+/// threads if the "parallel" feature is enabled, otherwise one.
+///
+/// # Return
+///
+/// batch_size - The number of items per batch
+pub fn parallel_batch_size() -> usize {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::current_num_threads()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        1
+    }
+}
+
+/// Apply a function to each index of a range, on all cores if the "parallel" feature is enabled.
+///
+/// # Arguments
+///
+/// range - The indices to be processed
+/// f - The function receiving an index
+///
+/// # Return
+///
+/// Ok(results) - The result of each index in ascending order
+/// Err(err) - The function failed on an index
+fn try_map_range<R, F>(range: std::ops::Range<usize>, f: F) -> Result<Vec<R>>
+where
+    R: Send,
+    F: Fn(usize) -> Result<R> + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        range.into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        range.map(f).collect()
+    }
+}
+
+/// Check the limits and the significance level of passed configuration. Strict mode refuses any
+/// limit differing from the NIST recommendations and any significance level outside of the range
+/// NIST considers typical, while permissive mode only logs the overrides.
+///
+/// # Arguments
+///
+/// config - The configuration containing the compliance mode, the limits and alpha
+///
+/// # Return
+///
+/// Ok() - The limits are acceptable in the configured mode
+/// Err(err) - alpha is not a probability, the limits are out of range or overridden in strict mode
+pub fn check_limits(config: &customtypes::SuiteConfig) -> Result<()> {
+    log::trace!("utils::check_limits()");
+
+    if !(config.alpha > 0.0 && config.alpha < 1.0) {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "alpha".to_owned(),
+            reason: format!(
+                "Significance level ({}) must be between 0 and 1",
+                config.alpha
+            ),
+        });
+    }
+    if !(constants::MIN_P_VALUE_THRESHOLD..=constants::P_VALUE_THRESHOLD).contains(&config.alpha) {
+        if config.compliance_mode == customtypes::ComplianceMode::Strict {
+            return Err(customtypes::NistError::InvalidParameter {
+                test: None,
+                name: "alpha".to_owned(),
+                reason: format!(
+                    "Significance level ({}) outside of [{}, {}] is refused in strict mode",
+                    config.alpha,
+                    constants::MIN_P_VALUE_THRESHOLD,
+                    constants::P_VALUE_THRESHOLD
+                ),
+            });
+        }
+        log::warn!(
+            "Significance level ({}) is outside of the range [{}, {}] of NIST SP 800-22",
+            config.alpha,
+            constants::MIN_P_VALUE_THRESHOLD,
+            constants::P_VALUE_THRESHOLD
+        );
+    }
+
+    // overridden limits have to stay meaningful in any mode, e.g. to not divide by zero blocks
+    let limits = &config.limits;
+    let invalid = if limits.max_number_of_blocks == 0 {
+        Some("Maximum number of blocks must be at least 1".to_owned())
+    } else if limits.template_len.0 == 0 || limits.template_len.0 > limits.template_len.1 {
+        Some(format!(
+            "Template lengths ({}, {}) must be at least 1 and in ascending order",
+            limits.template_len.0, limits.template_len.1
+        ))
+    } else if limits.max_state_excursions_variant < 1 {
+        Some(format!(
+            "Largest state ({}) must be at least 1",
+            limits.max_state_excursions_variant
+        ))
+    } else {
+        None
+    };
+    if let Some(reason) = invalid {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "limits".to_owned(),
+            reason,
+        });
+    }
+
+    if !limits.is_overridden() {
+        return Ok(());
+    }
+
+    if config.compliance_mode == customtypes::ComplianceMode::Strict {
+        return Err(customtypes::NistError::InvalidParameter {
+            test: None,
+            name: "limits".to_owned(),
+            reason: format!(
+                "Overriding the limits of NIST SP 800-22 is refused in strict mode: {:?}",
+                config.limits
+            ),
+        });
+    }
+    log::warn!(
+        "Limits differ from NIST SP 800-22: {:?}. Results may not be statistically valid",
+        config.limits
+    );
+
+    Ok(())
+}
+
+/// Apply the compliance mode of passed configuration to a test result. In strict mode, any
+/// advisory or overridden limit makes the result unacceptable, while permissive mode passes the
+/// result on. Either way, the result is judged at the configured significance level.
+///
+/// # Arguments
+///
+/// config - The configuration containing the compliance mode
+/// result - The result of a test, including its advisories
+///
+/// # Return
+///
+/// Ok(result) - The result complies with the configured mode
+/// Err(err) - The result violates the NIST recommendations in strict mode
+pub fn check_compliance(
+    config: &customtypes::SuiteConfig,
+    result: customtypes::TestResult,
+) -> Result<customtypes::TestResult> {
+    log::trace!("utils::check_compliance()");
+
+    check_limits(config).with_context(|| format!("{}: Refused in strict mode", result.test))?;
+
+    if config.compliance_mode == customtypes::ComplianceMode::Strict
+        && !result.advisories.is_empty()
+    {
+        let parameters = result
+            .advisories
+            .iter()
+            .map(|advisory| advisory.parameter.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let violations = result
+            .advisories
+            .iter()
+            .map(|advisory| format!("{}: {}", advisory.parameter, advisory.message))
+            .collect::<Vec<String>>()
+            .join("; ");
+        return Err(customtypes::NistError::invalid_parameter(
+            result.test,
+            &parameters,
+            format!(
+                "Refused in strict mode because the NIST recommendations are violated ({})",
+                violations
+            ),
+        ));
+    }
+
+    Ok(result.with_alpha(config.alpha))
+}
+
+/// Collect the chosen configuration of a test into the parameters of its result.
+///
+/// # Arguments
+///
+/// parameters - Pairs of parameter name and value, e.g. ("M", 128)
+///
+/// # Return
+///
+/// parameter_map - The parameters indexed by their names
+pub fn parameter_map(parameters: &[(&str, usize)]) -> BTreeMap<String, usize> {
+    parameters
+        .iter()
+        .map(|&(name, value)| (name.to_owned(), value))
+        .collect()
+}
+
+/// Derive a template length and number of blocks satisfying all requirements of a template test
+/// for a bit string of given length. The parameters of the suite (m = 9, N = 8) are preferred.
+/// Inputs too short for them get fewer blocks first and shorter templates then.
+///
+/// # Arguments
+///
+/// test - The template test the parameters are derived for
+/// bit_string_length - Length of bit string
+/// limits - The bounds of the template length and the number of blocks
+/// evaluate - Checks a template length and number of blocks against the requirements of the test
+///
+/// # Return
+///
+/// Ok((template_len, number_of_blocks)) - The first valid pair in order of preference
+/// Err(err) - No template length and number of blocks fit the bit string length
+pub(crate) fn tune_template_params<F>(
+    test: customtypes::Test,
+    bit_string_length: usize,
+    limits: &customtypes::Limits,
+    evaluate: F,
+) -> Result<(usize, usize)>
+where
+    F: Fn(usize, usize) -> Result<usize>,
+{
+    log::trace!("utils::tune_template_params()");
+
+    // the suite length first, then the lengths below the upper recommended one in descending order
+    let template_lens = std::iter::once(constants::SUITE_TEMPLATE_LEN).chain(
+        (limits.template_len.0..=limits.recommended_template_len.1)
+            .rev()
+            .filter(|&template_len| template_len != constants::SUITE_TEMPLATE_LEN),
+    );
+
+    for template_len in template_lens {
+        for number_of_blocks in (1..=constants::SUITE_NUMBER_OF_TEMPLATE_BLOCKS).rev() {
+            if evaluate(template_len, number_of_blocks).is_ok() {
+                log::debug!(
+                    "{}: Tuned template length = {}, Number of blocks N = {} for length {}",
+                    test,
+                    template_len,
+                    number_of_blocks,
+                    bit_string_length
+                );
+                return Ok((template_len, number_of_blocks));
+            }
+        }
+    }
+
+    Err(customtypes::NistError::invalid_parameter(
+        test,
+        "m",
+        format!(
+            "No template length and number of blocks fit the bit string length {}",
+            bit_string_length
+        ),
+    ))
 }
 
 /// Convert a given vector of hexadecimal bytes into a bit string.
@@ -62,7 +706,9 @@ pub fn hex_bytes_to_bit_string(hex_bytes: Vec<u8>) -> Result<String> {
 
     // check if given vector is empty or not
     if hex_bytes.is_empty() {
-        anyhow::bail!("No hexadecimal bytes to convert passed!");
+        return Err(customtypes::NistError::InvalidInput {
+            reason: "No hexadecimal bytes to convert passed!".to_owned(),
+        });
     }
 
     // now convert hex bytes to bit string
@@ -104,10 +750,8 @@ pub fn read_random_numbers(file_path: &str) -> Result<String> {
     // if the random number is separated into multiple lines, concatenate them into one line
     let mut random_string = String::new();
     let reader = BufReader::new(file);
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            random_string.push_str(&line);
-        }
+    for line in reader.lines().map_while(Result::ok) {
+        random_string.push_str(&line);
     }
 
     // remove any whitespace characters from the string
@@ -118,15 +762,19 @@ pub fn read_random_numbers(file_path: &str) -> Result<String> {
         random_string
     } else if random_string.chars().all(|c| c.is_ascii_hexdigit()) {
         // parse the hexadecimal string into bytes
-        let random_bytes = hex::decode(&random_string)
-            .map_err(|e| anyhow::anyhow!("Failed to parse hexadecimal string: {}", e))?;
+        let random_bytes =
+            hex::decode(&random_string).map_err(|e| customtypes::NistError::InvalidInput {
+                reason: format!("Failed to parse hexadecimal string: {}", e),
+            })?;
         // now convert to bit string
         hex_bytes_to_bit_string(random_bytes)?
     } else {
-        anyhow::bail!(
-            "File '{}' neither contains valid hex bytes nor valid bit string!",
-            file_path
-        );
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!(
+                "File '{}' neither contains valid hex bytes nor valid bit string!",
+                file_path
+            ),
+        });
     };
 
     log::info!(
@@ -149,18 +797,23 @@ pub fn read_random_numbers(file_path: &str) -> Result<String> {
 ///
 /// Ok() - Successfully unpacked archive
 /// Err(err) - Some error occured
+#[cfg(feature = "template-tables")]
 pub fn untar_archive(archive_name: &str, dest: &str) -> Result<()> {
     log::trace!("utils::untar_archive()");
 
     // check whether archive and destination exist
     if !std::path::Path::new(archive_name).exists() {
-        anyhow::bail!("Archive '{}' does not exist!", archive_name);
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!("Archive '{}' does not exist!", archive_name),
+        });
     }
     if !(std::path::Path::new(dest).exists() && std::path::Path::new(dest).is_dir()) {
-        anyhow::bail!(
-            "Destination path '{}' neither exists nor is a directory",
-            dest
-        );
+        return Err(customtypes::NistError::InvalidInput {
+            reason: format!(
+                "Destination path '{}' neither exists nor is a directory",
+                dest
+            ),
+        });
     }
 
     // now try to untar the archive